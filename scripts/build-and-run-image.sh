@@ -28,6 +28,7 @@ IMAGE_NAME="omnectshareddevacr.azurecr.io/omnect-ui:${IMAGE_TAG}"
 # Ensure required directories exist
 mkdir -p "$PROJECT_ROOT/temp/data"
 mkdir -p "$PROJECT_ROOT/temp/network"
+mkdir -p "$PROJECT_ROOT/temp/wpa_supplicant"
 
 # ensure presence of:
 # /tmp/api.sock (normally created by a local instance of omnect-device-service)
@@ -38,6 +39,7 @@ docker run --rm \
   -v /tmp:/socket \
   -v "$PROJECT_ROOT/temp/data:/data" \
   -v "$PROJECT_ROOT/temp/network:/network" \
+  -v "$PROJECT_ROOT/temp/wpa_supplicant:/wpa_supplicant" \
   -u "$(id -u):$(id -g)" \
   -e RUST_LOG=debug \
   -e UI_PORT="$UI_PORT" \
@@ -38,6 +38,8 @@ pub enum CentrifugoOutput {
         data: Option<String>,
     },
     Error {
+        /// Centrifugo error code, None for failures without one
+        code: Option<u32>,
         message: String,
     },
 }
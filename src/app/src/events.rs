@@ -18,6 +18,7 @@ pub enum AuthEvent {
         password: String,
     },
     CheckRequiresPasswordSet,
+    SessionExpired,
     #[serde(skip)]
     LoginResponse(Result<AuthToken, String>),
     #[serde(skip)]
@@ -25,7 +26,7 @@ pub enum AuthEvent {
     #[serde(skip)]
     SetPasswordResponse(Result<AuthToken, String>),
     #[serde(skip)]
-    UpdatePasswordResponse(Result<(), String>),
+    UpdatePasswordResponse(Result<UpdatePasswordResult, String>),
     #[serde(skip)]
     CheckRequiresPasswordSetResponse(Result<bool, String>),
 }
@@ -37,6 +38,8 @@ pub enum DeviceEvent {
     FactoryResetRequest {
         mode: String,
         preserve: Vec<String>,
+        /// Re-authenticates the request, required if the backend asked for it
+        password: Option<String>,
     },
     SetNetworkConfig {
         config: String,
@@ -50,20 +53,39 @@ pub enum DeviceEvent {
     NetworkFormReset {
         adapter_name: String,
     },
+    NetworkFormDiscardAll,
     LoadUpdate {
         file_path: String,
     },
     UploadStarted,
-    UploadProgress(u8),
+    UploadProgress {
+        uploaded_bytes: u64,
+        total_bytes: u64,
+        /// Milliseconds since the epoch
+        timestamp_ms: u64,
+    },
     UploadCompleted(String),
     UploadFailed(String),
+    UploadCancelled,
     RunUpdate {
         validate_iothub_connection: bool,
     },
+    /// Wait for the device to come back from an operation not started by the UI,
+    /// e.g. a power cycle announced by the operator
+    ExpectReconnection {
+        operation: DeviceOperation,
+    },
     ReconnectionCheckTick,
     ReconnectionTimeout,
-    NewIpCheckTick,
+    ConnectionCheckTick,
+    NewIpCheckTick {
+        /// Milliseconds since the epoch
+        timestamp_ms: u64,
+    },
     NewIpCheckTimeout,
+    /// Sent by the shell after redirecting to a reachable new IP, also if the redirect failed
+    NetworkChangeComplete,
+    TriggerRollbackNow,
     AckRollback,
     AckFactoryResetResult,
     AckUpdateValidation,
@@ -72,6 +94,8 @@ pub enum DeviceEvent {
     #[serde(skip)]
     FactoryResetResponse(Result<(), String>),
     #[serde(skip)]
+    FactoryResetReauthRequired,
+    #[serde(skip)]
     SetNetworkConfigResponse(Result<crate::types::SetNetworkConfigResponse, String>),
     #[serde(skip)]
     LoadUpdateResponse(Result<UpdateManifest, String>),
@@ -80,6 +104,10 @@ pub enum DeviceEvent {
     #[serde(skip)]
     HealthcheckResponse(Result<HealthcheckInfo, String>),
     #[serde(skip)]
+    ResumeOperationResponse(Result<HealthcheckInfo, String>),
+    #[serde(skip)]
+    TriggerRollbackNowResponse(Result<RollbackNowResponse, String>),
+    #[serde(skip)]
     AckRollbackResponse(Result<(), String>),
     #[serde(skip)]
     AckFactoryResetResultResponse(Result<(), String>),
@@ -100,6 +128,13 @@ pub enum WebSocketEvent {
     TimeoutsUpdated(String),
     Connected,
     Disconnected,
+    /// Connecting or subscribing failed, or the server disconnected
+    ///
+    /// `code` is the centrifugo error or disconnect code, None for transport failures.
+    ConnectionFailed {
+        code: Option<u32>,
+        message: String,
+    },
 }
 
 /// UI action events
@@ -107,7 +142,23 @@ pub enum WebSocketEvent {
 pub enum UiEvent {
     ClearError,
     ClearSuccess,
+    /// Clear all transient messages at once, e.g. after navigating to another page
+    ClearAllNotifications,
     SetBrowserHostname(String),
+    ToggleAdvancedMode,
+    /// Restore the advanced mode preference persisted by the shell
+    SetAdvancedMode(bool),
+    /// Set the update validation statuses meaning an update is complete, provided by the backend config
+    SetUpdateCompleteStatuses(Vec<String>),
+    BootstrapRetry,
+    #[serde(skip)]
+    BootstrapConfigResponse(Result<FrontendConfig, String>),
+    #[serde(skip)]
+    BootstrapVersionResponse(Result<String, String>),
+    /// Ask the device service to publish its data again
+    Refresh,
+    #[serde(skip)]
+    RefreshResponse(Result<(), String>),
 }
 
 /// Main event enum - wraps domain events
@@ -126,16 +177,16 @@ impl fmt::Debug for AuthEvent {
         match self {
             AuthEvent::Login { .. } => f
                 .debug_struct("Login")
-                .field("password", &"<redacted>")
+                .field("password", &REDACTED)
                 .finish(),
             AuthEvent::SetPassword { .. } => f
                 .debug_struct("SetPassword")
-                .field("password", &"<redacted>")
+                .field("password", &REDACTED)
                 .finish(),
             AuthEvent::UpdatePassword { .. } => f
                 .debug_struct("UpdatePassword")
-                .field("current_password", &"<redacted>")
-                .field("password", &"<redacted>")
+                .field("current_password", &REDACTED)
+                .field("password", &REDACTED)
                 .finish(),
             AuthEvent::LoginResponse(result) => match result {
                 Ok(_) => f
@@ -149,6 +200,7 @@ impl fmt::Debug for AuthEvent {
             },
             AuthEvent::Logout => write!(f, "Logout"),
             AuthEvent::CheckRequiresPasswordSet => write!(f, "CheckRequiresPasswordSet"),
+            AuthEvent::SessionExpired => write!(f, "SessionExpired"),
             AuthEvent::LogoutResponse(r) => f.debug_tuple("LogoutResponse").field(r).finish(),
             AuthEvent::SetPasswordResponse(result) => match result {
                 Ok(_) => f
@@ -177,9 +229,64 @@ impl fmt::Debug for Event {
         match self {
             Event::Initialize => write!(f, "Initialize"),
             Event::Auth(e) => write!(f, "Auth({e:?})"),
+            // the config is JSON that may hold the wifi pre-shared key
+            Event::Device(DeviceEvent::SetNetworkConfig { config }) => {
+                match serde_json::from_str::<NetworkConfigRequest>(config) {
+                    Ok(request) => write!(f, "Device(SetNetworkConfig {{ config: {request:?} }})"),
+                    Err(_) => write!(f, "Device(SetNetworkConfig {{ config: {REDACTED} }})"),
+                }
+            }
             Event::Device(e) => write!(f, "Device({e:?})"),
             Event::WebSocket(e) => write!(f, "WebSocket({e:?})"),
             Event::Ui(e) => write!(f, "Ui({e:?})"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "s3cr3t-value";
+
+    #[test]
+    fn auth_events_redact_passwords() {
+        let events = [
+            Event::Auth(AuthEvent::Login {
+                password: SECRET.to_string(),
+            }),
+            Event::Auth(AuthEvent::UpdatePassword {
+                current_password: SECRET.to_string(),
+                password: SECRET.to_string(),
+            }),
+            Event::Auth(AuthEvent::LoginResponse(Ok(AuthToken {
+                token: SECRET.to_string(),
+            }))),
+        ];
+
+        for event in events {
+            assert!(!format!("{event:?}").contains(SECRET));
+        }
+    }
+
+    #[test]
+    fn set_network_config_redacts_psk() {
+        let config = format!(
+            r#"{{"isServerAddr":false,"ipChanged":false,"name":"wlan0","dhcp":true,"previousIp":"192.168.0.100","gateway":[],"dns":[],"wifi":{{"ssid":"omnect","psk":"{SECRET}"}}}}"#
+        );
+        let event = Event::Device(DeviceEvent::SetNetworkConfig { config });
+
+        let logged = format!("{event:?}");
+        assert!(logged.contains("omnect"));
+        assert!(!logged.contains(SECRET));
+    }
+
+    #[test]
+    fn set_network_config_with_invalid_json_is_redacted() {
+        let event = Event::Device(DeviceEvent::SetNetworkConfig {
+            config: format!("{{ not json {SECRET}"),
+        });
+
+        assert!(!format!("{event:?}").contains(SECRET));
+    }
+}
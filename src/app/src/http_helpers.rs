@@ -3,7 +3,7 @@
 //! This module extracts common HTTP response handling logic from macros
 //! into debuggable, testable functions.
 
-use crux_http::{HttpError, Response};
+use crux_http::{http::StatusCode, HttpError, Response};
 
 /// Base URL for omnect-device API endpoints.
 ///
@@ -39,6 +39,42 @@ pub fn is_response_success(response: &Response<Vec<u8>>) -> bool {
     response.status().is_success()
 }
 
+/// Checks whether an HTTP result signals an expired session.
+///
+/// Returns `true` if the request was rejected with 401 Unauthorized, either as a
+/// plain response or as an HTTP error raised by `crux_http`.
+pub fn is_session_expired(result: &crux_http::Result<Response<Vec<u8>>>) -> bool {
+    match result {
+        Ok(response) => response.status() == StatusCode::Unauthorized,
+        Err(HttpError::Http { code, .. }) => *code == StatusCode::Unauthorized,
+        Err(_) => false,
+    }
+}
+
+/// Body of a request rejected because it requires the user password.
+///
+/// Sent with 403 Forbidden, e.g. by a factory reset with re-authentication enabled.
+pub const REAUTH_REQUIRED: &str = "reauth_required";
+
+/// Checks whether an HTTP result asks for the user password.
+///
+/// Returns `true` if the request was rejected with 403 Forbidden and a
+/// [`REAUTH_REQUIRED`] body. Unlike a 401 the session stays valid.
+pub fn is_reauth_required(result: &crux_http::Result<Response<Vec<u8>>>) -> bool {
+    let is_reauth_body =
+        |body: Option<&Vec<u8>>| body.is_some_and(|b| b == REAUTH_REQUIRED.as_bytes());
+
+    match result {
+        Ok(response) => {
+            response.status() == StatusCode::Forbidden && is_reauth_body(response.body())
+        }
+        Err(HttpError::Http { code, body, .. }) => {
+            *code == StatusCode::Forbidden && is_reauth_body(body.as_ref())
+        }
+        Err(_) => false,
+    }
+}
+
 /// Extracts error message from successful HTTP response.
 ///
 /// This is used when an API returns a 2xx status but indicates failure in the body,
@@ -53,7 +89,10 @@ pub fn extract_error_message(action: &str, response: &mut Response<Vec<u8>>) ->
                 format!("{action} failed: HTTP {status_str} (Empty body)")
             } else {
                 match String::from_utf8(body) {
-                    Ok(msg) => msg,
+                    Ok(msg) => match crate::types::DeviceServiceError::from_body(&msg) {
+                        Some(device_error) => device_error.to_string(),
+                        None => msg,
+                    },
                     Err(e) => format!("{action} failed: HTTP {status_str} (Invalid UTF-8: {e})"),
                 }
             }
@@ -179,8 +218,121 @@ where
 mod tests {
     use super::*;
 
+    use crux_http::testing::ResponseBuilder;
+
     #[test]
     fn test_build_url() {
         assert_eq!(build_url("/test"), "https://relative/test");
     }
+
+    mod is_session_expired {
+        use super::*;
+
+        #[test]
+        fn unauthorized_response_is_session_expired() {
+            let result = Ok(ResponseBuilder::with_status(StatusCode::Unauthorized)
+                .body(Vec::new())
+                .build());
+
+            assert!(is_session_expired(&result));
+        }
+
+        #[test]
+        fn unauthorized_http_error_is_session_expired() {
+            let result = Err(HttpError::Http {
+                code: StatusCode::Unauthorized,
+                message: "Unauthorized".into(),
+                body: None,
+            });
+
+            assert!(is_session_expired(&result));
+        }
+
+        #[test]
+        fn other_statuses_are_not_session_expired() {
+            let ok = Ok(ResponseBuilder::ok().body(Vec::new()).build());
+            let forbidden = Ok(ResponseBuilder::with_status(StatusCode::Forbidden)
+                .body(Vec::new())
+                .build());
+            let server_error = Err(HttpError::Http {
+                code: StatusCode::InternalServerError,
+                message: "Internal Server Error".into(),
+                body: None,
+            });
+
+            assert!(!is_session_expired(&ok));
+            assert!(!is_session_expired(&forbidden));
+            assert!(!is_session_expired(&server_error));
+        }
+    }
+
+    mod is_reauth_required {
+        use super::*;
+
+        fn forbidden(body: &str) -> crux_http::Result<Response<Vec<u8>>> {
+            Ok(ResponseBuilder::with_status(StatusCode::Forbidden)
+                .body(body.as_bytes().to_vec())
+                .build())
+        }
+
+        #[test]
+        fn forbidden_reauth_response_requires_password() {
+            let result = forbidden(REAUTH_REQUIRED);
+
+            assert!(is_reauth_required(&result));
+            assert!(!is_session_expired(&result));
+        }
+
+        #[test]
+        fn forbidden_reauth_http_error_requires_password() {
+            let result = Err(HttpError::Http {
+                code: StatusCode::Forbidden,
+                message: "Forbidden".into(),
+                body: Some(REAUTH_REQUIRED.as_bytes().to_vec()),
+            });
+
+            assert!(is_reauth_required(&result));
+        }
+
+        #[test]
+        fn other_rejections_do_not_require_password() {
+            let unauthorized = Ok(ResponseBuilder::with_status(StatusCode::Unauthorized)
+                .body(REAUTH_REQUIRED.as_bytes().to_vec())
+                .build());
+
+            assert!(!is_reauth_required(&forbidden("demo mode")));
+            assert!(!is_reauth_required(&unauthorized));
+        }
+    }
+
+    mod extract_error_message {
+        use super::*;
+
+        fn error_response(body: &str) -> Response<Vec<u8>> {
+            ResponseBuilder::with_status(StatusCode::InternalServerError)
+                .body(body.as_bytes().to_vec())
+                .build()
+        }
+
+        #[test]
+        fn structured_device_service_error_shows_code() {
+            let mut response =
+                error_response(r#"{"code":"E42","message":"update image is corrupt"}"#);
+
+            assert_eq!(
+                extract_error_message("Load update", &mut response),
+                "update image is corrupt (error code E42)"
+            );
+        }
+
+        #[test]
+        fn plain_text_error_is_passed_on() {
+            let mut response = error_response("device service unavailable");
+
+            assert_eq!(
+                extract_error_message("Load update", &mut response),
+                "device service unavailable"
+            );
+        }
+    }
 }
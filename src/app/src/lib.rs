@@ -17,8 +17,9 @@ pub use crate::{
     events::Event,
     http_helpers::{
         build_url, check_response_status, extract_error_message, extract_string_response,
-        handle_auth_error, handle_request_error, is_response_success, map_http_error,
-        parse_json_response, process_json_response, process_status_response, BASE_URL,
+        handle_auth_error, handle_request_error, is_reauth_required, is_response_success,
+        is_session_expired, map_http_error, parse_json_response, process_json_response,
+        process_status_response, BASE_URL, REAUTH_REQUIRED,
     },
     model::Model,
     types::*,
@@ -50,6 +51,11 @@ impl crux_core::App for App {
     }
 
     fn view(&self, model: &Self::Model) -> Self::ViewModel {
-        model.clone()
+        let mut view = model.clone();
+        view.pending_acknowledgments = model.pending_acknowledgments();
+        view.device_service_outdated = model.device_service_outdated();
+        view.has_notifications = model.has_notifications();
+        view.editing_current_adapter = model.editing_current_adapter();
+        view
     }
 }
@@ -43,8 +43,8 @@ macro_rules! update_field {
 // Re-export http_helpers functions for macro use
 pub use crate::http_helpers::{
     build_url, check_response_status, extract_error_message, extract_string_response,
-    handle_auth_error, handle_request_error, is_response_success, map_http_error,
-    parse_json_response, process_json_response, process_status_response, BASE_URL,
+    handle_auth_error, handle_request_error, is_response_success, is_session_expired,
+    map_http_error, parse_json_response, process_json_response, process_status_response, BASE_URL,
 };
 
 /// Macro for unauthenticated POST requests with standard error handling.
@@ -243,6 +243,7 @@ macro_rules! auth_post_basic {
 
 /// Macro for authenticated POST requests with standard error handling.
 /// Reduces boilerplate for POST requests that require authentication.
+/// A 401 response is routed to `AuthEvent::SessionExpired` instead of the response event.
 ///
 /// NOTE: URLs are prefixed with `https://relative`.
 /// `crux_http` requires absolute URLs and rejects relative paths.
@@ -269,6 +270,13 @@ macro_rules! auth_post_basic {
 /// )
 /// ```
 ///
+/// Pattern 6: POST without body expecting JSON response
+/// ```ignore
+/// auth_post!(model, "/network/rollback-now", TriggerRollbackNowResponse, "Rollback",
+///     expect_json: RollbackNowResponse
+/// )
+/// ```
+///
 /// NOTE: The macro requires a domain parameter to specify the event wrapper.
 /// Examples:
 /// - Auth domain: `auth_post!(Auth, AuthEvent, model, "/logout", LogoutResponse, "Logout")`
@@ -285,6 +293,11 @@ macro_rules! auth_post {
                     .header("Authorization", format!("Bearer {token}"))
                     .build()
                     .then_send(|result| {
+                        if $crate::is_session_expired(&result) {
+                            return $crate::events::Event::Auth(
+                                $crate::events::AuthEvent::SessionExpired,
+                            );
+                        }
                         let event_result = $crate::process_status_response($action, result);
                         $crate::events::Event::$domain(
                             $crate::events::$domain_event::$response_event(event_result),
@@ -308,6 +321,11 @@ macro_rules! auth_post {
                 Ok(builder) => crux_core::Command::all([
                     crux_core::render::render(),
                     builder.build().then_send(|result| {
+                        if $crate::is_session_expired(&result) {
+                            return $crate::events::Event::Auth(
+                                $crate::events::AuthEvent::SessionExpired,
+                            );
+                        }
                         let event_result = $crate::process_status_response($action, result);
                         $crate::events::Event::$domain(
                             $crate::events::$domain_event::$response_event(event_result),
@@ -333,6 +351,11 @@ macro_rules! auth_post {
                     .body_string($body)
                     .build()
                     .then_send(|result| {
+                        if $crate::is_session_expired(&result) {
+                            return $crate::events::Event::Auth(
+                                $crate::events::AuthEvent::SessionExpired,
+                            );
+                        }
                         let event_result = $crate::process_status_response($action, result);
                         $crate::events::Event::$domain(
                             $crate::events::$domain_event::$response_event(event_result),
@@ -356,6 +379,11 @@ macro_rules! auth_post {
                 Ok(builder) => crux_core::Command::all([
                     crux_core::render::render(),
                     builder.build().then_send(|result| {
+                        if $crate::is_session_expired(&result) {
+                            return $crate::events::Event::Auth(
+                                $crate::events::AuthEvent::SessionExpired,
+                            );
+                        }
                         let event_result: Result<$response_type, String> =
                             $crate::process_json_response($action, result);
                         $crate::events::Event::$domain(
@@ -382,6 +410,38 @@ macro_rules! auth_post {
                     .body_string($body)
                     .build()
                     .then_send(|result| {
+                        if $crate::is_session_expired(&result) {
+                            return $crate::events::Event::Auth(
+                                $crate::events::AuthEvent::SessionExpired,
+                            );
+                        }
+                        let event_result: Result<$response_type, String> =
+                            $crate::process_json_response($action, result);
+                        $crate::events::Event::$domain(
+                            $crate::events::$domain_event::$response_event(event_result),
+                        )
+                    }),
+            ])
+        } else {
+            $crate::handle_auth_error($model, $action)
+        }
+    }};
+
+    // Pattern 6: POST without body expecting JSON response
+    ($domain:ident, $domain_event:ident, $model:expr, $endpoint:expr, $response_event:ident, $action:expr, expect_json: $response_type:ty) => {{
+        $model.start_loading();
+        if let Some(token) = &$model.auth_token {
+            crux_core::Command::all([
+                crux_core::render::render(),
+                $crate::HttpCmd::post($crate::build_url($endpoint))
+                    .header("Authorization", format!("Bearer {token}"))
+                    .build()
+                    .then_send(|result| {
+                        if $crate::is_session_expired(&result) {
+                            return $crate::events::Event::Auth(
+                                $crate::events::AuthEvent::SessionExpired,
+                            );
+                        }
                         let event_result: Result<$response_type, String> =
                             $crate::process_json_response($action, result);
                         $crate::events::Event::$domain(
@@ -533,7 +593,7 @@ macro_rules! handle_response {
         $model.stop_loading();
         match $result {
             Ok(()) => {
-                $model.success_message = Some($msg.to_string());
+                $model.set_success($msg);
             }
             Err(e) => {
                 $model.set_error(e);
@@ -571,7 +631,7 @@ macro_rules! handle_response {
                 #[allow(clippy::redundant_locals)]
                 let $success_model = $model;
                 $success_body
-                $model.success_message = Some($msg.to_string());
+                $model.set_success($msg);
             }
             Err(e) => {
                 $model.set_error(e);
@@ -593,7 +653,7 @@ macro_rules! handle_response {
                 $success_body
             }
             Err(e) => {
-                $model.error_message = Some(e);
+                $model.set_error_message(e);
             }
         }
         crux_core::render::render()
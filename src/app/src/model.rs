@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::types::*;
 
@@ -29,22 +30,48 @@ pub struct Model {
     pub auth_token: Option<String>,
     pub is_authenticated: bool,
     pub requires_password_set: bool,
+    pub auth_bootstrap: AuthBootstrapState,
+    /// Set when an authenticated request was rejected and the user has to log in again
+    pub session_expired: bool,
+    /// Set when a factory reset was rejected for lacking the password, the
+    /// shell then asks for it and repeats the request with it
+    pub factory_reset_password_required: bool,
 
     // UI state
     pub is_loading: bool,
+    /// Set while a manual refresh is in flight, further refreshes are ignored
+    pub is_refreshing: bool,
     pub error_message: Option<String>,
     pub success_message: Option<String>,
+    /// Bumped whenever an error or success message is set, so repeated identical
+    /// messages can be detected by the shell
+    pub message_seq: u32,
 
     // WebSocket state
     pub is_connected: bool,
+    /// Why the last connection attempt failed, cleared once connected
+    pub websocket_error: Option<WebSocketError>,
+
+    // Bootstrap state (initial config/version requests)
+    pub bootstrap_state: BootstrapState,
+    pub backend_version: Option<String>,
 
     // Device operation state (reboot/factory reset reconnection)
     pub device_operation_state: DeviceOperationState,
     pub reconnection_attempt: u32,
     pub device_went_offline: bool,
 
+    // Connection loss outside device operations (e.g. device crashed)
+    pub healthcheck_failures: u32,
+    pub connection_lost: bool,
+
     // Network change state (IP change detection and polling)
     pub network_change_state: NetworkChangeState,
+    /// Time to wait for the new IP, measured from the first new IP check tick
+    pub new_ip_timeout_secs: Option<u64>,
+    /// Timestamp of the first new IP check tick in milliseconds since the epoch
+    #[serde(skip)]
+    pub new_ip_check_start_ms: Option<u64>,
 
     // Network form state
     pub network_form_state: NetworkFormState,
@@ -52,6 +79,13 @@ pub struct Model {
     // Network form dirty flag (tracks unsaved changes)
     pub network_form_dirty: bool,
 
+    // Unsaved edits of all adapters except the active one, keyed by adapter name
+    pub network_forms: HashMap<String, AdapterFormState>,
+    /// Fields of the active network form differing from the adapter data,
+    /// the rollback modal flags are only recomputed if these change
+    #[serde(skip)]
+    pub network_form_changed_fields: Vec<&'static str>,
+
     // Browser hostname
     pub browser_hostname: Option<String>,
 
@@ -64,6 +98,32 @@ pub struct Model {
 
     // Firmware upload state
     pub firmware_upload_state: UploadState,
+    /// First progress sample of the running upload, reference for its rate
+    #[serde(skip)]
+    pub firmware_upload_start: Option<UploadProgressSample>,
+
+    /// Update validation statuses meaning an update is complete, provided by the
+    /// backend config, `None` uses the defaults
+    #[serde(skip)]
+    pub update_complete_statuses: Option<Vec<String>>,
+    /// Name patterns of the adapters shown, provided by the backend config
+    #[serde(skip)]
+    pub network_interface_filter: NetworkInterfaceFilter,
+
+    // Advanced mode unlocking dangerous actions (persisted by the shell)
+    pub advanced_mode: bool,
+
+    // Results awaiting acknowledgment (computed in view)
+    pub pending_acknowledgments: Vec<PendingAcknowledgment>,
+
+    // Device service doesn't satisfy the required version (computed in view)
+    pub device_service_outdated: bool,
+
+    // Any transient message is shown (computed in view)
+    pub has_notifications: bool,
+
+    // Adapter being edited serves the UI (computed in view)
+    pub editing_current_adapter: bool,
 
     // Overlay spinner state
     pub overlay_spinner: OverlaySpinnerState,
@@ -74,6 +134,11 @@ impl Model {
     pub fn invalidate_session(&mut self) {
         self.is_authenticated = false;
         self.auth_token = None;
+        // a pending refresh is answered by SessionExpired, never by RefreshResponse
+        self.is_refreshing = false;
+        if self.auth_bootstrap == AuthBootstrapState::Authenticated {
+            self.auth_bootstrap = AuthBootstrapState::RequiresLogin;
+        }
     }
 
     /// Start a loading operation (sets is_loading=true, clears error)
@@ -91,7 +156,19 @@ impl Model {
     /// Set an error message and stop loading
     pub fn set_error(&mut self, error: String) {
         self.is_loading = false;
+        self.set_error_message(error);
+    }
+
+    /// Set an error message without affecting the loading state
+    pub fn set_error_message(&mut self, error: String) {
         self.error_message = Some(error);
+        self.message_seq = self.message_seq.wrapping_add(1);
+    }
+
+    /// Set a success message
+    pub fn set_success(&mut self, message: impl Into<String>) {
+        self.success_message = Some(message.into());
+        self.message_seq = self.message_seq.wrapping_add(1);
     }
 
     /// Set an error message, stop loading, and return a render command
@@ -111,6 +188,41 @@ impl Model {
         self.error_message = None;
     }
 
+    /// Move unsaved edits of the active adapter to the staged adapter forms
+    ///
+    /// Clean forms are dropped, editing them again starts from the current
+    /// network status anyway.
+    pub fn stage_network_form(&mut self) {
+        if let NetworkFormState::Editing {
+            adapter_name,
+            form_data,
+            original_data,
+            errors,
+        } = &self.network_form_state
+        {
+            if self.network_form_dirty {
+                self.network_forms.insert(
+                    adapter_name.clone(),
+                    AdapterFormState {
+                        form_data: form_data.clone(),
+                        original_data: original_data.clone(),
+                        errors: errors.clone(),
+                        dirty: true,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Reset all network form related state to a clean slate
+    pub fn reset_network_form(&mut self) {
+        self.network_form_state = NetworkFormState::Idle;
+        self.network_form_dirty = false;
+        self.network_form_changed_fields.clear();
+        self.should_show_rollback_modal = false;
+        self.default_rollback_enabled = false;
+    }
+
     /// Update current connection adapter based on browser_hostname and network_status
     pub fn update_current_connection_adapter(&mut self) {
         self.current_connection_adapter = self
@@ -120,6 +232,62 @@ impl Model {
             .map(|adapter| adapter.name.clone());
     }
 
+    /// Results awaiting acknowledgment by the operator
+    ///
+    /// Lets the shell show a single badge instead of checking the rollback,
+    /// update validation and factory reset fields separately.
+    pub fn pending_acknowledgments(&self) -> Vec<PendingAcknowledgment> {
+        let healthcheck = self.healthcheck.as_ref();
+        let mut pending = Vec::new();
+
+        if healthcheck.is_some_and(|h| h.network_rollback_occurred) {
+            pending.push(PendingAcknowledgment::NetworkRollback);
+        }
+
+        let update_validated = self
+            .update_validation_status
+            .as_ref()
+            .is_some_and(|s| s.is_update_result(self.update_complete_statuses.as_deref()));
+        if update_validated && healthcheck.is_some_and(|h| !h.update_validation_acked) {
+            pending.push(PendingAcknowledgment::UpdateValidation);
+        }
+
+        let factory_reset_finished = self
+            .factory_reset
+            .as_ref()
+            .and_then(|f| f.result.as_ref())
+            .is_some_and(|r| r.status != FactoryResetStatus::Unknown);
+        if factory_reset_finished && healthcheck.is_some_and(|h| !h.factory_reset_result_acked) {
+            pending.push(PendingAcknowledgment::FactoryResetResult);
+        }
+
+        pending
+    }
+
+    /// Whether a success or error message is waiting to be shown or dismissed
+    pub fn has_notifications(&self) -> bool {
+        self.success_message.is_some() || self.error_message.is_some()
+    }
+
+    /// Whether the device service doesn't satisfy the version required by the backend
+    ///
+    /// False as long as no healthcheck was received.
+    pub fn device_service_outdated(&self) -> bool {
+        self.healthcheck
+            .as_ref()
+            .is_some_and(|h| h.version_info.mismatch)
+    }
+
+    /// Whether the adapter being edited is the one the UI is served on
+    ///
+    /// Changing it may cut the connection, so the shell shows the rollback warning.
+    pub fn editing_current_adapter(&self) -> bool {
+        match &self.network_form_state {
+            NetworkFormState::Editing { adapter_name, .. } => self.is_current_adapter(adapter_name),
+            _ => false,
+        }
+    }
+
     /// Check if the given adapter name matches the current connection adapter
     pub fn is_current_adapter(&self, name: &str) -> bool {
         self.current_connection_adapter
@@ -134,3 +302,293 @@ impl ModelErrorHandler for Model {
         Model::set_error(self, error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_success_messages_bump_sequence_each_time() {
+        let mut model = Model::default();
+
+        model.set_success("Saved");
+        model.set_success("Saved");
+
+        assert_eq!(model.success_message, Some("Saved".to_string()));
+        assert_eq!(model.message_seq, 2);
+    }
+
+    #[test]
+    fn errors_bump_sequence() {
+        let mut model = Model {
+            is_loading: true,
+            ..Default::default()
+        };
+
+        model.set_error("failed".to_string());
+        model.set_error_message("failed".to_string());
+
+        assert_eq!(model.error_message, Some("failed".to_string()));
+        assert!(!model.is_loading);
+        assert_eq!(model.message_seq, 2);
+    }
+
+    #[test]
+    fn clearing_messages_keeps_sequence() {
+        let mut model = Model::default();
+        model.set_error("failed".to_string());
+
+        model.clear_error();
+
+        assert_eq!(model.message_seq, 1);
+    }
+
+    mod pending_acknowledgments {
+        use super::*;
+
+        fn healthcheck(rollback_occurred: bool, update_validation_acked: bool) -> HealthcheckInfo {
+            HealthcheckInfo {
+                network_rollback_occurred: rollback_occurred,
+                update_validation_acked,
+                factory_reset_result_acked: true,
+                ..Default::default()
+            }
+        }
+
+        fn update_validation(status: &str) -> Option<UpdateValidationStatus> {
+            Some(UpdateValidationStatus {
+                status: status.to_string(),
+            })
+        }
+
+        #[test]
+        fn none_pending_by_default() {
+            assert!(Model::default().pending_acknowledgments().is_empty());
+        }
+
+        #[test]
+        fn counts_rollback_notice() {
+            let model = Model {
+                healthcheck: Some(healthcheck(true, true)),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                model.pending_acknowledgments(),
+                vec![PendingAcknowledgment::NetworkRollback]
+            );
+        }
+
+        #[test]
+        fn counts_rollback_notice_and_update_validation_result() {
+            let model = Model {
+                healthcheck: Some(healthcheck(true, false)),
+                update_validation_status: update_validation("Recovered"),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                model.pending_acknowledgments(),
+                vec![
+                    PendingAcknowledgment::NetworkRollback,
+                    PendingAcknowledgment::UpdateValidation
+                ]
+            );
+        }
+
+        #[test]
+        fn acknowledged_update_validation_is_not_counted() {
+            let model = Model {
+                healthcheck: Some(healthcheck(true, true)),
+                update_validation_status: update_validation("Succeeded"),
+                ..Default::default()
+            };
+
+            assert_eq!(model.pending_acknowledgments().len(), 1);
+        }
+
+        #[test]
+        fn update_validation_in_progress_is_not_counted() {
+            let model = Model {
+                healthcheck: Some(healthcheck(false, false)),
+                update_validation_status: update_validation("ValidatingTrial"),
+                ..Default::default()
+            };
+
+            assert!(model.pending_acknowledgments().is_empty());
+        }
+
+        #[test]
+        fn no_update_is_not_counted() {
+            let model = Model {
+                healthcheck: Some(healthcheck(false, false)),
+                update_validation_status: update_validation("NoUpdate"),
+                ..Default::default()
+            };
+
+            assert!(model.pending_acknowledgments().is_empty());
+        }
+
+        #[test]
+        fn counts_configured_update_complete_status() {
+            let model = Model {
+                healthcheck: Some(healthcheck(false, false)),
+                update_validation_status: update_validation("SucceededWithWarnings"),
+                update_complete_statuses: Some(vec![
+                    "SucceededWithWarnings".to_string(),
+                    "NoUpdate".to_string(),
+                ]),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                model.pending_acknowledgments(),
+                vec![PendingAcknowledgment::UpdateValidation]
+            );
+        }
+
+        #[test]
+        fn counts_unacknowledged_factory_reset_result() {
+            let model = Model {
+                healthcheck: Some(HealthcheckInfo {
+                    factory_reset_result_acked: false,
+                    ..healthcheck(false, true)
+                }),
+                factory_reset: Some(FactoryReset {
+                    keys: vec![],
+                    result: Some(FactoryResetResult {
+                        status: FactoryResetStatus::ModeSupported,
+                        context: None,
+                        error: String::new(),
+                        paths: vec![],
+                    }),
+                }),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                model.pending_acknowledgments(),
+                vec![PendingAcknowledgment::FactoryResetResult]
+            );
+        }
+
+        #[test]
+        fn is_reflected_in_view_model() {
+            use crux_core::App as _;
+
+            let model = Model {
+                healthcheck: Some(healthcheck(true, false)),
+                update_validation_status: update_validation("Succeeded"),
+                ..Default::default()
+            };
+
+            assert_eq!(crate::App.view(&model).pending_acknowledgments.len(), 2);
+        }
+    }
+    mod device_service_outdated {
+        use super::*;
+
+        fn healthcheck(mismatch: bool) -> HealthcheckInfo {
+            HealthcheckInfo {
+                version_info: VersionInfo {
+                    required: ">=0.41.0".to_string(),
+                    current: if mismatch { "0.40.0" } else { "0.41.0" }.to_string(),
+                    mismatch,
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn false_without_healthcheck() {
+            assert!(!Model::default().device_service_outdated());
+        }
+
+        #[test]
+        fn true_for_mismatching_version() {
+            let model = Model {
+                healthcheck: Some(healthcheck(true)),
+                ..Default::default()
+            };
+
+            assert!(model.device_service_outdated());
+        }
+
+        #[test]
+        fn false_for_matching_version() {
+            let model = Model {
+                healthcheck: Some(healthcheck(false)),
+                ..Default::default()
+            };
+
+            assert!(!model.device_service_outdated());
+        }
+
+        #[test]
+        fn is_reflected_in_view_model() {
+            use crux_core::App as _;
+
+            let model = Model {
+                healthcheck: Some(healthcheck(true)),
+                ..Default::default()
+            };
+
+            assert!(crate::App.view(&model).device_service_outdated);
+        }
+    }
+
+    mod editing_current_adapter {
+        use super::*;
+
+        fn editing(adapter_name: &str) -> Model {
+            Model {
+                current_connection_adapter: Some("eth0".to_string()),
+                network_form_state: NetworkFormState::Editing {
+                    adapter_name: adapter_name.to_string(),
+                    form_data: NetworkFormData::default(),
+                    original_data: NetworkFormData::default(),
+                    errors: HashMap::new(),
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn true_for_serving_adapter() {
+            assert!(editing("eth0").editing_current_adapter());
+        }
+
+        #[test]
+        fn false_for_other_adapter() {
+            assert!(!editing("wlan0").editing_current_adapter());
+        }
+
+        #[test]
+        fn false_without_known_serving_adapter() {
+            let model = Model {
+                current_connection_adapter: None,
+                ..editing("eth0")
+            };
+
+            assert!(!model.editing_current_adapter());
+        }
+
+        #[test]
+        fn false_while_not_editing() {
+            let model = Model {
+                network_form_state: NetworkFormState::Idle,
+                ..editing("eth0")
+            };
+
+            assert!(!model.editing_current_adapter());
+        }
+
+        #[test]
+        fn is_reflected_in_view_model() {
+            use crux_core::App as _;
+
+            assert!(crate::App.view(&editing("eth0")).editing_current_adapter);
+            assert!(!crate::App.view(&editing("wlan0")).editing_current_adapter);
+        }
+    }
+}
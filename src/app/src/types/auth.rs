@@ -1,27 +1,66 @@
 use serde::{Deserialize, Serialize};
 use serde_valid::Validate;
+use std::fmt;
+
+use super::REDACTED;
 
 /// Authentication token returned from login
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AuthToken {
     pub token: String,
 }
 
+impl fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthToken")
+            .field("token", &REDACTED)
+            .finish()
+    }
+}
+
+/// Which authentication screen the shell has to show
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthBootstrapState {
+    /// Waiting for the backend to report whether a password is set
+    #[default]
+    Checking,
+    RequiresSetPassword,
+    RequiresLogin,
+    Authenticated,
+}
+
 /// Login credentials
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct LoginCredentials {
     pub password: String,
 }
 
+impl fmt::Debug for LoginCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoginCredentials")
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
 /// Request to set initial password
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Validate)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Validate)]
 pub struct SetPasswordRequest {
     #[validate(min_length = 1)]
     pub password: String,
 }
 
+impl fmt::Debug for SetPasswordRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetPasswordRequest")
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
 /// Request to update existing password
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Validate)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdatePasswordRequest {
     #[validate(min_length = 1)]
@@ -29,3 +68,37 @@ pub struct UpdatePasswordRequest {
     #[validate(min_length = 1)]
     pub password: String,
 }
+
+impl fmt::Debug for UpdatePasswordRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdatePasswordRequest")
+            .field("current_password", &REDACTED)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
+/// Follow-up the client has to perform after a successful request
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ClientAction {
+    /// The session was ended, the client has to drop its token and log in again
+    Reauthenticate,
+}
+
+/// Response of a successful password update
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpdatePasswordResult {
+    pub action: ClientAction,
+}
+
+/// Password rules enforced when setting or updating the password
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_special: bool,
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+/// Placeholder for secrets in `Debug` output, which ends up in logs
+pub const REDACTED: &str = "<redacted>";
+
 /// Operating system information
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -41,6 +44,99 @@ pub struct Timeouts {
     pub wait_online_timeout: Duration,
 }
 
+/// Progress of the initial backend requests (config and version) on app start
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BootstrapState {
+    #[default]
+    Idle,
+    Loading {
+        attempt: u32,
+    },
+    WaitingRetry {
+        attempt: u32,
+        delay_ms: u32,
+    },
+    Completed,
+    Failed {
+        reason: String,
+    },
+}
+
+/// Frontend config loaded from `config.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct FrontendConfig {
+    pub keycloak_url: Option<String>,
+    pub base_path: Option<String>,
+    pub update_complete_statuses: Option<Vec<String>>,
+    pub network_interface_include: Option<String>,
+    pub network_interface_exclude: Option<String>,
+}
+
+/// Device result awaiting acknowledgment by the operator
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PendingAcknowledgment {
+    NetworkRollback,
+    UpdateValidation,
+    FactoryResetResult,
+}
+
+/// Centrifugo error codes of a rejected token: unauthorized, permission denied and token expired
+const AUTH_FAILURE_ERROR_CODES: [u32; 3] = [101, 103, 109];
+/// Centrifugo disconnect codes of a rejected token: invalid token and permission denied
+const AUTH_FAILURE_DISCONNECT_CODES: [u32; 2] = [3500, 3507];
+
+/// Reason the WebSocket connection to centrifugo failed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WebSocketError {
+    /// Centrifugo rejected the token, the user has to log in again
+    AuthenticationFailed,
+    /// The connection could not be established or broke
+    ConnectionLost,
+}
+
+impl WebSocketError {
+    /// Classify the error or disconnect code reported by the centrifugo client
+    ///
+    /// Failures without a code, e.g. of the transport, are a lost connection.
+    pub fn from_code(code: Option<u32>) -> Self {
+        match code {
+            Some(code)
+                if AUTH_FAILURE_ERROR_CODES.contains(&code)
+                    || AUTH_FAILURE_DISCONNECT_CODES.contains(&code) =>
+            {
+                Self::AuthenticationFailed
+            }
+            _ => Self::ConnectionLost,
+        }
+    }
+}
+
+impl std::fmt::Display for WebSocketError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AuthenticationFailed => write!(f, "Authentication failed, please log in again"),
+            Self::ConnectionLost => write!(f, "Connection lost"),
+        }
+    }
+}
+
+/// Widget the overlay spinner is rendered with
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlaySpinnerMode {
+    /// Plain spinner without known progress
+    #[default]
+    Indeterminate,
+    /// Progress in percent
+    Progress(u8),
+    /// Seconds remaining
+    Countdown(u32),
+}
+
 /// Overlay spinner state (UI state)
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -51,6 +147,8 @@ pub struct OverlaySpinnerState {
     timed_out: bool,
     progress: Option<u8>,
     countdown_seconds: Option<u32>,
+    mode: OverlaySpinnerMode,
+    estimated_seconds_remaining: Option<u32>,
 }
 
 impl OverlaySpinnerState {
@@ -63,6 +161,8 @@ impl OverlaySpinnerState {
             timed_out: false,
             progress: None,
             countdown_seconds: None,
+            mode: OverlaySpinnerMode::Indeterminate,
+            estimated_seconds_remaining: None,
         }
     }
 
@@ -74,13 +174,13 @@ impl OverlaySpinnerState {
 
     /// Builder pattern: add optional progress to the spinner
     pub fn with_progress(mut self, progress: u8) -> Self {
-        self.progress = Some(progress);
+        self.set_progress(progress);
         self
     }
 
     /// Builder pattern: add optional countdown to the spinner
     pub fn with_countdown(mut self, seconds: u32) -> Self {
-        self.countdown_seconds = Some(seconds);
+        self.set_countdown(seconds);
         self
     }
 
@@ -92,11 +192,18 @@ impl OverlaySpinnerState {
     /// Update the optional progress
     pub fn set_progress(&mut self, progress: u8) {
         self.progress = Some(progress);
+        self.mode = OverlaySpinnerMode::Progress(progress);
     }
 
     /// Update the optional countdown
     pub fn set_countdown(&mut self, seconds: u32) {
         self.countdown_seconds = Some(seconds);
+        self.mode = OverlaySpinnerMode::Countdown(seconds);
+    }
+
+    /// Update the estimated time until the operation completes, None while unknown
+    pub fn set_estimated_seconds_remaining(&mut self, seconds: Option<u32>) {
+        self.estimated_seconds_remaining = seconds;
     }
 
     /// Mark the spinner as timed out
@@ -108,6 +215,10 @@ impl OverlaySpinnerState {
     pub fn set_loading(&mut self) {
         self.timed_out = false;
         self.countdown_seconds = None;
+        self.mode = self.progress.map_or(
+            OverlaySpinnerMode::Indeterminate,
+            OverlaySpinnerMode::Progress,
+        );
     }
 
     /// Show the overlay spinner
@@ -159,4 +270,98 @@ impl OverlaySpinnerState {
     pub fn countdown_seconds(&self) -> Option<u32> {
         self.countdown_seconds
     }
+
+    /// Get the optional estimated seconds until the operation completes
+    pub fn estimated_seconds_remaining(&self) -> Option<u32> {
+        self.estimated_seconds_remaining
+    }
+
+    /// Get the widget the spinner is rendered with
+    pub fn mode(&self) -> OverlaySpinnerMode {
+        self.mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod overlay_spinner_mode {
+        use super::*;
+
+        #[test]
+        fn new_spinner_is_indeterminate() {
+            let spinner = OverlaySpinnerState::new("Loading");
+
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Indeterminate);
+        }
+
+        #[test]
+        fn progress_setters_select_progress() {
+            assert_eq!(
+                OverlaySpinnerState::new("Uploading")
+                    .with_progress(30)
+                    .mode(),
+                OverlaySpinnerMode::Progress(30)
+            );
+
+            let mut spinner = OverlaySpinnerState::new("Uploading");
+            spinner.set_progress(42);
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Progress(42));
+        }
+
+        #[test]
+        fn countdown_setters_select_countdown() {
+            assert_eq!(
+                OverlaySpinnerState::new("Rebooting")
+                    .with_countdown(90)
+                    .mode(),
+                OverlaySpinnerMode::Countdown(90)
+            );
+
+            let mut spinner = OverlaySpinnerState::new("Rebooting");
+            spinner.set_countdown(60);
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Countdown(60));
+        }
+
+        #[test]
+        fn last_setter_wins() {
+            let mut spinner = OverlaySpinnerState::new("Updating").with_countdown(90);
+            spinner.set_progress(10);
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Progress(10));
+
+            spinner.set_countdown(80);
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Countdown(80));
+        }
+
+        #[test]
+        fn set_loading_drops_countdown() {
+            let mut spinner = OverlaySpinnerState::new("Applying").with_countdown(90);
+            spinner.set_loading();
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Indeterminate);
+
+            let mut spinner = OverlaySpinnerState::new("Updating")
+                .with_progress(50)
+                .with_countdown(90);
+            spinner.set_loading();
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Progress(50));
+        }
+
+        #[test]
+        fn clear_resets_to_indeterminate() {
+            let mut spinner = OverlaySpinnerState::new("Uploading").with_progress(30);
+            spinner.clear();
+
+            assert_eq!(spinner.mode(), OverlaySpinnerMode::Indeterminate);
+        }
+
+        #[test]
+        fn serializes_mode_in_camel_case() {
+            let json =
+                serde_json::to_value(OverlaySpinnerState::new("Uploading").with_progress(30))
+                    .unwrap();
+
+            assert_eq!(json["mode"], serde_json::json!({ "progress": 30 }));
+        }
+    }
 }
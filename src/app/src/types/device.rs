@@ -1,5 +1,50 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
+/// Long-running device operation that is followed by a reconnection
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceOperation {
+    Reboot,
+    FactoryReset,
+    Update,
+}
+
+impl fmt::Display for DeviceOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reboot => write!(f, "Reboot"),
+            Self::FactoryReset => write!(f, "Factory Reset"),
+            Self::Update => write!(f, "Update"),
+        }
+    }
+}
+
+/// Structured error reported by the device service, e.g. `{"code": "E42", "message": "..."}`
+///
+/// Passed on by the backend as error body, so the device's error code can be shown.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeviceServiceError {
+    pub code: String,
+    pub message: String,
+}
+
+impl DeviceServiceError {
+    /// Parse an error body, None if it is no structured device service error
+    pub fn from_body(body: &str) -> Option<Self> {
+        serde_json::from_str(body).ok()
+    }
+}
+
+impl fmt::Display for DeviceServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (error code {})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for DeviceServiceError {}
+
 /// State of long-running device operations (reboot, factory reset, update)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
@@ -10,28 +55,84 @@ pub enum DeviceOperationState {
     FactoryResetting,
     Updating,
     WaitingReconnection {
-        operation: String,
+        operation: DeviceOperation,
         attempt: u32,
     },
     ReconnectionFailed {
-        operation: String,
+        operation: DeviceOperation,
         reason: String,
     },
     ReconnectionSuccessful {
-        operation: String,
+        operation: DeviceOperation,
     },
 }
 
 impl DeviceOperationState {
-    pub fn operation_name(&self) -> String {
+    /// Initial state after the given operation has been triggered
+    pub fn started(operation: DeviceOperation) -> Self {
+        match operation {
+            DeviceOperation::Reboot => Self::Rebooting,
+            DeviceOperation::FactoryReset => Self::FactoryResetting,
+            DeviceOperation::Update => Self::Updating,
+        }
+    }
+
+    /// Operation that is still waiting for the device to come back, if any
+    pub fn pending_operation(&self) -> Option<DeviceOperation> {
         match self {
-            Self::Rebooting => "Reboot".to_string(),
-            Self::FactoryResetting => "Factory Reset".to_string(),
-            Self::Updating => "Update".to_string(),
-            Self::WaitingReconnection { operation, .. }
-            | Self::ReconnectionFailed { operation, .. }
-            | Self::ReconnectionSuccessful { operation } => operation.clone(),
-            Self::Idle => "Unknown".to_string(),
+            Self::Rebooting => Some(DeviceOperation::Reboot),
+            Self::FactoryResetting => Some(DeviceOperation::FactoryReset),
+            Self::Updating => Some(DeviceOperation::Update),
+            Self::WaitingReconnection { operation, .. } => Some(*operation),
+            Self::Idle | Self::ReconnectionFailed { .. } | Self::ReconnectionSuccessful { .. } => {
+                None
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_uses_human_readable_names() {
+        assert_eq!(DeviceOperation::Reboot.to_string(), "Reboot");
+        assert_eq!(DeviceOperation::FactoryReset.to_string(), "Factory Reset");
+        assert_eq!(DeviceOperation::Update.to_string(), "Update");
+    }
+
+    #[test]
+    fn started_state_maps_back_to_operation() {
+        for operation in [
+            DeviceOperation::Reboot,
+            DeviceOperation::FactoryReset,
+            DeviceOperation::Update,
+        ] {
+            assert_eq!(
+                DeviceOperationState::started(operation).pending_operation(),
+                Some(operation)
+            );
+        }
+    }
+
+    #[test]
+    fn terminal_states_have_no_pending_operation() {
+        assert_eq!(DeviceOperationState::Idle.pending_operation(), None);
+        assert_eq!(
+            DeviceOperationState::ReconnectionFailed {
+                operation: DeviceOperation::Reboot,
+                reason: "timeout".into(),
+            }
+            .pending_operation(),
+            None
+        );
+        assert_eq!(
+            DeviceOperationState::ReconnectionSuccessful {
+                operation: DeviceOperation::Update,
+            }
+            .pending_operation(),
+            None
+        );
+    }
+}
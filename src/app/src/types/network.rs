@@ -1,7 +1,10 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_valid::Validate;
 use std::collections::HashMap;
 
+use super::REDACTED;
+
 /// Validate IPv4 address format
 pub fn is_valid_ipv4(ip: &str) -> bool {
     if ip.is_empty() {
@@ -23,7 +26,9 @@ pub fn is_valid_ipv4(ip: &str) -> bool {
 }
 
 /// Convert CIDR prefix length to Dotted Decimal Subnet Mask
-pub fn cidr_to_subnet(cidr: u32) -> String {
+///
+/// Returns an empty string for prefix lengths above 32.
+pub fn cidr_to_subnet_mask(cidr: u32) -> String {
     if cidr > 32 {
         return "".to_string();
     }
@@ -89,6 +94,9 @@ pub struct IpAddress {
     pub addr: String,
     pub dhcp: bool,
     pub prefix_len: u32,
+    /// `prefix_len` as dotted decimal subnet mask, e.g. `255.255.255.0` for 24
+    #[serde(default)]
+    pub subnet_mask: String,
 }
 
 /// Internet protocol configuration (IPv4/IPv6)
@@ -110,6 +118,12 @@ pub struct DeviceNetwork {
     pub online: bool,
     #[serde(default)]
     pub file: Option<String>,
+    /// Whether a link is detected, `Some(false)` means e.g. an unplugged cable,
+    /// `None` if unknown
+    ///
+    /// Distinguishes an adapter without link from one that is up but has no address.
+    #[serde(default)]
+    pub carrier: Option<bool>,
 }
 
 /// Network status from WebSocket
@@ -120,6 +134,21 @@ pub struct NetworkStatus {
 }
 
 impl NetworkStatus {
+    /// Sort adapters by name and drop duplicate entries
+    ///
+    /// The device service reports adapters in arbitrary order and may list an
+    /// adapter twice after a hot-plug event. Of duplicates the online entry with
+    /// the most addresses is kept.
+    pub fn normalize(&mut self) {
+        self.network_status.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| b.online.cmp(&a.online))
+                .then_with(|| b.ipv4.addrs.len().cmp(&a.ipv4.addrs.len()))
+        });
+        self.network_status.dedup_by(|a, b| a.name == b.name);
+    }
+
     /// Determine which adapter is the current connection based on browser hostname
     pub fn current_connection_adapter(
         &self,
@@ -156,6 +185,66 @@ impl NetworkStatus {
     }
 }
 
+/// Name patterns of the adapters shown, configured by the backend
+///
+/// The device service publishes all adapters, virtual ones like `docker0` or
+/// `veth*` are hidden by the backend's default exclude pattern.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkInterfaceFilter {
+    /// Only adapters whose name matches are kept (all adapters if unset)
+    pub include: Option<String>,
+    /// Adapters whose name matches are dropped, applied after `include`
+    pub exclude: Option<String>,
+}
+
+impl NetworkInterfaceFilter {
+    /// Drop the adapters rejected by the filter
+    ///
+    /// The backend validates the patterns on startup, an invalid pattern is
+    /// ignored rather than hiding all adapters.
+    pub fn retain_allowed(&self, status: &mut NetworkStatus) {
+        let compile = |pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .filter(|p| !p.is_empty())
+                .and_then(|p| match Regex::new(p) {
+                    Ok(re) => Some(re),
+                    Err(e) => {
+                        log::warn!("Ignoring invalid network interface pattern {p}: {e}");
+                        None
+                    }
+                })
+        };
+        let include = compile(&self.include);
+        let exclude = compile(&self.exclude);
+
+        status.network_status.retain(|adapter| {
+            include.as_ref().is_none_or(|re| re.is_match(&adapter.name))
+                && !exclude
+                    .as_ref()
+                    .is_some_and(|re| re.is_match(&adapter.name))
+        });
+    }
+}
+
+/// Wifi credentials of a wireless adapter
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiConfig {
+    pub ssid: String,
+    pub psk: String,
+}
+
+// the pre-shared key must never end up in logs
+impl std::fmt::Debug for WifiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WifiConfig")
+            .field("ssid", &self.ssid)
+            .field("psk", &REDACTED)
+            .finish()
+    }
+}
+
 /// Network configuration request
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -179,6 +268,22 @@ pub struct NetworkConfigRequest {
     /// Whether this change is switching to DHCP
     #[serde(default)]
     pub switching_to_dhcp: bool,
+    /// Emitted as `IPv6AcceptRA=` in the [Network] section if set
+    #[serde(default)]
+    pub ipv6_accept_ra: Option<bool>,
+    /// Emitted as `LinkLocalAddressing=` in the [Network] section if set
+    #[serde(default)]
+    #[validate(enumerate = ["yes", "no", "ipv4", "ipv6"])]
+    pub link_local_addressing: Option<String>,
+    /// Emitted as `DNSOverTLS=` in the [Network] section if set, requires static DNS servers
+    #[serde(default)]
+    pub dns_over_tls: Option<bool>,
+    /// Emitted as `DNSSEC=` in the [Network] section if set, requires static DNS servers
+    #[serde(default)]
+    pub dnssec: Option<bool>,
+    /// Credentials written to the wpa_supplicant config of a wireless adapter
+    #[serde(default)]
+    pub wifi: Option<WifiConfig>,
 }
 
 /// Form data for network configuration
@@ -193,6 +298,22 @@ pub struct NetworkFormData {
     pub gateways: Vec<String>,
 }
 
+impl NetworkFormData {
+    /// Names of the fields differing from `other`, in form field order
+    pub fn changed_fields(&self, other: &Self) -> Vec<&'static str> {
+        [
+            ("ipAddress", self.ip_address != other.ip_address),
+            ("dhcp", self.dhcp != other.dhcp),
+            ("subnetMask", self.subnet_mask != other.subnet_mask),
+            ("dns", self.dns != other.dns),
+            ("gateways", self.gateways != other.gateways),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+}
+
 impl From<&DeviceNetwork> for NetworkFormData {
     fn from(adapter: &DeviceNetwork) -> Self {
         let addr = adapter.ipv4.addrs.first();
@@ -200,13 +321,24 @@ impl From<&DeviceNetwork> for NetworkFormData {
             name: adapter.name.clone(),
             ip_address: addr.map(|a| a.addr.clone()).unwrap_or_default(),
             dhcp: addr.map(|a| a.dhcp).unwrap_or(false),
-            subnet_mask: cidr_to_subnet(addr.map(|a| a.prefix_len).unwrap_or(24)),
+            subnet_mask: cidr_to_subnet_mask(addr.map(|a| a.prefix_len).unwrap_or(24)),
             dns: adapter.ipv4.dns.clone(),
             gateways: adapter.ipv4.gateways.clone(),
         }
     }
 }
 
+/// Unsaved edits of a network adapter which is not the active one in the UI
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterFormState {
+    pub form_data: NetworkFormData,
+    pub original_data: NetworkFormData,
+    #[serde(default)]
+    pub errors: HashMap<String, String>,
+    pub dirty: bool,
+}
+
 /// State of network form
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
@@ -273,6 +405,16 @@ impl NetworkFormState {
             None
         }
     }
+
+    /// Name of the adapter being edited or submitted
+    pub fn adapter_name(&self) -> Option<&str> {
+        match self {
+            Self::Idle => None,
+            Self::Editing { adapter_name, .. } | Self::Submitting { adapter_name, .. } => {
+                Some(adapter_name)
+            }
+        }
+    }
 }
 
 /// State machine for network IP change after configuration.
@@ -337,6 +479,8 @@ impl NetworkFormState {
 /// - **WaitingForNewIp**: Polling new IP to verify reachability before rollback timeout
 /// - **NewIpReachable**: New IP confirmed reachable, will redirect browser
 /// - **NewIpTimeout**: Timeout expired without rollback enabled, show manual nav message
+/// - **AppliedReachabilityUnknown**: Applied without rollback, but the new IP cannot be
+///   probed (e.g. healthcheck requires auth), show manual nav message right away
 /// - **WaitingForOldIp**: Rollback assumed, now polling old IP to verify device is back
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
@@ -370,6 +514,8 @@ pub enum NetworkChangeState {
         ui_port: u16,
         switching_to_dhcp: bool,
     },
+    /// Configuration applied, but the new IP cannot be probed (rollback disabled case)
+    AppliedReachabilityUnknown { new_ip: String, ui_port: u16 },
     /// Rollback assumed complete, polling old IP to verify device is accessible
     WaitingForOldIp {
         old_ip: String,
@@ -385,6 +531,18 @@ pub struct SetNetworkConfigResponse {
     pub rollback_timeout_seconds: u64,
     pub ui_port: u16,
     pub rollback_enabled: bool,
+    /// Whether the healthcheck rejects unauthenticated requests, which
+    /// prevents probing the new IP from the browser
+    #[serde(default)]
+    pub healthcheck_require_auth: bool,
+}
+
+/// Response from backend when rolling back before the deadline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackNowResponse {
+    /// Whether a rollback was pending and has been executed
+    pub rollback_executed: bool,
 }
 
 #[cfg(test)]
@@ -435,12 +593,29 @@ mod tests {
         }
 
         #[test]
-        fn cidr_to_subnet_converts_correctly() {
-            assert_eq!(cidr_to_subnet(24), "255.255.255.0");
-            assert_eq!(cidr_to_subnet(16), "255.255.0.0");
-            assert_eq!(cidr_to_subnet(8), "255.0.0.0");
-            assert_eq!(cidr_to_subnet(32), "255.255.255.255");
-            assert_eq!(cidr_to_subnet(0), "0.0.0.0");
+        fn cidr_to_subnet_mask_converts_correctly() {
+            assert_eq!(cidr_to_subnet_mask(24), "255.255.255.0");
+            assert_eq!(cidr_to_subnet_mask(16), "255.255.0.0");
+            assert_eq!(cidr_to_subnet_mask(8), "255.0.0.0");
+            assert_eq!(cidr_to_subnet_mask(32), "255.255.255.255");
+            assert_eq!(cidr_to_subnet_mask(0), "0.0.0.0");
+        }
+
+        #[test]
+        fn cidr_to_subnet_mask_handles_point_to_point_prefixes() {
+            assert_eq!(cidr_to_subnet_mask(31), "255.255.255.254");
+            assert_eq!(cidr_to_subnet_mask(32), "255.255.255.255");
+            assert_eq!(cidr_to_subnet_mask(33), "");
+        }
+
+        #[test]
+        fn subnet_mask_round_trips_for_all_prefix_lengths() {
+            for prefix_len in 0..=32 {
+                assert_eq!(
+                    subnet_to_cidr(&cidr_to_subnet_mask(prefix_len)),
+                    Some(prefix_len)
+                );
+            }
         }
 
         #[test]
@@ -450,6 +625,7 @@ mod tests {
             assert_eq!(subnet_to_cidr("255.0.0.0"), Some(8));
             assert_eq!(subnet_to_cidr("255.255.255.255"), Some(32));
             assert_eq!(subnet_to_cidr("0.0.0.0"), Some(0));
+            assert_eq!(subnet_to_cidr("255.255.255.254"), Some(31));
         }
 
         #[test]
@@ -461,6 +637,131 @@ mod tests {
         }
     }
 
+    mod normalize {
+        use super::*;
+
+        fn adapter(name: &str, online: bool, addrs: &[&str]) -> DeviceNetwork {
+            DeviceNetwork {
+                name: name.to_string(),
+                online,
+                ipv4: InternetProtocol {
+                    addrs: addrs
+                        .iter()
+                        .map(|addr| IpAddress {
+                            addr: addr.to_string(),
+                            dhcp: false,
+                            prefix_len: 24,
+                            subnet_mask: "255.255.255.0".to_string(),
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn sorts_and_deduplicates_by_name() {
+            let mut status = NetworkStatus {
+                network_status: vec![
+                    adapter("wlan0", true, &["10.0.0.2"]),
+                    adapter("eth1", false, &[]),
+                    adapter("eth0", false, &[]),
+                    adapter("eth1", true, &["192.168.2.10"]),
+                    adapter("eth0", true, &["192.168.1.10"]),
+                ],
+            };
+
+            status.normalize();
+
+            let names: Vec<&str> = status
+                .network_status
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["eth0", "eth1", "wlan0"]);
+            assert!(status.network_status.iter().all(|a| a.online));
+        }
+
+        #[test]
+        fn prefers_entry_with_most_addresses() {
+            let mut status = NetworkStatus {
+                network_status: vec![
+                    adapter("eth0", true, &["192.168.1.10"]),
+                    adapter("eth0", true, &["192.168.1.10", "192.168.1.11"]),
+                ],
+            };
+
+            status.normalize();
+
+            assert_eq!(status.network_status.len(), 1);
+            assert_eq!(status.network_status[0].ipv4.addrs.len(), 2);
+        }
+    }
+
+    mod network_interface_filter {
+        use super::*;
+
+        fn status(names: &[&str]) -> NetworkStatus {
+            NetworkStatus {
+                network_status: names
+                    .iter()
+                    .map(|name| DeviceNetwork {
+                        name: name.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            }
+        }
+
+        fn names(status: &NetworkStatus) -> Vec<&str> {
+            status
+                .network_status
+                .iter()
+                .map(|adapter| adapter.name.as_str())
+                .collect()
+        }
+
+        #[test]
+        fn default_exclude_drops_virtual_adapters() {
+            let filter = NetworkInterfaceFilter {
+                include: None,
+                exclude: Some("^(docker|veth|br-|virbr|cni|flannel)".to_string()),
+            };
+            let mut status = status(&["docker0", "eth0", "eth1", "veth1a2b3c"]);
+
+            filter.retain_allowed(&mut status);
+
+            assert_eq!(names(&status), vec!["eth0", "eth1"]);
+        }
+
+        #[test]
+        fn include_is_applied_before_exclude() {
+            let filter = NetworkInterfaceFilter {
+                include: Some("^eth".to_string()),
+                exclude: Some("^eth1$".to_string()),
+            };
+            let mut status = status(&["eth0", "eth1", "wlan0"]);
+
+            filter.retain_allowed(&mut status);
+
+            assert_eq!(names(&status), vec!["eth0"]);
+        }
+
+        #[test]
+        fn empty_and_invalid_patterns_keep_all_adapters() {
+            let filter = NetworkInterfaceFilter {
+                include: Some("(eth".to_string()),
+                exclude: Some(String::new()),
+            };
+            let mut status = status(&["docker0", "eth0"]);
+
+            filter.retain_allowed(&mut status);
+
+            assert_eq!(names(&status), vec!["docker0", "eth0"]);
+        }
+    }
+
     mod current_connection {
         use super::*;
 
@@ -470,11 +771,13 @@ mod tests {
                 mac: "00:11:22:33:44:55".to_string(),
                 online,
                 file: Some("/etc/network/interfaces".to_string()),
+                carrier: online.then_some(true),
                 ipv4: InternetProtocol {
                     addrs: vec![IpAddress {
                         addr: ip.to_string(),
                         dhcp: false,
                         prefix_len: 24,
+                        subnet_mask: "255.255.255.0".to_string(),
                     }],
                     dns: vec![],
                     gateways: vec![],
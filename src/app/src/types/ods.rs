@@ -15,9 +15,9 @@ use serde::{Deserialize, Serialize};
 use serde_repr::Deserialize_repr;
 
 use crate::types::{
-    DeviceNetwork, Duration, FactoryReset, FactoryResetResult, FactoryResetStatus,
-    InternetProtocol, IpAddress, NetworkStatus, OnlineStatus, OsInfo, SystemInfo, Timeouts,
-    UpdateValidationStatus,
+    cidr_to_subnet_mask, DeviceNetwork, Duration, FactoryReset, FactoryResetResult,
+    FactoryResetStatus, InternetProtocol, IpAddress, NetworkStatus, OnlineStatus, OsInfo,
+    SystemInfo, Timeouts, UpdateValidationStatus,
 };
 
 /// Online status update from ODS
@@ -108,6 +108,7 @@ impl From<OdsIpAddress> for IpAddress {
             addr: ods.addr,
             dhcp: ods.dhcp,
             prefix_len: ods.prefix_len,
+            subnet_mask: cidr_to_subnet_mask(ods.prefix_len),
         }
     }
 }
@@ -141,16 +142,23 @@ pub struct OdsDeviceNetwork {
     pub online: bool,
     #[serde(default)]
     pub file: Option<String>,
+    /// Link state, only reported by newer ODS versions
+    #[serde(default)]
+    pub carrier: Option<bool>,
 }
 
 impl From<OdsDeviceNetwork> for DeviceNetwork {
     fn from(ods: OdsDeviceNetwork) -> Self {
+        // an online adapter has a link, an offline one may lack a link or just an address
+        let carrier = ods.carrier.or(ods.online.then_some(true));
+
         Self {
             ipv4: ods.ipv4.into(),
             mac: ods.mac,
             name: ods.name,
             online: ods.online,
             file: ods.file,
+            carrier,
         }
     }
 }
@@ -164,9 +172,11 @@ pub struct OdsNetworkStatus {
 
 impl From<OdsNetworkStatus> for NetworkStatus {
     fn from(ods: OdsNetworkStatus) -> Self {
-        Self {
+        let mut status = Self {
             network_status: ods.network_status.into_iter().map(Into::into).collect(),
-        }
+        };
+        status.normalize();
+        status
     }
 }
 
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::{DeviceOperation, FactoryResetRequest};
+
 /// Update validation status from WebSocket
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -7,6 +9,30 @@ pub struct UpdateValidationStatus {
     pub status: String,
 }
 
+/// Update validation statuses meaning an update is complete, unless configured otherwise
+pub const DEFAULT_UPDATE_COMPLETE_STATUSES: &[&str] = &["Succeeded", "Recovered", NO_UPDATE_STATUS];
+
+/// Update validation status if no update ran
+pub const NO_UPDATE_STATUS: &str = "NoUpdate";
+
+impl UpdateValidationStatus {
+    /// Whether the status is final
+    ///
+    /// `complete_statuses` overrides [`DEFAULT_UPDATE_COMPLETE_STATUSES`] if configured.
+    pub fn is_complete(&self, complete_statuses: Option<&[String]>) -> bool {
+        match complete_statuses {
+            Some(statuses) => statuses.contains(&self.status),
+            None => DEFAULT_UPDATE_COMPLETE_STATUSES.contains(&self.status.as_str()),
+        }
+    }
+
+    /// Whether the status is the final result of an update that actually ran,
+    /// i.e. final but not [`NO_UPDATE_STATUS`]
+    pub fn is_update_result(&self, complete_statuses: Option<&[String]>) -> bool {
+        self.status != NO_UPDATE_STATUS && self.is_complete(complete_statuses)
+    }
+}
+
 /// Version information for healthcheck
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -28,6 +54,18 @@ pub struct HealthcheckInfo {
     pub factory_reset_result_acked: bool,
     #[serde(default)]
     pub update_validation_acked: bool,
+    #[serde(default)]
+    pub primary_mac: Option<String>,
+    #[serde(default)]
+    pub update_progress: Option<u8>,
+    #[serde(default)]
+    pub boot_time: Option<String>,
+    /// Device operation still in progress on the backend
+    #[serde(default)]
+    pub last_operation: Option<DeviceOperation>,
+    /// Mode and preserved keys of the last factory reset
+    #[serde(default)]
+    pub last_factory_reset: Option<FactoryResetRequest>,
 }
 
 /// Request to load update manifest
@@ -71,6 +109,46 @@ pub struct UpdateManifest {
     pub manifest_version: String,
 }
 
+/// Progress of the firmware upload at a point in time, as reported by the shell
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadProgressSample {
+    pub uploaded_bytes: u64,
+    /// Milliseconds since the epoch
+    pub timestamp_ms: u64,
+}
+
+impl UploadProgressSample {
+    /// Uploaded percentage of `total_bytes`, rounded up
+    pub fn percentage(&self, total_bytes: u64) -> u8 {
+        if total_bytes == 0 {
+            return 0;
+        }
+        let uploaded = u128::from(self.uploaded_bytes.min(total_bytes));
+        (uploaded * 100).div_ceil(u128::from(total_bytes)) as u8
+    }
+
+    /// Seconds until `total_bytes` are uploaded at the average rate since `start`
+    ///
+    /// None as long as no rate is known, i.e. nothing was uploaded or no time
+    /// passed since `start` (e.g. on the first sample).
+    pub fn estimated_seconds_remaining(&self, start: &Self, total_bytes: u64) -> Option<u32> {
+        let uploaded = self
+            .uploaded_bytes
+            .checked_sub(start.uploaded_bytes)
+            .filter(|bytes| *bytes > 0)?;
+        let elapsed_ms = self
+            .timestamp_ms
+            .checked_sub(start.timestamp_ms)
+            .filter(|ms| *ms > 0)?;
+        let remaining = total_bytes.saturating_sub(self.uploaded_bytes);
+
+        let remaining_ms =
+            (u128::from(remaining) * u128::from(elapsed_ms)).div_ceil(u128::from(uploaded));
+        Some(u32::try_from(remaining_ms.div_ceil(1000)).unwrap_or(u32::MAX))
+    }
+}
+
 /// State of the firmware upload
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -80,4 +158,6 @@ pub enum UploadState {
     Uploading,
     Completed,
     Failed(String),
+    /// Upload aborted by the user or browser, not an error
+    Cancelled,
 }
@@ -6,7 +6,10 @@ use crate::{
     events::{AuthEvent, Event},
     handle_response,
     model::Model,
-    types::{AuthToken, SetPasswordRequest, UpdatePasswordRequest},
+    types::{
+        AuthBootstrapState, AuthToken, ClientAction, SetPasswordRequest, UpdatePasswordRequest,
+        UpdatePasswordResult,
+    },
     unauth_post, Effect,
 };
 
@@ -24,9 +27,18 @@ pub fn handle(event: AuthEvent, model: &mut Model) -> Command<Effect, Event> {
             on_success: |model, auth| {
                 model.auth_token = Some(auth.token);
                 model.is_authenticated = true;
+                model.auth_bootstrap = AuthBootstrapState::Authenticated;
+                model.session_expired = false;
             },
         }),
 
+        AuthEvent::SessionExpired => {
+            model.invalidate_session();
+            model.session_expired = true;
+            model.set_error("Session expired, please log in again".into());
+            crux_core::render::render()
+        }
+
         AuthEvent::Logout => {
             auth_post!(Auth, AuthEvent, model, "/logout", LogoutResponse, "Logout")
         }
@@ -49,6 +61,7 @@ pub fn handle(event: AuthEvent, model: &mut Model) -> Command<Effect, Event> {
                 model.requires_password_set = false;
                 model.auth_token = Some(auth.token);
                 model.is_authenticated = true;
+                model.auth_bootstrap = AuthBootstrapState::Authenticated;
             },
         }),
 
@@ -61,27 +74,45 @@ pub fn handle(event: AuthEvent, model: &mut Model) -> Command<Effect, Event> {
                 password,
             };
             auth_post!(Auth, AuthEvent, model, "/update-password", UpdatePasswordResponse, "Update password",
-                body_json: &request
+                body_json: &request,
+                expect_json: UpdatePasswordResult
             )
         }
 
         AuthEvent::UpdatePasswordResponse(result) => handle_response!(model, result, {
-            on_success: |model, _| {},
+            on_success: |model, response| {
+                // the backend ended the session, the old token is no longer valid
+                if response.action == ClientAction::Reauthenticate {
+                    model.invalidate_session();
+                }
+            },
             success_message: "Password updated successfully",
         }),
 
         AuthEvent::CheckRequiresPasswordSet => {
+            if !model.is_authenticated {
+                model.auth_bootstrap = AuthBootstrapState::Checking;
+            }
             unauth_post!(Auth, AuthEvent, model, "/require-set-password", CheckRequiresPasswordSetResponse, "Check password",
                 method: get,
                 expect_json: bool
             )
         }
 
-        AuthEvent::CheckRequiresPasswordSetResponse(result) => handle_response!(model, result, {
-            on_success: |model, requires| {
-                model.requires_password_set = requires;
-            },
-        }),
+        AuthEvent::CheckRequiresPasswordSetResponse(result) => {
+            model.auth_bootstrap = match &result {
+                _ if model.is_authenticated => AuthBootstrapState::Authenticated,
+                Ok(true) => AuthBootstrapState::RequiresSetPassword,
+                // fall back to the login screen, the error is shown there
+                Ok(false) | Err(_) => AuthBootstrapState::RequiresLogin,
+            };
+
+            handle_response!(model, result, {
+                on_success: |model, requires| {
+                    model.requires_password_set = requires;
+                },
+            })
+        }
     }
 }
 
@@ -163,6 +194,87 @@ mod tests {
         }
     }
 
+    mod session_expired {
+        use super::*;
+
+        #[test]
+        fn clears_session_and_requests_login() {
+            let mut model = Model {
+                is_authenticated: true,
+                auth_token: Some("expired_token".into()),
+                is_loading: true,
+                ..Default::default()
+            };
+
+            let _ = handle(AuthEvent::SessionExpired, &mut model);
+
+            assert!(!model.is_authenticated);
+            assert!(model.auth_token.is_none());
+            assert!(model.session_expired);
+            assert!(!model.is_loading);
+            assert_eq!(
+                model.error_message,
+                Some("Session expired, please log in again".into())
+            );
+        }
+
+        #[test]
+        fn unauthorized_response_routes_to_session_expired() {
+            use crux_http::protocol::{HttpResponse, HttpResult};
+
+            let mut model = Model {
+                is_authenticated: true,
+                auth_token: Some("expired_token".into()),
+                ..Default::default()
+            };
+
+            let mut cmd = handle(
+                AuthEvent::UpdatePassword {
+                    current_password: "current".into(),
+                    password: "new".into(),
+                },
+                &mut model,
+            );
+            let mut request = cmd
+                .effects()
+                .find_map(|effect| match effect {
+                    Effect::Http(request) => Some(request),
+                    _ => None,
+                })
+                .expect("update password request");
+            request
+                .resolve(HttpResult::Ok(HttpResponse::status(401).build()))
+                .expect("resolve update password request");
+
+            let Some(Event::Auth(event @ AuthEvent::SessionExpired)) = cmd.events().next() else {
+                panic!("401 response is not routed to SessionExpired");
+            };
+            let _ = handle(event, &mut model);
+
+            assert!(model.session_expired);
+            assert!(!model.is_authenticated);
+            assert!(model.auth_token.is_none());
+        }
+
+        #[test]
+        fn successful_login_clears_session_expired() {
+            let mut model = Model {
+                session_expired: true,
+                ..Default::default()
+            };
+
+            let _ = handle(
+                AuthEvent::LoginResponse(Ok(AuthToken {
+                    token: "new_token".into(),
+                })),
+                &mut model,
+            );
+
+            assert!(!model.session_expired);
+            assert!(model.is_authenticated);
+        }
+    }
+
     mod logout {
         use super::*;
 
@@ -302,24 +414,39 @@ mod tests {
         }
 
         #[test]
-        fn success_shows_success_message() {
+        fn success_shows_success_message_and_requires_login() {
             let mut model = Model {
                 is_authenticated: true,
                 auth_token: Some("token".into()),
+                auth_bootstrap: AuthBootstrapState::Authenticated,
                 is_loading: true,
                 ..Default::default()
             };
 
-            let _ = handle(AuthEvent::UpdatePasswordResponse(Ok(())), &mut model);
+            let _ = handle(
+                AuthEvent::UpdatePasswordResponse(Ok(UpdatePasswordResult {
+                    action: ClientAction::Reauthenticate,
+                })),
+                &mut model,
+            );
 
             assert!(!model.is_loading);
             assert_eq!(
                 model.success_message,
                 Some("Password updated successfully".into())
             );
-            // Session should remain valid
-            assert!(model.is_authenticated);
-            assert!(model.auth_token.is_some());
+            // The backend purged the session, the client has to log in again
+            assert!(!model.is_authenticated);
+            assert!(model.auth_token.is_none());
+            assert_eq!(model.auth_bootstrap, AuthBootstrapState::RequiresLogin);
+        }
+
+        #[test]
+        fn parses_reauthenticate_response() {
+            let response: UpdatePasswordResult =
+                serde_json::from_str(r#"{"action":"reauthenticate"}"#).unwrap();
+
+            assert_eq!(response.action, ClientAction::Reauthenticate);
         }
 
         #[test]
@@ -408,4 +535,82 @@ mod tests {
             assert_eq!(model.error_message, Some("Server error".into()));
         }
     }
+
+    mod auth_bootstrap {
+        use super::*;
+
+        #[test]
+        fn check_starts_in_checking_state() {
+            let mut model = Model {
+                auth_bootstrap: AuthBootstrapState::RequiresLogin,
+                ..Default::default()
+            };
+
+            let _ = handle(AuthEvent::CheckRequiresPasswordSet, &mut model);
+
+            assert_eq!(model.auth_bootstrap, AuthBootstrapState::Checking);
+        }
+
+        #[test]
+        fn response_true_requires_set_password() {
+            let mut model = Model::default();
+
+            let _ = handle(
+                AuthEvent::CheckRequiresPasswordSetResponse(Ok(true)),
+                &mut model,
+            );
+
+            assert_eq!(
+                model.auth_bootstrap,
+                AuthBootstrapState::RequiresSetPassword
+            );
+        }
+
+        #[test]
+        fn response_false_requires_login() {
+            let mut model = Model::default();
+
+            let _ = handle(
+                AuthEvent::CheckRequiresPasswordSetResponse(Ok(false)),
+                &mut model,
+            );
+
+            assert_eq!(model.auth_bootstrap, AuthBootstrapState::RequiresLogin);
+        }
+
+        #[test]
+        fn response_keeps_authenticated_session() {
+            let mut model = Model {
+                is_authenticated: true,
+                auth_bootstrap: AuthBootstrapState::Authenticated,
+                ..Default::default()
+            };
+
+            let _ = handle(
+                AuthEvent::CheckRequiresPasswordSetResponse(Ok(false)),
+                &mut model,
+            );
+
+            assert_eq!(model.auth_bootstrap, AuthBootstrapState::Authenticated);
+        }
+
+        #[test]
+        fn login_and_logout_transition_state() {
+            let mut model = Model {
+                auth_bootstrap: AuthBootstrapState::RequiresLogin,
+                ..Default::default()
+            };
+
+            let _ = handle(
+                AuthEvent::LoginResponse(Ok(AuthToken {
+                    token: "token".into(),
+                })),
+                &mut model,
+            );
+            assert_eq!(model.auth_bootstrap, AuthBootstrapState::Authenticated);
+
+            let _ = handle(AuthEvent::LogoutResponse(Ok(())), &mut model);
+            assert_eq!(model.auth_bootstrap, AuthBootstrapState::RequiresLogin);
+        }
+    }
 }
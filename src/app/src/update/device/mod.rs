@@ -4,26 +4,32 @@ mod reconnection;
 
 pub use network::{
     handle_ack_factory_reset_result, handle_ack_rollback, handle_ack_update_validation,
+    handle_network_change_complete, handle_network_form_discard_all, handle_network_form_reset,
     handle_network_form_start_edit, handle_network_form_update, handle_new_ip_check_tick,
     handle_new_ip_check_timeout, handle_set_network_config, handle_set_network_config_response,
+    handle_trigger_rollback_now, handle_trigger_rollback_now_response,
 };
-pub use operations::handle_device_operation_response;
+pub use operations::{handle_device_operation_response, handle_expect_reconnection};
 pub use reconnection::{
-    handle_healthcheck_response, handle_reconnection_check_tick, handle_reconnection_timeout,
+    check_pending_operation, handle_connection_check_tick, handle_healthcheck_response,
+    handle_reconnection_check_tick, handle_reconnection_timeout, handle_resume_operation_response,
 };
 
+use base64::prelude::*;
 use crux_core::Command;
 
 use crate::{
-    auth_post,
-    events::{DeviceEvent, Event},
-    handle_response,
+    auth_post, build_url,
+    events::{AuthEvent, DeviceEvent, Event},
+    handle_auth_error, handle_request_error, handle_response, is_reauth_required,
+    is_session_expired,
     model::Model,
+    process_status_response,
     types::{
-        DeviceOperationState, FactoryResetRequest, LoadUpdateRequest, OverlaySpinnerState,
-        RunUpdateRequest, UpdateManifest, UploadState,
+        DeviceOperation, FactoryResetRequest, LoadUpdateRequest, OverlaySpinnerState,
+        RunUpdateRequest, UpdateManifest, UploadProgressSample, UploadState,
     },
-    Effect,
+    Effect, HttpCmd,
 };
 
 /// Handle device action events (reboot, factory reset, network, updates)
@@ -31,31 +37,57 @@ pub fn handle(event: DeviceEvent, model: &mut Model) -> Command<Effect, Event> {
     match event {
         DeviceEvent::UploadStarted => {
             model.firmware_upload_state = UploadState::Uploading;
+            model.firmware_upload_start = None;
             model.overlay_spinner = OverlaySpinnerState::new("Uploading firmware...")
                 .with_text("Please do not close this window.")
                 .with_progress(0);
             crux_core::render::render()
         }
 
-        DeviceEvent::UploadProgress(percentage) => {
+        DeviceEvent::UploadProgress {
+            uploaded_bytes,
+            total_bytes,
+            timestamp_ms,
+        } => {
+            let sample = UploadProgressSample {
+                uploaded_bytes,
+                timestamp_ms,
+            };
+            // the first sample has no rate yet, it's the reference for later ones
+            let start = *model.firmware_upload_start.get_or_insert(sample);
+
             model.firmware_upload_state = UploadState::Uploading;
-            model.overlay_spinner.set_progress(percentage);
+            model
+                .overlay_spinner
+                .set_progress(sample.percentage(total_bytes));
+            model.overlay_spinner.set_estimated_seconds_remaining(
+                sample.estimated_seconds_remaining(&start, total_bytes),
+            );
             crux_core::render::render()
         }
 
         DeviceEvent::UploadCompleted(_) => {
+            model.firmware_upload_start = None;
             model.firmware_upload_state = UploadState::Completed;
-            model.success_message = Some("Upload successful".to_string());
+            model.set_success("Upload successful");
             model.overlay_spinner.clear();
             crux_core::render::render()
         }
 
         DeviceEvent::UploadFailed(error) => {
+            model.firmware_upload_start = None;
             model.firmware_upload_state = UploadState::Failed(error.clone());
             model.overlay_spinner.clear();
             model.set_error_and_render(format!("Upload failed: {error}"))
         }
 
+        DeviceEvent::UploadCancelled => {
+            model.firmware_upload_start = None;
+            model.firmware_upload_state = UploadState::Cancelled;
+            model.overlay_spinner.clear();
+            crux_core::render::render()
+        }
+
         DeviceEvent::Reboot => {
             model.overlay_spinner = OverlaySpinnerState::new("Requesting device reboot...");
             auth_post!(
@@ -71,14 +103,16 @@ pub fn handle(event: DeviceEvent, model: &mut Model) -> Command<Effect, Event> {
         DeviceEvent::RebootResponse(result) => handle_device_operation_response(
             result,
             model,
-            DeviceOperationState::Rebooting,
+            DeviceOperation::Reboot,
             "Reboot initiated",
             "Reboot initiated (connection lost)",
-            "Device is rebooting",
-            None,
         ),
 
-        DeviceEvent::FactoryResetRequest { mode, preserve } => {
+        DeviceEvent::FactoryResetRequest {
+            mode,
+            preserve,
+            password,
+        } => {
             let parsed_mode = match mode.parse::<u8>() {
                 Ok(m) => m,
                 Err(e) => {
@@ -91,24 +125,31 @@ pub fn handle(event: DeviceEvent, model: &mut Model) -> Command<Effect, Event> {
                 preserve,
             };
             model.overlay_spinner = OverlaySpinnerState::new("Requesting factory reset...");
-            auth_post!(Device, DeviceEvent, model, "/factory-reset", FactoryResetResponse, "Factory reset",
-                body_json: &request
-            )
+            request_factory_reset(model, &request, password.as_deref())
         }
 
-        DeviceEvent::FactoryResetResponse(result) => handle_device_operation_response(
-            result,
-            model,
-            DeviceOperationState::FactoryResetting,
-            "Factory reset initiated",
-            "Factory reset initiated (connection lost)",
-            "The device is resetting",
-            Some(
-                "Please wait while the device resets. The app will be temporarily \
-                 removed and reinstalled automatically when the device is back online."
-                    .to_string(),
-            ),
-        ),
+        DeviceEvent::FactoryResetReauthRequired => {
+            model.overlay_spinner.clear();
+            // the shell only sends a password once it has been asked for
+            model.set_error(if model.factory_reset_password_required {
+                "Factory reset failed: incorrect password".to_string()
+            } else {
+                "Factory reset requires your password".to_string()
+            });
+            model.factory_reset_password_required = true;
+            crux_core::render::render()
+        }
+
+        DeviceEvent::FactoryResetResponse(result) => {
+            model.factory_reset_password_required = false;
+            handle_device_operation_response(
+                result,
+                model,
+                DeviceOperation::FactoryReset,
+                "Factory reset initiated",
+                "Factory reset initiated (connection lost)",
+            )
+        }
 
         DeviceEvent::SetNetworkConfig { config } => handle_set_network_config(config, model),
 
@@ -154,24 +195,41 @@ pub fn handle(event: DeviceEvent, model: &mut Model) -> Command<Effect, Event> {
         DeviceEvent::RunUpdateResponse(result) => handle_device_operation_response(
             result,
             model,
-            DeviceOperationState::Updating,
+            DeviceOperation::Update,
             "Update installed, initiating reboot...",
             "Update installed, initiating reboot (connection lost)...",
-            "Rebooting to new firmware",
-            Some("The device is restarting with the updated firmware.".to_string()),
         ),
 
         DeviceEvent::HealthcheckResponse(result) => handle_healthcheck_response(result, model),
 
+        DeviceEvent::ResumeOperationResponse(result) => {
+            handle_resume_operation_response(result, model)
+        }
+
+        DeviceEvent::ExpectReconnection { operation } => {
+            handle_expect_reconnection(model, operation)
+        }
+
         // Device reconnection events (reboot/factory reset/update)
         // Shell sends these tick events based on watching device_operation_state
         DeviceEvent::ReconnectionCheckTick => handle_reconnection_check_tick(model),
         DeviceEvent::ReconnectionTimeout => handle_reconnection_timeout(model),
 
+        // Connection monitoring outside device operations
+        // Shell sends this tick periodically while authenticated
+        DeviceEvent::ConnectionCheckTick => handle_connection_check_tick(model),
+
         // Network IP change events
         // Shell sends these tick events based on watching network_change_state
-        DeviceEvent::NewIpCheckTick => handle_new_ip_check_tick(model),
+        DeviceEvent::NewIpCheckTick { timestamp_ms } => {
+            handle_new_ip_check_tick(model, timestamp_ms)
+        }
         DeviceEvent::NewIpCheckTimeout => handle_new_ip_check_timeout(model),
+        DeviceEvent::NetworkChangeComplete => handle_network_change_complete(model),
+        DeviceEvent::TriggerRollbackNow => handle_trigger_rollback_now(model),
+        DeviceEvent::TriggerRollbackNowResponse(result) => {
+            handle_trigger_rollback_now_response(result, model)
+        }
 
         // Acknowledge events
         DeviceEvent::AckRollback => handle_ack_rollback(model),
@@ -202,8 +260,53 @@ pub fn handle(event: DeviceEvent, model: &mut Model) -> Command<Effect, Event> {
             handle_network_form_update(form_data, model)
         }
         DeviceEvent::NetworkFormReset { adapter_name } => {
-            handle_network_form_start_edit(adapter_name, model)
+            handle_network_form_reset(adapter_name, model)
         }
+        DeviceEvent::NetworkFormDiscardAll => handle_network_form_discard_all(model),
+    }
+}
+
+/// Request a factory reset, re-authenticated by the password if given
+///
+/// The session cookie authenticates the request, so the authorization header
+/// is free for the password. A rejection for a missing or wrong password is
+/// routed to [`DeviceEvent::FactoryResetReauthRequired`] and keeps the session.
+fn request_factory_reset(
+    model: &mut Model,
+    request: &FactoryResetRequest,
+    password: Option<&str>,
+) -> Command<Effect, Event> {
+    const ACTION: &str = "Factory reset";
+
+    model.start_loading();
+    let Some(token) = &model.auth_token else {
+        return handle_auth_error(model, ACTION);
+    };
+    let authorization = match password {
+        Some(password) => format!("Basic {}", BASE64_STANDARD.encode(format!(":{password}"))),
+        None => format!("Bearer {token}"),
+    };
+
+    match HttpCmd::post(build_url("/factory-reset"))
+        .header("Authorization", authorization)
+        .header("Content-Type", "application/json")
+        .body_json(request)
+    {
+        Ok(builder) => Command::all([
+            crux_core::render::render(),
+            builder.build().then_send(|result| {
+                if is_reauth_required(&result) {
+                    return Event::Device(DeviceEvent::FactoryResetReauthRequired);
+                }
+                if is_session_expired(&result) {
+                    return Event::Auth(AuthEvent::SessionExpired);
+                }
+                Event::Device(DeviceEvent::FactoryResetResponse(process_status_response(
+                    ACTION, result,
+                )))
+            }),
+        ]),
+        Err(e) => handle_request_error(model, ACTION, e),
     }
 }
 
@@ -212,7 +315,7 @@ mod tests {
     use super::*;
     use crate::events::DeviceEvent;
     use crate::types::{DeviceOperationState, UploadState};
-    use crate::UpdateManifest;
+    use crate::{UpdateManifest, REAUTH_REQUIRED};
 
     mod reboot {
         use super::*;
@@ -293,6 +396,7 @@ mod tests {
                 DeviceEvent::FactoryResetRequest {
                     mode: "invalid".into(),
                     preserve: vec![],
+                    password: None,
                 },
                 &mut model,
             );
@@ -306,6 +410,72 @@ mod tests {
                 .contains("Invalid factory reset mode"));
         }
 
+        #[test]
+        fn reauth_response_does_not_expire_session() {
+            use crux_http::protocol::{HttpResponse, HttpResult};
+
+            let mut model = Model {
+                auth_token: Some("token".into()),
+                ..Default::default()
+            };
+
+            let mut cmd = handle(
+                DeviceEvent::FactoryResetRequest {
+                    mode: "1".into(),
+                    preserve: vec![],
+                    password: None,
+                },
+                &mut model,
+            );
+            let mut request = cmd
+                .effects()
+                .find_map(|effect| match effect {
+                    Effect::Http(request) => Some(request),
+                    _ => None,
+                })
+                .expect("factory reset request");
+            request
+                .resolve(HttpResult::Ok(
+                    HttpResponse::status(403).body(REAUTH_REQUIRED).build(),
+                ))
+                .expect("resolve factory reset request");
+
+            let event = cmd.events().next().expect("factory reset response event");
+            assert!(matches!(
+                event,
+                Event::Device(DeviceEvent::FactoryResetReauthRequired)
+            ));
+        }
+
+        #[test]
+        fn reauth_required_asks_for_password_and_keeps_session() {
+            let mut model = Model {
+                is_loading: true,
+                auth_token: Some("token".into()),
+                overlay_spinner: OverlaySpinnerState::new("Requesting factory reset..."),
+                ..Default::default()
+            };
+
+            let _ = handle(DeviceEvent::FactoryResetReauthRequired, &mut model);
+
+            assert!(!model.is_loading);
+            assert!(model.factory_reset_password_required);
+            assert!(!model.session_expired);
+            assert_eq!(model.auth_token, Some("token".into()));
+            assert!(!model.overlay_spinner.is_visible());
+            assert_eq!(
+                model.error_message,
+                Some("Factory reset requires your password".into())
+            );
+
+            let _ = handle(DeviceEvent::FactoryResetReauthRequired, &mut model);
+
+            assert_eq!(
+                model.error_message,
+                Some("Factory reset failed: incorrect password".into())
+            );
+        }
+
         #[test]
         fn success_sets_factory_resetting_state() {
             let mut model = Model {
@@ -374,9 +544,74 @@ mod tests {
                 ..Default::default()
             };
 
-            let _ = handle(DeviceEvent::UploadProgress(50), &mut model);
+            let _ = handle(upload_progress(50, 100, 1_000), &mut model);
 
             assert_eq!(model.firmware_upload_state, UploadState::Uploading);
+            assert_eq!(model.overlay_spinner.progress(), Some(50));
+        }
+
+        fn upload_progress(
+            uploaded_bytes: u64,
+            total_bytes: u64,
+            timestamp_ms: u64,
+        ) -> DeviceEvent {
+            DeviceEvent::UploadProgress {
+                uploaded_bytes,
+                total_bytes,
+                timestamp_ms,
+            }
+        }
+
+        #[test]
+        fn first_progress_tick_has_no_eta() {
+            let mut model = Model::default();
+            let _ = handle(DeviceEvent::UploadStarted, &mut model);
+
+            let _ = handle(upload_progress(1_000, 10_000, 5_000), &mut model);
+
+            assert_eq!(model.overlay_spinner.progress(), Some(10));
+            assert_eq!(model.overlay_spinner.estimated_seconds_remaining(), None);
+        }
+
+        #[test]
+        fn eta_is_computed_from_rate_since_first_tick() {
+            let mut model = Model::default();
+            let _ = handle(DeviceEvent::UploadStarted, &mut model);
+
+            let _ = handle(upload_progress(1_000, 10_000, 5_000), &mut model);
+            // 2000 bytes in 4s, 7000 bytes remaining
+            let _ = handle(upload_progress(3_000, 10_000, 9_000), &mut model);
+
+            assert_eq!(model.overlay_spinner.progress(), Some(30));
+            assert_eq!(
+                model.overlay_spinner.estimated_seconds_remaining(),
+                Some(14)
+            );
+        }
+
+        #[test]
+        fn stalled_upload_has_no_eta() {
+            let mut model = Model::default();
+            let _ = handle(DeviceEvent::UploadStarted, &mut model);
+
+            let _ = handle(upload_progress(1_000, 10_000, 5_000), &mut model);
+            let _ = handle(upload_progress(1_000, 10_000, 9_000), &mut model);
+
+            assert_eq!(model.overlay_spinner.estimated_seconds_remaining(), None);
+        }
+
+        #[test]
+        fn restarted_upload_uses_new_reference() {
+            let mut model = Model::default();
+            let _ = handle(DeviceEvent::UploadStarted, &mut model);
+            let _ = handle(upload_progress(5_000, 10_000, 1_000), &mut model);
+            let _ = handle(DeviceEvent::UploadCancelled, &mut model);
+
+            let _ = handle(DeviceEvent::UploadStarted, &mut model);
+            let _ = handle(upload_progress(0, 10_000, 60_000), &mut model);
+            let _ = handle(upload_progress(1_000, 10_000, 61_000), &mut model);
+
+            assert_eq!(model.overlay_spinner.estimated_seconds_remaining(), Some(9));
         }
 
         #[test]
@@ -420,6 +655,21 @@ mod tests {
                 .contains("Upload failed"));
             assert!(!model.overlay_spinner.is_visible());
         }
+
+        #[test]
+        fn upload_cancelled_is_distinct_from_failure() {
+            let mut model = Model {
+                firmware_upload_state: UploadState::Uploading,
+                overlay_spinner: OverlaySpinnerState::new("Uploading...").with_progress(30),
+                ..Default::default()
+            };
+
+            let _ = handle(DeviceEvent::UploadCancelled, &mut model);
+
+            assert_eq!(model.firmware_upload_state, UploadState::Cancelled);
+            assert!(model.error_message.is_none());
+            assert!(!model.overlay_spinner.is_visible());
+        }
     }
 
     mod load_update {
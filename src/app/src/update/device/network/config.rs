@@ -5,17 +5,44 @@ use crate::{
     auth_post,
     events::Event,
     model::Model,
-    types::{subnet_to_cidr, NetworkChangeState, NetworkConfigRequest, NetworkFormState},
+    types::{
+        is_valid_ipv4, subnet_to_cidr, NetworkChangeState, NetworkConfigRequest, NetworkFormState,
+        SetNetworkConfigResponse,
+    },
     Effect,
 };
 
-use super::verification::update_network_state_and_spinner;
+use super::verification::{enter_applied_reachability_unknown, update_network_state_and_spinner};
 
 /// Success message for network configuration update
-const NETWORK_CONFIG_SUCCESS: &str = "Network configuration updated";
+pub const NETWORK_CONFIG_SUCCESS: &str = "Network configuration updated";
+
+/// Whether the browser is able to probe the healthcheck at the new IP
+///
+/// The probe is sent without credentials, so it is rejected if the healthcheck
+/// requires authentication. It also needs a valid IPv4 address to target.
+fn new_ip_probe_possible(new_ip: &str, response: &SetNetworkConfigResponse) -> bool {
+    !response.healthcheck_require_auth && is_valid_ipv4(new_ip)
+}
 
 /// Handle network configuration request
 pub fn handle_set_network_config(config: String, model: &mut Model) -> Command<Effect, Event> {
+    // Don't submit a form with validation errors, the backend would only reject it
+    if let NetworkFormState::Editing { errors, .. } = &model.network_form_state {
+        if !errors.is_empty() {
+            let mut messages: Vec<_> = errors.iter().collect();
+            messages.sort();
+            let messages = messages
+                .into_iter()
+                .map(|(_, message)| message.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return model.set_error_and_render(format!(
+                "Please correct the network settings before applying: {messages}"
+            ));
+        }
+    }
+
     // Parse the JSON config to extract metadata
     let parsed_config: Result<NetworkConfigRequest, _> = serde_json::from_str(&config);
 
@@ -54,10 +81,6 @@ pub fn handle_set_network_config(config: String, model: &mut Model) -> Command<E
             // Clear dirty flag when submitting
             model.network_form_dirty = false;
 
-            // Clear any previous messages.
-            model.success_message = None;
-            model.error_message = None;
-
             // Send the request to backend
             let body = serde_json::to_string(&config_req).unwrap_or(config);
 
@@ -103,6 +126,8 @@ pub fn handle_set_network_config_response(
                         *switching_to_dhcp,
                         true,
                     );
+                } else if !*switching_to_dhcp && !new_ip_probe_possible(new_ip, &response) {
+                    enter_applied_reachability_unknown(model, new_ip.clone(), response.ui_port);
                 } else {
                     update_network_state_and_spinner(
                         model,
@@ -120,7 +145,12 @@ pub fn handle_set_network_config_response(
                 model.overlay_spinner.clear();
             }
 
-            model.success_message = Some(NETWORK_CONFIG_SUCCESS.to_string());
+            // While the new IP still has to be confirmed (or the change may be
+            // rolled back) the update isn't settled yet, so the success message is
+            // deferred until the new IP is reachable
+            if model.network_change_state == NetworkChangeState::Idle {
+                model.set_success(NETWORK_CONFIG_SUCCESS);
+            }
 
             // Transition back to editing state with the new data as original
             if let NetworkFormState::Submitting {
@@ -139,8 +169,10 @@ pub fn handle_set_network_config_response(
                 model.network_form_state = NetworkFormState::Idle;
             }
 
-            // Clear rollback modal flag after config is applied
+            // Clear rollback modal flag after config is applied, the applied
+            // data is the new original, so no field differs anymore
             model.should_show_rollback_modal = false;
+            model.network_form_changed_fields.clear();
             crux_core::render::render()
         }
         Err(e) => {
@@ -291,6 +323,7 @@ mod tests {
             rollback_timeout_seconds: 0,
             ui_port: 80,
             rollback_enabled: false,
+            healthcheck_require_auth: false,
         });
 
         let _ = handle_set_network_config_response(result, &mut model);
@@ -331,6 +364,7 @@ mod tests {
             rollback_timeout_seconds: 0,
             ui_port: 80,
             rollback_enabled: false,
+            healthcheck_require_auth: false,
         });
 
         let _ = handle_set_network_config_response(result, &mut model);
@@ -378,4 +412,245 @@ mod tests {
         assert!(model.network_form_dirty);
         assert_eq!(model.error_message, Some("Failed to set config".into()));
     }
+
+    mod validation_guard {
+        use super::*;
+
+        fn editing_model(errors: HashMap<String, String>) -> Model {
+            let form_data = crate::types::NetworkFormData {
+                name: "eth0".to_string(),
+                ip_address: "192.168.1.300".to_string(),
+                dhcp: false,
+                subnet_mask: "255.255.255.0".to_string(),
+                dns: vec![],
+                gateways: vec![],
+            };
+            Model {
+                network_form_state: NetworkFormState::Editing {
+                    adapter_name: "eth0".to_string(),
+                    form_data: form_data.clone(),
+                    original_data: form_data,
+                    errors,
+                },
+                auth_token: Some("token".to_string()),
+                ..Default::default()
+            }
+        }
+
+        fn config() -> String {
+            r#"{
+                "isServerAddr": false,
+                "ipChanged": false,
+                "name": "eth0",
+                "dhcp": false,
+                "ip": "192.168.1.100",
+                "netmask": 24,
+                "gateway": [],
+                "dns": []
+            }"#
+            .to_string()
+        }
+
+        #[test]
+        fn submit_with_ip_address_error_is_blocked() {
+            let mut model = editing_model(HashMap::from([(
+                "ipAddress".to_string(),
+                "Invalid IPv4-Address".to_string(),
+            )]));
+
+            let _ = handle_set_network_config(config(), &mut model);
+
+            assert!(matches!(
+                model.network_form_state,
+                NetworkFormState::Editing { .. }
+            ));
+            assert!(!model.is_loading);
+            assert!(model
+                .error_message
+                .as_deref()
+                .is_some_and(|e| e.contains("Invalid IPv4-Address")));
+        }
+
+        #[test]
+        fn clean_form_is_submitted() {
+            let mut model = editing_model(HashMap::new());
+
+            let _ = handle_set_network_config(config(), &mut model);
+
+            assert!(matches!(
+                model.network_form_state,
+                NetworkFormState::Submitting { .. }
+            ));
+            assert!(model.is_loading);
+            assert!(model.error_message.is_none());
+        }
+    }
+
+    mod applied_reachability_unknown {
+        use super::*;
+
+        fn applying_model(new_ip: &str, switching_to_dhcp: bool) -> Model {
+            Model {
+                network_change_state: NetworkChangeState::ApplyingConfig {
+                    is_server_addr: true,
+                    ip_changed: true,
+                    new_ip: new_ip.to_string(),
+                    old_ip: "192.168.1.100".to_string(),
+                    switching_to_dhcp,
+                },
+                ..Default::default()
+            }
+        }
+
+        fn response(
+            rollback_enabled: bool,
+            healthcheck_require_auth: bool,
+        ) -> SetNetworkConfigResponse {
+            SetNetworkConfigResponse {
+                rollback_timeout_seconds: if rollback_enabled { 90 } else { 0 },
+                ui_port: 1977,
+                rollback_enabled,
+                healthcheck_require_auth,
+            }
+        }
+
+        #[test]
+        fn entered_when_healthcheck_requires_auth() {
+            let mut model = applying_model("192.168.1.101", false);
+
+            let _ = handle_set_network_config_response(Ok(response(false, true)), &mut model);
+
+            assert_eq!(
+                model.network_change_state,
+                NetworkChangeState::AppliedReachabilityUnknown {
+                    new_ip: "192.168.1.101".to_string(),
+                    ui_port: 1977,
+                }
+            );
+            assert!(model.overlay_spinner.timed_out());
+            assert_eq!(model.new_ip_timeout_secs, None);
+        }
+
+        #[test]
+        fn entered_when_new_ip_is_invalid() {
+            let mut model = applying_model("", false);
+
+            let _ = handle_set_network_config_response(Ok(response(false, false)), &mut model);
+
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::AppliedReachabilityUnknown { .. }
+            ));
+        }
+
+        #[test]
+        fn not_entered_when_probe_is_possible() {
+            let mut model = applying_model("192.168.1.101", false);
+
+            let _ = handle_set_network_config_response(Ok(response(false, false)), &mut model);
+
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { .. }
+            ));
+        }
+
+        #[test]
+        fn not_entered_when_rollback_is_enabled() {
+            let mut model = applying_model("192.168.1.101", false);
+
+            let _ = handle_set_network_config_response(Ok(response(true, true)), &mut model);
+
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { .. }
+            ));
+        }
+
+        #[test]
+        fn not_entered_when_switching_to_dhcp() {
+            let mut model = applying_model("", true);
+
+            let _ = handle_set_network_config_response(Ok(response(false, true)), &mut model);
+
+            assert_eq!(model.network_change_state, NetworkChangeState::Idle);
+        }
+    }
+
+    mod success_message {
+        use super::*;
+
+        fn applying_model(switching_to_dhcp: bool) -> Model {
+            Model {
+                network_change_state: NetworkChangeState::ApplyingConfig {
+                    is_server_addr: true,
+                    ip_changed: true,
+                    new_ip: "192.168.1.101".to_string(),
+                    old_ip: "192.168.1.100".to_string(),
+                    switching_to_dhcp,
+                },
+                ..Default::default()
+            }
+        }
+
+        fn response(rollback_enabled: bool) -> SetNetworkConfigResponse {
+            SetNetworkConfigResponse {
+                rollback_timeout_seconds: if rollback_enabled { 90 } else { 0 },
+                ui_port: 1977,
+                rollback_enabled,
+                healthcheck_require_auth: false,
+            }
+        }
+
+        #[test]
+        fn deferred_while_waiting_for_new_ip_with_rollback() {
+            let mut model = applying_model(false);
+
+            let _ = handle_set_network_config_response(Ok(response(true)), &mut model);
+
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { .. }
+            ));
+            assert!(model.success_message.is_none());
+        }
+
+        #[test]
+        fn deferred_while_waiting_for_new_ip_without_rollback() {
+            let mut model = applying_model(false);
+
+            let _ = handle_set_network_config_response(Ok(response(false)), &mut model);
+
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { .. }
+            ));
+            assert!(model.success_message.is_none());
+        }
+
+        #[test]
+        fn set_if_no_confirmation_is_pending() {
+            let mut model = applying_model(true);
+
+            let _ = handle_set_network_config_response(Ok(response(false)), &mut model);
+
+            assert_eq!(model.network_change_state, NetworkChangeState::Idle);
+            assert_eq!(
+                model.success_message.as_deref(),
+                Some(NETWORK_CONFIG_SUCCESS)
+            );
+        }
+
+        #[test]
+        fn set_if_connection_is_not_affected() {
+            let mut model = Model::default();
+
+            let _ = handle_set_network_config_response(Ok(response(false)), &mut model);
+
+            assert_eq!(
+                model.success_message.as_deref(),
+                Some(NETWORK_CONFIG_SUCCESS)
+            );
+        }
+    }
 }
@@ -6,11 +6,43 @@ use crate::model::Model;
 use crate::types::{is_valid_ipv4, subnet_to_cidr, NetworkFormData, NetworkFormState};
 use crate::Effect;
 
-/// Handle network form start edit - initialize form with current network adapter data
+/// Handle network form start edit - make the given adapter the active one
+///
+/// Unsaved edits of the previously active adapter are staged, so switching
+/// back restores them. Without staged edits the form is initialized from the
+/// current network adapter data.
 pub fn handle_network_form_start_edit(
     adapter_name: String,
     model: &mut Model,
 ) -> Command<Effect, Event> {
+    model.stage_network_form();
+
+    // Drop everything left over from the previously active adapter, so
+    // switching adapters never leaks errors, dirty or rollback modal flags
+    model.reset_network_form();
+
+    if let Some(staged) = model.network_forms.remove(&adapter_name) {
+        let (should_show_modal, default_enabled) = compute_rollback_modal_state(
+            &staged.form_data,
+            &staged.original_data,
+            &adapter_name,
+            model,
+        );
+
+        model.network_form_state = NetworkFormState::Editing {
+            adapter_name,
+            form_data: staged.form_data,
+            original_data: staged.original_data,
+            errors: staged.errors,
+        };
+        model.network_form_dirty = staged.dirty;
+        model.network_form_changed_fields = staged.form_data.changed_fields(&staged.original_data);
+        model.should_show_rollback_modal = should_show_modal;
+        model.default_rollback_enabled = default_enabled;
+
+        return crux_core::render::render();
+    }
+
     // Find the network adapter and copy its data to form state
     if let Some(network_status) = &model.network_status {
         if let Some(adapter) = network_status
@@ -26,17 +58,41 @@ pub fn handle_network_form_start_edit(
                 original_data: form_data,
                 errors: HashMap::new(),
             };
-            // Clear dirty flag when starting a fresh edit
-            model.network_form_dirty = false;
-            // Clear rollback modal flags
-            model.should_show_rollback_modal = false;
-            model.default_rollback_enabled = false;
         }
     }
 
     crux_core::render::render()
 }
 
+/// Handle network form reset - discard unsaved edits of the given adapter
+///
+/// The adapter becomes the active one, edited from its current data.
+pub fn handle_network_form_reset(
+    adapter_name: String,
+    model: &mut Model,
+) -> Command<Effect, Event> {
+    model.network_forms.remove(&adapter_name);
+
+    if model.network_form_state.adapter_name() == Some(adapter_name.as_str()) {
+        model.reset_network_form();
+    }
+
+    handle_network_form_start_edit(adapter_name, model)
+}
+
+/// Handle network form discard all - drop unsaved edits of all adapters
+pub fn handle_network_form_discard_all(model: &mut Model) -> Command<Effect, Event> {
+    model.network_forms.clear();
+
+    if let NetworkFormState::Editing { adapter_name, .. } = &model.network_form_state {
+        let adapter_name = adapter_name.clone();
+        model.reset_network_form();
+        return handle_network_form_start_edit(adapter_name, model);
+    }
+
+    crux_core::render::render()
+}
+
 /// Handle network form update - update form data from user input
 pub fn handle_network_form_update(
     form_data_json: String,
@@ -73,9 +129,20 @@ pub fn handle_network_form_update(
 
                 let is_dirty = form_data != *original_data;
 
-                // Compute rollback modal flags
-                let (should_show_modal, default_enabled) =
-                    compute_rollback_modal_state(&form_data, original_data, adapter_name, model);
+                // The rollback modal flags only depend on which fields changed, so
+                // a burst of keystrokes within the same field leaves them untouched
+                let changed_fields = form_data.changed_fields(original_data);
+                if changed_fields != model.network_form_changed_fields {
+                    let (should_show_modal, default_enabled) = compute_rollback_modal_state(
+                        &form_data,
+                        original_data,
+                        adapter_name,
+                        model,
+                    );
+                    model.should_show_rollback_modal = should_show_modal;
+                    model.default_rollback_enabled = default_enabled;
+                    model.network_form_changed_fields = changed_fields;
+                }
 
                 model.network_form_state = NetworkFormState::Editing {
                     adapter_name: adapter_name.clone(),
@@ -84,8 +151,6 @@ pub fn handle_network_form_update(
                     errors,
                 };
                 model.network_form_dirty = is_dirty;
-                model.should_show_rollback_modal = should_show_modal;
-                model.default_rollback_enabled = default_enabled;
             }
             crux_core::render::render()
         }
@@ -128,11 +193,13 @@ mod tests {
             mac: "00:11:22:33:44:55".to_string(),
             online: true,
             file: Some("/etc/network/interfaces".to_string()),
+            carrier: Some(true),
             ipv4: InternetProtocol {
                 addrs: vec![IpAddress {
                     addr: ip.to_string(),
                     dhcp,
                     prefix_len: 24,
+                    subnet_mask: "255.255.255.0".to_string(),
                 }],
                 dns: vec!["8.8.8.8".to_string()],
                 gateways: vec!["192.168.1.1".to_string()],
@@ -262,7 +329,7 @@ mod tests {
                 ..Default::default()
             };
 
-            let _ = handle_network_form_start_edit("eth0".to_string(), &mut model);
+            let _ = handle_network_form_reset("eth0".to_string(), &mut model);
 
             if let NetworkFormState::Editing {
                 form_data,
@@ -323,6 +390,303 @@ mod tests {
         }
     }
 
+    mod adapter_switch {
+        use super::*;
+
+        fn model_editing_eth0_with_stale_state() -> Model {
+            let eth0 = create_test_network_adapter("eth0", "192.168.1.100", false);
+            let wlan0 = create_test_network_adapter("wlan0", "192.168.2.100", true);
+            let original_data = NetworkFormData::from(&eth0);
+            let mut form_data = original_data.clone();
+            form_data.ip_address = "invalid".to_string();
+
+            Model {
+                network_status: Some(NetworkStatus {
+                    network_status: vec![eth0, wlan0],
+                }),
+                current_connection_adapter: Some("eth0".to_string()),
+                network_form_state: NetworkFormState::Editing {
+                    adapter_name: "eth0".to_string(),
+                    form_data,
+                    original_data,
+                    errors: HashMap::from([(
+                        "ipAddress".to_string(),
+                        "Invalid IPv4-Address".to_string(),
+                    )]),
+                },
+                network_form_dirty: true,
+                should_show_rollback_modal: true,
+                default_rollback_enabled: true,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn switching_adapter_starts_with_clean_slate() {
+            let mut model = model_editing_eth0_with_stale_state();
+
+            let _ = handle_network_form_start_edit("wlan0".to_string(), &mut model);
+
+            let NetworkFormState::Editing {
+                adapter_name,
+                form_data,
+                original_data,
+                errors,
+            } = &model.network_form_state
+            else {
+                panic!("expected editing state");
+            };
+            assert_eq!(adapter_name, "wlan0");
+            assert_eq!(form_data.ip_address, "192.168.2.100");
+            assert_eq!(form_data, original_data);
+            assert!(errors.is_empty());
+            assert!(!model.network_form_dirty);
+            assert!(!model.should_show_rollback_modal);
+            assert!(!model.default_rollback_enabled);
+        }
+
+        #[test]
+        fn switching_to_unknown_adapter_clears_stale_state() {
+            let mut model = model_editing_eth0_with_stale_state();
+
+            let _ = handle_network_form_start_edit("eth9".to_string(), &mut model);
+
+            assert_eq!(model.network_form_state, NetworkFormState::Idle);
+            assert!(!model.network_form_dirty);
+            assert!(!model.should_show_rollback_modal);
+            assert!(!model.default_rollback_enabled);
+        }
+
+        #[test]
+        fn update_after_switch_validates_new_adapter_only() {
+            let mut model = model_editing_eth0_with_stale_state();
+
+            let _ = handle_network_form_start_edit("wlan0".to_string(), &mut model);
+
+            let NetworkFormState::Editing { original_data, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            let unchanged = original_data.clone();
+            let _ =
+                handle_network_form_update(serde_json::to_string(&unchanged).unwrap(), &mut model);
+
+            let NetworkFormState::Editing { errors, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            assert!(errors.is_empty());
+            assert!(!model.network_form_dirty);
+            assert!(!model.should_show_rollback_modal);
+        }
+    }
+
+    mod update_burst {
+        use super::*;
+
+        fn model_editing_current_adapter() -> (Model, NetworkFormData) {
+            let eth0 = create_test_network_adapter("eth0", "192.168.1.100", false);
+            let mut model = Model {
+                network_status: Some(NetworkStatus {
+                    network_status: vec![eth0],
+                }),
+                current_connection_adapter: Some("eth0".to_string()),
+                ..Default::default()
+            };
+            let _ = handle_network_form_start_edit("eth0".to_string(), &mut model);
+            let NetworkFormState::Editing { form_data, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            let form_data = form_data.clone();
+            (model, form_data)
+        }
+
+        fn type_ip(ip_address: &str, form_data: &NetworkFormData, model: &mut Model) {
+            let mut changed = form_data.clone();
+            changed.ip_address = ip_address.to_string();
+            let _ = handle_network_form_update(serde_json::to_string(&changed).unwrap(), model);
+        }
+
+        #[test]
+        fn burst_in_same_field_keeps_modal_flags_stable() {
+            let (mut model, form_data) = model_editing_current_adapter();
+
+            type_ip("192.168.1.10", &form_data, &mut model);
+            assert!(model.should_show_rollback_modal);
+            assert!(model.default_rollback_enabled);
+            assert_eq!(model.network_form_changed_fields, vec!["ipAddress"]);
+
+            // a modified flag shows that further keystrokes don't recompute it
+            model.default_rollback_enabled = false;
+            for ip in ["192.168.1.1", "192.168.1.", "192.168.1.2", "192.168.1.20"] {
+                type_ip(ip, &form_data, &mut model);
+
+                assert!(model.should_show_rollback_modal);
+                assert!(!model.default_rollback_enabled);
+                assert!(model.network_form_dirty);
+            }
+        }
+
+        #[test]
+        fn errors_follow_every_keystroke() {
+            let (mut model, form_data) = model_editing_current_adapter();
+
+            type_ip("192.168.1.", &form_data, &mut model);
+            let NetworkFormState::Editing { errors, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            assert!(errors.contains_key("ipAddress"));
+
+            type_ip("192.168.1.2", &form_data, &mut model);
+            let NetworkFormState::Editing { errors, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            assert!(errors.is_empty());
+        }
+
+        #[test]
+        fn changing_another_field_recomputes_modal_flags() {
+            let (mut model, form_data) = model_editing_current_adapter();
+
+            type_ip("192.168.1.10", &form_data, &mut model);
+            assert!(model.default_rollback_enabled);
+
+            let mut dhcp = form_data.clone();
+            dhcp.ip_address = "192.168.1.10".to_string();
+            dhcp.dhcp = true;
+            let _ = handle_network_form_update(serde_json::to_string(&dhcp).unwrap(), &mut model);
+
+            // switching to DHCP disables rollback by default
+            assert!(model.should_show_rollback_modal);
+            assert!(!model.default_rollback_enabled);
+        }
+
+        #[test]
+        fn typing_back_the_original_value_hides_modal() {
+            let (mut model, form_data) = model_editing_current_adapter();
+
+            type_ip("192.168.1.10", &form_data, &mut model);
+            type_ip("192.168.1.100", &form_data, &mut model);
+
+            assert!(!model.should_show_rollback_modal);
+            assert!(!model.network_form_dirty);
+            assert!(model.network_form_changed_fields.is_empty());
+        }
+    }
+
+    mod staged_adapters {
+        use super::*;
+
+        fn model_with_two_adapters() -> Model {
+            Model {
+                network_status: Some(NetworkStatus {
+                    network_status: vec![
+                        create_test_network_adapter("eth0", "192.168.1.100", false),
+                        create_test_network_adapter("wlan0", "192.168.2.100", false),
+                    ],
+                }),
+                current_connection_adapter: Some("eth0".to_string()),
+                ..Default::default()
+            }
+        }
+
+        fn edit_ip(adapter_name: &str, ip_address: &str, model: &mut Model) {
+            let _ = handle_network_form_start_edit(adapter_name.to_string(), model);
+            let NetworkFormState::Editing { form_data, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            let mut changed = form_data.clone();
+            changed.ip_address = ip_address.to_string();
+            let _ = handle_network_form_update(serde_json::to_string(&changed).unwrap(), model);
+        }
+
+        fn active_ip_address(model: &Model) -> &str {
+            let NetworkFormState::Editing { form_data, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            &form_data.ip_address
+        }
+
+        #[test]
+        fn editing_two_adapters_preserves_both_unsaved_edits() {
+            let mut model = model_with_two_adapters();
+
+            edit_ip("eth0", "192.168.1.200", &mut model);
+            edit_ip("wlan0", "invalid", &mut model);
+
+            assert_eq!(model.network_form_state.adapter_name(), Some("wlan0"));
+            assert!(model.network_form_dirty);
+            assert!(model.network_forms["eth0"].dirty);
+            assert_eq!(
+                model.network_forms["eth0"].form_data.ip_address,
+                "192.168.1.200"
+            );
+
+            let _ = handle_network_form_start_edit("eth0".to_string(), &mut model);
+
+            assert_eq!(active_ip_address(&model), "192.168.1.200");
+            assert!(model.network_form_dirty);
+            assert!(model.should_show_rollback_modal);
+            assert!(!model.network_forms.contains_key("eth0"));
+
+            let wlan0 = &model.network_forms["wlan0"];
+            assert!(wlan0.dirty);
+            assert_eq!(wlan0.form_data.ip_address, "invalid");
+            assert!(wlan0.errors.contains_key("ipAddress"));
+
+            let _ = handle_network_form_start_edit("wlan0".to_string(), &mut model);
+
+            assert_eq!(active_ip_address(&model), "invalid");
+            assert!(model.network_form_dirty);
+            assert!(!model.should_show_rollback_modal);
+            let NetworkFormState::Editing { errors, .. } = &model.network_form_state else {
+                panic!("expected editing state");
+            };
+            assert!(errors.contains_key("ipAddress"));
+        }
+
+        #[test]
+        fn clean_adapters_are_not_staged() {
+            let mut model = model_with_two_adapters();
+
+            let _ = handle_network_form_start_edit("eth0".to_string(), &mut model);
+            let _ = handle_network_form_start_edit("wlan0".to_string(), &mut model);
+
+            assert!(model.network_forms.is_empty());
+        }
+
+        #[test]
+        fn reset_discards_unsaved_edits_of_staged_adapter() {
+            let mut model = model_with_two_adapters();
+
+            edit_ip("eth0", "192.168.1.200", &mut model);
+            edit_ip("wlan0", "192.168.2.200", &mut model);
+
+            let _ = handle_network_form_reset("eth0".to_string(), &mut model);
+
+            assert_eq!(active_ip_address(&model), "192.168.1.100");
+            assert!(!model.network_form_dirty);
+            assert!(!model.network_forms.contains_key("eth0"));
+            assert_eq!(
+                model.network_forms["wlan0"].form_data.ip_address,
+                "192.168.2.200"
+            );
+        }
+
+        #[test]
+        fn discard_all_drops_unsaved_edits_of_all_adapters() {
+            let mut model = model_with_two_adapters();
+
+            edit_ip("eth0", "192.168.1.200", &mut model);
+            edit_ip("wlan0", "192.168.2.200", &mut model);
+
+            let _ = handle_network_form_discard_all(&mut model);
+
+            assert!(model.network_forms.is_empty());
+            assert_eq!(model.network_form_state.adapter_name(), Some("wlan0"));
+            assert_eq!(active_ip_address(&model), "192.168.2.100");
+            assert!(!model.network_form_dirty);
+        }
+    }
+
     mod rollback_modal_flags {
         use super::*;
 
@@ -333,11 +697,13 @@ mod tests {
                     mac: "00:11:22:33:44:55".to_string(),
                     online: true,
                     file: Some("/etc/network/interfaces".to_string()),
+                    carrier: Some(true),
                     ipv4: InternetProtocol {
                         addrs: vec![IpAddress {
                             addr: ip.to_string(),
                             dhcp: false,
                             prefix_len: 24,
+                            subnet_mask: "255.255.255.0".to_string(),
                         }],
                         dns: vec![],
                         gateways: vec![],
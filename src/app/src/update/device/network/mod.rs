@@ -3,10 +3,14 @@ pub mod form;
 pub mod verification;
 
 pub use config::{handle_set_network_config, handle_set_network_config_response};
-pub use form::{handle_network_form_start_edit, handle_network_form_update};
+pub use form::{
+    handle_network_form_discard_all, handle_network_form_reset, handle_network_form_start_edit,
+    handle_network_form_update,
+};
 pub use verification::{
     handle_ack_factory_reset_result, handle_ack_rollback, handle_ack_update_validation,
-    handle_new_ip_check_tick, handle_new_ip_check_timeout,
+    handle_network_change_complete, handle_new_ip_check_tick, handle_new_ip_check_timeout,
+    handle_trigger_rollback_now, handle_trigger_rollback_now_response,
 };
 
 /*
@@ -40,4 +44,11 @@ User applies config → ApplyingConfig
                         ↓                          ↓
                       Idle                    (manual nav)
 
+While a rollback is pending, the user may trigger it before its deadline
+(TriggerRollbackNow), which leads to WaitingForOldIp right away.
+
+If rollback is disabled and the new IP cannot be probed (e.g. the healthcheck
+requires auth), the backend response leads to AppliedReachabilityUnknown
+instead of WaitingForNewIp, which asks for manual navigation right away.
+
 */
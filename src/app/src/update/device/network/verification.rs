@@ -1,13 +1,17 @@
 use crux_core::Command;
 
 use crate::{
+    auth_post,
     events::{DeviceEvent, Event, UiEvent},
     http_get_silent,
     model::Model,
-    types::{HealthcheckInfo, NetworkChangeState, OverlaySpinnerState},
+    types::{HealthcheckInfo, NetworkChangeState, OverlaySpinnerState, RollbackNowResponse},
     unauth_post, Effect,
 };
 
+/// Time to wait for the new IP if no rollback deadline applies
+pub const NEW_IP_TIMEOUT_WITHOUT_ROLLBACK_SECS: u64 = 90;
+
 /// Helper to update network state and spinner based on configuration response
 pub fn update_network_state_and_spinner(
     model: &mut Model,
@@ -23,6 +27,14 @@ pub fn update_network_state_and_spinner(
     if !rollback_enabled && switching_to_dhcp {
         model.network_change_state = NetworkChangeState::Idle;
     } else {
+        // explicit deadline so the probe ends even if the shell timer is imprecise,
+        // measured by elapsed time as the shell decides how often it ticks
+        model.new_ip_timeout_secs = Some(if rollback_enabled {
+            rollback_timeout_seconds
+        } else {
+            NEW_IP_TIMEOUT_WITHOUT_ROLLBACK_SECS
+        });
+        model.new_ip_check_start_ms = None;
         model.network_change_state = NetworkChangeState::WaitingForNewIp {
             new_ip,
             old_ip,
@@ -62,8 +74,38 @@ pub fn update_network_state_and_spinner(
     };
 }
 
+/// Enter the terminal state for an applied configuration whose new IP cannot be probed
+///
+/// Used when rollback is disabled and probing the new IP is impossible, so the
+/// user is asked to navigate manually right away instead of waiting for a timeout.
+pub fn enter_applied_reachability_unknown(model: &mut Model, new_ip: String, ui_port: u16) {
+    model.new_ip_timeout_secs = None;
+    model.new_ip_check_start_ms = None;
+    model.network_change_state = NetworkChangeState::AppliedReachabilityUnknown { new_ip, ui_port };
+
+    let mut spinner = OverlaySpinnerState::new("Network settings applied").with_text(
+        "The new address cannot be verified automatically. Click below to navigate manually.",
+    );
+    spinner.set_timed_out();
+    model.overlay_spinner = spinner;
+}
+
 /// Handle new IP check tick - polls new IP to see if it's reachable
-pub fn handle_new_ip_check_tick(model: &mut Model) -> Command<Effect, Event> {
+///
+/// Once the timeout has elapsed since the first tick the check times out
+/// without waiting for the shell's timeout.
+pub fn handle_new_ip_check_tick(model: &mut Model, timestamp_ms: u64) -> Command<Effect, Event> {
+    if let (NetworkChangeState::WaitingForNewIp { .. }, Some(timeout_secs)) =
+        (&model.network_change_state, model.new_ip_timeout_secs)
+    {
+        let start_ms = *model.new_ip_check_start_ms.get_or_insert(timestamp_ms);
+        let elapsed_ms = timestamp_ms.saturating_sub(start_ms);
+        if elapsed_ms >= timeout_secs * 1000 {
+            log::warn!("New IP not reachable after {}s", elapsed_ms / 1000);
+            return handle_new_ip_check_timeout(model);
+        }
+    }
+
     match &mut model.network_change_state {
         NetworkChangeState::WaitingForNewIp {
             new_ip,
@@ -124,16 +166,8 @@ pub fn handle_new_ip_check_timeout(model: &mut Model) -> Command<Effect, Event>
     } = &model.network_change_state
     {
         if *rollback_timeout_seconds > 0 {
-            model.network_change_state = NetworkChangeState::WaitingForOldIp {
-                old_ip: old_ip.clone(),
-                ui_port: *ui_port,
-                attempt: 0,
-            };
-            model
-                .overlay_spinner
-                .set_text("Rollback in progress. Verifying original address...");
-            // Ensure spinner is spinning (not timed out state)
-            model.overlay_spinner.set_loading();
+            let (old_ip, ui_port) = (old_ip.clone(), *ui_port);
+            wait_for_old_ip(model, old_ip, ui_port);
         } else {
             model.network_change_state = NetworkChangeState::NewIpTimeout {
                 new_ip: new_ip.clone(),
@@ -153,6 +187,90 @@ pub fn handle_new_ip_check_timeout(model: &mut Model) -> Command<Effect, Event>
     crux_core::render::render()
 }
 
+/// Handle network change complete - finish the change once the shell redirected
+///
+/// Clears the redirect overlay, so it doesn't linger if the redirect was blocked.
+/// Only applies to a reachable new IP, other states are left untouched.
+pub fn handle_network_change_complete(model: &mut Model) -> Command<Effect, Event> {
+    if !matches!(
+        model.network_change_state,
+        NetworkChangeState::NewIpReachable { .. }
+    ) {
+        return Command::done();
+    }
+
+    model.network_change_state = NetworkChangeState::Idle;
+    model.new_ip_timeout_secs = None;
+    model.new_ip_check_start_ms = None;
+    model.overlay_spinner.clear();
+
+    crux_core::render::render()
+}
+
+/// Handle trigger rollback now - ask the backend to roll back immediately
+///
+/// Only possible while waiting for the new IP with a rollback pending.
+pub fn handle_trigger_rollback_now(model: &mut Model) -> Command<Effect, Event> {
+    match &model.network_change_state {
+        NetworkChangeState::WaitingForNewIp {
+            rollback_timeout_seconds,
+            ..
+        } if *rollback_timeout_seconds > 0 => auth_post!(
+            Device,
+            DeviceEvent,
+            model,
+            "/network/rollback-now",
+            TriggerRollbackNowResponse,
+            "Rollback",
+            expect_json: RollbackNowResponse
+        ),
+        _ => Command::done(),
+    }
+}
+
+/// Handle trigger rollback now response - verify the original address
+pub fn handle_trigger_rollback_now_response(
+    result: Result<RollbackNowResponse, String>,
+    model: &mut Model,
+) -> Command<Effect, Event> {
+    model.stop_loading();
+
+    match result {
+        Ok(RollbackNowResponse {
+            rollback_executed: true,
+        }) => {
+            if let NetworkChangeState::WaitingForNewIp {
+                old_ip, ui_port, ..
+            } = &model.network_change_state
+            {
+                let (old_ip, ui_port) = (old_ip.clone(), *ui_port);
+                wait_for_old_ip(model, old_ip, ui_port);
+            }
+        }
+        // no rollback pending anymore, e.g. cancelled meanwhile, so the state is kept
+        Ok(RollbackNowResponse {
+            rollback_executed: false,
+        }) => model.set_error("No pending rollback to execute".to_string()),
+        Err(e) => model.set_error(e),
+    }
+
+    crux_core::render::render()
+}
+
+/// Start polling the original address after a rollback
+fn wait_for_old_ip(model: &mut Model, old_ip: String, ui_port: u16) {
+    model.network_change_state = NetworkChangeState::WaitingForOldIp {
+        old_ip,
+        ui_port,
+        attempt: 0,
+    };
+    model
+        .overlay_spinner
+        .set_text("Rollback in progress. Verifying original address...");
+    // Ensure spinner is spinning (not timed out state)
+    model.overlay_spinner.set_loading();
+}
+
 /// Handle acknowledge factory reset result - clear the result
 pub fn handle_ack_factory_reset_result(model: &mut Model) -> Command<Effect, Event> {
     if let Some(factory_reset) = &mut model.factory_reset {
@@ -170,8 +288,15 @@ pub fn handle_ack_factory_reset_result(model: &mut Model) -> Command<Effect, Eve
 }
 
 /// Handle acknowledge update validation - clear the status
+///
+/// Marks the result as acknowledged in the healthcheck as well, so a
+/// republished validation status (e.g. a failed validation) doesn't ask for
+/// acknowledgment again.
 pub fn handle_ack_update_validation(model: &mut Model) -> Command<Effect, Event> {
     model.update_validation_status = None;
+    if let Some(healthcheck) = &mut model.healthcheck {
+        healthcheck.update_validation_acked = true;
+    }
 
     unauth_post!(
         Device,
@@ -206,6 +331,54 @@ mod tests {
     use super::*;
     use crate::types::{HealthcheckInfo, UpdateValidationStatus, VersionInfo};
 
+    mod network_change_complete {
+        use super::*;
+
+        #[test]
+        fn reachable_new_ip_transitions_to_idle() {
+            let mut model = Model {
+                network_change_state: NetworkChangeState::NewIpReachable {
+                    new_ip: "192.168.1.101".to_string(),
+                    ui_port: 443,
+                },
+                new_ip_timeout_secs: Some(90),
+                new_ip_check_start_ms: Some(1_000),
+                overlay_spinner: OverlaySpinnerState::new("Network settings applied")
+                    .with_text("Redirecting to new IP: 192.168.1.101:443"),
+                ..Default::default()
+            };
+
+            let _ = handle_network_change_complete(&mut model);
+
+            assert_eq!(model.network_change_state, NetworkChangeState::Idle);
+            assert_eq!(model.new_ip_timeout_secs, None);
+            assert_eq!(model.new_ip_check_start_ms, None);
+            assert!(!model.overlay_spinner.is_visible());
+        }
+
+        #[test]
+        fn ignored_while_waiting_for_new_ip() {
+            let state = NetworkChangeState::WaitingForNewIp {
+                new_ip: "192.168.1.101".to_string(),
+                old_ip: "192.168.1.100".to_string(),
+                attempt: 2,
+                rollback_timeout_seconds: 90,
+                ui_port: 443,
+                switching_to_dhcp: false,
+            };
+            let mut model = Model {
+                network_change_state: state.clone(),
+                overlay_spinner: OverlaySpinnerState::new("Applying network settings"),
+                ..Default::default()
+            };
+
+            let _ = handle_network_change_complete(&mut model);
+
+            assert_eq!(model.network_change_state, state);
+            assert!(model.overlay_spinner.is_visible());
+        }
+    }
+
     mod ip_change_detection {
         use super::*;
 
@@ -223,7 +396,7 @@ mod tests {
                 ..Default::default()
             };
 
-            let _ = handle_new_ip_check_tick(&mut model);
+            let _ = handle_new_ip_check_tick(&mut model, 0);
 
             if let NetworkChangeState::WaitingForNewIp { attempt, .. } = model.network_change_state
             {
@@ -247,7 +420,7 @@ mod tests {
                 ..Default::default()
             };
 
-            let _ = handle_new_ip_check_tick(&mut model);
+            let _ = handle_new_ip_check_tick(&mut model, 0);
 
             if let NetworkChangeState::WaitingForNewIp { attempt, .. } = model.network_change_state
             {
@@ -353,6 +526,243 @@ mod tests {
         }
     }
 
+    mod new_ip_timeout {
+        use super::*;
+
+        const START_MS: u64 = 1_700_000_000_000;
+
+        fn waiting_for_new_ip(rollback_timeout_seconds: u64) -> Model {
+            Model {
+                network_change_state: NetworkChangeState::WaitingForNewIp {
+                    new_ip: "192.168.1.101".to_string(),
+                    old_ip: "192.168.1.100".to_string(),
+                    attempt: 0,
+                    rollback_timeout_seconds,
+                    ui_port: 443,
+                    switching_to_dhcp: false,
+                },
+                new_ip_timeout_secs: Some(15),
+                overlay_spinner: OverlaySpinnerState::new("Test Spinner"),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn timeout_is_taken_from_rollback_deadline() {
+            let mut model = Model::default();
+
+            update_network_state_and_spinner(
+                &mut model,
+                "192.168.1.101".to_string(),
+                "192.168.1.100".to_string(),
+                443,
+                92,
+                false,
+                true,
+            );
+
+            assert_eq!(model.new_ip_timeout_secs, Some(92));
+            assert_eq!(model.new_ip_check_start_ms, None);
+        }
+
+        #[test]
+        fn timeout_without_rollback_uses_default() {
+            let mut model = Model::default();
+
+            update_network_state_and_spinner(
+                &mut model,
+                "192.168.1.101".to_string(),
+                "192.168.1.100".to_string(),
+                443,
+                90,
+                false,
+                false,
+            );
+
+            assert_eq!(
+                model.new_ip_timeout_secs,
+                Some(NEW_IP_TIMEOUT_WITHOUT_ROLLBACK_SECS)
+            );
+        }
+
+        #[test]
+        fn first_tick_starts_the_clock() {
+            let mut model = waiting_for_new_ip(60);
+
+            let _ = handle_new_ip_check_tick(&mut model, START_MS);
+
+            assert_eq!(model.new_ip_check_start_ms, Some(START_MS));
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { attempt: 1, .. }
+            ));
+        }
+
+        #[test]
+        fn frequent_ticks_within_timeout_keep_polling() {
+            let mut model = waiting_for_new_ip(60);
+
+            // a shell ticking every 100ms must not exhaust the timeout early
+            for tick in 0..100 {
+                let _ = handle_new_ip_check_tick(&mut model, START_MS + tick * 100);
+            }
+
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { attempt: 100, .. }
+            ));
+        }
+
+        #[test]
+        fn elapsed_timeout_transitions_to_waiting_for_old_ip_if_rollback_enabled() {
+            let mut model = waiting_for_new_ip(60);
+            let _ = handle_new_ip_check_tick(&mut model, START_MS);
+
+            let _ = handle_new_ip_check_tick(&mut model, START_MS + 15_000);
+
+            assert_eq!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForOldIp {
+                    old_ip: "192.168.1.100".to_string(),
+                    ui_port: 443,
+                    attempt: 0,
+                }
+            );
+        }
+
+        #[test]
+        fn elapsed_timeout_transitions_to_timeout_if_rollback_disabled() {
+            let mut model = waiting_for_new_ip(0);
+            let _ = handle_new_ip_check_tick(&mut model, START_MS);
+
+            let _ = handle_new_ip_check_tick(&mut model, START_MS + 15_000);
+
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::NewIpTimeout { .. }
+            ));
+            assert!(model.overlay_spinner.timed_out());
+        }
+    }
+
+    mod trigger_rollback_now {
+        use super::*;
+
+        fn waiting_for_new_ip(rollback_timeout_seconds: u64) -> Model {
+            Model {
+                network_change_state: NetworkChangeState::WaitingForNewIp {
+                    new_ip: "192.168.1.101".to_string(),
+                    old_ip: "192.168.1.100".to_string(),
+                    attempt: 3,
+                    rollback_timeout_seconds,
+                    ui_port: 443,
+                    switching_to_dhcp: false,
+                },
+                overlay_spinner: OverlaySpinnerState::new("Test Spinner"),
+                auth_token: Some("token".to_string()),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn request_is_sent_while_rollback_pending() {
+            let mut model = waiting_for_new_ip(60);
+
+            let _ = handle_trigger_rollback_now(&mut model);
+
+            assert!(model.is_loading);
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { .. }
+            ));
+        }
+
+        #[test]
+        fn ignored_if_rollback_disabled() {
+            let mut model = waiting_for_new_ip(0);
+
+            let _ = handle_trigger_rollback_now(&mut model);
+
+            assert!(!model.is_loading);
+        }
+
+        #[test]
+        fn ignored_if_not_waiting_for_new_ip() {
+            let mut model = Model {
+                auth_token: Some("token".to_string()),
+                ..Default::default()
+            };
+
+            let _ = handle_trigger_rollback_now(&mut model);
+
+            assert!(!model.is_loading);
+            assert_eq!(model.network_change_state, NetworkChangeState::Idle);
+        }
+
+        #[test]
+        fn success_transitions_to_waiting_for_old_ip() {
+            let mut model = waiting_for_new_ip(60);
+            model.is_loading = true;
+
+            let _ = crate::update::device::handle(
+                DeviceEvent::TriggerRollbackNowResponse(Ok(RollbackNowResponse {
+                    rollback_executed: true,
+                })),
+                &mut model,
+            );
+
+            assert!(!model.is_loading);
+            assert_eq!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForOldIp {
+                    old_ip: "192.168.1.100".to_string(),
+                    ui_port: 443,
+                    attempt: 0,
+                }
+            );
+            assert!(model.overlay_spinner.is_visible());
+            assert!(!model.overlay_spinner.timed_out());
+        }
+
+        #[test]
+        fn no_executed_rollback_keeps_waiting_for_new_ip() {
+            let mut model = waiting_for_new_ip(60);
+            model.is_loading = true;
+
+            let _ = crate::update::device::handle(
+                DeviceEvent::TriggerRollbackNowResponse(Ok(RollbackNowResponse {
+                    rollback_executed: false,
+                })),
+                &mut model,
+            );
+
+            assert!(!model.is_loading);
+            assert!(model.error_message.is_some());
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { .. }
+            ));
+        }
+
+        #[test]
+        fn error_keeps_waiting_for_new_ip() {
+            let mut model = waiting_for_new_ip(60);
+            model.is_loading = true;
+
+            let _ = crate::update::device::handle(
+                DeviceEvent::TriggerRollbackNowResponse(Err("Rollback failed".to_string())),
+                &mut model,
+            );
+
+            assert!(!model.is_loading);
+            assert!(model.error_message.is_some());
+            assert!(matches!(
+                model.network_change_state,
+                NetworkChangeState::WaitingForNewIp { .. }
+            ));
+        }
+    }
+
     mod rollback_acknowledgment {
         use super::*;
 
@@ -500,6 +910,48 @@ mod tests {
             assert!(model.update_validation_status.is_none());
         }
 
+        #[test]
+        fn clears_failed_validation_from_pending_acknowledgments() {
+            let mut model = Model {
+                update_validation_status: Some(UpdateValidationStatus {
+                    status: "Recovered".to_string(),
+                }),
+                healthcheck: Some(HealthcheckInfo {
+                    update_validation_acked: false,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            assert_eq!(
+                model.pending_acknowledgments(),
+                vec![crate::types::PendingAcknowledgment::UpdateValidation]
+            );
+
+            let _ = handle_ack_update_validation(&mut model);
+
+            assert!(model.update_validation_status.is_none());
+            assert!(model.healthcheck.as_ref().unwrap().update_validation_acked);
+            assert!(model.pending_acknowledgments().is_empty());
+        }
+
+        #[test]
+        fn republished_failed_validation_stays_acknowledged() {
+            let mut model = Model {
+                update_validation_status: Some(UpdateValidationStatus {
+                    status: "Recovered".to_string(),
+                }),
+                healthcheck: Some(HealthcheckInfo::default()),
+                ..Default::default()
+            };
+
+            let _ = handle_ack_update_validation(&mut model);
+            model.update_validation_status = Some(UpdateValidationStatus {
+                status: "Recovered".to_string(),
+            });
+
+            assert!(model.pending_acknowledgments().is_empty());
+        }
+
         #[test]
         fn response_ok_stops_loading() {
             let mut model = Model {
@@ -1,13 +1,20 @@
 use crux_core::Command;
 
 use crate::model::Model;
-use crate::types::{DeviceOperationState, OverlaySpinnerState};
+use crate::types::{DeviceOperation, DeviceOperationState, HealthcheckInfo, OverlaySpinnerState};
 use crate::Effect;
 
 pub const REBOOT_TIMEOUT_SECS: u32 = 300; // 5 minutes
 pub const FACTORY_RESET_TIMEOUT_SECS: u32 = 600; // 10 minutes
 pub const FIRMWARE_UPDATE_TIMEOUT_SECS: u32 = 600; // 10 minutes
 
+/// Interval of the reconnection check ticks sent by the shell
+pub const RECONNECTION_CHECK_INTERVAL_SECS: u32 = 5;
+
+/// Upper bound of the reconnection attempt counter, covers the longest reconnection timeout
+pub const MAX_RECONNECTION_ATTEMPTS: u32 =
+    FACTORY_RESET_TIMEOUT_SECS.div_ceil(RECONNECTION_CHECK_INTERVAL_SECS);
+
 /// Check if an error message indicates a network error
 pub fn is_network_error(error: &str) -> bool {
     let e_lower = error.to_lowercase();
@@ -16,52 +23,92 @@ pub fn is_network_error(error: &str) -> bool {
         || error.contains("IO error")
 }
 
+/// Maximum time to wait for the device to come back online after an operation
+pub fn reconnection_timeout_secs(operation: DeviceOperation) -> u32 {
+    match operation {
+        DeviceOperation::Reboot => REBOOT_TIMEOUT_SECS,
+        DeviceOperation::FactoryReset => FACTORY_RESET_TIMEOUT_SECS,
+        DeviceOperation::Update => FIRMWARE_UPDATE_TIMEOUT_SECS,
+    }
+}
+
+/// Overlay title shown while waiting for the device to come back
+pub fn overlay_title(operation: DeviceOperation) -> &'static str {
+    match operation {
+        DeviceOperation::Reboot => "Device is rebooting",
+        DeviceOperation::FactoryReset => "The device is resetting",
+        DeviceOperation::Update => "Rebooting to new firmware",
+    }
+}
+
+/// Overlay text shown while waiting for the device to come back, if any
+pub fn overlay_text(operation: DeviceOperation) -> Option<&'static str> {
+    match operation {
+        DeviceOperation::Reboot => None,
+        DeviceOperation::FactoryReset => Some(
+            "Please wait while the device resets. The app will be temporarily \
+             removed and reinstalled automatically when the device is back online.",
+        ),
+        DeviceOperation::Update => Some("The device is restarting with the updated firmware."),
+    }
+}
+
+/// Check if an operation is complete based on a healthcheck result.
+/// Reboot and factory reset are done once the device responds, an update
+/// additionally requires a final update validation status.
+///
+/// `complete_statuses` overrides [`crate::types::DEFAULT_UPDATE_COMPLETE_STATUSES`] if configured.
+pub fn is_operation_complete(
+    operation: DeviceOperation,
+    result: &Result<HealthcheckInfo, String>,
+    complete_statuses: Option<&[String]>,
+) -> bool {
+    match operation {
+        DeviceOperation::Update => result
+            .as_ref()
+            .is_ok_and(|info| is_update_complete(info, complete_statuses)),
+        DeviceOperation::Reboot | DeviceOperation::FactoryReset => result.is_ok(),
+    }
+}
+
 /// Check if an update is complete based on healthcheck status
-pub fn is_update_complete(info: &crate::types::HealthcheckInfo) -> bool {
-    let status = &info.update_validation_status.status;
-    status == "Succeeded" || status == "Recovered" || status == "NoUpdate"
+///
+/// `complete_statuses` overrides [`crate::types::DEFAULT_UPDATE_COMPLETE_STATUSES`] if configured.
+pub fn is_update_complete(info: &HealthcheckInfo, complete_statuses: Option<&[String]>) -> bool {
+    info.update_validation_status.is_complete(complete_statuses)
 }
 
-/// Returns true when an actual firmware update completed (succeeded or rolled back).
+/// Returns true when an actual firmware update completed (e.g. succeeded or rolled back).
 /// Distinct from `is_update_complete`, which also matches `"NoUpdate"` for polling purposes.
-pub fn is_actual_update_result(info: &crate::types::HealthcheckInfo) -> bool {
-    let status = &info.update_validation_status.status;
-    status == "Succeeded" || status == "Recovered"
+///
+/// `complete_statuses` overrides [`crate::types::DEFAULT_UPDATE_COMPLETE_STATUSES`] if configured.
+pub fn is_actual_update_result(
+    info: &HealthcheckInfo,
+    complete_statuses: Option<&[String]>,
+) -> bool {
+    info.update_validation_status
+        .is_update_result(complete_statuses)
 }
 
 /// Generic handler for device operation responses (reboot, factory reset, update)
 pub fn handle_device_operation_response(
     result: Result<(), String>,
     model: &mut Model,
-    operation: DeviceOperationState,
+    operation: DeviceOperation,
     success_msg: &str,
     connection_lost_msg: &str,
-    overlay_title: &str,
-    overlay_text: Option<String>,
 ) -> Command<Effect, crate::Event> {
     model.stop_loading();
 
     let is_network_err = result.as_ref().is_err_and(|e| is_network_error(e));
 
     if result.is_ok() || is_network_err {
-        model.success_message = Some(if is_network_err {
-            connection_lost_msg.to_string()
+        model.set_success(if is_network_err {
+            connection_lost_msg
         } else {
-            success_msg.to_string()
+            success_msg
         });
-        let timeout_secs = match &operation {
-            DeviceOperationState::FactoryResetting => FACTORY_RESET_TIMEOUT_SECS,
-            DeviceOperationState::Updating => FIRMWARE_UPDATE_TIMEOUT_SECS,
-            _ => REBOOT_TIMEOUT_SECS,
-        };
-        model.device_operation_state = operation;
-        model.reconnection_attempt = 0;
-        model.device_went_offline = false;
-        let mut spinner = OverlaySpinnerState::new(overlay_title).with_countdown(timeout_secs);
-        if let Some(text) = overlay_text {
-            spinner = spinner.with_text(text);
-        }
-        model.overlay_spinner = spinner;
+        start_waiting_for_device(model, operation);
     } else if let Err(e) = result {
         model.set_error(e);
         model.overlay_spinner.clear();
@@ -69,3 +116,396 @@ pub fn handle_device_operation_response(
 
     crux_core::render::render()
 }
+
+/// Countdown of the wait for the device coming back from an operation
+///
+/// An update is validated by the device service within its wait online timeout,
+/// so the countdown follows this timeout if known and falls back to
+/// [`FIRMWARE_UPDATE_TIMEOUT_SECS`] otherwise.
+fn countdown_secs(model: &Model, operation: DeviceOperation) -> u32 {
+    let validation_timeout_secs = model
+        .timeouts
+        .as_ref()
+        .map(|timeouts| &timeouts.wait_online_timeout)
+        .filter(|timeout| timeout.secs > 0)
+        .map(|timeout| timeout.secs + u64::from(timeout.nanos > 0));
+
+    match (operation, validation_timeout_secs) {
+        (DeviceOperation::Update, Some(secs)) => u32::try_from(secs).unwrap_or(u32::MAX),
+        _ => reconnection_timeout_secs(operation),
+    }
+}
+
+/// Handle an announced reconnection - wait for the device without a backend request
+///
+/// Ignored while another operation is pending, so its state isn't reset.
+pub fn handle_expect_reconnection(
+    model: &mut Model,
+    operation: DeviceOperation,
+) -> Command<Effect, crate::Event> {
+    if model.device_operation_state.pending_operation().is_some() {
+        return Command::done();
+    }
+
+    start_waiting_for_device(model, operation);
+    crux_core::render::render()
+}
+
+/// Enter the initial state of an operation and wait for the device to come back
+///
+/// Also used to resume waiting for an operation that is still in progress after
+/// a page reload.
+pub fn start_waiting_for_device(model: &mut Model, operation: DeviceOperation) {
+    model.device_operation_state = DeviceOperationState::started(operation);
+    model.reconnection_attempt = 0;
+    model.device_went_offline = false;
+    let mut spinner = OverlaySpinnerState::new(overlay_title(operation))
+        .with_countdown(countdown_secs(model, operation));
+    if let Some(text) = overlay_text(operation) {
+        spinner = spinner.with_text(text);
+    }
+    model.overlay_spinner = spinner;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::UpdateValidationStatus;
+
+    fn healthcheck_with_status(status: &str) -> HealthcheckInfo {
+        HealthcheckInfo {
+            update_validation_status: UpdateValidationStatus {
+                status: status.to_string(),
+            },
+            ..Default::default()
+        }
+    }
+
+    mod reconnection_timeout {
+        use super::*;
+
+        #[test]
+        fn maps_each_operation_to_its_timeout() {
+            assert_eq!(
+                reconnection_timeout_secs(DeviceOperation::Reboot),
+                REBOOT_TIMEOUT_SECS
+            );
+            assert_eq!(
+                reconnection_timeout_secs(DeviceOperation::FactoryReset),
+                FACTORY_RESET_TIMEOUT_SECS
+            );
+            assert_eq!(
+                reconnection_timeout_secs(DeviceOperation::Update),
+                FIRMWARE_UPDATE_TIMEOUT_SECS
+            );
+        }
+    }
+
+    mod reconnection_attempt {
+        use super::*;
+        use crate::events::DeviceEvent;
+        use crate::update::device::handle;
+
+        #[test]
+        fn max_attempts_cover_all_timeouts() {
+            for operation in [
+                DeviceOperation::Reboot,
+                DeviceOperation::FactoryReset,
+                DeviceOperation::Update,
+            ] {
+                assert!(
+                    MAX_RECONNECTION_ATTEMPTS * RECONNECTION_CHECK_INTERVAL_SECS
+                        >= reconnection_timeout_secs(operation)
+                );
+            }
+        }
+
+        #[test]
+        fn reboot_after_update_resets_counter() {
+            let mut model = Model {
+                device_operation_state: DeviceOperationState::ReconnectionSuccessful {
+                    operation: DeviceOperation::Update,
+                },
+                reconnection_attempt: 42,
+                ..Default::default()
+            };
+
+            let _ = handle(DeviceEvent::RebootResponse(Ok(())), &mut model);
+
+            assert_eq!(model.reconnection_attempt, 0);
+            assert_eq!(
+                model.device_operation_state,
+                DeviceOperationState::Rebooting
+            );
+        }
+
+        #[test]
+        fn connection_lost_on_request_resets_counter() {
+            let mut model = Model {
+                reconnection_attempt: 42,
+                ..Default::default()
+            };
+
+            let _ = handle(
+                DeviceEvent::RebootResponse(Err("Failed to fetch".to_string())),
+                &mut model,
+            );
+
+            assert_eq!(model.reconnection_attempt, 0);
+        }
+    }
+
+    mod countdown {
+        use super::*;
+        use crate::types::{Duration, Timeouts};
+
+        fn model_with_wait_online_timeout(secs: u64, nanos: u32) -> Model {
+            Model {
+                timeouts: Some(Timeouts {
+                    wait_online_timeout: Duration { nanos, secs },
+                }),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn update_countdown_initializes_from_wait_online_timeout() {
+            let mut model = model_with_wait_online_timeout(300, 0);
+
+            start_waiting_for_device(&mut model, DeviceOperation::Update);
+
+            assert_eq!(model.overlay_spinner.countdown_seconds(), Some(300));
+        }
+
+        #[test]
+        fn fractional_wait_online_timeout_is_rounded_up() {
+            let mut model = model_with_wait_online_timeout(300, 500_000_000);
+
+            start_waiting_for_device(&mut model, DeviceOperation::Update);
+
+            assert_eq!(model.overlay_spinner.countdown_seconds(), Some(301));
+        }
+
+        #[test]
+        fn update_countdown_falls_back_without_timeout() {
+            let mut model = Model::default();
+
+            start_waiting_for_device(&mut model, DeviceOperation::Update);
+
+            assert_eq!(
+                model.overlay_spinner.countdown_seconds(),
+                Some(FIRMWARE_UPDATE_TIMEOUT_SECS)
+            );
+        }
+
+        #[test]
+        fn update_countdown_falls_back_for_zero_timeout() {
+            let mut model = model_with_wait_online_timeout(0, 0);
+
+            start_waiting_for_device(&mut model, DeviceOperation::Update);
+
+            assert_eq!(
+                model.overlay_spinner.countdown_seconds(),
+                Some(FIRMWARE_UPDATE_TIMEOUT_SECS)
+            );
+        }
+
+        #[test]
+        fn other_operations_ignore_wait_online_timeout() {
+            let mut model = model_with_wait_online_timeout(30, 0);
+
+            start_waiting_for_device(&mut model, DeviceOperation::Reboot);
+
+            assert_eq!(
+                model.overlay_spinner.countdown_seconds(),
+                Some(REBOOT_TIMEOUT_SECS)
+            );
+        }
+    }
+
+    mod expect_reconnection {
+        use super::*;
+        use crate::events::DeviceEvent;
+        use crate::update::device::handle;
+
+        #[test]
+        fn enters_waiting_state() {
+            let mut model = Model::default();
+
+            let _ = handle(
+                DeviceEvent::ExpectReconnection {
+                    operation: DeviceOperation::Reboot,
+                },
+                &mut model,
+            );
+
+            assert_eq!(
+                model.device_operation_state,
+                DeviceOperationState::Rebooting
+            );
+            assert!(model.overlay_spinner.is_visible());
+        }
+
+        #[test]
+        fn completes_on_healthcheck_success_after_failure() {
+            let mut model = Model::default();
+
+            let _ = handle(
+                DeviceEvent::ExpectReconnection {
+                    operation: DeviceOperation::Reboot,
+                },
+                &mut model,
+            );
+            let _ = handle(
+                DeviceEvent::HealthcheckResponse(Err("Failed to fetch".to_string())),
+                &mut model,
+            );
+            let _ = handle(
+                DeviceEvent::HealthcheckResponse(Ok(healthcheck_with_status("NoUpdate"))),
+                &mut model,
+            );
+
+            assert_eq!(
+                model.device_operation_state,
+                DeviceOperationState::ReconnectionSuccessful {
+                    operation: DeviceOperation::Reboot
+                }
+            );
+        }
+
+        #[test]
+        fn ignored_while_operation_is_pending() {
+            let mut model = Model {
+                device_operation_state: DeviceOperationState::WaitingReconnection {
+                    operation: DeviceOperation::Update,
+                    attempt: 3,
+                },
+                device_went_offline: true,
+                ..Default::default()
+            };
+
+            let _ = handle(
+                DeviceEvent::ExpectReconnection {
+                    operation: DeviceOperation::Reboot,
+                },
+                &mut model,
+            );
+
+            assert_eq!(
+                model.device_operation_state,
+                DeviceOperationState::WaitingReconnection {
+                    operation: DeviceOperation::Update,
+                    attempt: 3,
+                }
+            );
+            assert!(model.device_went_offline);
+        }
+    }
+
+    mod operation_complete {
+        use super::*;
+
+        #[test]
+        fn reboot_and_factory_reset_complete_on_any_healthcheck() {
+            let result = Ok(healthcheck_with_status("InProgress"));
+
+            assert!(is_operation_complete(
+                DeviceOperation::Reboot,
+                &result,
+                None
+            ));
+            assert!(is_operation_complete(
+                DeviceOperation::FactoryReset,
+                &result,
+                None
+            ));
+        }
+
+        #[test]
+        fn update_requires_final_status() {
+            assert!(!is_operation_complete(
+                DeviceOperation::Update,
+                &Ok(healthcheck_with_status("InProgress")),
+                None
+            ));
+            for status in ["Succeeded", "Recovered", "NoUpdate"] {
+                assert!(is_operation_complete(
+                    DeviceOperation::Update,
+                    &Ok(healthcheck_with_status(status)),
+                    None
+                ));
+            }
+        }
+
+        #[test]
+        fn configured_extra_status_completes_update() {
+            let statuses = ["Succeeded".to_string(), "SucceededWithWarnings".to_string()];
+            let result = Ok(healthcheck_with_status("SucceededWithWarnings"));
+
+            assert!(!is_operation_complete(
+                DeviceOperation::Update,
+                &result,
+                None
+            ));
+            assert!(is_operation_complete(
+                DeviceOperation::Update,
+                &result,
+                Some(&statuses)
+            ));
+        }
+
+        #[test]
+        fn configured_statuses_replace_defaults() {
+            let statuses = ["Succeeded".to_string()];
+
+            assert!(!is_operation_complete(
+                DeviceOperation::Update,
+                &Ok(healthcheck_with_status("NoUpdate")),
+                Some(&statuses)
+            ));
+        }
+
+        #[test]
+        fn actual_update_result_follows_configured_statuses() {
+            let statuses = ["SucceededWithWarnings".to_string(), "NoUpdate".to_string()];
+
+            assert!(is_actual_update_result(
+                &healthcheck_with_status("SucceededWithWarnings"),
+                Some(&statuses)
+            ));
+            assert!(!is_actual_update_result(
+                &healthcheck_with_status("Succeeded"),
+                Some(&statuses)
+            ));
+            assert!(!is_actual_update_result(
+                &healthcheck_with_status("NoUpdate"),
+                Some(&statuses)
+            ));
+            assert!(is_actual_update_result(
+                &healthcheck_with_status("Recovered"),
+                None
+            ));
+        }
+
+        #[test]
+        fn failed_healthcheck_never_completes() {
+            let result = Err("offline".to_string());
+
+            assert!(!is_operation_complete(
+                DeviceOperation::Reboot,
+                &result,
+                None
+            ));
+            assert!(!is_operation_complete(
+                DeviceOperation::FactoryReset,
+                &result,
+                None
+            ));
+            assert!(!is_operation_complete(
+                DeviceOperation::Update,
+                &result,
+                None
+            ));
+        }
+    }
+}
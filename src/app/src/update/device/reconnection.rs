@@ -5,26 +5,32 @@ use crate::{
     http_get,
     http_helpers::build_url,
     model::Model,
-    types::{DeviceOperationState, NetworkChangeState, OverlaySpinnerState, UploadState},
+    types::{
+        DeviceOperation, DeviceOperationState, NetworkChangeState, OverlaySpinnerState, UploadState,
+    },
     Effect,
 };
 
-use super::operations::{is_actual_update_result, is_update_complete};
+use super::network::config::NETWORK_CONFIG_SUCCESS;
+use super::operations::{
+    is_actual_update_result, is_network_error, is_operation_complete, start_waiting_for_device,
+    MAX_RECONNECTION_ATTEMPTS,
+};
+
+/// Consecutive unreachable healthchecks outside an operation before the connection counts as lost
+pub const CONNECTION_LOST_THRESHOLD: u32 = 3;
 
 /// Handle reconnection check tick - polls healthcheck endpoint
 pub fn handle_reconnection_check_tick(model: &mut Model) -> Command<Effect, Event> {
     // Only check if we're waiting for reconnection
-    if !matches!(
-        model.device_operation_state,
-        DeviceOperationState::Rebooting
-            | DeviceOperationState::FactoryResetting
-            | DeviceOperationState::Updating
-            | DeviceOperationState::WaitingReconnection { .. }
-    ) {
+    if model.device_operation_state.pending_operation().is_none() {
         return crux_core::render::render();
     }
 
-    model.reconnection_attempt += 1;
+    model.reconnection_attempt = model
+        .reconnection_attempt
+        .saturating_add(1)
+        .min(MAX_RECONNECTION_ATTEMPTS);
 
     // Send healthcheck request
     http_get!(
@@ -36,26 +42,42 @@ pub fn handle_reconnection_check_tick(model: &mut Model) -> Command<Effect, Even
     )
 }
 
+/// Handle connection check tick - polls healthcheck endpoint outside device operations
+///
+/// Reconnection and network change polling send their own healthchecks, so
+/// nothing is done while either is in progress.
+pub fn handle_connection_check_tick(model: &mut Model) -> Command<Effect, Event> {
+    if !is_monitoring_connection(model) {
+        return crux_core::render::render();
+    }
+
+    http_get!(
+        Device,
+        DeviceEvent,
+        &build_url("/healthcheck"),
+        HealthcheckResponse,
+        crate::types::HealthcheckInfo
+    )
+}
+
+/// Whether healthcheck results are tracked for connection loss
+fn is_monitoring_connection(model: &Model) -> bool {
+    model.device_operation_state.pending_operation().is_none()
+        && model.network_change_state == NetworkChangeState::Idle
+}
+
 /// Handle reconnection timeout - device didn't come back online
 pub fn handle_reconnection_timeout(model: &mut Model) -> Command<Effect, Event> {
     // Early return if not in a device operation state
-    if !matches!(
-        &model.device_operation_state,
-        DeviceOperationState::Rebooting
-            | DeviceOperationState::FactoryResetting
-            | DeviceOperationState::Updating
-            | DeviceOperationState::WaitingReconnection { .. }
-    ) {
+    let Some(operation) = model.device_operation_state.pending_operation() else {
         return crux_core::render::render();
-    }
-
-    let operation = model.device_operation_state.operation_name();
+    };
 
     let timeout_msg =
         "Device did not come back online. You may need to re-accept the security certificate.";
 
     model.device_operation_state = DeviceOperationState::ReconnectionFailed {
-        operation: operation.clone(),
+        operation,
         reason: timeout_msg.to_string(),
     };
 
@@ -66,6 +88,40 @@ pub fn handle_reconnection_timeout(model: &mut Model) -> Command<Effect, Event>
     crux_core::render::render()
 }
 
+/// Ask the backend for a device operation still in progress, e.g. after a page reload
+pub fn check_pending_operation() -> Command<Effect, Event> {
+    http_get!(
+        Device,
+        DeviceEvent,
+        &build_url("/healthcheck"),
+        ResumeOperationResponse,
+        crate::types::HealthcheckInfo
+    )
+}
+
+/// Handle the healthcheck response of the initial pending operation check
+///
+/// Restores the waiting state of an operation the backend still reports as in
+/// progress, so reconnection polling resumes. The response is then processed
+/// like any other healthcheck.
+pub fn handle_resume_operation_response(
+    result: Result<crate::types::HealthcheckInfo, String>,
+    model: &mut Model,
+) -> Command<Effect, Event> {
+    if let Ok(crate::types::HealthcheckInfo {
+        last_operation: Some(operation),
+        ..
+    }) = &result
+    {
+        if model.device_operation_state == DeviceOperationState::Idle {
+            log::info!("Resuming {operation} after reload");
+            start_waiting_for_device(model, *operation);
+        }
+    }
+
+    handle_healthcheck_response(result, model)
+}
+
 /// Handle healthcheck response - manages reconnection and network change state machines
 pub fn handle_healthcheck_response(
     result: Result<crate::types::HealthcheckInfo, String>,
@@ -74,104 +130,71 @@ pub fn handle_healthcheck_response(
     // Update healthcheck info if success
     if let Ok(info) = &result {
         model.healthcheck = Some(info.clone());
-    }
-
-    // Handle reconnection state machine
-    match &model.device_operation_state {
-        DeviceOperationState::Rebooting
-        | DeviceOperationState::FactoryResetting
-        | DeviceOperationState::Updating => {
-            // First check - if it fails, mark as "waiting"
-            let is_updating =
-                matches!(model.device_operation_state, DeviceOperationState::Updating);
-
-            // For updates, we also check the status field
-            let update_done = if is_updating {
-                result.as_ref().ok().is_some_and(is_update_complete)
-            } else {
-                result.is_ok()
-            };
 
-            if result.is_err() {
-                // Device went offline - mark it
-                model.device_went_offline = true;
-                // Transition to waiting
-                let operation = model.device_operation_state.operation_name();
-                model.device_operation_state = DeviceOperationState::WaitingReconnection {
-                    operation,
-                    attempt: model.reconnection_attempt,
-                };
-            } else if (update_done || !is_updating) && model.device_went_offline {
-                // Device came back online after going offline - reconnection successful
-                let operation = model.device_operation_state.operation_name();
-                model.device_operation_state =
-                    DeviceOperationState::ReconnectionSuccessful { operation };
-
-                // Invalidate session as backend restart clears tokens
-                model.invalidate_session();
-
-                // Clear overlay spinner
-                model.overlay_spinner.clear();
-
-                // Clear stale firmware page state so the update page is fresh on re-login.
-                // Only when an actual update ran — "NoUpdate" preserves the loaded manifest.
-                if is_updating {
-                    if let Ok(info) = &result {
-                        if is_actual_update_result(info) {
-                            model.update_manifest = None;
-                            model.firmware_upload_state = UploadState::Idle;
-                        }
-                    }
-                }
+        // Show install progress reported by the device service while an update is running
+        if model.device_operation_state.pending_operation() == Some(DeviceOperation::Update) {
+            if let Some(progress) = info.update_progress {
+                model.overlay_spinner.set_progress(progress);
             }
-            // else: healthcheck succeeded but device never went offline - keep checking
         }
-        DeviceOperationState::WaitingReconnection { operation, .. } => {
-            let is_update = operation == "Update";
+    }
 
-            if result.is_err() {
-                // Still offline - mark it
-                model.device_went_offline = true;
-                // Update attempt count
-                model.device_operation_state = DeviceOperationState::WaitingReconnection {
-                    operation: operation.clone(),
-                    attempt: model.reconnection_attempt,
-                };
-            } else {
-                // Consider update done when status is Succeeded, Recovered, or NoUpdate
-                let update_done = if is_update {
-                    result.as_ref().ok().is_some_and(is_update_complete)
-                } else {
-                    true
-                };
+    // Track connection loss outside device operations and network changes,
+    // an HTTP error status (e.g. 503 or 401) still proves the device is reachable
+    if is_monitoring_connection(model) {
+        if result.as_ref().is_err_and(|e| is_network_error(e)) {
+            model.healthcheck_failures += 1;
+            if model.healthcheck_failures >= CONNECTION_LOST_THRESHOLD {
+                model.connection_lost = true;
+            }
+        } else {
+            model.healthcheck_failures = 0;
+            model.connection_lost = false;
+        }
+    } else {
+        // operations and network changes report connectivity on their own
+        model.healthcheck_failures = 0;
+        model.connection_lost = false;
+    }
 
-                if update_done && model.device_went_offline {
-                    // Success! Device is back online (or update finished) AND it went offline
-                    model.device_operation_state = DeviceOperationState::ReconnectionSuccessful {
-                        operation: operation.clone(),
-                    };
-
-                    // Invalidate session as backend restart clears tokens
-                    model.invalidate_session();
-
-                    // Clear overlay spinner
-                    model.overlay_spinner.clear();
-
-                    // Clear stale firmware page state so the update page is fresh on re-login.
-                    // Only when an actual update ran — "NoUpdate" preserves the loaded manifest.
-                    if is_update {
-                        if let Ok(info) = &result {
-                            if is_actual_update_result(info) {
-                                model.update_manifest = None;
-                                model.firmware_upload_state = UploadState::Idle;
-                            }
-                        }
+    // Handle reconnection state machine
+    if let Some(operation) = model.device_operation_state.pending_operation() {
+        if result.is_err() {
+            // Device went offline (or is still offline) - mark it
+            model.device_went_offline = true;
+            // Transition to waiting and update attempt count
+            model.device_operation_state = DeviceOperationState::WaitingReconnection {
+                operation,
+                attempt: model.reconnection_attempt,
+            };
+        } else if is_operation_complete(
+            operation,
+            &result,
+            model.update_complete_statuses.as_deref(),
+        ) && model.device_went_offline
+        {
+            // Device came back online after going offline - reconnection successful
+            model.device_operation_state =
+                DeviceOperationState::ReconnectionSuccessful { operation };
+
+            // Invalidate session as backend restart clears tokens
+            model.invalidate_session();
+
+            // Clear overlay spinner
+            model.overlay_spinner.clear();
+
+            // Clear stale firmware page state so the update page is fresh on re-login.
+            // Only when an actual update ran — "NoUpdate" preserves the loaded manifest.
+            if operation == DeviceOperation::Update {
+                if let Ok(info) = &result {
+                    if is_actual_update_result(info, model.update_complete_statuses.as_deref()) {
+                        model.update_manifest = None;
+                        model.firmware_upload_state = UploadState::Idle;
                     }
                 }
-                // else: healthcheck succeeded but device never went offline - keep checking
             }
         }
-        _ => {} // Do nothing for other states
+        // else: healthcheck succeeded but device never went offline (or update still running) - keep checking
     }
 
     // Handle network change state machine for IP change polling
@@ -188,25 +211,33 @@ pub fn handle_healthcheck_response(
                     new_ip: new_ip.clone(),
                     ui_port: port,
                 };
-                // Clear any leftover messages
-                model.success_message = None;
+                // The change is confirmed now, the success message was deferred until here
                 model.error_message = None;
+                model.set_success(NETWORK_CONFIG_SUCCESS);
                 // Update overlay for redirect
                 model.overlay_spinner = OverlaySpinnerState::new("Network settings applied")
                     .with_text(format!("Redirecting to new IP: {new_ip}:{port}"));
             }
         }
         NetworkChangeState::WaitingForOldIp { .. } => {
-            if result.is_ok() {
+            if let Ok(info) = &result {
                 // Old IP is reachable - Rollback successful
+                if info.network_rollback_occurred {
+                    log::info!("Device reachable at old IP after network rollback");
+                } else {
+                    log::warn!("Device reachable at old IP, but no network rollback reported");
+                }
                 model.network_change_state = NetworkChangeState::Idle;
+                model.new_ip_timeout_secs = None;
+                model.new_ip_check_start_ms = None;
                 model.overlay_spinner.clear();
                 model.invalidate_session();
                 // Clear any leftover messages
                 model.success_message = None;
                 model.error_message = None;
                 // Do not show success message here. The "Network Settings Rolled Back" modal
-                // will be triggered by the `network_rollback_occurred` flag in the healthcheck response.
+                // is triggered by the `network_rollback_occurred` flag of the stored healthcheck,
+                // which also lists the rollback in the pending acknowledgments.
             }
         }
         _ => {}
@@ -281,6 +312,19 @@ mod tests {
             assert_eq!(model.reconnection_attempt, 1);
         }
 
+        #[test]
+        fn attempt_counter_is_clamped() {
+            let mut model = Model {
+                device_operation_state: DeviceOperationState::Rebooting,
+                reconnection_attempt: MAX_RECONNECTION_ATTEMPTS,
+                ..Default::default()
+            };
+
+            let _ = handle_reconnection_check_tick(&mut model);
+
+            assert_eq!(model.reconnection_attempt, MAX_RECONNECTION_ATTEMPTS);
+        }
+
         #[test]
         fn does_not_increment_when_idle() {
             let mut model = Model {
@@ -314,7 +358,7 @@ mod tests {
             if let DeviceOperationState::ReconnectionFailed { operation, reason } =
                 &model.device_operation_state
             {
-                assert_eq!(operation, "Reboot");
+                assert_eq!(*operation, DeviceOperation::Reboot);
                 assert!(reason.contains("security certificate"));
             }
             assert!(model.overlay_spinner.timed_out());
@@ -336,7 +380,7 @@ mod tests {
             if let DeviceOperationState::ReconnectionFailed { operation, reason } =
                 &model.device_operation_state
             {
-                assert_eq!(operation, "Factory Reset");
+                assert_eq!(*operation, DeviceOperation::FactoryReset);
                 assert!(reason.contains("security certificate"));
             }
         }
@@ -357,7 +401,7 @@ mod tests {
             if let DeviceOperationState::ReconnectionFailed { operation, .. } =
                 &model.device_operation_state
             {
-                assert_eq!(operation, "Update");
+                assert_eq!(*operation, DeviceOperation::Update);
             }
         }
 
@@ -400,7 +444,7 @@ mod tests {
                 if let DeviceOperationState::WaitingReconnection { operation, attempt } =
                     &model.device_operation_state
                 {
-                    assert_eq!(operation, "Reboot");
+                    assert_eq!(*operation, DeviceOperation::Reboot);
                     assert_eq!(*attempt, 2);
                 }
             }
@@ -487,7 +531,7 @@ mod tests {
                 if let DeviceOperationState::ReconnectionSuccessful { operation } =
                     &model.device_operation_state
                 {
-                    assert_eq!(operation, "Factory Reset");
+                    assert_eq!(*operation, DeviceOperation::FactoryReset);
                 }
                 assert!(!model.is_authenticated);
             }
@@ -496,6 +540,38 @@ mod tests {
         mod update {
             use super::*;
 
+            #[test]
+            fn update_progress_is_shown_on_overlay_spinner() {
+                let mut model = Model {
+                    device_operation_state: DeviceOperationState::Updating,
+                    ..Default::default()
+                };
+                let info = HealthcheckInfo {
+                    update_progress: Some(42),
+                    ..create_healthcheck("InProgress", false)
+                };
+
+                let _ = handle_healthcheck_response(Ok(info), &mut model);
+
+                assert_eq!(model.overlay_spinner.progress(), Some(42));
+            }
+
+            #[test]
+            fn update_progress_is_ignored_for_other_operations() {
+                let mut model = Model {
+                    device_operation_state: DeviceOperationState::Rebooting,
+                    ..Default::default()
+                };
+                let info = HealthcheckInfo {
+                    update_progress: Some(42),
+                    ..create_healthcheck("InProgress", false)
+                };
+
+                let _ = handle_healthcheck_response(Ok(info), &mut model);
+
+                assert_eq!(model.overlay_spinner.progress(), None);
+            }
+
             #[test]
             fn error_marks_device_offline() {
                 let mut model = Model {
@@ -575,6 +651,29 @@ mod tests {
                 ));
             }
 
+            #[test]
+            fn success_with_configured_extra_status_after_offline_completes() {
+                let mut model = Model {
+                    device_operation_state: DeviceOperationState::Updating,
+                    device_went_offline: true,
+                    update_complete_statuses: Some(vec![
+                        "Succeeded".to_string(),
+                        "SucceededWithWarnings".to_string(),
+                    ]),
+                    ..Default::default()
+                };
+
+                let _ = handle_healthcheck_response(
+                    Ok(create_healthcheck("SucceededWithWarnings", false)),
+                    &mut model,
+                );
+
+                assert!(matches!(
+                    model.device_operation_state,
+                    DeviceOperationState::ReconnectionSuccessful { .. }
+                ));
+            }
+
             #[test]
             fn success_with_incomplete_status_keeps_checking() {
                 let mut model = Model {
@@ -660,7 +759,7 @@ mod tests {
             fn error_updates_attempt_count() {
                 let mut model = Model {
                     device_operation_state: DeviceOperationState::WaitingReconnection {
-                        operation: "Reboot".to_string(),
+                        operation: DeviceOperation::Reboot,
                         attempt: 5,
                     },
                     reconnection_attempt: 10,
@@ -686,7 +785,7 @@ mod tests {
             fn success_for_non_update_operation_completes() {
                 let mut model = Model {
                     device_operation_state: DeviceOperationState::WaitingReconnection {
-                        operation: "Reboot".to_string(),
+                        operation: DeviceOperation::Reboot,
                         attempt: 5,
                     },
                     device_went_offline: true,
@@ -709,7 +808,7 @@ mod tests {
             fn success_for_update_with_completed_status() {
                 let mut model = Model {
                     device_operation_state: DeviceOperationState::WaitingReconnection {
-                        operation: "Update".to_string(),
+                        operation: DeviceOperation::Update,
                         attempt: 3,
                     },
                     device_went_offline: true,
@@ -731,7 +830,7 @@ mod tests {
             fn success_for_update_with_incomplete_status_keeps_waiting() {
                 let mut model = Model {
                     device_operation_state: DeviceOperationState::WaitingReconnection {
-                        operation: "Update".to_string(),
+                        operation: DeviceOperation::Update,
                         attempt: 3,
                     },
                     device_went_offline: true,
@@ -753,7 +852,7 @@ mod tests {
             fn clears_manifest_and_upload_state_on_succeeded_from_waiting() {
                 let mut model = Model {
                     device_operation_state: DeviceOperationState::WaitingReconnection {
-                        operation: "Update".to_string(),
+                        operation: DeviceOperation::Update,
                         attempt: 3,
                     },
                     device_went_offline: true,
@@ -775,7 +874,7 @@ mod tests {
             fn clears_manifest_and_upload_state_on_recovered_from_waiting() {
                 let mut model = Model {
                     device_operation_state: DeviceOperationState::WaitingReconnection {
-                        operation: "Update".to_string(),
+                        operation: DeviceOperation::Update,
                         attempt: 3,
                     },
                     device_went_offline: true,
@@ -798,7 +897,7 @@ mod tests {
                 let manifest = crate::types::UpdateManifest::default();
                 let mut model = Model {
                     device_operation_state: DeviceOperationState::WaitingReconnection {
-                        operation: "Update".to_string(),
+                        operation: DeviceOperation::Update,
                         attempt: 3,
                     },
                     device_went_offline: true,
@@ -853,6 +952,98 @@ mod tests {
                 assert!(model.overlay_spinner.is_visible());
             }
 
+            #[test]
+            fn reachable_new_ip_sets_deferred_success_message() {
+                let mut model = Model {
+                    network_change_state: NetworkChangeState::WaitingForNewIp {
+                        new_ip: "192.168.1.101".to_string(),
+                        old_ip: "192.168.1.100".to_string(),
+                        attempt: 1,
+                        rollback_timeout_seconds: 60,
+                        ui_port: 443,
+                        switching_to_dhcp: false,
+                    },
+                    ..Default::default()
+                };
+
+                let _ =
+                    handle_healthcheck_response(Err("Connection failed".to_string()), &mut model);
+                assert!(model.success_message.is_none());
+
+                let _ =
+                    handle_healthcheck_response(Ok(create_healthcheck("valid", false)), &mut model);
+                assert_eq!(
+                    model.success_message.as_deref(),
+                    Some(NETWORK_CONFIG_SUCCESS)
+                );
+            }
+
+            fn waiting_for_old_ip() -> Model {
+                Model {
+                    network_change_state: NetworkChangeState::WaitingForOldIp {
+                        old_ip: "192.168.1.100".to_string(),
+                        ui_port: 443,
+                        attempt: 3,
+                    },
+                    overlay_spinner: OverlaySpinnerState::new("Restoring previous settings"),
+                    is_authenticated: true,
+                    auth_token: Some("token".to_string()),
+                    ..Default::default()
+                }
+            }
+
+            #[test]
+            fn old_ip_responding_after_rollback_transitions_to_idle() {
+                let mut model = waiting_for_old_ip();
+                let info = HealthcheckInfo {
+                    network_rollback_occurred: true,
+                    ..create_healthcheck("valid", false)
+                };
+
+                let _ = handle_healthcheck_response(Ok(info), &mut model);
+
+                assert_eq!(model.network_change_state, NetworkChangeState::Idle);
+                assert!(!model.overlay_spinner.is_visible());
+                assert!(!model.is_authenticated);
+                assert!(model.auth_token.is_none());
+                assert!(model.success_message.is_none());
+                assert!(model.error_message.is_none());
+            }
+
+            #[test]
+            fn old_ip_responding_after_rollback_surfaces_rollback_notice() {
+                let mut model = waiting_for_old_ip();
+                let info = HealthcheckInfo {
+                    network_rollback_occurred: true,
+                    ..create_healthcheck("valid", false)
+                };
+
+                let _ = handle_healthcheck_response(Ok(info), &mut model);
+
+                assert!(model
+                    .healthcheck
+                    .as_ref()
+                    .is_some_and(|h| h.network_rollback_occurred));
+                assert!(model
+                    .pending_acknowledgments()
+                    .contains(&crate::types::PendingAcknowledgment::NetworkRollback));
+            }
+
+            #[test]
+            fn old_ip_not_responding_keeps_waiting() {
+                let mut model = waiting_for_old_ip();
+
+                let _ =
+                    handle_healthcheck_response(Err("Connection failed".to_string()), &mut model);
+
+                assert!(matches!(
+                    model.network_change_state,
+                    NetworkChangeState::WaitingForOldIp { .. }
+                ));
+                assert!(model.overlay_spinner.is_visible());
+                assert!(model.is_authenticated);
+            }
+
             #[test]
             fn failed_healthcheck_keeps_waiting() {
                 let mut model = Model {
@@ -877,4 +1068,187 @@ mod tests {
             }
         }
     }
+
+    mod connection_monitoring {
+        use super::*;
+
+        fn fail(model: &mut Model, times: u32) {
+            for _ in 0..times {
+                let _ = handle_healthcheck_response(Err("Failed to fetch".to_string()), model);
+            }
+        }
+
+        #[test]
+        fn consecutive_failures_set_connection_lost() {
+            let mut model = Model::default();
+
+            fail(&mut model, CONNECTION_LOST_THRESHOLD - 1);
+            assert!(!model.connection_lost);
+
+            fail(&mut model, 1);
+            assert!(model.connection_lost);
+            assert_eq!(model.healthcheck_failures, CONNECTION_LOST_THRESHOLD);
+        }
+
+        #[test]
+        fn success_clears_connection_lost() {
+            let mut model = Model::default();
+            fail(&mut model, CONNECTION_LOST_THRESHOLD);
+
+            let _ =
+                handle_healthcheck_response(Ok(create_healthcheck("NoUpdate", false)), &mut model);
+
+            assert!(!model.connection_lost);
+            assert_eq!(model.healthcheck_failures, 0);
+        }
+
+        #[test]
+        fn success_resets_failure_count() {
+            let mut model = Model::default();
+            fail(&mut model, CONNECTION_LOST_THRESHOLD - 1);
+
+            let _ =
+                handle_healthcheck_response(Ok(create_healthcheck("NoUpdate", false)), &mut model);
+            fail(&mut model, CONNECTION_LOST_THRESHOLD - 1);
+
+            assert!(!model.connection_lost);
+        }
+
+        #[test]
+        fn http_errors_are_not_counted() {
+            let mut model = Model::default();
+
+            for status in ["503 Service Unavailable", "401 Unauthorized"] {
+                fail(&mut model, CONNECTION_LOST_THRESHOLD - 1);
+                let _ = handle_healthcheck_response(
+                    Err(format!(
+                        "HealthcheckResponse failed: HTTP {status} (No body)"
+                    )),
+                    &mut model,
+                );
+
+                assert!(!model.connection_lost);
+                assert_eq!(model.healthcheck_failures, 0);
+            }
+        }
+
+        #[test]
+        fn failures_during_operation_are_not_counted() {
+            let mut model = Model {
+                device_operation_state: DeviceOperationState::Rebooting,
+                ..Default::default()
+            };
+
+            fail(&mut model, CONNECTION_LOST_THRESHOLD);
+
+            assert!(!model.connection_lost);
+            assert_eq!(model.healthcheck_failures, 0);
+        }
+    }
+
+    mod resume_operation {
+        use super::*;
+
+        fn healthcheck_with_operation(operation: Option<DeviceOperation>) -> HealthcheckInfo {
+            HealthcheckInfo {
+                last_operation: operation,
+                ..create_healthcheck("InProgress", false)
+            }
+        }
+
+        #[test]
+        fn restores_rebooting_state() {
+            let mut model = Model::default();
+
+            let _ = handle_resume_operation_response(
+                Ok(healthcheck_with_operation(Some(DeviceOperation::Reboot))),
+                &mut model,
+            );
+
+            assert_eq!(
+                model.device_operation_state,
+                DeviceOperationState::Rebooting
+            );
+            assert_eq!(model.reconnection_attempt, 0);
+            assert!(!model.device_went_offline);
+            assert!(model.overlay_spinner.is_visible());
+            assert_eq!(model.overlay_spinner.title(), "Device is rebooting");
+        }
+
+        #[test]
+        fn restores_updating_state() {
+            let mut model = Model::default();
+
+            let _ = handle_resume_operation_response(
+                Ok(healthcheck_with_operation(Some(DeviceOperation::Update))),
+                &mut model,
+            );
+
+            assert_eq!(model.device_operation_state, DeviceOperationState::Updating);
+        }
+
+        #[test]
+        fn resumed_operation_completes_after_reconnection() {
+            let mut model = Model::default();
+            let _ = handle_resume_operation_response(
+                Ok(healthcheck_with_operation(Some(DeviceOperation::Reboot))),
+                &mut model,
+            );
+
+            let _ = handle_healthcheck_response(Err("Connection failed".to_string()), &mut model);
+            let _ =
+                handle_healthcheck_response(Ok(create_healthcheck("NoUpdate", false)), &mut model);
+
+            assert_eq!(
+                model.device_operation_state,
+                DeviceOperationState::ReconnectionSuccessful {
+                    operation: DeviceOperation::Reboot
+                }
+            );
+        }
+
+        #[test]
+        fn stays_idle_without_operation_in_progress() {
+            let mut model = Model::default();
+
+            let _ =
+                handle_resume_operation_response(Ok(healthcheck_with_operation(None)), &mut model);
+
+            assert_eq!(model.device_operation_state, DeviceOperationState::Idle);
+            assert!(model.healthcheck.is_some());
+        }
+
+        #[test]
+        fn stays_idle_if_backend_is_unreachable() {
+            let mut model = Model::default();
+
+            let _ =
+                handle_resume_operation_response(Err("Connection failed".to_string()), &mut model);
+
+            assert_eq!(model.device_operation_state, DeviceOperationState::Idle);
+        }
+
+        #[test]
+        fn does_not_replace_operation_already_in_progress() {
+            let mut model = Model {
+                device_operation_state: DeviceOperationState::WaitingReconnection {
+                    operation: DeviceOperation::FactoryReset,
+                    attempt: 3,
+                },
+                reconnection_attempt: 3,
+                ..Default::default()
+            };
+
+            let _ = handle_resume_operation_response(
+                Ok(healthcheck_with_operation(Some(DeviceOperation::Reboot))),
+                &mut model,
+            );
+
+            assert_eq!(model.reconnection_attempt, 3);
+            assert_ne!(
+                model.device_operation_state,
+                DeviceOperationState::Rebooting
+            );
+        }
+    }
 }
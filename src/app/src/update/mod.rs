@@ -3,7 +3,7 @@ mod device;
 mod ui;
 mod websocket;
 
-use crux_core::{render::render, Command};
+use crux_core::Command;
 
 use crate::events::Event;
 use crate::model::Model;
@@ -17,7 +17,11 @@ pub fn update(event: Event, model: &mut Model) -> Command<Effect, Event> {
     match event {
         Event::Initialize => {
             model.start_loading();
-            render()
+            // resume waiting for an operation that was in progress before a page reload
+            Command::all([
+                ui::start_bootstrap(model),
+                device::check_pending_operation(),
+            ])
         }
         Event::Auth(auth_event) => auth::handle(auth_event, model),
         Event::Device(device_event) => device::handle(device_event, model),
@@ -1,22 +1,147 @@
-use crux_core::Command;
+use crux_core::{render::render, Command};
 
 use crate::{
+    auth_post,
     events::{Event, UiEvent},
+    extract_string_response, map_http_error,
     model::Model,
-    update_field, Effect,
+    types::{BootstrapState, FrontendConfig, NetworkInterfaceFilter},
+    update_field, Effect, HttpCmd,
 };
 
+/// Maximum number of attempts for the bootstrap requests
+const BOOTSTRAP_MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first bootstrap retry, doubled for every further retry
+const BOOTSTRAP_INITIAL_RETRY_DELAY_MS: u32 = 500;
+
 /// Handle UI-related events (clear messages, etc.)
 pub fn handle(event: UiEvent, model: &mut Model) -> Command<Effect, Event> {
     match event {
         UiEvent::ClearError => update_field!(model.error_message, None),
         UiEvent::ClearSuccess => update_field!(model.success_message, None),
+        UiEvent::ClearAllNotifications => update_field!(
+            model.success_message, None;
+            model.error_message, None;
+        ),
         UiEvent::SetBrowserHostname(hostname) => {
             model.browser_hostname = Some(hostname);
             model.update_current_connection_adapter();
             crux_core::render::render()
         }
+        UiEvent::ToggleAdvancedMode => update_field!(model.advanced_mode, !model.advanced_mode),
+        UiEvent::SetAdvancedMode(enabled) => update_field!(model.advanced_mode, enabled),
+        UiEvent::SetUpdateCompleteStatuses(statuses) => {
+            model.update_complete_statuses = (!statuses.is_empty()).then_some(statuses);
+            Command::done()
+        }
+        UiEvent::BootstrapRetry => match model.bootstrap_state {
+            BootstrapState::WaitingRetry { attempt, .. } => bootstrap_attempt(model, attempt + 1),
+            _ => Command::done(),
+        },
+        UiEvent::BootstrapConfigResponse(Ok(config)) => {
+            apply_frontend_config(model, config);
+            fetch_version()
+        }
+        UiEvent::BootstrapConfigResponse(Err(e)) => handle_bootstrap_failure(model, e),
+        UiEvent::BootstrapVersionResponse(Ok(version)) => {
+            model.backend_version = Some(version.trim().to_string());
+            model.bootstrap_state = BootstrapState::Completed;
+            model.stop_loading();
+            render()
+        }
+        UiEvent::BootstrapVersionResponse(Err(e)) => handle_bootstrap_failure(model, e),
+        UiEvent::Refresh => {
+            if model.is_refreshing {
+                return Command::done();
+            }
+            model.is_refreshing = model.auth_token.is_some();
+            auth_post!(Ui, UiEvent, model, "/republish", RefreshResponse, "Refresh")
+        }
+        UiEvent::RefreshResponse(result) => {
+            model.is_refreshing = false;
+            match result {
+                Ok(()) => model.stop_loading(),
+                Err(e) => model.set_error(e),
+            }
+            render()
+        }
+    }
+}
+
+/// Start the initial backend requests (config and version)
+pub fn start_bootstrap(model: &mut Model) -> Command<Effect, Event> {
+    bootstrap_attempt(model, 1)
+}
+
+fn bootstrap_attempt(model: &mut Model, attempt: u32) -> Command<Effect, Event> {
+    model.bootstrap_state = BootstrapState::Loading { attempt };
+    Command::all([render(), fetch_config()])
+}
+
+fn fetch_config() -> Command<Effect, Event> {
+    HttpCmd::get(crate::build_url("/config.json"))
+        .build()
+        .then_send(|result| {
+            let event_result = match result {
+                Ok(mut response) => {
+                    extract_string_response("Load config", &mut response).map(|json| {
+                        // a config the core can't read must not keep the UI from starting
+                        serde_json::from_str(&json).unwrap_or_else(|e| {
+                            log::warn!("Failed to parse config.json: {e}");
+                            FrontendConfig::default()
+                        })
+                    })
+                }
+                Err(e) => Err(map_http_error("Load config", e)),
+            };
+            Event::Ui(UiEvent::BootstrapConfigResponse(event_result))
+        })
+}
+
+/// Apply the loaded frontend config, also if the shell missed it, e.g.
+/// because `config.js` was only reachable on a bootstrap retry
+fn apply_frontend_config(model: &mut Model, config: FrontendConfig) {
+    if let Some(statuses) = config
+        .update_complete_statuses
+        .filter(|statuses| !statuses.is_empty())
+    {
+        model.update_complete_statuses = Some(statuses);
     }
+    model.network_interface_filter = NetworkInterfaceFilter {
+        include: config.network_interface_include,
+        exclude: config.network_interface_exclude,
+    };
+}
+
+fn fetch_version() -> Command<Effect, Event> {
+    HttpCmd::get(crate::build_url("/version"))
+        .build()
+        .then_send(|result| {
+            let event_result = match result {
+                Ok(mut response) => extract_string_response("Load version", &mut response),
+                Err(e) => Err(map_http_error("Load version", e)),
+            };
+            Event::Ui(UiEvent::BootstrapVersionResponse(event_result))
+        })
+}
+
+/// Schedule a retry with exponential backoff or give up after the last attempt
+fn handle_bootstrap_failure(model: &mut Model, error: String) -> Command<Effect, Event> {
+    let attempt = match model.bootstrap_state {
+        BootstrapState::Loading { attempt } => attempt,
+        _ => BOOTSTRAP_MAX_ATTEMPTS,
+    };
+
+    if attempt < BOOTSTRAP_MAX_ATTEMPTS {
+        let delay_ms = BOOTSTRAP_INITIAL_RETRY_DELAY_MS << (attempt - 1);
+        log::warn!("Bootstrap attempt {attempt} failed, retrying in {delay_ms}ms: {error}");
+        model.bootstrap_state = BootstrapState::WaitingRetry { attempt, delay_ms };
+    } else {
+        model.set_error(format!("Failed to connect to device: {error}"));
+        model.bootstrap_state = BootstrapState::Failed { reason: error };
+    }
+
+    render()
 }
 
 #[cfg(test)]
@@ -25,6 +150,199 @@ mod tests {
     use crate::events::UiEvent;
     use crate::types::{DeviceNetwork, InternetProtocol, IpAddress, NetworkStatus};
 
+    mod bootstrap {
+        use super::*;
+        use crate::update::update;
+
+        #[test]
+        fn initialize_starts_first_attempt() {
+            let mut model = Model::default();
+
+            let _ = update(Event::Initialize, &mut model);
+
+            assert_eq!(
+                model.bootstrap_state,
+                BootstrapState::Loading { attempt: 1 }
+            );
+            assert!(model.is_loading);
+        }
+
+        #[test]
+        fn initial_failure_followed_by_success_resolves_without_error() {
+            let mut model = Model::default();
+            let _ = update(Event::Initialize, &mut model);
+
+            let _ = handle(
+                UiEvent::BootstrapConfigResponse(Err("connection refused".into())),
+                &mut model,
+            );
+            assert_eq!(
+                model.bootstrap_state,
+                BootstrapState::WaitingRetry {
+                    attempt: 1,
+                    delay_ms: BOOTSTRAP_INITIAL_RETRY_DELAY_MS
+                }
+            );
+            assert!(model.error_message.is_none());
+
+            let _ = handle(UiEvent::BootstrapRetry, &mut model);
+            assert_eq!(
+                model.bootstrap_state,
+                BootstrapState::Loading { attempt: 2 }
+            );
+
+            let _ = handle(
+                UiEvent::BootstrapConfigResponse(Ok(FrontendConfig::default())),
+                &mut model,
+            );
+            let _ = handle(
+                UiEvent::BootstrapVersionResponse(Ok("1.2.3\n".into())),
+                &mut model,
+            );
+
+            assert_eq!(model.bootstrap_state, BootstrapState::Completed);
+            assert_eq!(model.backend_version, Some("1.2.3".to_string()));
+            assert!(model.error_message.is_none());
+            assert!(!model.is_loading);
+        }
+
+        #[test]
+        fn config_of_retry_after_failure_is_applied() {
+            use crux_http::protocol::{HttpResponse, HttpResult};
+
+            let mut model = Model::default();
+            let _ = update(Event::Initialize, &mut model);
+            let _ = handle(
+                UiEvent::BootstrapConfigResponse(Err("connection refused".into())),
+                &mut model,
+            );
+
+            let mut cmd = handle(UiEvent::BootstrapRetry, &mut model);
+            let mut request = cmd
+                .effects()
+                .find_map(|effect| match effect {
+                    Effect::Http(request) => Some(request),
+                    _ => None,
+                })
+                .expect("retry should request config.json");
+            assert!(request.operation.url.ends_with("/config.json"));
+            request
+                .resolve(HttpResult::Ok(
+                    HttpResponse::ok()
+                        .body(
+                            r#"{"KEYCLOAK_URL":"https://keycloak.example.com/realms/omnect","BASE_PATH":"","UPDATE_COMPLETE_STATUSES":["Succeeded"],"NETWORK_INTERFACE_INCLUDE":null,"NETWORK_INTERFACE_EXCLUDE":"^docker"}"#,
+                        )
+                        .build(),
+                ))
+                .expect("effect should resolve");
+            let event = cmd.events().next().expect("config response event");
+
+            let _ = update(event, &mut model);
+
+            assert_eq!(
+                model.update_complete_statuses,
+                Some(vec!["Succeeded".to_string()])
+            );
+            assert_eq!(
+                model.network_interface_filter,
+                NetworkInterfaceFilter {
+                    include: None,
+                    exclude: Some("^docker".to_string()),
+                }
+            );
+        }
+
+        #[test]
+        fn unparsable_config_does_not_fail_bootstrap() {
+            use crux_http::protocol::{HttpResponse, HttpResult};
+
+            let mut model = Model::default();
+            let mut cmd = start_bootstrap(&mut model);
+            let mut request = cmd
+                .effects()
+                .find_map(|effect| match effect {
+                    Effect::Http(request) => Some(request),
+                    _ => None,
+                })
+                .expect("bootstrap should request config.json");
+            request
+                .resolve(HttpResult::Ok(
+                    HttpResponse::ok()
+                        .body("window.__APP_CONFIG__ = {};")
+                        .build(),
+                ))
+                .expect("effect should resolve");
+            let event = cmd.events().next().expect("config response event");
+
+            assert!(matches!(
+                event,
+                Event::Ui(UiEvent::BootstrapConfigResponse(Ok(ref config)))
+                    if *config == FrontendConfig::default()
+            ));
+        }
+
+        #[test]
+        fn retry_delay_doubles_per_attempt() {
+            let mut model = Model {
+                bootstrap_state: BootstrapState::Loading { attempt: 3 },
+                ..Default::default()
+            };
+
+            let _ = handle(
+                UiEvent::BootstrapVersionResponse(Err("not ready".into())),
+                &mut model,
+            );
+
+            assert_eq!(
+                model.bootstrap_state,
+                BootstrapState::WaitingRetry {
+                    attempt: 3,
+                    delay_ms: BOOTSTRAP_INITIAL_RETRY_DELAY_MS * 4
+                }
+            );
+        }
+
+        #[test]
+        fn last_failed_attempt_surfaces_error() {
+            let mut model = Model {
+                bootstrap_state: BootstrapState::Loading {
+                    attempt: BOOTSTRAP_MAX_ATTEMPTS,
+                },
+                is_loading: true,
+                ..Default::default()
+            };
+
+            let _ = handle(
+                UiEvent::BootstrapConfigResponse(Err("connection refused".into())),
+                &mut model,
+            );
+
+            assert_eq!(
+                model.bootstrap_state,
+                BootstrapState::Failed {
+                    reason: "connection refused".into()
+                }
+            );
+            assert_eq!(
+                model.error_message,
+                Some("Failed to connect to device: connection refused".into())
+            );
+            assert!(!model.is_loading);
+        }
+
+        #[test]
+        fn retry_is_ignored_when_not_waiting() {
+            let mut model = Model {
+                bootstrap_state: BootstrapState::Completed,
+                ..Default::default()
+            };
+
+            let _ = handle(UiEvent::BootstrapRetry, &mut model);
+
+            assert_eq!(model.bootstrap_state, BootstrapState::Completed);
+        }
+    }
+
     #[test]
     fn clear_error_removes_error_message() {
         let mut model = Model {
@@ -49,6 +367,22 @@ mod tests {
         assert_eq!(model.success_message, None);
     }
 
+    #[test]
+    fn clear_all_notifications_removes_all_messages() {
+        let mut model = Model {
+            success_message: Some("Test success".to_string()),
+            error_message: Some("Test error".to_string()),
+            ..Default::default()
+        };
+        assert!(model.has_notifications());
+
+        let _ = handle(UiEvent::ClearAllNotifications, &mut model);
+
+        assert_eq!(model.success_message, None);
+        assert_eq!(model.error_message, None);
+        assert!(!model.has_notifications());
+    }
+
     #[test]
     fn set_browser_hostname_stores_hostname() {
         let mut model = Model::default();
@@ -70,11 +404,13 @@ mod tests {
                     mac: "00:11:22:33:44:55".to_string(),
                     online: true,
                     file: Some("/etc/network/interfaces".to_string()),
+                    carrier: Some(true),
                     ipv4: InternetProtocol {
                         addrs: vec![IpAddress {
                             addr: "192.168.1.100".to_string(),
                             dhcp: false,
                             prefix_len: 24,
+                            subnet_mask: "255.255.255.0".to_string(),
                         }],
                         dns: vec![],
                         gateways: vec![],
@@ -91,4 +427,165 @@ mod tests {
 
         assert_eq!(model.current_connection_adapter, Some("eth0".to_string()));
     }
+
+    mod update_complete_statuses {
+        use super::*;
+
+        #[test]
+        fn set_stores_configured_statuses() {
+            let mut model = Model::default();
+
+            let _ = handle(
+                UiEvent::SetUpdateCompleteStatuses(vec!["Succeeded".to_string()]),
+                &mut model,
+            );
+
+            assert_eq!(
+                model.update_complete_statuses,
+                Some(vec!["Succeeded".to_string()])
+            );
+        }
+
+        #[test]
+        fn empty_list_falls_back_to_defaults() {
+            let mut model = Model {
+                update_complete_statuses: Some(vec!["Succeeded".to_string()]),
+                ..Default::default()
+            };
+
+            let _ = handle(UiEvent::SetUpdateCompleteStatuses(vec![]), &mut model);
+
+            assert_eq!(model.update_complete_statuses, None);
+        }
+    }
+
+    mod advanced_mode {
+        use super::*;
+        use crux_core::App as _;
+
+        #[test]
+        fn is_disabled_by_default() {
+            assert!(!Model::default().advanced_mode);
+        }
+
+        #[test]
+        fn toggle_flips_flag() {
+            let mut model = Model::default();
+
+            let _ = handle(UiEvent::ToggleAdvancedMode, &mut model);
+            assert!(model.advanced_mode);
+
+            let _ = handle(UiEvent::ToggleAdvancedMode, &mut model);
+            assert!(!model.advanced_mode);
+        }
+
+        #[test]
+        fn set_restores_persisted_preference() {
+            let mut model = Model::default();
+
+            let _ = handle(UiEvent::SetAdvancedMode(true), &mut model);
+
+            assert!(model.advanced_mode);
+        }
+
+        #[test]
+        fn is_reflected_in_view_model() {
+            let mut model = Model::default();
+
+            let _ = handle(UiEvent::ToggleAdvancedMode, &mut model);
+
+            assert!(crate::App.view(&model).advanced_mode);
+        }
+    }
+
+    mod refresh {
+        use super::*;
+        use crate::events::AuthEvent;
+
+        fn authenticated_model() -> Model {
+            Model {
+                auth_token: Some("token".into()),
+                is_authenticated: true,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn refresh_shows_loading_indicator() {
+            let mut model = authenticated_model();
+
+            let _ = handle(UiEvent::Refresh, &mut model);
+
+            assert!(model.is_refreshing);
+            assert!(model.is_loading);
+        }
+
+        #[test]
+        fn refresh_in_flight_ignores_repeats() {
+            let mut model = authenticated_model();
+            let _ = handle(UiEvent::Refresh, &mut model);
+            model.set_error_message("shown until the refresh completes".into());
+
+            let _ = handle(UiEvent::Refresh, &mut model);
+
+            // a repeated request would have started loading and cleared the error
+            assert!(model.is_refreshing);
+            assert_eq!(
+                model.error_message,
+                Some("shown until the refresh completes".to_string())
+            );
+        }
+
+        #[test]
+        fn response_allows_next_refresh() {
+            let mut model = authenticated_model();
+            let _ = handle(UiEvent::Refresh, &mut model);
+
+            let _ = handle(UiEvent::RefreshResponse(Ok(())), &mut model);
+
+            assert!(!model.is_refreshing);
+            assert!(!model.is_loading);
+            assert!(model.error_message.is_none());
+
+            let _ = handle(UiEvent::Refresh, &mut model);
+            assert!(model.is_refreshing);
+        }
+
+        #[test]
+        fn failure_shows_error() {
+            let mut model = authenticated_model();
+            let _ = handle(UiEvent::Refresh, &mut model);
+
+            let _ = handle(
+                UiEvent::RefreshResponse(Err("Refresh failed: HTTP 500".into())),
+                &mut model,
+            );
+
+            assert!(!model.is_refreshing);
+            assert_eq!(
+                model.error_message,
+                Some("Refresh failed: HTTP 500".to_string())
+            );
+        }
+
+        #[test]
+        fn unauthenticated_refresh_is_not_left_pending() {
+            let mut model = Model::default();
+
+            let _ = handle(UiEvent::Refresh, &mut model);
+
+            assert!(!model.is_refreshing);
+            assert!(model.error_message.is_some());
+        }
+
+        #[test]
+        fn expired_session_resets_pending_refresh() {
+            let mut model = authenticated_model();
+            let _ = handle(UiEvent::Refresh, &mut model);
+
+            let _ = crate::update::auth::handle(AuthEvent::SessionExpired, &mut model);
+
+            assert!(!model.is_refreshing);
+        }
+    }
 }
@@ -1,12 +1,16 @@
 use crux_core::Command;
 
 use crate::{
+    commands::centrifugo::CentrifugoOutput,
     events::{Event, WebSocketEvent},
     model::Model,
     parse_ods_update,
-    types::ods::{
-        OdsFactoryReset, OdsNetworkStatus, OdsOnlineStatus, OdsSystemInfo, OdsTimeouts,
-        OdsUpdateValidationStatus,
+    types::{
+        ods::{
+            OdsFactoryReset, OdsNetworkStatus, OdsOnlineStatus, OdsSystemInfo, OdsTimeouts,
+            OdsUpdateValidationStatus,
+        },
+        WebSocketError,
     },
     update_field, CentrifugoCmd, Effect,
 };
@@ -18,7 +22,7 @@ pub fn handle(event: WebSocketEvent, model: &mut Model) -> Command<Effect, Event
             // Issue Centrifugo effect (shell sends WebSocket data as events directly)
             CentrifugoCmd::subscribe_all()
                 .build()
-                .then_send(|_| Event::WebSocket(WebSocketEvent::Connected))
+                .then_send(|output| Event::WebSocket(subscribe_result(output)))
         }
 
         WebSocketEvent::UnsubscribeFromChannels => {
@@ -38,7 +42,9 @@ pub fn handle(event: WebSocketEvent, model: &mut Model) -> Command<Effect, Event
                 OdsNetworkStatus,
                 "NetworkStatus",
                 |m, status| {
-                    m.network_status = Some(status.into());
+                    let mut status = status.into();
+                    m.network_interface_filter.retain_allowed(&mut status);
+                    m.network_status = Some(status);
                     m.update_current_connection_adapter();
                     crux_core::render::render()
                 }
@@ -63,8 +69,29 @@ pub fn handle(event: WebSocketEvent, model: &mut Model) -> Command<Effect, Event
             parse_ods_update!(model, json, OdsTimeouts, timeouts, "Timeouts")
         }
 
-        WebSocketEvent::Connected => update_field!(model.is_connected, true),
+        WebSocketEvent::Connected => update_field!(
+            model.is_connected, true;
+            model.websocket_error, None;
+        ),
         WebSocketEvent::Disconnected => update_field!(model.is_connected, false),
+        WebSocketEvent::ConnectionFailed { code, message } => {
+            let error = WebSocketError::from_code(code);
+            log::warn!("centrifugo connection failed ({error:?}, code {code:?}): {message}");
+            update_field!(
+                model.is_connected, false;
+                model.websocket_error, Some(error);
+            )
+        }
+    }
+}
+
+/// Event reporting the outcome of subscribing to all channels
+fn subscribe_result(output: CentrifugoOutput) -> WebSocketEvent {
+    match output {
+        CentrifugoOutput::Error { code, message } => {
+            WebSocketEvent::ConnectionFailed { code, message }
+        }
+        _ => WebSocketEvent::Connected,
     }
 }
 
@@ -201,7 +228,9 @@ mod tests {
 
     mod network_status {
         use super::*;
-        use crate::types::{DeviceNetwork, InternetProtocol, IpAddress, NetworkStatus};
+        use crate::types::{
+            DeviceNetwork, InternetProtocol, IpAddress, NetworkInterfaceFilter, NetworkStatus,
+        };
 
         #[test]
         fn updates_network_status() {
@@ -231,11 +260,13 @@ mod tests {
                     mac: "00:11:22:33:44:55".to_string(),
                     online: true,
                     file: Some("/etc/network/interfaces".to_string()),
+                    carrier: Some(true),
                     ipv4: InternetProtocol {
                         addrs: vec![IpAddress {
                             addr: "192.168.1.100".to_string(),
                             dhcp: false,
                             prefix_len: 24,
+                            subnet_mask: "255.255.255.0".to_string(),
                         }],
                         dns: vec![],
                         gateways: vec![],
@@ -250,5 +281,170 @@ mod tests {
 
             assert_eq!(model.network_status, Some(expected_status));
         }
+
+        #[test]
+        fn configured_filter_hides_adapters() {
+            let mut model = Model {
+                network_interface_filter: NetworkInterfaceFilter {
+                    include: None,
+                    exclude: Some("^(docker|veth)".to_string()),
+                },
+                ..Default::default()
+            };
+            let adapter = |name: &str| {
+                format!(
+                    r#"{{ "name": "{name}", "mac": "00:11:22:33:44:55", "online": true,
+                        "ipv4": {{ "addrs": [], "dns": [], "gateways": [] }} }}"#
+                )
+            };
+            let json = format!(
+                r#"{{ "network_status": [{}, {}, {}] }}"#,
+                adapter("veth1a2b3c"),
+                adapter("eth0"),
+                adapter("docker0")
+            );
+
+            let _ = handle(WebSocketEvent::NetworkStatusUpdated(json), &mut model);
+
+            let names: Vec<String> = model
+                .network_status
+                .unwrap()
+                .network_status
+                .into_iter()
+                .map(|adapter| adapter.name)
+                .collect();
+            assert_eq!(names, vec!["eth0"]);
+        }
+
+        /// Carrier of a single adapter after mapping its ODS status
+        fn mapped_carrier(online: bool, carrier: &str) -> Option<bool> {
+            let mut model = Model::default();
+            let json = format!(
+                r#"{{
+                    "network_status": [{{
+                        "name": "eth0",
+                        "mac": "00:11:22:33:44:55",
+                        "online": {online},
+                        {carrier}
+                        "ipv4": {{ "addrs": [], "dns": [], "gateways": [] }}
+                    }}]
+                }}"#
+            );
+
+            let _ = handle(WebSocketEvent::NetworkStatusUpdated(json), &mut model);
+
+            model.network_status.unwrap().network_status[0].carrier
+        }
+
+        #[test]
+        fn no_carrier_is_reported_as_unplugged() {
+            assert_eq!(mapped_carrier(false, r#""carrier": false,"#), Some(false));
+        }
+
+        #[test]
+        fn reported_carrier_without_address_is_kept() {
+            assert_eq!(mapped_carrier(false, r#""carrier": true,"#), Some(true));
+        }
+
+        #[test]
+        fn carrier_is_inferred_for_online_adapter() {
+            assert_eq!(mapped_carrier(true, ""), Some(true));
+        }
+
+        #[test]
+        fn carrier_is_unknown_for_offline_adapter_without_report() {
+            assert_eq!(mapped_carrier(false, ""), None);
+        }
+    }
+
+    mod connection_errors {
+        use super::*;
+
+        fn failed_with(code: Option<u32>) -> Model {
+            let mut model = Model {
+                is_connected: true,
+                ..Default::default()
+            };
+
+            let _ = handle(
+                WebSocketEvent::ConnectionFailed {
+                    code,
+                    message: "failed".into(),
+                },
+                &mut model,
+            );
+
+            model
+        }
+
+        #[test]
+        fn rejected_token_is_an_authentication_failure() {
+            // unauthorized, permission denied, token expired, invalid token, permission denied
+            for code in [101, 103, 109, 3500, 3507] {
+                let model = failed_with(Some(code));
+
+                assert_eq!(
+                    model.websocket_error,
+                    Some(WebSocketError::AuthenticationFailed),
+                    "{code}"
+                );
+                assert!(!model.is_connected);
+            }
+        }
+
+        #[test]
+        fn other_failures_are_a_lost_connection() {
+            // internal, not available, shutdown, stale and transport failures without code
+            for code in [Some(100), Some(108), Some(3001), Some(3502), None] {
+                let model = failed_with(code);
+
+                assert_eq!(
+                    model.websocket_error,
+                    Some(WebSocketError::ConnectionLost),
+                    "{code:?}"
+                );
+                assert!(!model.is_connected);
+            }
+        }
+
+        #[test]
+        fn connecting_clears_the_error() {
+            let mut model = failed_with(None);
+
+            let _ = handle(WebSocketEvent::Connected, &mut model);
+
+            assert_eq!(model.websocket_error, None);
+            assert!(model.is_connected);
+        }
+
+        #[test]
+        fn subscribe_error_output_reports_the_failure() {
+            assert_eq!(
+                subscribe_result(CentrifugoOutput::Error {
+                    code: Some(101),
+                    message: "unauthorized".into()
+                }),
+                WebSocketEvent::ConnectionFailed {
+                    code: Some(101),
+                    message: "unauthorized".into()
+                }
+            );
+            assert_eq!(
+                subscribe_result(CentrifugoOutput::Connected),
+                WebSocketEvent::Connected
+            );
+        }
+
+        #[test]
+        fn errors_read_as_user_hints() {
+            assert_eq!(
+                WebSocketError::AuthenticationFailed.to_string(),
+                "Authentication failed, please log in again"
+            );
+            assert_eq!(
+                WebSocketError::ConnectionLost.to_string(),
+                "Connection lost"
+            );
+        }
     }
 }
@@ -1,27 +1,161 @@
 use crate::{
+    clock::SystemClock,
     config::AppConfig,
     http_client::handle_service_result,
-    keycloak_client::SingleSignOnProvider,
+    keycloak_client::{KeycloakLoginConfig, KeycloakProvider, SingleSignOnProvider},
     omnect_device_service_client::{DeviceServiceClient, FactoryReset, RunUpdate},
     services::{
         auth::{AuthorizationService, PasswordService, TokenManager},
-        firmware::FirmwareService,
+        centrifugo::{CentrifugoService, SetLogLevelRequest},
+        certificate::CertificateService,
+        factory_reset::FactoryResetService,
+        firmware::{
+            ChunkOutOfOrder, ChunkedUpload, ContentRange, FirmwareService, InsufficientStorage,
+            UploadCancelled, UploadLock,
+        },
+        identity::IdentityService,
         marker,
-        network::{NetworkConfigRequest, NetworkConfigService},
+        network::{
+            CancelRollbackResponse, NetworkConfigRequest, NetworkConfigService,
+            RawNetworkConfigRejected, RawNetworkConfigRequest, ResolveRequest,
+        },
+        operation::{OperationService, OperationStarted},
+        republish::{RepublishThrottle, RepublishTrigger},
+        schedule::{ScheduleQuery, Scheduler},
     },
 };
 use actix_files::NamedFile;
 use actix_multipart::Multipart;
 use actix_session::Session;
-use actix_web::{HttpResponse, Responder, web};
-use anyhow::Result;
+use actix_tls::accept::rustls_0_23::TlsStream;
+use actix_web::{
+    HttpRequest, HttpResponse, Responder,
+    dev::Extensions,
+    http::{
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_RANGE, RETRY_AFTER},
+    },
+    rt::net::TcpStream,
+    web,
+};
+use anyhow::{Result, bail};
 use futures_util::StreamExt;
-use log::{debug, error};
-pub use omnect_ui_core::types::{SetPasswordRequest, UpdatePasswordRequest};
-use std::collections::HashMap;
+use log::{debug, error, warn};
+pub use omnect_ui_core::types::{
+    ClientAction, SetPasswordRequest, UpdatePasswordRequest, UpdatePasswordResult,
+};
+use omnect_ui_core::types::{DeviceOperation, FactoryResetRequest};
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    sync::Mutex,
+};
 
 pub type StaticResources = HashMap<&'static str, static_files::Resource>;
 
+/// Local address of the last accepted connection
+static LAST_LOCAL_ADDR: Mutex<Option<IpAddr>> = Mutex::new(None);
+
+/// Local address a connection was accepted on
+///
+/// Unlike the `Host` header it can't be chosen by the client, so it identifies
+/// the address and interface serving the UI.
+#[derive(Clone, Copy, Debug)]
+pub struct LocalAddr(pub SocketAddr);
+
+impl LocalAddr {
+    /// Store the local address of a new connection, for `HttpServer::on_connect`
+    pub fn on_connect(connection: &dyn Any, data: &mut Extensions) {
+        let stream = connection
+            .downcast_ref::<TlsStream<TcpStream>>()
+            .map(|tls| tls.get_ref().0)
+            .or_else(|| connection.downcast_ref::<TcpStream>());
+
+        match stream.map(TcpStream::local_addr) {
+            Some(Ok(addr)) => {
+                *LAST_LOCAL_ADDR.lock().unwrap() = Some(addr.ip());
+                data.insert(Self(addr));
+            }
+            Some(Err(e)) => warn!("failed to get local address of connection: {e}"),
+            None => warn!("failed to get local address of connection: unknown stream type"),
+        }
+    }
+
+    /// Local IP address a request was received on, None if unknown
+    pub fn of(req: &HttpRequest) -> Option<IpAddr> {
+        req.conn_data::<Self>().map(|addr| addr.0.ip())
+    }
+
+    /// Local IP address of the last accepted connection, e.g. to keep serving it after a restart
+    pub fn last() -> Option<IpAddr> {
+        *LAST_LOCAL_ADDR.lock().unwrap()
+    }
+}
+
+/// Response header carrying a machine-readable reason for a 503 healthcheck
+pub const UNAVAILABLE_REASON_HEADER: &str = "X-Omnect-Unavailable-Reason";
+/// Unavailable reason reported when the device service version is not supported
+pub const VERSION_MISMATCH_REASON: &str = "version_mismatch";
+
+/// Tag loading the frontend config in index.html
+const APP_CONFIG_SCRIPT_TAG: &str = "<script src=\"config.js\"></script>";
+/// Non-standard status of a request closed by the client (as used by nginx)
+pub const CLIENT_CLOSED_REQUEST: u16 = 499;
+/// Request header carrying the session id of a chunked firmware upload
+pub const UPLOAD_ID_HEADER: &str = "X-Upload-Id";
+/// Maximum size of a portal token body, larger bodies are rejected before validation
+pub const PORTAL_TOKEN_LIMIT_BYTES: usize = 8 * 1024;
+
+/// index.html as served, rendered once on startup
+pub struct IndexPage {
+    body: String,
+    mime_type: &'static str,
+}
+
+impl IndexPage {
+    /// Relocate the assets of the embedded index.html below the base path and
+    /// insert the frontend config
+    ///
+    /// With `INLINE_APP_CONFIG` the config file is read once here, so a
+    /// provided file has to be in place on startup.
+    pub fn new(static_resources: &StaticResources) -> Result<Self> {
+        let Some(index_html) = static_resources.get("index.html") else {
+            bail!("index.html not found in embedded resources");
+        };
+
+        // assets are built for `/static`, relocate them below the base path
+        let base_path = &AppConfig::get().ui.base_path;
+        let html = String::from_utf8_lossy(index_html.data)
+            .replace("\"/static/", &format!("\"{base_path}/static/"));
+        if !html.contains(APP_CONFIG_SCRIPT_TAG) {
+            warn!("index.html has no {APP_CONFIG_SCRIPT_TAG}, the frontend config isn't loaded");
+        }
+        // the index is served for every client route, so config.js has to be
+        // loaded absolutely, inlining it saves the round trip altogether
+        let config_js = format!("<script src=\"{base_path}/config.js\"></script>");
+        let app_config_script = if AppConfig::get().ui.inline_app_config {
+            // the file is either generated or provided by the operator
+            let path = &AppConfig::get().paths.app_config_path;
+            match std::fs::read_to_string(path) {
+                Ok(script) => format!("<script>{script}</script>"),
+                Err(e) => {
+                    warn!("failed to read {path:?} to inline it, loading it instead: {e}");
+                    config_js
+                }
+            }
+        } else {
+            config_js
+        };
+
+        Ok(Self {
+            body: html.replace(APP_CONFIG_SCRIPT_TAG, &app_config_script),
+            mime_type: index_html.mime_type,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct Api<ServiceClient, SingleSignOn>
 where
@@ -46,36 +180,59 @@ where
 
     pub async fn index(
         api: web::Data<Self>,
-        static_resources: web::Data<StaticResources>,
+        index_page: web::Data<IndexPage>,
+        throttle: web::Data<RepublishThrottle>,
     ) -> actix_web::Result<HttpResponse> {
         debug!("index() called");
 
-        api.service_client.republish().await.map_err(|e| {
-            error!("republish failed: {e:#}");
-            actix_web::error::ErrorInternalServerError("republish failed")
-        })?;
-
-        let Some(index_html) = static_resources.get("index.html") else {
-            return Err(actix_web::error::ErrorNotFound(
-                "index.html not found in embedded resources",
-            ));
-        };
+        if AppConfig::get()
+            .ui
+            .index_republish
+            .should_republish(&throttle)
+        {
+            api.service_client.republish().await.map_err(|e| {
+                error!("republish failed: {e:#}");
+                actix_web::error::ErrorInternalServerError("republish failed")
+            })?;
+            throttle.record(RepublishTrigger::Index);
+        }
 
         Ok(HttpResponse::Ok()
-            .content_type(index_html.mime_type)
-            .body(index_html.data.to_vec()))
+            .content_type(index_page.mime_type)
+            .body(index_page.body.clone()))
     }
 
     pub async fn config() -> actix_web::Result<NamedFile> {
         Ok(NamedFile::open(&AppConfig::get().paths.app_config_path)?)
     }
 
-    pub async fn healthcheck(api: web::Data<Self>) -> impl Responder {
+    pub async fn config_json() -> impl Responder {
+        debug!("config_json() called");
+
+        HttpResponse::Ok().json(KeycloakProvider::frontend_config())
+    }
+
+    pub async fn auth_config() -> impl Responder {
+        debug!("auth_config() called");
+
+        HttpResponse::Ok().json(KeycloakLoginConfig::from(&AppConfig::get().keycloak))
+    }
+
+    pub async fn healthcheck(req: HttpRequest, api: web::Data<Self>) -> impl Responder {
         debug!("healthcheck() called");
 
-        match api.service_client.healthcheck_info().await {
+        match api
+            .service_client
+            .healthcheck_info(LocalAddr::of(&req))
+            .await
+        {
             Ok(info) if info.version_info.mismatch => {
-                HttpResponse::ServiceUnavailable().json(&info)
+                let mut response = HttpResponse::ServiceUnavailable();
+                response.insert_header((UNAVAILABLE_REASON_HEADER, VERSION_MISMATCH_REASON));
+                if let Some(retry_after) = AppConfig::get().ui.healthcheck_retry_after {
+                    response.insert_header((RETRY_AFTER, retry_after.as_secs()));
+                }
+                response.json(&info)
             }
             Ok(info) => HttpResponse::Ok().json(&info),
             Err(e) => {
@@ -92,19 +249,69 @@ where
     ) -> impl Responder {
         debug!("factory_reset() called: {body:?}");
 
+        let mut factory_reset = body.into_inner();
+        if let Err(e) = factory_reset.normalize_preserve() {
+            return HttpResponse::BadRequest().body(e.to_string());
+        }
+
         marker::FACTORY_RESET_RESULT_ACKED.clear();
-        let result = api.service_client.factory_reset(body.into_inner()).await;
+        let request = FactoryResetRequest::from(&factory_reset);
+        let result = api.service_client.factory_reset(factory_reset).await;
 
         if result.is_ok() {
             session.purge();
+            FactoryResetService::record_or_log(&request);
+            marker::record_device_operation(DeviceOperation::FactoryReset);
         }
 
         handle_service_result(result, "factory_reset")
     }
 
-    pub async fn reboot(api: web::Data<Self>) -> impl Responder {
-        debug!("reboot() called");
-        handle_service_result(api.service_client.reboot().await, "reboot")
+    pub async fn reboot(
+        api: web::Data<Self>,
+        query: web::Query<ScheduleQuery>,
+        scheduler: web::Data<Scheduler>,
+    ) -> impl Responder
+    where
+        ServiceClient: Send + Sync + 'static,
+        SingleSignOn: Send + Sync + 'static,
+    {
+        debug!("reboot() called: {query:?}");
+
+        if let Some(scheduled_at) = &query.scheduled_at {
+            return Self::schedule_operation(
+                &scheduler,
+                DeviceOperation::Reboot,
+                scheduled_at,
+                async move { api.reboot_now().await.map(|_| ()) },
+            );
+        }
+
+        handle_service_result(api.reboot_now().await, "reboot")
+    }
+
+    async fn reboot_now(&self) -> Result<OperationStarted> {
+        self.service_client.reboot().await?;
+
+        Ok(OperationService::start(DeviceOperation::Reboot))
+    }
+
+    pub async fn republish(
+        api: web::Data<Self>,
+        throttle: web::Data<RepublishThrottle>,
+    ) -> impl Responder {
+        debug!("republish() called");
+
+        // repeated requests are answered without republishing again
+        if !throttle.is_due(RepublishTrigger::Manual) {
+            return HttpResponse::Ok().finish();
+        }
+
+        let result = api.service_client.republish().await;
+        if result.is_ok() {
+            throttle.record(RepublishTrigger::Manual);
+        }
+        handle_service_result(result, "republish")
     }
 
     pub async fn token(session: Session, token_manager: web::Data<TokenManager>) -> impl Responder {
@@ -124,26 +331,104 @@ where
         HttpResponse::Ok().body(env!("CARGO_PKG_VERSION"))
     }
 
-    pub async fn upload_firmware_file(mut payload: Multipart) -> impl Responder {
+    pub async fn upload_firmware_file(
+        req: HttpRequest,
+        mut payload: Multipart,
+        upload_lock: web::Data<UploadLock>,
+    ) -> impl Responder {
         debug!("upload_firmware_file() called");
 
+        let Some(_upload_guard) = upload_lock.try_acquire() else {
+            warn!("upload rejected: another upload is in progress");
+            return HttpResponse::Conflict().body("upload already in progress");
+        };
+
+        // reject uploads not fitting into the data folder before consuming the stream
+        if let Some(size) = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            && let Err(e) = FirmwareService::ensure_free_space(size).await
+        {
+            return Self::free_space_error_response(e);
+        }
+
         while let Some(item) = payload.next().await {
             let field = match item {
                 Ok(field) => field,
+                Err(e) if UploadCancelled::is_cause_of(&e) => {
+                    return Self::upload_cancelled_response();
+                }
                 Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
             };
 
             if field.name() == Some("file") {
-                return handle_service_result(
-                    FirmwareService::receive_firmware(field).await,
-                    "upload_firmware_file",
-                );
+                return match FirmwareService::receive_firmware(field).await {
+                    Err(e) if e.is::<UploadCancelled>() => Self::upload_cancelled_response(),
+                    result => handle_service_result(result, "upload_firmware_file"),
+                };
             }
         }
 
         HttpResponse::BadRequest().body("Missing file field")
     }
 
+    pub async fn upload_firmware_chunk(
+        req: HttpRequest,
+        body: web::Bytes,
+        upload_lock: web::Data<UploadLock>,
+        chunked_upload: web::Data<ChunkedUpload>,
+    ) -> impl Responder {
+        let range = match req
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::parse::<ContentRange>)
+        {
+            Some(Ok(range)) => range,
+            Some(Err(e)) => return HttpResponse::BadRequest().body(e.to_string()),
+            None => return HttpResponse::BadRequest().body("Missing Content-Range header"),
+        };
+        debug!(
+            "upload_firmware_chunk() called: {}-{}/{}",
+            range.start, range.end, range.total
+        );
+
+        if body.len() as u64 != range.size() {
+            return HttpResponse::BadRequest().body("chunk size doesn't match Content-Range");
+        }
+
+        let Some(_upload_guard) = upload_lock.try_acquire() else {
+            warn!("chunk rejected: another upload is in progress");
+            return HttpResponse::Conflict().body("upload already in progress");
+        };
+
+        if range.start == 0
+            && let Err(e) = FirmwareService::ensure_free_space(range.total).await
+        {
+            return Self::free_space_error_response(e);
+        }
+
+        let upload_id = req
+            .headers()
+            .get(UPLOAD_ID_HEADER)
+            .and_then(|v| v.to_str().ok());
+
+        match FirmwareService::receive_firmware_chunk(&chunked_upload, upload_id, range, &body)
+            .await
+        {
+            Err(e) => match e.downcast::<ChunkOutOfOrder>() {
+                Ok(ChunkOutOfOrder {
+                    status: Some(status),
+                }) => HttpResponse::Conflict().json(status),
+                Ok(e) => HttpResponse::Conflict().body(e.to_string()),
+                Err(e) => handle_service_result::<()>(Err(e), "upload_firmware_chunk"),
+            },
+            result => handle_service_result(result, "upload_firmware_chunk"),
+        }
+    }
+
     pub async fn load_update(api: web::Data<Self>) -> impl Responder {
         debug!("load_update() called");
 
@@ -153,15 +438,98 @@ where
         )
     }
 
-    pub async fn run_update(body: web::Json<RunUpdate>, api: web::Data<Self>) -> impl Responder {
-        debug!("run_update() called with validate_iothub_connection: {body:?}");
+    pub async fn run_update(
+        body: web::Json<RunUpdate>,
+        api: web::Data<Self>,
+        query: web::Query<ScheduleQuery>,
+        scheduler: web::Data<Scheduler>,
+    ) -> impl Responder
+    where
+        ServiceClient: Send + Sync + 'static,
+        SingleSignOn: Send + Sync + 'static,
+    {
+        debug!("run_update() called with validate_iothub_connection: {body:?}, {query:?}");
+
+        if let Some(scheduled_at) = &query.scheduled_at {
+            return Self::schedule_operation(
+                &scheduler,
+                DeviceOperation::Update,
+                scheduled_at,
+                async move { api.run_update_now(body.into_inner()).await.map(|_| ()) },
+            );
+        }
+
+        handle_service_result(api.run_update_now(body.into_inner()).await, "run_update")
+    }
+
+    async fn run_update_now(&self, run_update: RunUpdate) -> Result<OperationStarted> {
         marker::UPDATE_VALIDATION_ACKED.clear();
+
+        FirmwareService::run_update(&self.service_client, run_update).await?;
+
+        Ok(OperationService::start(DeviceOperation::Update))
+    }
+
+    fn schedule_operation<F>(
+        scheduler: &Scheduler,
+        operation: DeviceOperation,
+        scheduled_at: &str,
+        action: F,
+    ) -> HttpResponse
+    where
+        F: Future<Output = Result<()>> + Send + 'static,
+    {
+        let at = match scheduler.parse_scheduled_at(scheduled_at) {
+            Ok(at) => at,
+            Err(e) => return HttpResponse::BadRequest().body(format!("{e:#}")),
+        };
+
         handle_service_result(
-            FirmwareService::run_update(&api.service_client, body.into_inner()).await,
-            "run_update",
+            scheduler.schedule(operation, at, action),
+            "schedule_operation",
         )
     }
 
+    pub async fn scheduled_operations(scheduler: web::Data<Scheduler>) -> impl Responder {
+        debug!("scheduled_operations() called");
+
+        handle_service_result(scheduler.pending(), "scheduled_operations")
+    }
+
+    pub async fn cancel_scheduled_operation(
+        schedule_id: web::Path<String>,
+        scheduler: web::Data<Scheduler>,
+    ) -> impl Responder {
+        debug!("cancel_scheduled_operation() called: {schedule_id}");
+
+        match scheduler.cancel(&schedule_id) {
+            Ok(true) => HttpResponse::Ok().finish(),
+            Ok(false) => HttpResponse::NotFound().body("unknown scheduled operation"),
+            Err(e) => {
+                error!("cancel_scheduled_operation failed: {e:#}");
+                HttpResponse::InternalServerError().body(e.to_string())
+            }
+        }
+    }
+
+    pub async fn device_identity(api: web::Data<Self>) -> impl Responder {
+        debug!("device_identity() called");
+
+        handle_service_result(
+            IdentityService::identity(&api.service_client).await,
+            "device_identity",
+        )
+    }
+
+    pub async fn operation_status(operation_id: web::Path<String>) -> impl Responder {
+        debug!("operation_status() called: {operation_id}");
+
+        match OperationService::status(&operation_id) {
+            Some(status) => HttpResponse::Ok().json(status),
+            None => HttpResponse::NotFound().body("unknown operation"),
+        }
+    }
+
     pub async fn set_password(
         body: web::Json<SetPasswordRequest>,
         session: Session,
@@ -170,9 +538,12 @@ where
         debug!("set_password() called");
 
         if PasswordService::password_exists() {
-            return HttpResponse::Found()
-                .append_header(("Location", "/login"))
-                .finish();
+            if !PasswordService::password_file_corrupt() {
+                return HttpResponse::Found()
+                    .append_header(("Location", "/login"))
+                    .finish();
+            }
+            warn!("password file is corrupt, allowing to set the password again");
         }
 
         let portal_validated = session
@@ -183,6 +554,12 @@ where
             return HttpResponse::Unauthorized().body("portal authentication required");
         }
 
+        if let Err(e) =
+            PasswordService::validate_strength(&body.password, &AppConfig::get().password_policy)
+        {
+            return HttpResponse::BadRequest().body(e.to_string());
+        }
+
         if let Err(e) = PasswordService::store_or_update_password(&body.password) {
             error!("set_password failed: {e:#}");
             return HttpResponse::InternalServerError().body(e.to_string());
@@ -202,33 +579,70 @@ where
             return HttpResponse::BadRequest().body("current password is not correct");
         }
 
+        if let Err(e) =
+            PasswordService::validate_strength(&body.password, &AppConfig::get().password_policy)
+        {
+            return HttpResponse::BadRequest().body(e.to_string());
+        }
+
         let result = PasswordService::store_or_update_password(&body.password);
 
         if result.is_ok() {
             session.purge();
         }
 
-        handle_service_result(result, "update_password")
+        // the session is gone, tell the client to log in again
+        handle_service_result(
+            result.map(|()| UpdatePasswordResult {
+                action: ClientAction::Reauthenticate,
+            }),
+            "update_password",
+        )
+    }
+
+    pub async fn password_policy() -> impl Responder {
+        debug!("password_policy() called");
+        HttpResponse::Ok().json(&AppConfig::get().password_policy)
+    }
+
+    pub async fn debug_config() -> impl Responder {
+        debug!("debug_config() called");
+        HttpResponse::Ok().json(AppConfig::get().redacted())
     }
 
     pub async fn require_set_password() -> impl Responder {
         debug!("require_set_password() called");
 
-        let password_exists = PasswordService::password_exists();
-        HttpResponse::Ok().json(!password_exists)
+        // a corrupt password file can't be used to log in, so it has to be set again
+        let password_set =
+            PasswordService::password_exists() && !PasswordService::password_file_corrupt();
+        HttpResponse::Ok().json(!password_set)
     }
 
     pub async fn validate_portal_token(
-        body: String,
+        payload: web::Payload,
         api: web::Data<Self>,
         session: Session,
     ) -> impl Responder {
         debug!("validate_portal_token() called");
 
+        // the global payload limit is sized for firmware uploads, so limit tokens explicitly
+        let body = match payload.to_bytes_limited(PORTAL_TOKEN_LIMIT_BYTES).await {
+            Ok(Ok(body)) => body,
+            Ok(Err(e)) => {
+                error!("validate_portal_token failed to read body: {e:#}");
+                return HttpResponse::BadRequest().finish();
+            }
+            Err(_) => return HttpResponse::PayloadTooLarge().finish(),
+        };
+        let Ok(token) = std::str::from_utf8(&body) else {
+            return HttpResponse::BadRequest().body("token is not valid UTF-8");
+        };
+
         if let Err(e) = AuthorizationService::validate_token_and_claims(
             &api.single_sign_on,
             &api.service_client,
-            &body,
+            token,
         )
         .await
         {
@@ -247,11 +661,128 @@ where
         debug!("set_network_config() called");
 
         handle_service_result(
-            NetworkConfigService::set_network_config(&api.service_client, &network_config).await,
+            NetworkConfigService::set_network_config(
+                &api.service_client,
+                &network_config,
+                &SystemClock,
+            )
+            .await,
             "set_network_config",
         )
     }
 
+    pub async fn set_raw_network_config(
+        req: HttpRequest,
+        query: web::Query<RawNetworkConfigRequest>,
+        body: String,
+        api: web::Data<Self>,
+    ) -> impl Responder {
+        debug!("set_raw_network_config() called: {query:?}");
+
+        match NetworkConfigService::set_raw_network_config(
+            &api.service_client,
+            &query,
+            &body,
+            LocalAddr::of(&req),
+            &SystemClock,
+        )
+        .await
+        {
+            Err(e) => match e.downcast::<RawNetworkConfigRejected>() {
+                Ok(e) => HttpResponse::BadRequest().body(e.to_string()),
+                Err(e) => handle_service_result::<()>(Err(e), "set_raw_network_config"),
+            },
+            result => handle_service_result(result, "set_raw_network_config"),
+        }
+    }
+
+    pub async fn cancel_rollback() -> impl Responder {
+        debug!("cancel_rollback() called");
+        HttpResponse::Ok().json(CancelRollbackResponse {
+            rollback_pending: NetworkConfigService::cancel_rollback(),
+        })
+    }
+
+    pub async fn rollback_status() -> impl Responder {
+        debug!("rollback_status() called");
+
+        handle_service_result(
+            NetworkConfigService::rollback_status(&SystemClock),
+            "rollback_status",
+        )
+    }
+
+    pub async fn rollback_now(api: web::Data<Self>) -> impl Responder {
+        debug!("rollback_now() called");
+
+        handle_service_result(
+            NetworkConfigService::rollback_now(&api.service_client, &SystemClock).await,
+            "rollback_now",
+        )
+    }
+
+    pub async fn resolve(
+        query: web::Query<ResolveRequest>,
+        api: web::Data<Self>,
+    ) -> impl Responder {
+        debug!("resolve() called: {query:?}");
+
+        if let Err(e) = NetworkConfigService::validate_hostname(&query.host) {
+            return HttpResponse::BadRequest().body(e.to_string());
+        }
+
+        handle_service_result(
+            NetworkConfigService::resolve(&api.service_client, &query.host).await,
+            "resolve",
+        )
+    }
+
+    pub async fn export_network(api: web::Data<Self>) -> impl Responder {
+        debug!("export_network() called");
+        handle_service_result(
+            NetworkConfigService::export(&api.service_client).await,
+            "export_network",
+        )
+    }
+
+    pub async fn certificate() -> impl Responder {
+        debug!("certificate() called");
+        handle_service_result(CertificateService::cert_info(), "certificate")
+    }
+
+    pub async fn regenerate_certificate(req: HttpRequest, api: web::Data<Self>) -> impl Responder {
+        debug!("regenerate_certificate() called");
+
+        let result = CertificateService::regenerate_module_certificate(
+            &api.service_client,
+            LocalAddr::of(&req),
+        )
+        .await
+        .and_then(|response| {
+            // the server has to restart to serve the new certificate
+            NetworkConfigService::trigger_server_restart()?;
+            Ok(response)
+        });
+
+        handle_service_result(result, "regenerate_certificate")
+    }
+
+    pub async fn set_centrifugo_log_level(body: web::Json<SetLogLevelRequest>) -> impl Responder {
+        debug!("set_centrifugo_log_level() called: {body:?}");
+
+        if let Err(e) = CentrifugoService::validate_log_level(&body.level) {
+            return HttpResponse::BadRequest().body(e.to_string());
+        }
+
+        match CentrifugoService::set_log_level(&body) {
+            Ok(()) => HttpResponse::Ok().finish(),
+            Err(e) => {
+                error!("set_centrifugo_log_level failed: {e:#}");
+                HttpResponse::InternalServerError().body(e.to_string())
+            }
+        }
+    }
+
     pub async fn ack_rollback() -> impl Responder {
         debug!("ack_rollback() called");
         marker::NETWORK_ROLLBACK_OCCURRED.clear();
@@ -286,4 +817,18 @@ where
 
         HttpResponse::Ok().body(token)
     }
+
+    fn free_space_error_response(e: anyhow::Error) -> HttpResponse {
+        if e.is::<InsufficientStorage>() {
+            warn!("upload rejected: {e}");
+            return HttpResponse::InsufficientStorage().body(e.to_string());
+        }
+
+        handle_service_result::<()>(Err(e), "ensure_free_space")
+    }
+
+    fn upload_cancelled_response() -> HttpResponse {
+        HttpResponse::build(StatusCode::from_u16(CLIENT_CLOSED_REQUEST).expect("valid status code"))
+            .body(UploadCancelled.to_string())
+    }
 }
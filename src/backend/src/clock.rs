@@ -0,0 +1,74 @@
+//! Clock abstraction
+//!
+//! Decouples time dependent logic (token expiry, rollback deadlines) from the
+//! system clock so that it can be tested deterministically.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    /// Current point in time
+    fn now(&self) -> SystemTime;
+
+    /// Current time as seconds since the unix epoch
+    fn now_since_epoch(&self) -> Duration {
+        self.now().duration_since(UNIX_EPOCH).unwrap_or_default()
+    }
+}
+
+/// Clock backed by the system time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Manually controlled clock for tests
+#[cfg(test)]
+#[derive(Debug)]
+pub struct FakeClock {
+    now: std::sync::Mutex<SystemTime>,
+}
+
+#[cfg(test)]
+impl FakeClock {
+    /// Create a fake clock starting at the given seconds since the unix epoch
+    pub fn new(secs_since_epoch: u64) -> Self {
+        Self {
+            now: std::sync::Mutex::new(UNIX_EPOCH + Duration::from_secs(secs_since_epoch)),
+        }
+    }
+
+    /// Move the clock forward by the given duration
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fake_clock_starts_at_given_time() {
+        let clock = FakeClock::new(1_000);
+        assert_eq!(clock.now_since_epoch(), Duration::from_secs(1_000));
+    }
+
+    #[test]
+    fn fake_clock_advances() {
+        let clock = FakeClock::new(1_000);
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(clock.now_since_epoch(), Duration::from_secs(1_090));
+    }
+}
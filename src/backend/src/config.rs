@@ -1,7 +1,22 @@
+use crate::{
+    omnect_device_service_client::OmnectDeviceServiceClient,
+    services::{auth::token::DEFAULT_TOKEN_CLOCK_SKEW_SECS, republish::IndexRepublish},
+};
 use anyhow::{Context, Result};
-use std::{env, path::PathBuf, sync::OnceLock};
+use omnect_ui_core::types::{DEFAULT_UPDATE_COMPLETE_STATUSES, PasswordPolicy};
+use regex::Regex;
+use serde_json::{Value, json};
+use std::{env, path::PathBuf, sync::OnceLock, time::Duration};
 use uuid::Uuid;
 
+/// Role granting full access unless ADMIN_ROLE is set
+pub const DEFAULT_ADMIN_ROLE: &str = "FleetAdministrator";
+/// Role granting fleet-scoped access unless OPERATOR_ROLE is set
+pub const DEFAULT_OPERATOR_ROLE: &str = "FleetOperator";
+
+/// Publish endpoint of centrifugo unless CENTRIFUGO_PUBLISH_URL is set
+pub const DEFAULT_CENTRIFUGO_PUBLISH_URL: &str = "https://localhost:{port}/api/publish";
+
 /// Application configuration loaded and validated at startup
 #[derive(Clone, Debug)]
 pub struct AppConfig {
@@ -20,6 +35,12 @@ pub struct AppConfig {
     /// TLS certificate configuration
     pub certificate: CertificateConfig,
 
+    /// Network interface filter configuration
+    pub network_interfaces: NetworkInterfaceConfig,
+
+    /// Password policy applied when setting or updating the password
+    pub password_policy: PasswordPolicy,
+
     /// IoT Edge workload API configuration
     #[cfg_attr(feature = "mock", allow(dead_code))]
     pub iot_edge: IoTEdgeConfig,
@@ -27,13 +48,70 @@ pub struct AppConfig {
     /// Path configuration
     pub paths: PathConfig,
 
-    /// Tenant identifier
-    pub tenant: String,
+    /// Tenant identifiers a token must match at least one of
+    pub tenants: Vec<String>,
+
+    /// Token roles granting access to the device
+    pub roles: RoleConfig,
+
+    /// Serve canned device data and reject all privileged operations
+    pub demo_mode: bool,
+
+    /// Maximum number of executed network rollbacks kept in the rollback history, 0 disables it
+    pub network_rollback_history_size: usize,
+
+    /// Permissions of the uploaded firmware file, which the device service has to read
+    pub update_file_mode: u32,
+
+    /// Free space in bytes that has to remain after a firmware upload
+    pub upload_space_margin: u64,
+
+    /// Time added to the network rollback deadline for the server restart
+    pub network_rollback_restart_margin: Duration,
 }
 
 #[derive(Clone, Debug)]
 pub struct UiConfig {
     pub port: u16,
+    /// Require authentication for `/healthcheck`
+    ///
+    /// Off by default: the browser probes `/healthcheck` unauthenticated to
+    /// detect the device at a new IP after a network change and while waiting
+    /// for it to come back after reboot, update or factory reset. With this
+    /// enabled those probes are rejected, so the UI can't detect reachability
+    /// and falls back to its timeout handling (the network rollback itself is
+    /// unaffected).
+    pub healthcheck_require_auth: bool,
+    /// Require the password for `/factory-reset`, even within a valid session
+    pub factory_reset_require_reauth: bool,
+    /// Path prefix of all routes, e.g. `/omnect` behind a reverse proxy
+    ///
+    /// Normalized without trailing slash, so the default `/` becomes empty.
+    pub base_path: String,
+    /// Maximum idle time of an authenticated session, `None` if sessions don't expire while idle
+    pub session_idle_timeout: Option<Duration>,
+    /// Time a session or SSO token is still accepted after its expiry
+    ///
+    /// Tolerates a device clock running ahead, e.g. before NTP sync.
+    pub token_clock_skew: Duration,
+    /// Republish behavior when the UI is loaded
+    pub index_republish: IndexRepublish,
+    /// Inline the frontend config into the index instead of loading `config.js`
+    pub inline_app_config: bool,
+    /// Generate the frontend config file on startup
+    ///
+    /// If disabled, an existing file is used as provided, e.g. from a read-only mount.
+    pub generate_app_config: bool,
+    /// `Retry-After` hint of a healthcheck failing due to a device service version mismatch,
+    /// `None` if the header is omitted
+    pub healthcheck_retry_after: Option<Duration>,
+    /// Update validation statuses the frontend treats as a completed update
+    ///
+    /// Lets the frontend recognize statuses added by newer device service
+    /// versions without a release.
+    pub update_complete_statuses: Vec<String>,
+    /// Number of HTTP worker threads, `None` if derived from the CPU count
+    pub workers: Option<usize>,
 }
 
 #[derive(Clone, Debug)]
@@ -47,20 +125,99 @@ pub struct CentrifugoConfig {
     pub config_path: PathBuf,
 }
 
+/// Names of the token roles granting access, e.g. for custom Keycloak roles
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoleConfig {
+    /// Role with access to every device of the tenant
+    pub admin: String,
+    /// Role with access to the devices of the fleets in the token's fleet list
+    pub operator: String,
+}
+
+impl Default for RoleConfig {
+    fn default() -> Self {
+        Self {
+            admin: DEFAULT_ADMIN_ROLE.to_string(),
+            operator: DEFAULT_OPERATOR_ROLE.to_string(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct KeycloakConfig {
+    /// Realm endpoint, e.g. `https://keycloak.example.com/realms/omnect`
     pub url: String,
+    pub realm: String,
+    pub client_id: String,
 }
 
 #[derive(Clone, Debug)]
 pub struct DeviceServiceConfig {
     pub socket_path: PathBuf,
+    /// Whether the publish endpoint is also unregistered on a server restart
+    pub unregister_on_restart: bool,
+    /// Delay before unregistering the publish endpoint on shutdown (lets in-flight publishes drain)
+    pub unregister_grace_period: Duration,
+    pub api_versions: DeviceServiceApiVersions,
+    pub pool: ClientPoolConfig,
+}
+
+/// Connection pool settings of an HTTP client, `None` keeps the reqwest default
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClientPoolConfig {
+    /// Maximum number of idle connections kept per host
+    pub max_idle_per_host: Option<usize>,
+    /// Time after which idle connections are closed
+    pub idle_timeout: Option<Duration>,
+}
+
+/// API version used for each device service endpoint, e.g. `v1`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceServiceApiVersions {
+    pub status: String,
+    pub republish: String,
+    pub factory_reset: String,
+    pub reboot: String,
+    pub reload_network: String,
+    pub restart_wpa_supplicant: String,
+    pub load_update: String,
+    pub run_update: String,
+    pub update_progress: String,
+    pub publish_endpoint: String,
+}
+
+impl Default for DeviceServiceApiVersions {
+    fn default() -> Self {
+        let v1 = || "v1".to_string();
+        Self {
+            status: v1(),
+            republish: v1(),
+            factory_reset: v1(),
+            reboot: v1(),
+            reload_network: v1(),
+            restart_wpa_supplicant: v1(),
+            load_update: v1(),
+            run_update: v1(),
+            update_progress: v1(),
+            publish_endpoint: v1(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct CertificateConfig {
     pub cert_path: PathBuf,
     pub key_path: PathBuf,
+    /// Common name of the certificate while no online interface has an IP address
+    pub fallback_common_name: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct NetworkInterfaceConfig {
+    /// Only interfaces whose name matches are kept (all interfaces if unset)
+    pub include: Option<Regex>,
+    /// Interfaces whose name matches are dropped, applied after `include`
+    pub exclude: Option<Regex>,
 }
 
 #[derive(Clone, Debug)]
@@ -77,6 +234,12 @@ pub struct PathConfig {
     pub app_config_path: PathBuf,
     pub data_dir: PathBuf,
     pub password_file: PathBuf,
+    /// Mode and preserved keys of the last factory reset
+    pub last_factory_reset_file: PathBuf,
+    /// Executed network rollbacks, oldest first
+    pub network_rollback_history_file: PathBuf,
+    /// Directory of the systemd-networkd `.network` files of the device
+    pub network_dir: PathBuf,
     pub host_update_file: PathBuf,
     pub local_update_file: PathBuf,
 }
@@ -92,10 +255,22 @@ impl AppConfig {
     /// Panics if configuration loading fails. This is intentional as the
     /// application cannot function without valid configuration.
     pub fn get() -> &'static Self {
+        Self::try_get().expect("failed to load application configuration")
+    }
+
+    /// Get or load the application configuration without panicking
+    ///
+    /// Same as `get()`, but returns an error if loading fails. A failed
+    /// load is not cached, so a later call tries again.
+    pub fn try_get() -> Result<&'static Self> {
         static APP_CONFIG: OnceLock<AppConfig> = OnceLock::new();
-        APP_CONFIG.get_or_init(|| {
-            Self::load_internal().expect("failed to load application configuration")
-        })
+
+        if let Some(config) = APP_CONFIG.get() {
+            return Ok(config);
+        }
+
+        let config = Self::load_internal()?;
+        Ok(APP_CONFIG.get_or_init(|| config))
     }
 
     /// Internal function to load and validate all configuration from environment variables
@@ -115,9 +290,31 @@ impl AppConfig {
         let keycloak = KeycloakConfig::load()?;
         let device_service = DeviceServiceConfig::load()?;
         let certificate = CertificateConfig::load()?;
-        let iot_edge = IoTEdgeConfig::load()?;
+        let network_interfaces = NetworkInterfaceConfig::load()?;
+        let password_policy = load_password_policy()?;
+        let demo_mode = env::var("DEMO_MODE")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("failed to parse DEMO_MODE: invalid format")?;
+        let iot_edge = IoTEdgeConfig::load(demo_mode)?;
         let paths = PathConfig::load()?;
-        let tenant = env::var("TENANT").unwrap_or_else(|_| "cp".to_string());
+        let tenants = load_tenants()?;
+        let roles = RoleConfig::load()?;
+        let network_rollback_history_size = env::var("NETWORK_ROLLBACK_HISTORY_SIZE")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<usize>()
+            .context("failed to parse NETWORK_ROLLBACK_HISTORY_SIZE: invalid format")?;
+        let update_file_mode = load_update_file_mode()?;
+        let upload_space_margin = env::var("UPLOAD_SPACE_MARGIN_MB")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<u64>()
+            .context("failed to parse UPLOAD_SPACE_MARGIN_MB: invalid format")?
+            .saturating_mul(1024 * 1024);
+        let network_rollback_restart_margin = env::var("NETWORK_ROLLBACK_RESTART_MARGIN_SECS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse::<u64>()
+            .map(Duration::from_secs)
+            .context("failed to parse NETWORK_ROLLBACK_RESTART_MARGIN_SECS: invalid format")?;
 
         Ok(Self {
             ui,
@@ -125,9 +322,118 @@ impl AppConfig {
             keycloak,
             device_service,
             certificate,
+            network_interfaces,
+            password_policy,
             iot_edge,
             paths,
-            tenant,
+            tenants,
+            roles,
+            demo_mode,
+            network_rollback_history_size,
+            update_file_mode,
+            upload_space_margin,
+            network_rollback_restart_margin,
+        })
+    }
+}
+
+impl AppConfig {
+    /// Non-secret view of the effective configuration for support purposes
+    ///
+    /// Tokens and keys (centrifugo client token, API key, publish endpoint
+    /// headers) are omitted.
+    pub fn redacted(&self) -> Value {
+        let unregister_grace_period_ms =
+            self.device_service.unregister_grace_period.as_millis() as u64;
+
+        json!({
+            "ui": {
+                "port": self.ui.port,
+                "healthcheckRequireAuth": self.ui.healthcheck_require_auth,
+                "factoryResetRequireReauth": self.ui.factory_reset_require_reauth,
+                "basePath": self.ui.base_path,
+                "sessionIdleTimeoutSecs": self.ui.session_idle_timeout.map(|t| t.as_secs()),
+                "tokenClockSkewSecs": self.ui.token_clock_skew.as_secs(),
+                "indexRepublish": self.ui.index_republish.to_string(),
+                "inlineAppConfig": self.ui.inline_app_config,
+                "generateAppConfig": self.ui.generate_app_config,
+                "healthcheckRetryAfterSecs": self.ui.healthcheck_retry_after.map(|t| t.as_secs()),
+                "updateCompleteStatuses": self.ui.update_complete_statuses,
+                "workers": self.ui.workers,
+            },
+            "centrifugo": {
+                "port": self.centrifugo.port,
+                "publishEndpointUrl": self.centrifugo.publish_endpoint.url,
+                "logLevel": self.centrifugo.log_level,
+                "binaryPath": self.centrifugo.binary_path,
+                "configPath": self.centrifugo.config_path,
+            },
+            "keycloak": {
+                "url": self.keycloak.url,
+                "realm": self.keycloak.realm,
+                "clientId": self.keycloak.client_id,
+            },
+            "deviceService": {
+                "socketPath": self.device_service.socket_path,
+                "requiredVersion": OmnectDeviceServiceClient::REQUIRED_CLIENT_VERSION,
+                "unregisterOnRestart": self.device_service.unregister_on_restart,
+                "unregisterGracePeriodMs": unregister_grace_period_ms,
+                "pool": {
+                    "maxIdlePerHost": self.device_service.pool.max_idle_per_host,
+                    "idleTimeoutSecs": self.device_service.pool.idle_timeout.map(|t| t.as_secs()),
+                },
+                "apiVersions": {
+                    "status": self.device_service.api_versions.status,
+                    "republish": self.device_service.api_versions.republish,
+                    "factoryReset": self.device_service.api_versions.factory_reset,
+                    "reboot": self.device_service.api_versions.reboot,
+                    "reloadNetwork": self.device_service.api_versions.reload_network,
+                    "restartWpaSupplicant": self.device_service.api_versions.restart_wpa_supplicant,
+                    "loadUpdate": self.device_service.api_versions.load_update,
+                    "runUpdate": self.device_service.api_versions.run_update,
+                    "updateProgress": self.device_service.api_versions.update_progress,
+                    "publishEndpoint": self.device_service.api_versions.publish_endpoint,
+                },
+            },
+            "certificate": {
+                "certPath": self.certificate.cert_path,
+                "keyPath": self.certificate.key_path,
+                "fallbackCommonName": self.certificate.fallback_common_name,
+            },
+            "networkInterfaces": {
+                "include": self.network_interfaces.include.as_ref().map(Regex::as_str),
+                "exclude": self.network_interfaces.exclude.as_ref().map(Regex::as_str),
+            },
+            "passwordPolicy": self.password_policy,
+            "iotEdge": {
+                "moduleId": self.iot_edge.module_id,
+                "moduleGenerationId": self.iot_edge.module_generation_id,
+                "apiVersion": self.iot_edge.api_version,
+                "workloadUri": self.iot_edge.workload_uri,
+            },
+            "paths": {
+                "appConfigPath": self.paths.app_config_path,
+                "dataDir": self.paths.data_dir,
+                "passwordFile": self.paths.password_file,
+                "networkRollbackHistoryFile": self.paths.network_rollback_history_file,
+                "networkDir": self.paths.network_dir,
+                "hostUpdateFile": self.paths.host_update_file,
+                "localUpdateFile": self.paths.local_update_file,
+            },
+            "timeouts": {
+                "networkRollbackSecs": crate::services::network::ROLLBACK_TIMEOUT_SECS,
+                "networkRollbackRestartMarginSecs": self.network_rollback_restart_margin.as_secs(),
+                "tokenExpireHours": crate::services::auth::token::TOKEN_EXPIRE_HOURS,
+            },
+            "tenants": self.tenants,
+            "roles": {
+                "admin": self.roles.admin,
+                "operator": self.roles.operator,
+            },
+            "demoMode": self.demo_mode,
+            "networkRollbackHistorySize": self.network_rollback_history_size,
+            "updateFileMode": format!("{:o}", self.update_file_mode),
+            "uploadSpaceMarginBytes": self.upload_space_margin,
         })
     }
 }
@@ -138,8 +444,64 @@ impl UiConfig {
             .unwrap_or_else(|_| "1977".to_string())
             .parse::<u16>()
             .context("failed to parse UI_PORT: invalid format")?;
+        let healthcheck_require_auth = env::var("HEALTHCHECK_REQUIRE_AUTH")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("failed to parse HEALTHCHECK_REQUIRE_AUTH: invalid format")?;
+        let factory_reset_require_reauth = env::var("FACTORY_RESET_REQUIRE_REAUTH")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("failed to parse FACTORY_RESET_REQUIRE_REAUTH: invalid format")?;
+        let base_path = env::var("BASE_PATH").unwrap_or_else(|_| "/".to_string());
+        let base_path = base_path.trim_end_matches('/').to_string();
+        anyhow::ensure!(
+            base_path.is_empty() || base_path.starts_with('/'),
+            "failed to parse BASE_PATH: must start with '/'"
+        );
+        let session_idle_timeout = env::var("SESSION_IDLE_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<u64>()
+            .context("failed to parse SESSION_IDLE_TIMEOUT_SECS: invalid format")?;
+        let session_idle_timeout =
+            (session_idle_timeout > 0).then(|| Duration::from_secs(session_idle_timeout));
+        let token_clock_skew = env::var("TOKEN_CLOCK_SKEW_SECS")
+            .unwrap_or_else(|_| DEFAULT_TOKEN_CLOCK_SKEW_SECS.to_string())
+            .parse::<u64>()
+            .context("failed to parse TOKEN_CLOCK_SKEW_SECS: invalid format")?;
+        let index_republish = env::var("INDEX_REPUBLISH")
+            .map_or(Ok(IndexRepublish::default()), |mode| mode.parse())
+            .context("failed to parse INDEX_REPUBLISH")?;
+        let inline_app_config = env::var("INLINE_APP_CONFIG")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("failed to parse INLINE_APP_CONFIG: invalid format")?;
+        let generate_app_config = env::var("GENERATE_APP_CONFIG")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse::<bool>()
+            .context("failed to parse GENERATE_APP_CONFIG: invalid format")?;
+        let healthcheck_retry_after = env::var("HEALTHCHECK_RETRY_AFTER_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse::<u64>()
+            .context("failed to parse HEALTHCHECK_RETRY_AFTER_SECS: invalid format")?;
+        let healthcheck_retry_after =
+            (healthcheck_retry_after > 0).then(|| Duration::from_secs(healthcheck_retry_after));
+        let update_complete_statuses = load_update_complete_statuses()?;
+        let workers = workers(env::var("UI_WORKERS").ok().as_deref())?;
 
-        Ok(Self { port })
+        Ok(Self {
+            port,
+            healthcheck_require_auth,
+            factory_reset_require_reauth,
+            base_path,
+            session_idle_timeout,
+            token_clock_skew: Duration::from_secs(token_clock_skew),
+            index_republish,
+            inline_app_config,
+            generate_app_config,
+            healthcheck_retry_after,
+            update_complete_statuses,
+            workers,
+        })
     }
 }
 
@@ -152,8 +514,10 @@ impl CentrifugoConfig {
         let client_token = Uuid::new_v4().to_string();
         let api_key = Uuid::new_v4().to_string();
 
+        let publish_url = publish_url(env::var("CENTRIFUGO_PUBLISH_URL").ok().as_deref(), &port)?;
+
         let publish_endpoint = crate::omnect_device_service_client::PublishEndpoint {
-            url: format!("https://localhost:{port}/api/publish"),
+            url: publish_url,
             headers: vec![
                 crate::omnect_device_service_client::HeaderKeyValue {
                     name: String::from("Content-Type"),
@@ -189,6 +553,22 @@ impl CentrifugoConfig {
     }
 }
 
+impl RoleConfig {
+    fn load() -> Result<Self> {
+        let role = |var: &str, default: &str| -> Result<String> {
+            let role = env::var(var).unwrap_or_else(|_| default.to_string());
+            let role = role.trim();
+            anyhow::ensure!(!role.is_empty(), "failed to parse {var}: empty role");
+            Ok(role.to_string())
+        };
+
+        Ok(Self {
+            admin: role("ADMIN_ROLE", DEFAULT_ADMIN_ROLE)?,
+            operator: role("OPERATOR_ROLE", DEFAULT_OPERATOR_ROLE)?,
+        })
+    }
+}
+
 impl KeycloakConfig {
     fn load() -> Result<Self> {
         let url = if cfg!(any(test, feature = "mock")) {
@@ -198,7 +578,27 @@ impl KeycloakConfig {
             env::var("KEYCLOAK_URL")?
         };
 
-        Ok(Self { url })
+        let realm = match env::var("KEYCLOAK_REALM") {
+            Ok(realm) => realm,
+            Err(_) => Self::realm_from_url(&url).with_context(|| {
+                format!("failed to get realm from KEYCLOAK_URL '{url}', set KEYCLOAK_REALM")
+            })?,
+        };
+
+        let client_id = env::var("KEYCLOAK_CLIENT_ID").unwrap_or_else(|_| "omnect-ui".to_string());
+
+        Ok(Self {
+            url,
+            realm,
+            client_id,
+        })
+    }
+
+    /// Realm name of a realm endpoint url, e.g. `omnect` of `https://host/realms/omnect`
+    fn realm_from_url(url: &str) -> Option<String> {
+        let (_, realm) = url.trim_end_matches('/').rsplit_once("/realms/")?;
+
+        (!realm.is_empty() && !realm.contains('/')).then(|| realm.to_string())
     }
 }
 
@@ -208,7 +608,72 @@ impl DeviceServiceConfig {
             .unwrap_or_else(|_| "/socket/device-service.sock".to_string())
             .into();
 
-        Ok(Self { socket_path })
+        let unregister_on_restart = env::var("DEVICE_SERVICE_UNREGISTER_ON_RESTART")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("failed to parse DEVICE_SERVICE_UNREGISTER_ON_RESTART: invalid format")?;
+
+        let unregister_grace_period = env::var("DEVICE_SERVICE_UNREGISTER_GRACE_PERIOD_MS")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse::<u64>()
+            .map(Duration::from_millis)
+            .context("failed to parse DEVICE_SERVICE_UNREGISTER_GRACE_PERIOD_MS: invalid format")?;
+
+        let max_idle_per_host = env::var("DEVICE_SERVICE_POOL_MAX_IDLE_PER_HOST")
+            .ok()
+            .map(|value| value.parse::<usize>())
+            .transpose()
+            .context("failed to parse DEVICE_SERVICE_POOL_MAX_IDLE_PER_HOST: invalid format")?;
+        let idle_timeout = env::var("DEVICE_SERVICE_POOL_IDLE_TIMEOUT_SECS")
+            .ok()
+            .map(|value| value.parse::<u64>().map(Duration::from_secs))
+            .transpose()
+            .context("failed to parse DEVICE_SERVICE_POOL_IDLE_TIMEOUT_SECS: invalid format")?;
+
+        Ok(Self {
+            socket_path,
+            unregister_on_restart,
+            unregister_grace_period,
+            api_versions: DeviceServiceApiVersions::load()?,
+            pool: ClientPoolConfig {
+                max_idle_per_host,
+                idle_timeout,
+            },
+        })
+    }
+}
+
+impl DeviceServiceApiVersions {
+    /// Load the version of each endpoint from `DEVICE_SERVICE_<ENDPOINT>_API_VERSION`
+    fn load() -> Result<Self> {
+        let defaults = Self::default();
+        let version = |endpoint: &str, default: String| -> Result<String> {
+            let var = format!("DEVICE_SERVICE_{endpoint}_API_VERSION");
+            let Ok(version) = env::var(&var) else {
+                return Ok(default);
+            };
+            anyhow::ensure!(
+                !version.is_empty() && version.chars().all(|c| c.is_ascii_alphanumeric()),
+                "failed to parse {var}: invalid version '{version}'"
+            );
+            Ok(version)
+        };
+
+        Ok(Self {
+            status: version("STATUS", defaults.status)?,
+            republish: version("REPUBLISH", defaults.republish)?,
+            factory_reset: version("FACTORY_RESET", defaults.factory_reset)?,
+            reboot: version("REBOOT", defaults.reboot)?,
+            reload_network: version("RELOAD_NETWORK", defaults.reload_network)?,
+            restart_wpa_supplicant: version(
+                "RESTART_WPA_SUPPLICANT",
+                defaults.restart_wpa_supplicant,
+            )?,
+            load_update: version("LOAD_UPDATE", defaults.load_update)?,
+            run_update: version("RUN_UPDATE", defaults.run_update)?,
+            update_progress: version("UPDATE_PROGRESS", defaults.update_progress)?,
+            publish_endpoint: version("PUBLISH_ENDPOINT", defaults.publish_endpoint)?,
+        })
     }
 }
 
@@ -222,17 +687,187 @@ impl CertificateConfig {
             .unwrap_or_else(|_| "/cert/key.pem".to_string())
             .into();
 
+        let fallback_common_name = env::var("COMMON_NAME").ok().filter(|cn| !cn.is_empty());
+
         Ok(Self {
             cert_path,
             key_path,
+            fallback_common_name,
         })
     }
 }
 
-impl IoTEdgeConfig {
+impl NetworkInterfaceConfig {
+    /// Virtual interfaces hidden by default (containers, bridges, CNI overlays)
+    pub const DEFAULT_EXCLUDE: &str = "^(docker|veth|br-|virbr|cni|flannel)";
+
     fn load() -> Result<Self> {
-        #[cfg(any(test, feature = "mock"))]
-        {
+        let include = env::var("NETWORK_INTERFACE_INCLUDE").ok();
+        let exclude = env::var("NETWORK_INTERFACE_EXCLUDE")
+            .unwrap_or_else(|_| Self::DEFAULT_EXCLUDE.to_string());
+
+        Self::new(include.as_deref(), Some(&exclude))
+    }
+
+    /// Build a filter from name patterns, empty patterns are treated as unset
+    pub fn new(include: Option<&str>, exclude: Option<&str>) -> Result<Self> {
+        let compile = |pattern: Option<&str>, var: &str| {
+            pattern
+                .filter(|p| !p.is_empty())
+                .map(Regex::new)
+                .transpose()
+                .with_context(|| format!("failed to parse {var}: invalid regex"))
+        };
+
+        Ok(Self {
+            include: compile(include, "NETWORK_INTERFACE_INCLUDE")?,
+            exclude: compile(exclude, "NETWORK_INTERFACE_EXCLUDE")?,
+        })
+    }
+
+    /// Whether an interface with the given name is shown and editable
+    pub fn is_allowed(&self, name: &str) -> bool {
+        self.include.as_ref().is_none_or(|re| re.is_match(name))
+            && !self.exclude.as_ref().is_some_and(|re| re.is_match(name))
+    }
+}
+
+fn load_password_policy() -> Result<PasswordPolicy> {
+    let flag = |var: &str| {
+        env::var(var)
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .with_context(|| format!("failed to parse {var}: invalid format"))
+    };
+
+    let min_length = env::var("PASSWORD_MIN_LENGTH")
+        .unwrap_or_else(|_| "1".to_string())
+        .parse::<usize>()
+        .context("failed to parse PASSWORD_MIN_LENGTH: invalid format")?;
+
+    Ok(PasswordPolicy {
+        min_length,
+        require_uppercase: flag("PASSWORD_REQUIRE_UPPERCASE")?,
+        require_lowercase: flag("PASSWORD_REQUIRE_LOWERCASE")?,
+        require_digit: flag("PASSWORD_REQUIRE_DIGIT")?,
+        require_special: flag("PASSWORD_REQUIRE_SPECIAL")?,
+    })
+}
+
+/// Load the update validation statuses meaning an update is complete from UPDATE_COMPLETE_STATUSES
+///
+/// Accepts a comma-separated list, defaults to [`DEFAULT_UPDATE_COMPLETE_STATUSES`].
+fn load_update_complete_statuses() -> Result<Vec<String>> {
+    let Ok(value) = env::var("UPDATE_COMPLETE_STATUSES") else {
+        return Ok(DEFAULT_UPDATE_COMPLETE_STATUSES
+            .iter()
+            .map(|status| status.to_string())
+            .collect());
+    };
+
+    let statuses: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect();
+
+    anyhow::ensure!(
+        !statuses.is_empty(),
+        "failed to parse UPDATE_COMPLETE_STATUSES: no status configured"
+    );
+
+    Ok(statuses)
+}
+
+/// Load the permissions of the uploaded firmware file from UPDATE_FILE_MODE
+///
+/// Accepts an octal mode with or without `0o` prefix, e.g. `0640`, defaults to `0750`.
+fn load_update_file_mode() -> Result<u32> {
+    let Ok(value) = env::var("UPDATE_FILE_MODE") else {
+        return Ok(0o750);
+    };
+
+    let mode = u32::from_str_radix(value.trim().trim_start_matches("0o"), 8)
+        .context("failed to parse UPDATE_FILE_MODE: invalid octal mode")?;
+
+    anyhow::ensure!(
+        mode <= 0o7777,
+        "failed to parse UPDATE_FILE_MODE: mode out of range"
+    );
+
+    Ok(mode)
+}
+
+/// Number of HTTP worker threads, `value` is the value of UI_WORKERS
+///
+/// None if unset, so the worker count is derived from the CPU count.
+fn workers(value: Option<&str>) -> Result<Option<usize>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    let workers = value
+        .trim()
+        .parse::<usize>()
+        .context("failed to parse UI_WORKERS: invalid format")?;
+
+    anyhow::ensure!(
+        workers >= 1,
+        "failed to parse UI_WORKERS: must be at least 1"
+    );
+
+    Ok(Some(workers))
+}
+
+/// Publish endpoint of centrifugo, `template` is the value of CENTRIFUGO_PUBLISH_URL
+///
+/// A `{port}` placeholder in the template is replaced by the centrifugo port,
+/// defaults to [`DEFAULT_CENTRIFUGO_PUBLISH_URL`].
+fn publish_url(template: Option<&str>, port: &str) -> Result<String> {
+    let url = template
+        .unwrap_or(DEFAULT_CENTRIFUGO_PUBLISH_URL)
+        .trim()
+        .replace("{port}", port);
+
+    let parsed = reqwest::Url::parse(&url)
+        .with_context(|| format!("failed to parse CENTRIFUGO_PUBLISH_URL: invalid url '{url}'"))?;
+
+    anyhow::ensure!(
+        matches!(parsed.scheme(), "http" | "https") && parsed.has_host(),
+        "failed to parse CENTRIFUGO_PUBLISH_URL: '{url}' is no http(s) url"
+    );
+
+    Ok(url)
+}
+
+/// Load the tenant allowlist from TENANTS, falling back to TENANT
+///
+/// Both accept a single tenant or a comma-separated list.
+fn load_tenants() -> Result<Vec<String>> {
+    let value = env::var("TENANTS")
+        .or_else(|_| env::var("TENANT"))
+        .unwrap_or_else(|_| "cp".to_string());
+
+    let tenants: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect();
+
+    anyhow::ensure!(
+        !tenants.is_empty(),
+        "failed to parse TENANTS: no tenant configured"
+    );
+
+    Ok(tenants)
+}
+
+impl IoTEdgeConfig {
+    fn load(demo_mode: bool) -> Result<Self> {
+        // tests and demo mode run without the IoT Edge runtime
+        if cfg!(any(test, feature = "mock")) || demo_mode {
             let module_id =
                 env::var("IOTEDGE_MODULEID").unwrap_or_else(|_| "test-module".to_string());
             let module_generation_id =
@@ -242,60 +877,62 @@ impl IoTEdgeConfig {
             let workload_uri = env::var("IOTEDGE_WORKLOADURI")
                 .unwrap_or_else(|_| "unix:///var/run/iotedge/workload.sock".to_string());
 
-            Ok(Self {
+            return Ok(Self {
                 module_id,
                 module_generation_id,
                 api_version,
                 workload_uri,
-            })
+            });
         }
 
-        #[cfg(not(any(test, feature = "mock")))]
-        {
-            let module_id =
-                env::var("IOTEDGE_MODULEID").context("failed to get IOTEDGE_MODULEID")?;
-            let module_generation_id = env::var("IOTEDGE_MODULEGENERATIONID")
-                .context("failed to get IOTEDGE_MODULEGENERATIONID")?;
-            let api_version =
-                env::var("IOTEDGE_APIVERSION").context("failed to get IOTEDGE_APIVERSION")?;
-            let workload_uri =
-                env::var("IOTEDGE_WORKLOADURI").context("failed to get IOTEDGE_WORKLOADURI")?;
+        let module_id = env::var("IOTEDGE_MODULEID").context("failed to get IOTEDGE_MODULEID")?;
+        let module_generation_id = env::var("IOTEDGE_MODULEGENERATIONID")
+            .context("failed to get IOTEDGE_MODULEGENERATIONID")?;
+        let api_version =
+            env::var("IOTEDGE_APIVERSION").context("failed to get IOTEDGE_APIVERSION")?;
+        let workload_uri =
+            env::var("IOTEDGE_WORKLOADURI").context("failed to get IOTEDGE_WORKLOADURI")?;
 
-            Ok(Self {
-                module_id,
-                module_generation_id,
-                api_version,
-                workload_uri,
-            })
-        }
+        Ok(Self {
+            module_id,
+            module_generation_id,
+            api_version,
+            workload_uri,
+        })
     }
 }
 
 impl PathConfig {
     fn load() -> Result<Self> {
         #[cfg(not(any(test, feature = "mock")))]
-        let (data_dir, host_data_dir) = (
+        let (data_dir, host_data_dir, network_dir) = (
             PathBuf::from("/data/"),
             PathBuf::from("/var/lib/").join(env!("CARGO_PKG_NAME")),
+            PathBuf::from("/network/"),
         );
 
         #[cfg(any(test, feature = "mock"))]
-        let (data_dir, host_data_dir) = {
+        let (data_dir, host_data_dir, network_dir) = {
             let data_dir = std::env::temp_dir().join("omnect-ui-test");
+            let network_dir = data_dir.join("network");
 
-            std::fs::create_dir_all(&data_dir)
-                .context("failed to create data directory")
+            std::fs::create_dir_all(&network_dir)
+                .context("failed to create network directory")
                 .unwrap();
 
-            (data_dir.clone(), data_dir)
+            (data_dir.clone(), data_dir, network_dir)
         };
 
         // Ensure config directory exists
         let config_dir = data_dir.join("config");
         std::fs::create_dir_all(&config_dir).context("failed to create config directory")?;
 
-        let app_config_path = config_dir.join("app_config.js");
+        let app_config_path = env::var("APP_CONFIG_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| config_dir.join("app_config.js"));
         let password_file = config_dir.join("password");
+        let last_factory_reset_file = config_dir.join("last_factory_reset.json");
+        let network_rollback_history_file = config_dir.join("network_rollback_history.json");
         let host_update_file = host_data_dir.join("update.tar");
         let local_update_file = data_dir.join("update.tar");
 
@@ -303,8 +940,75 @@ impl PathConfig {
             app_config_path,
             data_dir,
             password_file,
+            last_factory_reset_file,
+            network_rollback_history_file,
+            network_dir,
             host_update_file,
             local_update_file,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod publish_url {
+        use super::*;
+
+        #[test]
+        fn defaults_to_localhost() {
+            assert_eq!(
+                publish_url(None, "8000").unwrap(),
+                "https://localhost:8000/api/publish"
+            );
+        }
+
+        #[test]
+        fn replaces_port_placeholder() {
+            assert_eq!(
+                publish_url(
+                    Some("https://centrifugo:{port}/centrifugo/api/publish"),
+                    "8001"
+                )
+                .unwrap(),
+                "https://centrifugo:8001/centrifugo/api/publish"
+            );
+        }
+
+        #[test]
+        fn rejects_invalid_url() {
+            assert!(publish_url(Some("not a url"), "8000").is_err());
+            assert!(publish_url(Some("unix:/run/centrifugo.sock"), "8000").is_err());
+        }
+    }
+
+    mod workers {
+        use super::*;
+
+        #[test]
+        fn defaults_to_cpu_based_count() {
+            assert_eq!(workers(None).unwrap(), None);
+        }
+
+        #[test]
+        fn accepts_positive_count() {
+            assert_eq!(workers(Some("1")).unwrap(), Some(1));
+            assert_eq!(workers(Some(" 3 ")).unwrap(), Some(3));
+        }
+
+        #[test]
+        fn rejects_zero() {
+            let err = workers(Some("0")).unwrap_err();
+
+            assert!(err.to_string().contains("at least 1"));
+        }
+
+        #[test]
+        fn rejects_invalid_format() {
+            for value in ["", "-1", "two", "1.5"] {
+                assert!(workers(Some(value)).is_err(), "{value}");
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Device service client serving canned data for demo mode
+//!
+//! Used instead of the real device service when `DEMO_MODE` is set, e.g. for
+//! screenshots and training. Read operations return static data, all
+//! operations changing the device are rejected. The data the device service
+//! would publish is provided as canned centrifugo publications.
+
+use crate::omnect_device_service_client::{
+    DeviceServiceClient, FactoryReset, HealthcheckInfo, Ipv4AddrInfo, Ipv4Info, LoadUpdate,
+    NetworkInterface, NetworkStatus, OmnectDeviceServiceClient, PublishEndpoint, RunUpdate, Status,
+    SystemInfo, UpdateValidationStatus, VersionInfo,
+};
+use anyhow::{Result, bail};
+use serde_json::{Value, json};
+use std::{net::IpAddr, path::PathBuf};
+
+/// Error message returned for all operations rejected in demo mode
+pub const DEMO_MODE_ERROR: &str = "demo mode";
+
+const DEMO_FLEET_ID: &str = "demo-fleet";
+const DEMO_DEVICE_SERVICE_VERSION: &str = "0.41.0";
+const DEMO_IP_ADDRESS: &str = "192.168.0.100";
+const DEMO_MAC_ADDRESS: &str = "00:00:5e:00:53:01";
+const DEMO_RESOLVED_ADDRESS: &str = "192.0.2.1";
+const DEMO_HOSTNAME: &str = "omnect-demo";
+const DEMO_WAIT_ONLINE_TIMEOUT_SECS: u64 = 300;
+
+#[derive(Clone, Debug, Default)]
+pub struct DemoDeviceServiceClient;
+
+impl DemoDeviceServiceClient {
+    fn network_status() -> NetworkStatus {
+        NetworkStatus {
+            network_interfaces: vec![NetworkInterface {
+                online: true,
+                ipv4: Ipv4Info {
+                    addrs: vec![Ipv4AddrInfo {
+                        addr: DEMO_IP_ADDRESS.to_string(),
+                    }],
+                },
+                file: PathBuf::from("/etc/systemd/network/10-eth0.network"),
+                mac: DEMO_MAC_ADDRESS.to_string(),
+                name: "eth0".to_string(),
+            }],
+        }
+    }
+
+    fn update_validation_status() -> UpdateValidationStatus {
+        UpdateValidationStatus {
+            status: "NoUpdate".to_string(),
+        }
+    }
+
+    /// Publications the device service would send, in the format of centrifugo's publish API
+    pub fn publications() -> Vec<Value> {
+        let network_status: Vec<Value> = Self::network_status()
+            .network_interfaces
+            .into_iter()
+            .map(|iface| {
+                let addrs: Vec<Value> = iface
+                    .ipv4
+                    .addrs
+                    .iter()
+                    .map(|addr| json!({ "addr": addr.addr, "dhcp": false, "prefix_len": 24 }))
+                    .collect();
+
+                json!({
+                    "ipv4": {
+                        "addrs": addrs,
+                        "dns": [],
+                        "gateways": [],
+                    },
+                    "mac": iface.mac,
+                    "name": iface.name,
+                    "online": iface.online,
+                    "file": iface.file,
+                })
+            })
+            .collect();
+
+        [
+            ("OnlineStatusV1", json!({ "iothub": false })),
+            (
+                "SystemInfoV1",
+                json!({
+                    "os": { "name": "omnect-os", "version": "demo" },
+                    "azure_sdk_version": "demo",
+                    "omnect_device_service_version": DEMO_DEVICE_SERVICE_VERSION,
+                    "boot_time": null,
+                    "hostname": DEMO_HOSTNAME,
+                }),
+            ),
+            (
+                "TimeoutsV1",
+                json!({
+                    "wait_online_timeout": { "nanos": 0, "secs": DEMO_WAIT_ONLINE_TIMEOUT_SECS },
+                }),
+            ),
+            (
+                "NetworkStatusV1",
+                json!({ "network_status": network_status }),
+            ),
+            ("FactoryResetV1", json!({ "keys": [], "result": null })),
+            (
+                "UpdateValidationStatusV1",
+                json!({ "status": Self::update_validation_status().status }),
+            ),
+        ]
+        .into_iter()
+        .map(|(channel, data)| json!({ "channel": channel, "data": data }))
+        .collect()
+    }
+}
+
+impl DeviceServiceClient for DemoDeviceServiceClient {
+    async fn fleet_id(&self) -> Result<String> {
+        Ok(DEMO_FLEET_ID.to_string())
+    }
+
+    async fn ip_address(&self, _preferred: Option<IpAddr>) -> Result<String> {
+        Ok(DEMO_IP_ADDRESS.to_string())
+    }
+
+    async fn resolve(&self, _hostname: &str) -> Result<Vec<String>> {
+        Ok(vec![DEMO_RESOLVED_ADDRESS.to_string()])
+    }
+
+    async fn status(&self) -> Result<Status> {
+        Ok(Status {
+            network_status: Self::network_status(),
+            system_info: SystemInfo {
+                fleet_id: Some(DEMO_FLEET_ID.to_string()),
+                omnect_device_service_version: DEMO_DEVICE_SERVICE_VERSION.to_string(),
+                boot_time: None,
+            },
+            update_validation_status: Self::update_validation_status(),
+        })
+    }
+
+    async fn republish(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn factory_reset(&self, _factory_reset: FactoryReset) -> Result<()> {
+        bail!(DEMO_MODE_ERROR)
+    }
+
+    async fn reboot(&self) -> Result<()> {
+        bail!(DEMO_MODE_ERROR)
+    }
+
+    async fn register_publish_endpoint(&mut self, _endpoint: PublishEndpoint) -> Result<()> {
+        Ok(())
+    }
+
+    async fn reload_network(&self) -> Result<()> {
+        bail!(DEMO_MODE_ERROR)
+    }
+
+    async fn restart_wpa_supplicant(&self, _interface: &str) -> Result<()> {
+        bail!(DEMO_MODE_ERROR)
+    }
+
+    async fn load_update(&self, _load_update: LoadUpdate) -> Result<String> {
+        bail!(DEMO_MODE_ERROR)
+    }
+
+    async fn run_update(&self, _run_update: RunUpdate) -> Result<()> {
+        bail!(DEMO_MODE_ERROR)
+    }
+
+    async fn update_progress(&self) -> Result<u8> {
+        bail!("no update in progress")
+    }
+
+    async fn healthcheck_info(&self, _preferred: Option<IpAddr>) -> Result<HealthcheckInfo> {
+        Ok(HealthcheckInfo {
+            version_info: VersionInfo {
+                required: OmnectDeviceServiceClient::REQUIRED_CLIENT_VERSION.to_string(),
+                current: DEMO_DEVICE_SERVICE_VERSION.to_string(),
+                mismatch: false,
+            },
+            update_validation_status: Self::update_validation_status(),
+            network_rollback_occurred: false,
+            factory_reset_result_acked: true,
+            update_validation_acked: true,
+            primary_mac: Some(DEMO_MAC_ADDRESS.to_string()),
+            update_progress: None,
+            boot_time: None,
+            last_operation: None,
+            last_factory_reset: None,
+        })
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::{Version, VersionReq};
+
+    #[tokio::test]
+    async fn status_returns_canned_data() {
+        let status = DemoDeviceServiceClient.status().await.unwrap();
+
+        assert_eq!(status.system_info.fleet_id.as_deref(), Some(DEMO_FLEET_ID));
+        let iface = status
+            .network_status
+            .online_interface_with(DEMO_IP_ADDRESS.parse().unwrap());
+        assert!(iface.is_some());
+    }
+
+    #[test]
+    fn demo_version_satisfies_required_version() {
+        let required =
+            VersionReq::parse(OmnectDeviceServiceClient::REQUIRED_CLIENT_VERSION).unwrap();
+
+        assert!(required.matches(&Version::parse(DEMO_DEVICE_SERVICE_VERSION).unwrap()));
+    }
+
+    #[test]
+    fn publications_match_device_service_format() {
+        use omnect_ui_core::types::{
+            OdsFactoryReset, OdsNetworkStatus, OdsOnlineStatus, OdsSystemInfo, OdsTimeouts,
+            OdsUpdateValidationStatus,
+        };
+
+        for publication in DemoDeviceServiceClient::publications() {
+            let data = publication["data"].clone();
+            let parsed = match publication["channel"].as_str().unwrap() {
+                "OnlineStatusV1" => serde_json::from_value::<OdsOnlineStatus>(data).map(drop),
+                "SystemInfoV1" => serde_json::from_value::<OdsSystemInfo>(data).map(drop),
+                "TimeoutsV1" => serde_json::from_value::<OdsTimeouts>(data).map(drop),
+                "NetworkStatusV1" => serde_json::from_value::<OdsNetworkStatus>(data).map(drop),
+                "FactoryResetV1" => serde_json::from_value::<OdsFactoryReset>(data).map(drop),
+                "UpdateValidationStatusV1" => {
+                    serde_json::from_value::<OdsUpdateValidationStatus>(data).map(drop)
+                }
+                channel => panic!("unexpected channel {channel}"),
+            };
+            assert!(parsed.is_ok(), "{publication}: {parsed:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn mutating_operations_are_rejected() {
+        let client = DemoDeviceServiceClient;
+
+        assert_eq!(
+            client.reboot().await.unwrap_err().to_string(),
+            DEMO_MODE_ERROR
+        );
+        assert!(client.reload_network().await.is_err());
+        assert!(client.factory_reset(FactoryReset::default()).await.is_err());
+    }
+}
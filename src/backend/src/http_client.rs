@@ -1,7 +1,9 @@
-use actix_web::HttpResponse;
+use crate::config::ClientPoolConfig;
+use actix_web::{HttpResponse, http::header};
 use anyhow::{Context, Result, ensure};
-use log::error;
-use reqwest::{Client, Response};
+use log::{debug, error};
+use omnect_ui_core::types::DeviceServiceError;
+use reqwest::{Client, Response, StatusCode};
 use std::path::Path;
 
 /// Create a Unix socket client for local service communication
@@ -24,6 +26,15 @@ use std::path::Path;
 ///     .expect("failed to create client");
 /// ```
 pub fn unix_socket_client(socket_path: &str) -> Result<Client> {
+    unix_socket_client_with_pool(socket_path, &ClientPoolConfig::default())
+}
+
+/// Create a Unix socket client with the given connection pool settings
+///
+/// # Arguments
+/// * `socket_path` - Path to the Unix socket (with or without `unix://` prefix)
+/// * `pool` - Connection pool settings, unset values keep the reqwest default
+pub fn unix_socket_client_with_pool(socket_path: &str, pool: &ClientPoolConfig) -> Result<Client> {
     let socket_path = Path::new(socket_path.strip_prefix("unix://").unwrap_or(socket_path));
 
     // Verify the socket path exists
@@ -34,8 +45,15 @@ pub fn unix_socket_client(socket_path: &str) -> Result<Client> {
         "failed since socket path does not exist: {socket_path:?}"
     );
 
-    Client::builder()
-        .unix_socket(socket_path)
+    let mut builder = Client::builder().unix_socket(socket_path);
+    if let Some(max_idle_per_host) = pool.max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle_per_host);
+    }
+    if let Some(idle_timeout) = pool.idle_timeout {
+        builder = builder.pool_idle_timeout(idle_timeout);
+    }
+
+    builder
         .build()
         .context("failed to create Unix socket HTTP client")
 }
@@ -71,6 +89,89 @@ impl ServiceResultResponse for crate::services::network::SetNetworkConfigRespons
     }
 }
 
+impl ServiceResultResponse for crate::services::network::ResolveResponse {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::network::RollbackNowResponse {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::network::RollbackStatus {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::network::NetworkExport {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok()
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"network-export.json\"",
+            ))
+            .json(self)
+    }
+}
+
+impl ServiceResultResponse for omnect_ui_core::types::UpdatePasswordResult {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::identity::DeviceIdentity {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::firmware::LoadUpdateResult {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::firmware::ChunkUploadStatus {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::operation::OperationStarted {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::schedule::ScheduledOperation {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Accepted().json(self)
+    }
+}
+
+impl ServiceResultResponse for Vec<crate::services::schedule::ScheduledOperation> {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::certificate::CertificateInfo {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
+impl ServiceResultResponse for crate::services::certificate::RegenerateCertificateResponse {
+    fn into_response(self) -> HttpResponse {
+        HttpResponse::Ok().json(self)
+    }
+}
+
 /// Handle Result and extracting convert data to Response
 ///
 /// This is a common utility for processing Results and transform to HTTP responses.
@@ -90,7 +191,11 @@ where
         Ok(data) => data.into_response(),
         Err(e) => {
             error!("{operation} failed: {e:#}");
-            HttpResponse::InternalServerError().body(e.to_string())
+            // pass on a structured device service error, so the UI can show its code
+            match e.downcast_ref::<DeviceServiceError>() {
+                Some(device_error) => HttpResponse::InternalServerError().json(device_error),
+                None => HttpResponse::InternalServerError().body(e.to_string()),
+            }
         }
     }
 }
@@ -99,6 +204,8 @@ where
 ///
 /// This is a common utility for processing HTTP responses.
 /// It ensures the response status is successful and extracts the body text.
+/// Every 2xx status counts as success, e.g. `202 Accepted` of an operation
+/// running on asynchronously or `204 No Content` with an empty body.
 ///
 /// # Arguments
 /// * `res` - The HTTP response to handle
@@ -111,11 +218,31 @@ pub async fn handle_http_response(res: Response, context_msg: &str) -> Result<St
     let status = res.status();
     let body = res.text().await.context("failed to read response body")?;
 
+    check_response_status(status, body, context_msg)
+}
+
+/// Body of a response with the given status, an error unless the status is 2xx
+///
+/// A JSON error body of the device service is kept as [`DeviceServiceError`].
+fn check_response_status(status: StatusCode, body: String, context_msg: &str) -> Result<String> {
+    if !status.is_success()
+        && let Some(device_error) = DeviceServiceError::from_body(&body)
+    {
+        return Err(anyhow::Error::new(device_error)
+            .context(format!("{context_msg} failed with status {status}")));
+    }
+
     ensure!(
         status.is_success(),
         "{context_msg} failed with status {status} and body: {body}"
     );
 
+    match status {
+        StatusCode::ACCEPTED => debug!("{context_msg} accepted, processed asynchronously"),
+        StatusCode::NO_CONTENT => debug!("{context_msg} succeeded without content"),
+        _ => {}
+    }
+
     Ok(body)
 }
 
@@ -137,6 +264,112 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unix_socket_client_accepts_custom_pool_settings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("api.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+        let pool = ClientPoolConfig {
+            max_idle_per_host: Some(4),
+            idle_timeout: Some(std::time::Duration::from_secs(10)),
+        };
+
+        let result = unix_socket_client_with_pool(&socket_path.to_string_lossy(), &pool);
+
+        assert!(result.is_ok());
+    }
+
+    mod check_response_status {
+        use super::*;
+
+        #[test]
+        fn ok_returns_body() {
+            let body = check_response_status(StatusCode::OK, "data".to_string(), "GET /status");
+
+            assert_eq!(body.unwrap(), "data");
+        }
+
+        #[test]
+        fn accepted_is_success() {
+            let body = check_response_status(StatusCode::ACCEPTED, String::new(), "POST /reboot");
+
+            assert_eq!(body.unwrap(), "");
+        }
+
+        #[test]
+        fn no_content_is_success() {
+            let body =
+                check_response_status(StatusCode::NO_CONTENT, String::new(), "POST /factory-reset");
+
+            assert_eq!(body.unwrap(), "");
+        }
+
+        #[test]
+        fn json_error_body_is_kept_as_device_service_error() {
+            let err = check_response_status(
+                StatusCode::BAD_REQUEST,
+                r#"{"code":"E42","message":"update image is corrupt"}"#.to_string(),
+                "POST /fwupdate/load/v1",
+            )
+            .unwrap_err();
+
+            assert_eq!(
+                err.downcast_ref::<DeviceServiceError>(),
+                Some(&DeviceServiceError {
+                    code: "E42".to_string(),
+                    message: "update image is corrupt".to_string(),
+                })
+            );
+            assert!(err.to_string().contains("400"));
+        }
+
+        #[test]
+        fn plain_text_error_body_is_kept_as_text() {
+            let err = check_response_status(
+                StatusCode::BAD_REQUEST,
+                "update image is corrupt".to_string(),
+                "POST /fwupdate/load/v1",
+            )
+            .unwrap_err();
+
+            assert!(err.downcast_ref::<DeviceServiceError>().is_none());
+            assert!(err.to_string().contains("update image is corrupt"));
+        }
+
+        #[test]
+        fn device_service_error_is_passed_on_as_json() {
+            let device_error = DeviceServiceError {
+                code: "E42".to_string(),
+                message: "update image is corrupt".to_string(),
+            };
+            let result: Result<()> = Err(anyhow::Error::new(device_error.clone()).context("load"));
+
+            let response = handle_service_result(result, "load_update");
+
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(
+                response.headers().get(header::CONTENT_TYPE).unwrap(),
+                "application/json"
+            );
+        }
+
+        #[test]
+        fn other_statuses_are_errors() {
+            for status in [
+                StatusCode::MOVED_PERMANENTLY,
+                StatusCode::BAD_REQUEST,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ] {
+                let err = check_response_status(status, "reason".to_string(), "POST /reboot")
+                    .unwrap_err()
+                    .to_string();
+
+                assert!(err.contains(status.as_str()), "{err}");
+                assert!(err.contains("reason"), "{err}");
+            }
+        }
+    }
+
     #[test]
     fn test_unix_socket_client_rejects_nonexistent_unix_uri() {
         let socket_path = "unix:///tmp/nonexistent-workload.sock";
@@ -1,10 +1,13 @@
-use crate::config::AppConfig;
+use crate::config::{AppConfig, KeycloakConfig};
 use anyhow::{Context, Result};
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use log::info;
 #[cfg(feature = "mock")]
 use mockall::automock;
+use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::{path::Path, time::Duration};
 use trait_variant::make;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -14,6 +17,25 @@ pub struct TokenClaims {
     pub fleet_list: Option<Vec<String>>,
 }
 
+/// Keycloak settings the frontend needs to initiate the SSO login
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeycloakLoginConfig {
+    pub keycloak_url: String,
+    pub realm: String,
+    pub client_id: String,
+}
+
+impl From<&KeycloakConfig> for KeycloakLoginConfig {
+    fn from(config: &KeycloakConfig) -> Self {
+        Self {
+            keycloak_url: config.url.clone(),
+            realm: config.realm.clone(),
+            client_id: config.client_id.clone(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct RealmInfo {
     public_key: String,
@@ -30,21 +52,33 @@ pub struct KeycloakProvider;
 
 impl KeycloakProvider {
     pub fn create_frontend_config_file() -> Result<()> {
-        use anyhow::Context;
-        use std::io::Write;
-
-        let mut config_file = std::fs::File::create(&AppConfig::get().paths.app_config_path)
-            .context("failed to create frontend config file")?;
-
-        config_file
-            .write_all(
-                format!(
-                    "window.__APP_CONFIG__ = {{KEYCLOAK_URL:\"{}\"}};",
-                    AppConfig::get().keycloak.url
-                )
-                .as_bytes(),
-            )
-            .context("failed to write frontend config file")
+        let config = AppConfig::get();
+
+        write_frontend_config_file(
+            &config.paths.app_config_path,
+            &Self::frontend_config_script(),
+            config.ui.generate_app_config,
+        )
+    }
+
+    /// Frontend config, served as `config.json` for the core
+    pub fn frontend_config() -> serde_json::Value {
+        let config = AppConfig::get();
+        // the UI applies the interface filter to the adapters published by the device service
+        let interfaces = &config.network_interfaces;
+
+        serde_json::json!({
+            "KEYCLOAK_URL": config.keycloak.url,
+            "BASE_PATH": config.ui.base_path,
+            "UPDATE_COMPLETE_STATUSES": config.ui.update_complete_statuses,
+            "NETWORK_INTERFACE_INCLUDE": interfaces.include.as_ref().map(Regex::as_str),
+            "NETWORK_INTERFACE_EXCLUDE": interfaces.exclude.as_ref().map(Regex::as_str),
+        })
+    }
+
+    /// Script setting the frontend config, served as `config.js` or inlined into the index
+    pub fn frontend_config_script() -> String {
+        format!("window.__APP_CONFIG__ = {};", Self::frontend_config())
     }
 
     async fn realm_public_key(&self) -> Result<DecodingKey> {
@@ -62,6 +96,16 @@ impl KeycloakProvider {
     }
 }
 
+/// Write the frontend config file, an existing file is kept if generation is disabled
+fn write_frontend_config_file(path: &Path, script: &str, generate: bool) -> Result<()> {
+    if !generate && path.try_exists().unwrap_or(false) {
+        info!("using provided frontend config file {}", path.display());
+        return Ok(());
+    }
+
+    std::fs::write(path, script).context("failed to write frontend config file")
+}
+
 /// Parse base64-encoded SPKI public key (as returned by Keycloak's realm endpoint)
 /// into a DecodingKey for JWT verification.
 fn decoding_key_from_keycloak(base64_spki: &str) -> Result<DecodingKey> {
@@ -69,16 +113,23 @@ fn decoding_key_from_keycloak(base64_spki: &str) -> Result<DecodingKey> {
     DecodingKey::from_rsa_pem(pem.as_bytes()).context("failed to parse public key from PEM")
 }
 
+/// Validation of Keycloak tokens, accepting tokens expired less than `leeway` ago
+fn keycloak_validation(leeway: Duration) -> Validation {
+    let mut validation = Validation::new(Algorithm::RS256);
+    // Keycloak tokens usually have these, but we don't strictly require them here
+    // as we only care about the custom claims if the token is valid.
+    validation.validate_exp = true;
+    validation.validate_aud = false;
+    validation.required_spec_claims.remove("iss");
+    validation.required_spec_claims.remove("aud");
+    validation.leeway = leeway.as_secs();
+    validation
+}
+
 impl SingleSignOnProvider for KeycloakProvider {
     async fn verify_token(&self, token: &str) -> anyhow::Result<TokenClaims> {
         let pub_key = self.realm_public_key().await?;
-        let mut validation = Validation::new(Algorithm::RS256);
-        // Keycloak tokens usually have these, but we don't strictly require them here
-        // as we only care about the custom claims if the token is valid.
-        validation.validate_exp = true;
-        validation.validate_aud = false;
-        validation.required_spec_claims.remove("iss");
-        validation.required_spec_claims.remove("aud");
+        let validation = keycloak_validation(AppConfig::get().ui.token_clock_skew);
 
         let claims = decode::<TokenClaims>(token, &pub_key, &validation)?;
         Ok(claims.claims)
@@ -133,12 +184,14 @@ Y0282ogmR+NZiE25/g1ZBLkIDBuXU52hE2yKsO1VHf7ixbkxozNCt45XfzfNes9rH9swg4+sZWJ\
     const TOKEN_EXPIRE_HOURS: u64 = 2;
 
     fn sign_test_token(claims: &TokenClaims) -> String {
+        let iat = get_current_timestamp();
+        sign_test_token_with_times(claims, iat, iat + TOKEN_EXPIRE_HOURS * 3600)
+    }
+
+    fn sign_test_token_with_times(claims: &TokenClaims, iat: u64, exp: u64) -> String {
         let key = EncodingKey::from_rsa_pem(TEST_RSA_PRIVATE_KEY_PEM.as_bytes())
             .expect("test private key should parse");
 
-        let iat = get_current_timestamp();
-        let exp = iat + TOKEN_EXPIRE_HOURS * 3600;
-
         // Wrap TokenClaims with standard JWT fields
         #[derive(Serialize)]
         struct FullClaims<'a> {
@@ -176,11 +229,7 @@ Y0282ogmR+NZiE25/g1ZBLkIDBuXU52hE2yKsO1VHf7ixbkxozNCt45XfzfNes9rH9swg4+sZWJ\
         // Verify using the same code path as production
         let pub_key =
             decoding_key_from_keycloak(TEST_KEYCLOAK_PUBLIC_KEY).expect("key should parse");
-        let mut validation = Validation::new(Algorithm::RS256);
-        validation.validate_exp = true;
-        validation.validate_aud = false;
-        validation.required_spec_claims.remove("iss");
-        validation.required_spec_claims.remove("aud");
+        let validation = keycloak_validation(Duration::from_secs(60));
 
         let decoded = decode::<TokenClaims>(&token, &pub_key, &validation);
         assert!(decoded.is_ok(), "signature verification should succeed");
@@ -196,9 +245,68 @@ Y0282ogmR+NZiE25/g1ZBLkIDBuXU52hE2yKsO1VHf7ixbkxozNCt45XfzfNes9rH9swg4+sZWJ\
         );
     }
 
+    #[test]
+    fn token_expired_within_leeway_verifies() {
+        let claims = TokenClaims {
+            roles: None,
+            tenant_list: None,
+            fleet_list: None,
+        };
+        let now = get_current_timestamp();
+        // expired 5 minutes ago
+        let token = sign_test_token_with_times(&claims, now - 3600, now - 300);
+        let pub_key =
+            decoding_key_from_keycloak(TEST_KEYCLOAK_PUBLIC_KEY).expect("key should parse");
+
+        let within = keycloak_validation(Duration::from_secs(600));
+        assert!(decode::<TokenClaims>(&token, &pub_key, &within).is_ok());
+
+        let beyond = keycloak_validation(Duration::from_secs(60));
+        assert!(decode::<TokenClaims>(&token, &pub_key, &beyond).is_err());
+    }
+
     #[test]
     fn invalid_key_fails() {
         let result = decoding_key_from_keycloak("not-valid-base64-key!!!");
         assert!(result.is_err());
     }
+
+    mod frontend_config_file {
+        use super::*;
+        use tempfile::TempDir;
+
+        const SCRIPT: &str = "window.__APP_CONFIG__ = {};";
+
+        #[test]
+        fn existing_file_is_kept_if_generation_is_disabled() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("app_config.js");
+            std::fs::write(&path, "provided").unwrap();
+
+            write_frontend_config_file(&path, SCRIPT, false).unwrap();
+
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "provided");
+        }
+
+        #[test]
+        fn missing_file_is_generated_if_generation_is_disabled() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("app_config.js");
+
+            write_frontend_config_file(&path, SCRIPT, false).unwrap();
+
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), SCRIPT);
+        }
+
+        #[test]
+        fn existing_file_is_regenerated_by_default() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("app_config.js");
+            std::fs::write(&path, "stale").unwrap();
+
+            write_frontend_config_file(&path, SCRIPT, true).unwrap();
+
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), SCRIPT);
+        }
+    }
 }
@@ -1,5 +1,7 @@
 pub mod api;
+pub mod clock;
 pub mod config;
+pub mod demo_device_service_client;
 pub mod http_client;
 pub mod keycloak_client;
 pub mod middleware;
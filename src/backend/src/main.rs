@@ -1,20 +1,30 @@
 mod api;
+mod clock;
 mod config;
+mod demo_device_service_client;
 mod http_client;
 mod keycloak_client;
 mod middleware;
 mod omnect_device_service_client;
+mod preflight;
 mod services;
 
 use crate::{
-    api::Api,
+    api::{Api, IndexPage, LocalAddr},
+    clock::SystemClock,
     config::AppConfig,
-    keycloak_client::KeycloakProvider,
+    demo_device_service_client::DemoDeviceServiceClient,
+    keycloak_client::{KeycloakProvider, SingleSignOnProvider},
+    middleware::{AuthMw, DemoModeMw, ReauthMw},
     omnect_device_service_client::{DeviceServiceClient, OmnectDeviceServiceClient},
     services::{
-        auth::TokenManager,
+        auth::{SessionRegistry, TokenManager},
+        centrifugo::{CentrifugoService, ReadinessProbe},
         certificate::{CertificateService, CreateCertPayload},
+        firmware::{ChunkedUpload, UploadLock},
         network::NetworkConfigService,
+        republish::{REPUBLISH_MIN_INTERVAL, RepublishThrottle},
+        schedule::Scheduler,
     },
 };
 use actix_multipart::form::MultipartFormConfig;
@@ -25,8 +35,9 @@ use actix_session::{
     storage::CookieSessionStore,
 };
 use actix_web::{
-    App, HttpServer,
+    App, HttpServer, Scope,
     cookie::{Key, SameSite},
+    middleware::Condition,
     web::{self, Data},
 };
 use actix_web_static_files::ResourceFiles;
@@ -34,7 +45,7 @@ use anyhow::{Context, Result};
 use env_logger::{Builder, Env, Target};
 use log::{debug, error, info, warn};
 use rustls::crypto::{CryptoProvider, ring::default_provider};
-use std::{io::Write, sync::Mutex};
+use std::{io::Write, sync::Mutex, time::Duration};
 use tokio::{
     process::{Child, Command},
     signal::unix::{SignalKind, signal},
@@ -43,6 +54,10 @@ use tokio::{
 
 const UPLOAD_LIMIT_BYTES: usize = 1024 * 1024 * 1024;
 const MULTIPART_CHUNK_SIZE_BYTES: usize = 512 * 1024;
+/// Attempts to determine the certificate common name on startup
+const STARTUP_RETRY_ATTEMPTS: u32 = 5;
+/// Delay before the first retry, doubled for every further retry
+const STARTUP_RETRY_INITIAL_DELAY: Duration = Duration::from_secs(1);
 
 // Cached common name (IP address) used for the current certificate
 static CACHED_COMMON_NAME: Mutex<Option<String>> = Mutex::new(None);
@@ -56,13 +71,23 @@ fn static_files() -> std::collections::HashMap<&'static str, static_files::Resou
     generate()
 }
 
-type UiApi = Api<OmnectDeviceServiceClient, KeycloakProvider>;
+type UiApi<ServiceClient> = Api<ServiceClient, KeycloakProvider>;
 
 enum ShutdownReason {
     Restart,
     Shutdown,
 }
 
+impl ShutdownReason {
+    /// Whether the publish endpoint has to be unregistered from the device service
+    fn unregisters_publish_endpoint(&self, unregister_on_restart: bool) -> bool {
+        match self {
+            ShutdownReason::Restart => unregister_on_restart,
+            ShutdownReason::Shutdown => true,
+        }
+    }
+}
+
 impl std::fmt::Display for ShutdownReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -82,6 +107,8 @@ async fn main() {
 
 async fn run() -> Result<()> {
     initialize()?;
+    preflight::preflight()?;
+    prepare_startup()?;
 
     let mut restart_rx = NetworkConfigService::setup_restart_receiver()
         .map_err(|_| anyhow::anyhow!("restart receiver already initialized"))?;
@@ -89,11 +116,24 @@ async fn run() -> Result<()> {
     let mut sigterm =
         signal(SignalKind::terminate()).context("failed to install SIGTERM handler")?;
 
+    // scheduled operations outlive server restarts, so they stay listed and
+    // cancellable and run on this runtime instead of on a server worker
+    let scheduler = Scheduler::new(tokio::runtime::Handle::current());
+
+    if AppConfig::get().demo_mode {
+        return run_demo(&scheduler, &mut sigterm).await;
+    }
+
     let mut service_client =
         OmnectDeviceServiceClient::new().context("failed to create device service client")?;
 
-    while let ShutdownReason::Restart =
-        run_until_shutdown(&mut service_client, &mut restart_rx, &mut sigterm).await?
+    while let ShutdownReason::Restart = run_until_shutdown(
+        &mut service_client,
+        &scheduler,
+        &mut restart_rx,
+        &mut sigterm,
+    )
+    .await?
     {}
 
     Ok(())
@@ -125,6 +165,10 @@ fn initialize() -> Result<()> {
     CryptoProvider::install_default(default_provider())
         .map_err(|_| anyhow::anyhow!("crypto provider already installed"))?;
 
+    Ok(())
+}
+
+fn prepare_startup() -> Result<()> {
     KeycloakProvider::create_frontend_config_file()
         .context("failed to create frontend config file")?;
 
@@ -136,55 +180,80 @@ fn initialize() -> Result<()> {
 }
 
 async fn needs_certificate_recreation(
-    service_client: &OmnectDeviceServiceClient,
+    service_client: &impl DeviceServiceClient,
+    fallback_common_name: Option<&str>,
 ) -> Result<Option<String>> {
     // Check if we have a cached common name
     let cached = CACHED_COMMON_NAME.lock().unwrap().clone();
 
     // Get all current IP addresses from network interfaces
     let status = service_client.status().await?;
-    let all_ips: Vec<String> = status
+    let all_ips: Vec<&str> = status
         .network_status
         .network_interfaces
         .iter()
         .filter(|iface| iface.online)
-        .flat_map(|iface| iface.ipv4.addrs.iter().map(|addr| addr.addr.clone()))
+        .flat_map(|iface| iface.ipv4.addrs.iter().map(|addr| addr.addr.as_str()))
         .collect();
 
-    if let Some(cached_ip) = cached {
-        // Certificate needs recreation if cached IP is not in current IP list
-        if !all_ips.contains(&cached_ip) {
-            // Return the first IP as the new common name
-            Ok(Some(
-                all_ips
-                    .first()
-                    .cloned()
-                    .context("failed to get IP address from status")?,
-            ))
-        } else {
-            // Certificate still valid
-            Ok(None)
+    // Certificate still valid as long as the cached IP is online
+    if cached
+        .as_deref()
+        .is_some_and(|cached_ip| all_ips.contains(&cached_ip))
+    {
+        return Ok(None);
+    }
+
+    // Prefer the address the last client connected to, e.g. after regenerating
+    // the certificate on a device with several addresses. All interfaces down:
+    // issue the certificate for the fallback common name, if any.
+    let common_name = status
+        .network_status
+        .serving_address_or(LocalAddr::last(), fallback_common_name)?;
+
+    Ok((cached != Some(common_name.clone())).then_some(common_name))
+}
+
+/// Like `needs_certificate_recreation`, but retried with backoff
+///
+/// Right after boot the interfaces may not be online yet, so the fallback
+/// common name is only used if they are still down on the last attempt.
+async fn needs_certificate_recreation_with_retry(
+    service_client: &impl DeviceServiceClient,
+    fallback_common_name: Option<&str>,
+) -> Result<Option<String>> {
+    let mut delay = STARTUP_RETRY_INITIAL_DELAY;
+
+    for attempt in 1..STARTUP_RETRY_ATTEMPTS {
+        match needs_certificate_recreation(service_client, None).await {
+            Ok(common_name) => return Ok(common_name),
+            Err(e) => {
+                warn!(
+                    "failed to determine certificate common name (attempt {attempt}/{STARTUP_RETRY_ATTEMPTS}), retrying in {}s: {e:#}",
+                    delay.as_secs()
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
         }
-    } else {
-        // No cached IP, need to create certificate
-        Ok(Some(
-            all_ips
-                .first()
-                .cloned()
-                .context("failed to get IP address from status")?,
-        ))
     }
+
+    needs_certificate_recreation(service_client, fallback_common_name).await
 }
 
 async fn run_until_shutdown(
     service_client: &mut OmnectDeviceServiceClient,
+    scheduler: &Scheduler,
     restart_rx: &mut broadcast::Receiver<()>,
     sigterm: &mut tokio::signal::unix::Signal,
 ) -> Result<ShutdownReason> {
     info!("starting server");
 
     // 1. create the cert with the ip in CommonName (only if IP changed)
-    if let Some(current_ip) = needs_certificate_recreation(service_client).await? {
+    let fallback_common_name = AppConfig::get().certificate.fallback_common_name.as_deref();
+    if let Some(current_ip) =
+        needs_certificate_recreation_with_retry(service_client, fallback_common_name).await?
+    {
         info!("creating new certificate for IP: {current_ip}");
         CertificateService::create_module_certificate(CreateCertPayload {
             common_name: current_ip.clone(),
@@ -199,7 +268,9 @@ async fn run_until_shutdown(
     }
 
     // 2. run centrifugo with valid cert
-    let mut centrifugo = run_centrifugo().context("failed to start centrifugo")?;
+    let mut centrifugo = run_centrifugo()
+        .await
+        .context("failed to start centrifugo")?;
 
     // 3. register publish endpoint with running centrifugo
     if !service_client.has_publish_endpoint {
@@ -209,11 +280,14 @@ async fn run_until_shutdown(
             .context("failed to register publish endpoint")?;
     }
 
-    let (server_handle, server_task) = run_server(service_client.clone()).await?;
+    let (server_handle, server_task) =
+        run_server(service_client.clone(), scheduler.clone()).await?;
 
     let service_client_clone = service_client.clone();
     let rollback_task = tokio::spawn(async move {
-        if let Err(e) = NetworkConfigService::process_pending_rollback(service_client_clone).await {
+        if let Err(e) =
+            NetworkConfigService::process_pending_rollback(service_client_clone, &SystemClock).await
+        {
             error!("failed to process pending rollback: {e:#}");
         }
     });
@@ -248,25 +322,142 @@ async fn run_until_shutdown(
     rollback_task.abort();
     info!("{reason}");
 
-    server_handle.stop(true).await;
-    if let Err(e) = centrifugo.kill().await {
-        error!("failed to kill centrifugo: {e:#}");
+    let device_service = &AppConfig::get().device_service;
+    let unregister = reason.unregisters_publish_endpoint(device_service.unregister_on_restart);
+
+    shutdown_sequence(
+        &*service_client,
+        unregister,
+        device_service.unregister_grace_period,
+        server_handle.stop(true),
+        async {
+            if let Err(e) = centrifugo.kill().await {
+                error!("failed to kill centrifugo: {e:#}");
+            }
+        },
+    )
+    .await;
+
+    if unregister {
+        service_client.has_publish_endpoint = false;
     }
 
     if matches!(reason, ShutdownReason::Shutdown) {
-        if let Err(e) = service_client.shutdown().await {
-            error!("failed to shutdown service client: {e:#}");
-        }
         info!("shutdown complete");
     }
 
     Ok(reason)
 }
 
+/// Stop all services in a fixed order
+///
+/// 1. unregister the publish endpoint, while the device service client and
+///    centrifugo are still fully usable, so no new publishes are sent
+/// 2. wait `grace_period`, so in-flight publishes reach centrifugo
+/// 3. stop the server
+/// 4. stop centrifugo
+///
+/// Steps 1 and 2 are skipped if the publish endpoint stays registered.
+async fn shutdown_sequence(
+    service_client: &impl DeviceServiceClient,
+    unregister: bool,
+    grace_period: Duration,
+    stop_server: impl Future<Output = ()>,
+    stop_centrifugo: impl Future<Output = ()>,
+) {
+    if unregister {
+        if let Err(e) = service_client.shutdown().await {
+            error!("failed to unregister publish endpoint: {e:#}");
+        }
+        tokio::time::sleep(grace_period).await;
+    }
+
+    stop_server.await;
+    stop_centrifugo.await;
+}
+
+/// Serve the UI against canned device data until shutdown
+///
+/// Neither a device service nor a publish endpoint is involved, so there is
+/// nothing to restart, roll back or unregister.
+async fn run_demo(scheduler: &Scheduler, sigterm: &mut tokio::signal::unix::Signal) -> Result<()> {
+    warn!("demo mode enabled: serving canned data, privileged operations are disabled");
+
+    let service_client = DemoDeviceServiceClient;
+
+    // there is no IoT Edge workload API to issue the certificate
+    let fallback_common_name = AppConfig::get().certificate.fallback_common_name.as_deref();
+    if let Some(current_ip) =
+        needs_certificate_recreation(&service_client, fallback_common_name).await?
+    {
+        info!("creating self-signed certificate for IP: {current_ip}");
+        CertificateService::create_self_signed_certificate(CreateCertPayload {
+            common_name: current_ip,
+        })
+        .context("failed to create certificate")?;
+    }
+
+    let mut centrifugo = run_centrifugo()
+        .await
+        .context("failed to start centrifugo")?;
+    publish_demo_data().await;
+    let (server_handle, server_task) = run_server(service_client, scheduler.clone()).await?;
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => debug!("ctrl-c received"),
+        _ = sigterm.recv() => debug!("SIGTERM received"),
+        result = server_task => {
+            if let Ok(Err(e)) = result {
+                error!("server stopped with error: {e}");
+            }
+        },
+        _ = centrifugo.wait() => error!("centrifugo stopped unexpectedly"),
+    };
+
+    server_handle.stop(true).await;
+
+    if let Err(e) = centrifugo.kill().await {
+        error!("failed to kill centrifugo: {e:#}");
+    }
+
+    info!("shutdown complete");
+    Ok(())
+}
+
+/// Publish the canned device data in place of the device service
+///
+/// Centrifugo keeps the last publication of each channel, so clients connecting
+/// later receive it as well.
+async fn publish_demo_data() {
+    let config = AppConfig::get();
+    let client = match CentrifugoService::local_client(&config.certificate) {
+        Ok(client) => client,
+        Err(e) => {
+            error!("failed to publish demo data: {e:#}");
+            return;
+        }
+    };
+
+    for publication in DemoDeviceServiceClient::publications() {
+        if let Err(e) = CentrifugoService::publish(&client, &config.centrifugo, &publication).await
+        {
+            error!(
+                "failed to publish demo data to {}: {e:#}",
+                publication["channel"]
+            );
+        }
+    }
+}
+
 fn optimal_worker_count() -> usize {
     const MIN_WORKERS: usize = 2;
     const MAX_WORKERS: usize = 4;
 
+    if let Some(workers) = AppConfig::get().ui.workers {
+        info!("configuring {workers} worker threads (UI_WORKERS)");
+        return workers;
+    }
+
     let cpu_count = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(2);
@@ -281,13 +472,17 @@ fn optimal_worker_count() -> usize {
     workers
 }
 
-async fn run_server(
-    service_client: OmnectDeviceServiceClient,
+async fn run_server<ServiceClient>(
+    service_client: ServiceClient,
+    scheduler: Scheduler,
 ) -> Result<(
     ServerHandle,
     tokio::task::JoinHandle<Result<(), std::io::Error>>,
-)> {
-    let api = UiApi::new(service_client.clone(), Default::default())
+)>
+where
+    ServiceClient: DeviceServiceClient + Clone + Send + Sync + 'static,
+{
+    let api = UiApi::<ServiceClient>::new(service_client.clone(), Default::default())
         .await
         .context("failed to create api")?;
 
@@ -295,7 +490,14 @@ async fn run_server(
     let config = &AppConfig::get();
     let ui_port = config.ui.port;
     let session_key = Key::generate();
-    let token_manager = TokenManager::new(&config.centrifugo.client_token);
+    let token_manager =
+        TokenManager::new(&config.centrifugo.client_token, config.ui.token_clock_skew);
+    let session_registry = SessionRegistry::new(config.ui.session_idle_timeout);
+    let republish_throttle = RepublishThrottle::new(REPUBLISH_MIN_INTERVAL);
+    let upload_lock = UploadLock::default();
+    let chunked_upload = ChunkedUpload::default();
+    let index_page =
+        Data::new(IndexPage::new(&static_files()).context("failed to render index page")?);
 
     let server = HttpServer::new(move || {
         App::new()
@@ -316,68 +518,20 @@ async fn run_server(
             )
             .app_data(web::PayloadConfig::new(UPLOAD_LIMIT_BYTES))
             .app_data(Data::new(token_manager.clone()))
+            .app_data(Data::new(session_registry.clone()))
+            .app_data(Data::new(republish_throttle.clone()))
+            .app_data(Data::new(upload_lock.clone()))
+            .app_data(Data::new(chunked_upload.clone()))
+            .app_data(Data::new(scheduler.clone()))
             .app_data(Data::new(api.clone()))
-            .app_data(Data::new(static_files()))
-            .route("/", web::get().to(UiApi::index))
-            .route("/config.js", web::get().to(UiApi::config))
-            .route(
-                "/factory-reset",
-                web::post()
-                    .to(UiApi::factory_reset)
-                    .wrap(middleware::AuthMw),
-            )
-            .route(
-                "/reboot",
-                web::post().to(UiApi::reboot).wrap(middleware::AuthMw),
-            )
-            .route(
-                "/update/file",
-                web::post()
-                    .to(UiApi::upload_firmware_file)
-                    .wrap(middleware::AuthMw),
-            )
-            .route(
-                "/update/load",
-                web::post().to(UiApi::load_update).wrap(middleware::AuthMw),
-            )
-            .route(
-                "/update/run",
-                web::post().to(UiApi::run_update).wrap(middleware::AuthMw),
-            )
-            .route(
-                "/token/login",
-                web::post().to(UiApi::token).wrap(middleware::AuthMw),
+            .app_data(index_page.clone())
+            .service(
+                api_scope::<ServiceClient, KeycloakProvider>()
+                    .service(ResourceFiles::new("/static", static_files()))
+                    .default_service(web::route().to(UiApi::<ServiceClient>::index)),
             )
-            .route(
-                "/token/refresh",
-                web::get().to(UiApi::token).wrap(middleware::AuthMw),
-            )
-            .route(
-                "/token/validate",
-                web::post().to(UiApi::validate_portal_token),
-            )
-            .route(
-                "/require-set-password",
-                web::get().to(UiApi::require_set_password),
-            )
-            .route("/set-password", web::post().to(UiApi::set_password))
-            .route("/update-password", web::post().to(UiApi::update_password))
-            .route("/version", web::get().to(UiApi::version))
-            .route("/logout", web::post().to(UiApi::logout))
-            .route("/healthcheck", web::get().to(UiApi::healthcheck))
-            .route("/network", web::post().to(UiApi::set_network_config))
-            .route("/ack-rollback", web::post().to(UiApi::ack_rollback))
-            .route(
-                "/ack-factory-reset-result",
-                web::post().to(UiApi::ack_factory_reset_result),
-            )
-            .route(
-                "/ack-update-validation",
-                web::post().to(UiApi::ack_update_validation),
-            )
-            .service(ResourceFiles::new("/static", static_files()))
-            .default_service(web::route().to(UiApi::index))
     })
+    .on_connect(LocalAddr::on_connect)
     .workers(optimal_worker_count())
     .bind_rustls_0_23(format!("0.0.0.0:{ui_port}"), tls_config)
     .context("failed to bind server")?
@@ -387,30 +541,260 @@ async fn run_server(
     Ok((server.handle(), tokio::spawn(server)))
 }
 
-fn run_centrifugo() -> Result<Child> {
+/// Scope below `BASE_PATH` with the api routes and their middlewares
+///
+/// Static files and the fallback to `index.html` are added by the caller.
+fn api_scope<ServiceClient, SingleSignOn>() -> Scope
+where
+    ServiceClient: DeviceServiceClient + Send + Sync + 'static,
+    SingleSignOn: SingleSignOnProvider + Send + Sync + 'static,
+{
+    let config = AppConfig::get();
+
+    web::scope(&config.ui.base_path)
+        .route(
+            "/",
+            web::get().to(Api::<ServiceClient, SingleSignOn>::index),
+        )
+        .route(
+            "/config.js",
+            web::get().to(Api::<ServiceClient, SingleSignOn>::config),
+        )
+        .route(
+            "/config.json",
+            web::get().to(Api::<ServiceClient, SingleSignOn>::config_json),
+        )
+        .route(
+            "/auth/config",
+            web::get().to(Api::<ServiceClient, SingleSignOn>::auth_config),
+        )
+        .route(
+            "/factory-reset",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::factory_reset)
+                .wrap(Condition::new(
+                    config.ui.factory_reset_require_reauth,
+                    ReauthMw,
+                ))
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/reboot",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::reboot)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/republish",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::republish)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/device/identity",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::device_identity)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/update/file",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::upload_firmware_file)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/update/file/chunk",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::upload_firmware_chunk)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/update/load",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::load_update)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/update/run",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::run_update)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/operations/{id}",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::operation_status)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/schedule",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::scheduled_operations)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/schedule/{id}",
+            web::delete()
+                .to(Api::<ServiceClient, SingleSignOn>::cancel_scheduled_operation)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/certificate",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::certificate)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/certificate/regenerate",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::regenerate_certificate)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/centrifugo/log-level",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::set_centrifugo_log_level)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/debug/config",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::debug_config)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/token/login",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::token)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/token/refresh",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::token)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/token/validate",
+            web::post().to(Api::<ServiceClient, SingleSignOn>::validate_portal_token),
+        )
+        .route(
+            "/require-set-password",
+            web::get().to(Api::<ServiceClient, SingleSignOn>::require_set_password),
+        )
+        .route(
+            "/password-policy",
+            web::get().to(Api::<ServiceClient, SingleSignOn>::password_policy),
+        )
+        .route(
+            "/set-password",
+            web::post().to(Api::<ServiceClient, SingleSignOn>::set_password),
+        )
+        .route(
+            "/update-password",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::update_password)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/version",
+            web::get().to(Api::<ServiceClient, SingleSignOn>::version),
+        )
+        .route(
+            "/logout",
+            web::post().to(Api::<ServiceClient, SingleSignOn>::logout),
+        )
+        .route(
+            "/healthcheck",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::healthcheck)
+                .wrap(Condition::new(config.ui.healthcheck_require_auth, AuthMw)),
+        )
+        .route(
+            "/network/rollback-status",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::rollback_status)
+                .wrap(Condition::new(config.ui.healthcheck_require_auth, AuthMw)),
+        )
+        .route(
+            "/network",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::set_network_config)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/network/raw",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::set_raw_network_config)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/network/cancel-rollback",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::cancel_rollback)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/network/rollback-now",
+            web::post()
+                .to(Api::<ServiceClient, SingleSignOn>::rollback_now)
+                .wrap(AuthMw)
+                .wrap(DemoModeMw),
+        )
+        .route(
+            "/network/export",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::export_network)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/network/resolve",
+            web::get()
+                .to(Api::<ServiceClient, SingleSignOn>::resolve)
+                .wrap(AuthMw),
+        )
+        .route(
+            "/ack-rollback",
+            web::post().to(Api::<ServiceClient, SingleSignOn>::ack_rollback),
+        )
+        .route(
+            "/ack-factory-reset-result",
+            web::post().to(Api::<ServiceClient, SingleSignOn>::ack_factory_reset_result),
+        )
+        .route(
+            "/ack-update-validation",
+            web::post().to(Api::<ServiceClient, SingleSignOn>::ack_update_validation),
+        )
+}
+
+async fn run_centrifugo() -> Result<Child> {
     let config = &AppConfig::get().centrifugo;
     let certificate = &AppConfig::get().certificate;
+    let port = config
+        .port
+        .parse::<u16>()
+        .context("failed to parse CENTRIFUGO_HTTP_SERVER_PORT: invalid format")?;
+    let client = CentrifugoService::local_client(certificate)?;
 
-    let centrifugo = Command::new(&config.binary_path)
+    let mut centrifugo = Command::new(&config.binary_path)
         .arg("-c")
         .arg(&config.config_path)
-        .envs(vec![
-            (
-                "CENTRIFUGO_HTTP_SERVER_TLS_CERT_PEM",
-                certificate.cert_path.to_string_lossy().to_string(),
-            ),
-            (
-                "CENTRIFUGO_HTTP_SERVER_TLS_KEY_PEM",
-                certificate.key_path.to_string_lossy().to_string(),
-            ),
-            ("CENTRIFUGO_HTTP_SERVER_PORT", config.port.clone()),
-            (
-                "CENTRIFUGO_CLIENT_TOKEN_HMAC_SECRET_KEY",
-                config.client_token.clone(),
-            ),
-            ("CENTRIFUGO_HTTP_API_KEY", config.api_key.clone()),
-            ("CENTRIFUGO_LOG_LEVEL", config.log_level.clone()),
-        ])
+        .envs(CentrifugoService::spawn_envs(
+            config,
+            certificate,
+            CentrifugoService::log_level(config),
+        ))
         .spawn()
         .context("failed to spawn centrifugo process")?;
 
@@ -421,6 +805,23 @@ fn run_centrifugo() -> Result<Child> {
             .context("failed to get centrifugo process id")?
     );
 
+    let health_url = format!("https://127.0.0.1:{port}/health");
+    if let Err(e) = CentrifugoService::wait_until_ready(
+        &mut centrifugo,
+        &client,
+        &health_url,
+        ReadinessProbe::default(),
+    )
+    .await
+    {
+        if let Ok(None) = centrifugo.try_wait()
+            && let Err(e) = centrifugo.kill().await
+        {
+            error!("failed to kill centrifugo: {e:#}");
+        }
+        anyhow::bail!("centrifugo did not become ready on port {port}: {e:#}");
+    }
+
     Ok(centrifugo)
 }
 
@@ -457,3 +858,214 @@ fn load_tls_config() -> Result<rustls::ServerConfig> {
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod shutdown_reason {
+        use super::*;
+
+        #[test]
+        fn shutdown_always_unregisters_publish_endpoint() {
+            assert!(ShutdownReason::Shutdown.unregisters_publish_endpoint(false));
+            assert!(ShutdownReason::Shutdown.unregisters_publish_endpoint(true));
+        }
+
+        #[test]
+        fn restart_keeps_publish_endpoint_by_default() {
+            assert!(!ShutdownReason::Restart.unregisters_publish_endpoint(false));
+        }
+
+        #[test]
+        fn restart_unregisters_publish_endpoint_when_configured() {
+            assert!(ShutdownReason::Restart.unregisters_publish_endpoint(true));
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    mod api_scope {
+        use super::*;
+        use crate::{
+            keycloak_client::MockSingleSignOnProvider,
+            omnect_device_service_client::MockDeviceServiceClient,
+        };
+        use actix_web::{http::Method, http::StatusCode, test};
+
+        #[tokio::test]
+        async fn authenticated_routes_reject_requests_without_credentials() {
+            let config = AppConfig::get();
+            // no expectations, the device service must not be called without credentials
+            let api = Api {
+                service_client: MockDeviceServiceClient::new(),
+                single_sign_on: MockSingleSignOnProvider::new(),
+            };
+            let token_manager =
+                TokenManager::new(&config.centrifugo.client_token, config.ui.token_clock_skew);
+
+            let app = test::init_service(
+                App::new()
+                    .app_data(Data::new(api))
+                    .app_data(Data::new(token_manager))
+                    .service(api_scope::<MockDeviceServiceClient, MockSingleSignOnProvider>()),
+            )
+            .await;
+
+            // every route of api_scope wrapped in AuthMw unconditionally
+            for (method, path) in [
+                (Method::POST, "/factory-reset"),
+                (Method::POST, "/reboot"),
+                (Method::POST, "/republish"),
+                (Method::GET, "/device/identity"),
+                (Method::POST, "/update/file"),
+                (Method::POST, "/update/file/chunk"),
+                (Method::POST, "/update/load"),
+                (Method::POST, "/update/run"),
+                (Method::GET, "/operations/some-id"),
+                (Method::GET, "/schedule"),
+                (Method::DELETE, "/schedule/some-id"),
+                (Method::GET, "/certificate"),
+                (Method::POST, "/certificate/regenerate"),
+                (Method::POST, "/centrifugo/log-level"),
+                (Method::GET, "/debug/config"),
+                (Method::POST, "/token/login"),
+                (Method::GET, "/token/refresh"),
+                (Method::POST, "/network/raw?name=eth0"),
+                (Method::POST, "/network/cancel-rollback"),
+                (Method::POST, "/network/rollback-now"),
+                (Method::GET, "/network/export"),
+                (Method::GET, "/network/resolve?host=example.com"),
+            ] {
+                let req = test::TestRequest::default()
+                    .method(method.clone())
+                    .uri(&format!("{}{path}", config.ui.base_path))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+
+                assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{method} {path}");
+            }
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    mod needs_certificate_recreation_with_retry {
+        use super::*;
+        use crate::omnect_device_service_client::{MockDeviceServiceClient, Status};
+        use std::sync::{
+            Arc,
+            atomic::{AtomicU32, Ordering},
+        };
+
+        fn status(online: bool) -> Status {
+            serde_json::from_value(serde_json::json!({
+                "NetworkStatus": { "network_status": [{
+                    "online": online,
+                    "ipv4": { "addrs": [{ "addr": "10.0.0.5" }] },
+                    "file": "/etc/systemd/network/10-eth0.network",
+                    "mac": "00:11:22:33:44:55",
+                    "name": "eth0"
+                }] },
+                "SystemInfo": { "fleet_id": null, "omnect_device_service_version": "0.40.0" },
+                "UpdateValidationStatus": { "status": "NoUpdate" }
+            }))
+            .unwrap()
+        }
+
+        /// Service client whose interface comes online with the given status call
+        fn client_online_from(online_from_call: u32) -> (MockDeviceServiceClient, Arc<AtomicU32>) {
+            let calls = Arc::new(AtomicU32::default());
+            let counter = calls.clone();
+            let mut service_client = MockDeviceServiceClient::new();
+            service_client.expect_status().returning(move || {
+                let call = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                Box::pin(async move { Ok(status(call >= online_from_call)) })
+            });
+            (service_client, calls)
+        }
+
+        // single test, as the cached common name is shared process wide
+        #[tokio::test(start_paused = true)]
+        async fn fallback_is_only_used_after_last_attempt() {
+            let (service_client, calls) = client_online_from(2);
+            assert_eq!(
+                needs_certificate_recreation_with_retry(&service_client, Some("device.local"))
+                    .await
+                    .unwrap()
+                    .as_deref(),
+                Some("10.0.0.5")
+            );
+            assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+            let (service_client, calls) = client_online_from(u32::MAX);
+            assert_eq!(
+                needs_certificate_recreation_with_retry(&service_client, Some("device.local"))
+                    .await
+                    .unwrap()
+                    .as_deref(),
+                Some("device.local")
+            );
+            assert_eq!(calls.load(Ordering::SeqCst), STARTUP_RETRY_ATTEMPTS);
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    mod shutdown_sequence {
+        use super::*;
+        use crate::omnect_device_service_client::MockDeviceServiceClient;
+        use std::sync::Arc;
+
+        type Steps = Arc<Mutex<Vec<&'static str>>>;
+
+        async fn record(steps: &Steps, step: &'static str) {
+            steps.lock().unwrap().push(step);
+        }
+
+        fn service_client(steps: &Steps, unregister_calls: usize) -> MockDeviceServiceClient {
+            let steps = steps.clone();
+            let mut service_client = MockDeviceServiceClient::new();
+            service_client
+                .expect_shutdown()
+                .times(unregister_calls)
+                .returning(move || {
+                    steps.lock().unwrap().push("unregister");
+                    Box::pin(async { Ok(()) })
+                });
+            service_client
+        }
+
+        #[tokio::test]
+        async fn unregisters_once_before_stopping_server() {
+            let steps = Steps::default();
+
+            shutdown_sequence(
+                &service_client(&steps, 1),
+                true,
+                Duration::ZERO,
+                record(&steps, "server"),
+                record(&steps, "centrifugo"),
+            )
+            .await;
+
+            assert_eq!(
+                *steps.lock().unwrap(),
+                vec!["unregister", "server", "centrifugo"]
+            );
+        }
+
+        #[tokio::test]
+        async fn keeps_publish_endpoint_if_not_unregistering() {
+            let steps = Steps::default();
+
+            shutdown_sequence(
+                &service_client(&steps, 0),
+                false,
+                Duration::ZERO,
+                record(&steps, "server"),
+                record(&steps, "centrifugo"),
+            )
+            .await;
+
+            assert_eq!(*steps.lock().unwrap(), vec!["server", "centrifugo"]);
+        }
+    }
+}
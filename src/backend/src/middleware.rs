@@ -1,4 +1,8 @@
-use crate::services::auth::{TokenManager, password::PasswordService};
+use crate::{
+    config::AppConfig,
+    demo_device_service_client::DEMO_MODE_ERROR,
+    services::auth::{SessionRegistry, TokenManager, password::PasswordService},
+};
 use actix_session::SessionExt;
 use actix_web::{
     Error, FromRequest, HttpMessage, HttpResponse,
@@ -9,6 +13,7 @@ use actix_web::{
 use actix_web_httpauth::extractors::{basic::BasicAuth, bearer::BearerAuth};
 use anyhow::Result;
 use log::error;
+use omnect_ui_core::REAUTH_REQUIRED;
 use std::{
     future::{Future, Ready, ready},
     pin::Pin,
@@ -74,6 +79,12 @@ where
 
             // 1. Check Session Cookie
             if token_manager.verify_token(&token) {
+                // Reject sessions idle for longer than the idle timeout
+                if !is_session_active(&req, &token) {
+                    req.get_session().purge();
+                    return Ok(unauthorized_error(req).map_into_right_body());
+                }
+
                 let res = service.call(req).await?;
                 return Ok(res.map_into_left_body());
             }
@@ -87,9 +98,13 @@ where
             {
                 // 2. Check Bearer Token
                 Some(h) if h.starts_with("Bearer ") => {
+                    // the bearer token is the session token, so it expires with the session
                     BearerAuth::from_request(req.request(), &mut payload)
                         .await
-                        .is_ok_and(|auth| token_manager.verify_token(auth.token()))
+                        .is_ok_and(|auth| {
+                            token_manager.verify_token(auth.token())
+                                && is_session_active(&req, auth.token())
+                        })
                 }
                 // 3. Check Basic Auth
                 Some(h) if h.starts_with("Basic ") => {
@@ -111,6 +126,134 @@ where
     }
 }
 
+/// Requires the user password for a request, even within a valid session
+///
+/// Wrap it inside [`AuthMw`] for destructive operations, so a request needs
+/// both a valid session and a fresh basic auth password. A missing or wrong
+/// password is rejected with 403 and [`REAUTH_REQUIRED`].
+pub struct ReauthMw;
+
+impl<S, B> Transform<S, ServiceRequest> for ReauthMw
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ReauthMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ReauthMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct ReauthMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for ReauthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let mut payload = req.take_payload().take();
+
+            let is_authorized = req
+                .headers()
+                .get(actix_web::http::header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|h| h.starts_with("Basic "))
+                && BasicAuth::from_request(req.request(), &mut payload)
+                    .await
+                    .is_ok_and(verify_user);
+
+            if is_authorized {
+                req.set_payload(payload);
+                let res = service.call(req).await?;
+                return Ok(res.map_into_left_body());
+            }
+
+            Ok(reauth_required_error(req).map_into_right_body())
+        })
+    }
+}
+
+/// Rejects privileged requests with 403 while demo mode is enabled
+pub struct DemoModeMw;
+
+impl<S, B> Transform<S, ServiceRequest> for DemoModeMw
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = DemoModeMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(DemoModeMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct DemoModeMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for DemoModeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            if AppConfig::get().demo_mode {
+                let http_res = HttpResponse::Forbidden().body(DEMO_MODE_ERROR);
+                let (http_req, _) = req.into_parts();
+                return Ok(ServiceResponse::new(http_req, http_res).map_into_right_body());
+            }
+
+            let res = service.call(req).await?;
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+/// Record activity of the session of a token, false if it was idle for too long
+fn is_session_active(req: &ServiceRequest, token: &str) -> bool {
+    req.app_data::<web::Data<SessionRegistry>>()
+        .is_none_or(|registry| registry.touch(token))
+}
+
 fn verify_user(auth: BasicAuth) -> bool {
     let Some(password) = auth.password() else {
         return false;
@@ -124,6 +267,21 @@ fn verify_user(auth: BasicAuth) -> bool {
     true
 }
 
+/// Rejects a request lacking the password with 403, the session stays valid
+///
+/// A 401 would make the client end the session, so a missing or wrong password
+/// is reported as [`REAUTH_REQUIRED`] instead.
+fn reauth_required_error(req: ServiceRequest) -> ServiceResponse {
+    let http_res = HttpResponse::Forbidden()
+        .insert_header((
+            actix_web::http::header::WWW_AUTHENTICATE,
+            format!(r#"Basic realm="omnect-ui", error="{REAUTH_REQUIRED}""#),
+        ))
+        .body(REAUTH_REQUIRED);
+    let (http_req, _) = req.into_parts();
+    ServiceResponse::new(http_req, http_res)
+}
+
 fn unauthorized_error(req: ServiceRequest) -> ServiceResponse {
     let http_res = HttpResponse::Unauthorized().body("Invalid credentials");
     let (http_req, _) = req.into_parts();
@@ -133,7 +291,6 @@ fn unauthorized_error(req: ServiceRequest) -> ServiceResponse {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::config::AppConfig;
 
     const TOKEN_SUBJECT: &str = "omnect-ui";
     const TOKEN_EXPIRE_HOURS: u64 = 2;
@@ -234,6 +391,16 @@ pub mod tests {
         actix_http::Request,
         Response = ServiceResponse,
         Error = actix_web::Error,
+    > {
+        create_service_with_session_registry(SessionRegistry::new(None)).await
+    }
+
+    async fn create_service_with_session_registry(
+        session_registry: SessionRegistry,
+    ) -> impl actix_service::Service<
+        actix_http::Request,
+        Response = ServiceResponse,
+        Error = actix_web::Error,
     > {
         let key = Key::from(&SESSION_SECRET);
         let session_middleware = SessionMiddleware::builder(CookieSessionStore::default(), key)
@@ -245,11 +412,15 @@ pub mod tests {
             .cookie_http_only(true)
             .build();
 
-        let token_manager = TokenManager::new(AppConfig::get().centrifugo.client_token.as_str());
+        let token_manager = TokenManager::new(
+            AppConfig::get().centrifugo.client_token.as_str(),
+            AppConfig::get().ui.token_clock_skew,
+        );
 
         test::init_service(
             App::new()
                 .app_data(web::Data::new(token_manager))
+                .app_data(web::Data::new(session_registry))
                 .wrap(session_middleware)
                 .route("/", web::get().to(index).wrap(AuthMw))
                 .route("/echo", web::post().to(echo_json).wrap(AuthMw)),
@@ -367,6 +538,208 @@ pub mod tests {
         assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
     }
 
+    mod reauth {
+        use super::*;
+        use actix_web::middleware::Condition;
+
+        async fn create_service_with_reauth(
+            require_reauth: bool,
+        ) -> impl actix_service::Service<
+            actix_http::Request,
+            Response = ServiceResponse<impl actix_web::body::MessageBody>,
+            Error = actix_web::Error,
+        > {
+            let key = Key::from(&SESSION_SECRET);
+            let session_middleware = SessionMiddleware::builder(CookieSessionStore::default(), key)
+                .cookie_name(String::from("omnect-ui-session"))
+                .cookie_secure(true)
+                .session_lifecycle(BrowserSession::default())
+                .cookie_same_site(SameSite::Strict)
+                .cookie_content_security(CookieContentSecurity::Private)
+                .cookie_http_only(true)
+                .build();
+
+            let token_manager = TokenManager::new(
+                AppConfig::get().centrifugo.client_token.as_str(),
+                AppConfig::get().ui.token_clock_skew,
+            );
+
+            test::init_service(
+                App::new()
+                    .app_data(web::Data::new(token_manager))
+                    .app_data(web::Data::new(SessionRegistry::new(None)))
+                    .wrap(session_middleware)
+                    .route(
+                        "/factory-reset",
+                        web::post()
+                            .to(echo_json)
+                            .wrap(Condition::new(require_reauth, ReauthMw))
+                            .wrap(AuthMw),
+                    ),
+            )
+            .await
+        }
+
+        fn factory_reset_request(cookie: Cookie<'static>) -> test::TestRequest {
+            test::TestRequest::post()
+                .uri("/factory-reset")
+                .cookie(cookie)
+                .set_json(serde_json::json!({"mode": 1, "preserve": ["network"]}))
+        }
+
+        async fn session_cookie() -> Cookie<'static> {
+            let token = generate_token(generate_valid_claim());
+            create_cookie_for_token(&token).await.into_owned()
+        }
+
+        #[tokio::test]
+        async fn session_alone_passes_if_disabled() {
+            let app = create_service_with_reauth(false).await;
+
+            let req = factory_reset_request(session_cookie().await).to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert!(resp.status().is_success());
+        }
+
+        #[tokio::test]
+        async fn session_alone_is_rejected() {
+            let app = create_service_with_reauth(true).await;
+
+            let req = factory_reset_request(session_cookie().await).to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+            assert!(
+                resp.headers()
+                    .get(actix_web::http::header::WWW_AUTHENTICATE)
+                    .is_some()
+            );
+            assert_eq!(test::read_body(resp).await, REAUTH_REQUIRED);
+        }
+
+        #[tokio::test]
+        #[allow(clippy::await_holding_lock)]
+        async fn session_with_correct_password_passes() {
+            let _lock = PasswordService::lock_for_test();
+
+            let password = "some-password";
+            setup_password_file(password);
+
+            let app = create_service_with_reauth(true).await;
+
+            let encoded_password = BASE64_STANDARD.encode(format!(":{password}"));
+            let req = factory_reset_request(session_cookie().await)
+                .insert_header(("Authorization", format!("Basic {encoded_password}")))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert!(resp.status().is_success());
+
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(body["preserve"], serde_json::json!(["network"]));
+        }
+
+        #[tokio::test]
+        #[allow(clippy::await_holding_lock)]
+        async fn session_with_wrong_password_is_rejected() {
+            let _lock = PasswordService::lock_for_test();
+
+            setup_password_file("some-password");
+
+            let app = create_service_with_reauth(true).await;
+
+            let encoded_password = BASE64_STANDARD.encode(":some-other-password");
+            let req = factory_reset_request(session_cookie().await)
+                .insert_header(("Authorization", format!("Basic {encoded_password}")))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+            assert_eq!(test::read_body(resp).await, REAUTH_REQUIRED);
+        }
+    }
+
+    mod session_idle_timeout {
+        use super::*;
+        use crate::clock::FakeClock;
+        use std::{sync::Arc, time::Duration};
+
+        const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+        #[tokio::test]
+        async fn active_session_should_succeed() {
+            let clock = Arc::new(FakeClock::new(1_700_000_000));
+            let registry = SessionRegistry::with_clock(Some(IDLE_TIMEOUT), clock.clone());
+            let token = generate_token(generate_valid_claim());
+
+            let app = create_service_with_session_registry(registry).await;
+
+            for _ in 0..3 {
+                let cookie = create_cookie_for_token(&token).await;
+                let req = test::TestRequest::default()
+                    .insert_header(ContentType::plaintext())
+                    .cookie(cookie)
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+
+                assert!(resp.status().is_success());
+                clock.advance(IDLE_TIMEOUT);
+            }
+        }
+
+        #[tokio::test]
+        async fn idle_expired_session_should_require_login() {
+            let clock = Arc::new(FakeClock::new(1_700_000_000));
+            let registry = SessionRegistry::with_clock(Some(IDLE_TIMEOUT), clock.clone());
+            let token = generate_token(generate_valid_claim());
+
+            let app = create_service_with_session_registry(registry).await;
+
+            let cookie = create_cookie_for_token(&token).await;
+            let req = test::TestRequest::default()
+                .insert_header(ContentType::plaintext())
+                .cookie(cookie)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert!(resp.status().is_success());
+
+            clock.advance(IDLE_TIMEOUT + Duration::from_secs(1));
+
+            let cookie = create_cookie_for_token(&token).await;
+            let req = test::TestRequest::default()
+                .insert_header(ContentType::plaintext())
+                .cookie(cookie)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[tokio::test]
+        async fn idle_expired_bearer_token_should_require_login() {
+            let clock = Arc::new(FakeClock::new(1_700_000_000));
+            let registry = SessionRegistry::with_clock(Some(IDLE_TIMEOUT), clock.clone());
+            let token = generate_token(generate_valid_claim());
+
+            let app = create_service_with_session_registry(registry).await;
+            let bearer_request = || {
+                test::TestRequest::default()
+                    .insert_header(ContentType::plaintext())
+                    .insert_header(("Authorization", format!("Bearer {token}")))
+                    .to_request()
+            };
+
+            let resp = test::call_service(&app, bearer_request()).await;
+            assert!(resp.status().is_success());
+
+            clock.advance(IDLE_TIMEOUT + Duration::from_secs(1));
+
+            // the token itself is still valid, only the session is idle
+            let resp = test::call_service(&app, bearer_request()).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
     fn setup_password_file(password: &str) {
         PasswordService::store_or_update_password(password)
             .expect("failed to setup password file for test");
@@ -484,7 +857,10 @@ pub mod tests {
     async fn verify_correct_token_should_succeed() {
         let claim = generate_valid_claim();
         let token = generate_token(claim);
-        let token_manager = TokenManager::new(AppConfig::get().centrifugo.client_token.as_str());
+        let token_manager = TokenManager::new(
+            AppConfig::get().centrifugo.client_token.as_str(),
+            AppConfig::get().ui.token_clock_skew,
+        );
 
         assert!(token_manager.verify_token(token.as_str()));
     }
@@ -493,7 +869,10 @@ pub mod tests {
     async fn verify_expired_token_should_fail() {
         let claim = generate_expired_claim();
         let token = generate_token(claim);
-        let token_manager = TokenManager::new(AppConfig::get().centrifugo.client_token.as_str());
+        let token_manager = TokenManager::new(
+            AppConfig::get().centrifugo.client_token.as_str(),
+            AppConfig::get().ui.token_clock_skew,
+        );
 
         assert!(!token_manager.verify_token(token.as_str()));
     }
@@ -502,7 +881,10 @@ pub mod tests {
     async fn verify_token_with_invalid_subject_should_fail() {
         let claim = generate_unset_subject_claim();
         let token = generate_token(claim);
-        let token_manager = TokenManager::new(AppConfig::get().centrifugo.client_token.as_str());
+        let token_manager = TokenManager::new(
+            AppConfig::get().centrifugo.client_token.as_str(),
+            AppConfig::get().ui.token_clock_skew,
+        );
 
         assert!(!token_manager.verify_token(token.as_str()));
 
@@ -517,7 +899,10 @@ pub mod tests {
         let claim = generate_invalid_subject_claim();
         let _ = generate_token(claim);
         let token = "someinvalidtestbytes".to_string();
-        let token_manager = TokenManager::new(AppConfig::get().centrifugo.client_token.as_str());
+        let token_manager = TokenManager::new(
+            AppConfig::get().centrifugo.client_token.as_str(),
+            AppConfig::get().ui.token_clock_skew,
+        );
 
         assert!(!token_manager.verify_token(token.as_str()));
     }
@@ -1,19 +1,20 @@
 #![cfg_attr(feature = "mock", allow(dead_code, unused_imports))]
 
 use crate::{
-    config::AppConfig,
-    http_client::{handle_http_response, unix_socket_client},
-    services::marker,
+    config::{AppConfig, DeviceServiceApiVersions},
+    http_client::{handle_http_response, unix_socket_client_with_pool},
+    services::{factory_reset::FactoryResetService, marker},
 };
-use anyhow::{Context, Result, anyhow, bail};
-use log::info;
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use log::{debug, info, warn};
 #[cfg(feature = "mock")]
 use mockall::automock;
+use omnect_ui_core::types::{DeviceOperation, FactoryResetRequest};
 use reqwest::Client;
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use std::{env, fmt::Debug, path::PathBuf, sync::OnceLock};
+use std::{collections::HashSet, env, fmt::Debug, net::IpAddr, path::PathBuf, sync::OnceLock};
 use trait_variant::make;
 
 #[derive(Clone, Debug, Default, Deserialize_repr, PartialEq, Serialize_repr)]
@@ -32,11 +33,38 @@ pub struct FactoryReset {
     preserve: Vec<String>,
 }
 
+impl FactoryReset {
+    /// Trim the preserved keys, fails if a key is empty or whitespace only
+    pub fn normalize_preserve(&mut self) -> Result<()> {
+        for key in &mut self.preserve {
+            let trimmed = key.trim();
+            ensure!(!trimmed.is_empty(), "preserve keys must not be empty");
+            *key = trimmed.to_string();
+        }
+        Ok(())
+    }
+}
+
+impl From<&FactoryReset> for FactoryResetRequest {
+    fn from(factory_reset: &FactoryReset) -> Self {
+        Self {
+            mode: factory_reset.mode.clone() as u8,
+            preserve: factory_reset.preserve.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoadUpdate {
     pub update_file_path: PathBuf,
 }
 
+/// Interface whose `wpa_supplicant@<interface>` unit is restarted
+#[derive(Debug, Serialize)]
+struct RestartWpaSupplicant<'a> {
+    interface: &'a str,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RunUpdate {
     validate_iothub_connection: bool,
@@ -62,28 +90,100 @@ pub struct UpdateValidationStatus {
 pub struct SystemInfo {
     pub fleet_id: Option<String>,
     pub omnect_device_service_version: String,
+    #[serde(default)]
+    pub boot_time: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct NetworkStatus {
     #[serde(rename = "network_status")]
     pub network_interfaces: Vec<NetworkInterface>,
 }
 
-#[derive(Deserialize, Debug)]
+impl NetworkStatus {
+    /// Online interface owning the given address, None if no online interface has it
+    pub fn online_interface_with(&self, addr: IpAddr) -> Option<&NetworkInterface> {
+        let addr = addr.to_canonical();
+
+        self.network_interfaces
+            .iter()
+            .filter(|iface| iface.online)
+            .find(|iface| {
+                iface
+                    .ipv4
+                    .addrs
+                    .iter()
+                    .any(|a| a.addr.parse::<IpAddr>().is_ok_and(|ip| ip == addr))
+            })
+    }
+
+    /// Address the UI is served on, or the fallback if no interface is online
+    ///
+    /// That is the preferred address if an online interface has it, else the
+    /// first address of the serving interface.
+    pub fn serving_address_or(
+        &self,
+        preferred: Option<IpAddr>,
+        fallback: Option<&str>,
+    ) -> Result<String> {
+        if let Some((_, addr)) = self.serving(preferred) {
+            return Ok(addr.addr.clone());
+        }
+
+        let Some(fallback) = fallback else {
+            bail!("failed to get ip address from status")
+        };
+
+        warn!("no online interface with an ip address, falling back to {fallback}");
+        Ok(fallback.to_string())
+    }
+
+    /// Serving interface and address, the preferred address if it is online
+    fn serving(&self, preferred: Option<IpAddr>) -> Option<(&NetworkInterface, &Ipv4AddrInfo)> {
+        let mut online = self
+            .network_interfaces
+            .iter()
+            .filter(|iface| iface.online)
+            .flat_map(|iface| iface.ipv4.addrs.iter().map(move |addr| (iface, addr)));
+
+        match preferred.map(|ip| ip.to_canonical()) {
+            Some(preferred) => online
+                .clone()
+                .find(|(_, addr)| addr.addr.parse::<IpAddr>().is_ok_and(|ip| ip == preferred))
+                .or_else(|| online.next()),
+            None => online.next(),
+        }
+    }
+
+    /// Sort interfaces by name and drop duplicates reported after hot-plug events
+    ///
+    /// Of duplicates the online entry with the most addresses is kept.
+    pub fn normalize(&mut self) {
+        self.network_interfaces.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| b.online.cmp(&a.online))
+                .then_with(|| b.ipv4.addrs.len().cmp(&a.ipv4.addrs.len()))
+        });
+        self.network_interfaces.dedup_by(|a, b| a.name == b.name);
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 pub struct NetworkInterface {
     pub online: bool,
     pub ipv4: Ipv4Info,
     pub file: PathBuf,
+    pub mac: String,
     pub name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Ipv4Info {
     pub addrs: Vec<Ipv4AddrInfo>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Ipv4AddrInfo {
     pub addr: String,
 }
@@ -104,6 +204,14 @@ pub struct HealthcheckInfo {
     pub network_rollback_occurred: bool,
     pub factory_reset_result_acked: bool,
     pub update_validation_acked: bool,
+    pub primary_mac: Option<String>,
+    pub update_progress: Option<u8>,
+    /// Boot time as reported by the device service, available before the websocket connects
+    pub boot_time: Option<String>,
+    /// Device operation still in progress, lets a reloaded UI resume waiting for it
+    pub last_operation: Option<DeviceOperation>,
+    /// Mode and preserved keys of the last factory reset
+    pub last_factory_reset: Option<FactoryResetRequest>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -130,21 +238,27 @@ struct PublishIdEndpoint {
 pub struct OmnectDeviceServiceClient {
     client: Client,
     pub has_publish_endpoint: bool,
+    api_versions: DeviceServiceApiVersions,
+    fallback_common_name: Option<String>,
 }
 
 #[make(Send)]
 #[cfg_attr(feature = "mock", automock)]
 pub trait DeviceServiceClient {
     async fn fleet_id(&self) -> Result<String>;
+    async fn ip_address(&self, preferred: Option<IpAddr>) -> Result<String>;
+    async fn resolve(&self, hostname: &str) -> Result<Vec<String>>;
     async fn status(&self) -> Result<Status>;
     async fn republish(&self) -> Result<()>;
     async fn factory_reset(&self, factory_reset: FactoryReset) -> Result<()>;
     async fn reboot(&self) -> Result<()>;
     async fn register_publish_endpoint(&mut self, endpoint: PublishEndpoint) -> Result<()>;
     async fn reload_network(&self) -> Result<()>;
+    async fn restart_wpa_supplicant(&self, interface: &str) -> Result<()>;
     async fn load_update(&self, load_update: LoadUpdate) -> Result<String>;
     async fn run_update(&self, run_update: RunUpdate) -> Result<()>;
-    async fn healthcheck_info(&self) -> Result<HealthcheckInfo>;
+    async fn update_progress(&self) -> Result<u8>;
+    async fn healthcheck_info(&self, preferred: Option<IpAddr>) -> Result<HealthcheckInfo>;
     async fn shutdown(&self) -> Result<()>;
 }
 
@@ -156,32 +270,47 @@ impl Clone for MockDeviceServiceClient {
 }
 
 impl OmnectDeviceServiceClient {
-    const REQUIRED_CLIENT_VERSION: &str = ">=0.39.0";
-
-    // API endpoint constants
-    const STATUS_ENDPOINT: &str = "/status/v1";
-    const REPUBLISH_ENDPOINT: &str = "/republish/v1/";
-    const FACTORY_RESET_ENDPOINT: &str = "/factory-reset/v1";
-    const REBOOT_ENDPOINT: &str = "/reboot/v1";
-    const RELOAD_NETWORK_ENDPOINT: &str = "/reload-network/v1";
-    const LOAD_UPDATE_ENDPOINT: &str = "/fwupdate/load/v1";
-    const RUN_UPDATE_ENDPOINT: &str = "/fwupdate/run/v1";
-    const PUBLISH_ENDPOINT: &str = "/publish-endpoint/v1";
+    pub const REQUIRED_CLIENT_VERSION: &str = ">=0.39.0";
+    /// Device service versions providing `RESTART_WPA_SUPPLICANT_ENDPOINT`
+    pub const RESTART_WPA_SUPPLICANT_VERSION: &str = ">=0.41.0";
+    /// Device service versions providing `UPDATE_PROGRESS_ENDPOINT`
+    pub const UPDATE_PROGRESS_VERSION: &str = ">=0.41.0";
+
+    // API endpoint constants, the version is appended from `DeviceServiceApiVersions`
+    const STATUS_ENDPOINT: &str = "/status";
+    const REPUBLISH_ENDPOINT: &str = "/republish";
+    const FACTORY_RESET_ENDPOINT: &str = "/factory-reset";
+    const REBOOT_ENDPOINT: &str = "/reboot";
+    const RELOAD_NETWORK_ENDPOINT: &str = "/reload-network";
+    const RESTART_WPA_SUPPLICANT_ENDPOINT: &str = "/restart-wpa-supplicant";
+    const LOAD_UPDATE_ENDPOINT: &str = "/fwupdate/load";
+    const RUN_UPDATE_ENDPOINT: &str = "/fwupdate/run";
+    const UPDATE_PROGRESS_ENDPOINT: &str = "/fwupdate/progress";
+    const PUBLISH_ENDPOINT: &str = "/publish-endpoint";
+
+    /// Maximum number of characters of a response body quoted in errors
+    const BODY_SNIPPET_MAX_CHARS: usize = 200;
 
     pub fn new() -> Result<Self> {
-        let client = unix_socket_client(
-            &AppConfig::get()
-                .device_service
-                .socket_path
-                .to_string_lossy(),
+        let config = AppConfig::get();
+        let client = unix_socket_client_with_pool(
+            &config.device_service.socket_path.to_string_lossy(),
+            &config.device_service.pool,
         )?;
 
         Ok(OmnectDeviceServiceClient {
             client,
             has_publish_endpoint: false,
+            api_versions: config.device_service.api_versions.clone(),
+            fallback_common_name: config.certificate.fallback_common_name.clone(),
         })
     }
 
+    /// Path of an endpoint in the given API version
+    fn versioned(endpoint: &str, version: &str) -> String {
+        format!("{endpoint}/{version}")
+    }
+
     fn required_version() -> &'static VersionReq {
         static REQUIRED_VERSION: OnceLock<VersionReq> = OnceLock::new();
         REQUIRED_VERSION.get_or_init(|| {
@@ -190,6 +319,70 @@ impl OmnectDeviceServiceClient {
         })
     }
 
+    /// Whether a device service version provides an endpoint newer than `REQUIRED_CLIENT_VERSION`
+    fn provides(version: &Version, requirement: &str) -> bool {
+        VersionReq::parse(requirement)
+            .expect("invalid endpoint version requirement")
+            .matches(version)
+    }
+
+    /// Version of the device service
+    async fn version(&self) -> Result<Version> {
+        let version = self
+            .status()
+            .await?
+            .system_info
+            .omnect_device_service_version;
+        Version::parse(&version).map_err(|e| anyhow!("failed to parse current version: {e}"))
+    }
+
+    /// Addresses in the order of the resolver, each once
+    ///
+    /// The resolver reports an address per socket type, not necessarily adjacent.
+    fn unique_addresses(addrs: impl Iterator<Item = IpAddr>) -> Vec<String> {
+        let mut seen = HashSet::new();
+        addrs
+            .filter(|addr| seen.insert(*addr))
+            .map(|addr| addr.to_string())
+            .collect()
+    }
+
+    /// Parse the update progress percentage returned by the device service
+    fn parse_update_progress(body: &str) -> Result<u8> {
+        let progress: u8 =
+            serde_json::from_str(body.trim()).context("failed to parse update progress")?;
+        ensure!(progress <= 100, "update progress out of range: {progress}");
+        Ok(progress)
+    }
+
+    /// Parse the status returned by the device service
+    ///
+    /// The error of an unparsable body carries its content type and beginning,
+    /// e.g. to spot an HTML error page of a proxy.
+    fn parse_status(body: &str, content_type: Option<&str>) -> Result<Status> {
+        ensure!(
+            !body.trim().is_empty(),
+            "device service returned empty status"
+        );
+
+        serde_json::from_str(body).with_context(|| {
+            format!(
+                "failed to parse status (content-type: {}, body: {})",
+                content_type.unwrap_or("none"),
+                Self::body_snippet(body)
+            )
+        })
+    }
+
+    /// Beginning of a response body for error messages
+    fn body_snippet(body: &str) -> String {
+        let trimmed = body.trim();
+        match trimmed.char_indices().nth(Self::BODY_SNIPPET_MAX_CHARS) {
+            Some((end, _)) => format!("{}...", &trimmed[..end]),
+            None => trimmed.to_string(),
+        }
+    }
+
     fn build_url(&self, path: &str) -> String {
         // Normalize path to always start with a single "/"
         let normalized_path = path.trim_start_matches('/');
@@ -198,6 +391,12 @@ impl OmnectDeviceServiceClient {
 
     /// GET request to the device service API
     async fn get(&self, path: &str) -> Result<String> {
+        let (_, body) = self.get_with_content_type(path).await?;
+        Ok(body)
+    }
+
+    /// GET request to the device service API, returning the content type along with the body
+    async fn get_with_content_type(&self, path: &str) -> Result<(Option<String>, String)> {
         let url = self.build_url(path);
         info!("GET {url}");
 
@@ -208,7 +407,16 @@ impl OmnectDeviceServiceClient {
             .await
             .context(format!("failed to send GET request to {url}"))?;
 
-        handle_http_response(res, &format!("GET {url}")).await
+        let content_type = res
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        Ok((
+            content_type,
+            handle_http_response(res, &format!("GET {url}")).await?,
+        ))
     }
 
     /// POST request to the device service API (empty body)
@@ -249,8 +457,11 @@ impl DeviceServiceClient for OmnectDeviceServiceClient {
             id: env!("CARGO_PKG_NAME"),
             endpoint,
         };
-        self.post_json(Self::PUBLISH_ENDPOINT, publish_id_endpoint)
-            .await?;
+        self.post_json(
+            &Self::versioned(Self::PUBLISH_ENDPOINT, &self.api_versions.publish_endpoint),
+            publish_id_endpoint,
+        )
+        .await?;
 
         self.has_publish_endpoint = true;
 
@@ -267,15 +478,43 @@ impl DeviceServiceClient for OmnectDeviceServiceClient {
         Ok(fleet_id)
     }
 
+    async fn ip_address(&self, preferred: Option<IpAddr>) -> Result<String> {
+        self.status()
+            .await?
+            .network_status
+            .serving_address_or(preferred, self.fallback_common_name.as_deref())
+    }
+
+    async fn resolve(&self, hostname: &str) -> Result<Vec<String>> {
+        // uses the resolver of the container, not the one of the device
+        let addrs = Self::unique_addresses(
+            tokio::net::lookup_host((hostname, 0))
+                .await
+                .context(format!("failed to resolve {hostname}"))?
+                .map(|addr| addr.ip()),
+        );
+
+        ensure!(!addrs.is_empty(), "no address found for {hostname}");
+
+        Ok(addrs)
+    }
+
     async fn status(&self) -> Result<Status> {
-        let body = self.get(Self::STATUS_ENDPOINT).await?;
-        serde_json::from_str(&body).context("failed to parse status")
+        let (content_type, body) = self
+            .get_with_content_type(&Self::versioned(
+                Self::STATUS_ENDPOINT,
+                &self.api_versions.status,
+            ))
+            .await?;
+        let mut status = Self::parse_status(&body, content_type.as_deref())?;
+        status.network_status.normalize();
+        Ok(status)
     }
 
     async fn republish(&self) -> Result<()> {
         self.post(&format!(
-            "{}{}",
-            Self::REPUBLISH_ENDPOINT,
+            "{}/{}",
+            Self::versioned(Self::REPUBLISH_ENDPOINT, &self.api_versions.republish),
             env!("CARGO_PKG_NAME")
         ))
         .await?;
@@ -283,40 +522,107 @@ impl DeviceServiceClient for OmnectDeviceServiceClient {
     }
 
     async fn factory_reset(&self, factory_reset: FactoryReset) -> Result<()> {
-        self.post_json(Self::FACTORY_RESET_ENDPOINT, factory_reset)
-            .await?;
+        self.post_json(
+            &Self::versioned(
+                Self::FACTORY_RESET_ENDPOINT,
+                &self.api_versions.factory_reset,
+            ),
+            factory_reset,
+        )
+        .await?;
         Ok(())
     }
 
     async fn reboot(&self) -> Result<()> {
-        self.post(Self::REBOOT_ENDPOINT).await?;
+        self.post(&Self::versioned(
+            Self::REBOOT_ENDPOINT,
+            &self.api_versions.reboot,
+        ))
+        .await?;
         Ok(())
     }
 
     async fn reload_network(&self) -> Result<()> {
-        self.post(Self::RELOAD_NETWORK_ENDPOINT).await?;
+        self.post(&Self::versioned(
+            Self::RELOAD_NETWORK_ENDPOINT,
+            &self.api_versions.reload_network,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    async fn restart_wpa_supplicant(&self, interface: &str) -> Result<()> {
+        let version = self.version().await?;
+        ensure!(
+            Self::provides(&version, Self::RESTART_WPA_SUPPLICANT_VERSION),
+            "device service {version} can't restart wpa_supplicant, {} is required",
+            Self::RESTART_WPA_SUPPLICANT_VERSION
+        );
+
+        self.post_json(
+            &Self::versioned(
+                Self::RESTART_WPA_SUPPLICANT_ENDPOINT,
+                &self.api_versions.restart_wpa_supplicant,
+            ),
+            RestartWpaSupplicant { interface },
+        )
+        .await?;
         Ok(())
     }
 
     async fn load_update(&self, load_update: LoadUpdate) -> Result<String> {
-        self.post_json(Self::LOAD_UPDATE_ENDPOINT, load_update)
-            .await
+        self.post_json(
+            &Self::versioned(Self::LOAD_UPDATE_ENDPOINT, &self.api_versions.load_update),
+            load_update,
+        )
+        .await
     }
 
     async fn run_update(&self, run_update: RunUpdate) -> Result<()> {
-        self.post_json(Self::RUN_UPDATE_ENDPOINT, run_update)
-            .await?;
+        self.post_json(
+            &Self::versioned(Self::RUN_UPDATE_ENDPOINT, &self.api_versions.run_update),
+            run_update,
+        )
+        .await?;
         Ok(())
     }
 
-    async fn healthcheck_info(&self) -> Result<HealthcheckInfo> {
+    async fn update_progress(&self) -> Result<u8> {
+        let body = self
+            .get(&Self::versioned(
+                Self::UPDATE_PROGRESS_ENDPOINT,
+                &self.api_versions.update_progress,
+            ))
+            .await?;
+        Self::parse_update_progress(&body)
+    }
+
+    async fn healthcheck_info(&self, preferred: Option<IpAddr>) -> Result<HealthcheckInfo> {
         let status = self.status().await?;
         let current_version = status.system_info.omnect_device_service_version;
+        let boot_time = status.system_info.boot_time;
+        // the MAC of the interface the request was received on, not just any online one
+        let primary_mac = preferred
+            .and_then(|addr| status.network_status.online_interface_with(addr))
+            .map(|iface| iface.mac.clone());
 
         let required_version = Self::required_version();
         let parsed_current = Version::parse(&current_version)
             .map_err(|e| anyhow!("failed to parse current version: {e}"))?;
 
+        // progress is only queried while an update runs and if the device service provides it
+        let last_operation = marker::last_device_operation();
+        let update_progress = if last_operation == Some(DeviceOperation::Update)
+            && Self::provides(&parsed_current, Self::UPDATE_PROGRESS_VERSION)
+        {
+            self.update_progress()
+                .await
+                .inspect_err(|e| debug!("no update progress available: {e:#}"))
+                .ok()
+        } else {
+            None
+        };
+
         Ok(HealthcheckInfo {
             version_info: VersionInfo {
                 required: Self::REQUIRED_CLIENT_VERSION.to_string(),
@@ -327,12 +633,18 @@ impl DeviceServiceClient for OmnectDeviceServiceClient {
             network_rollback_occurred: marker::NETWORK_ROLLBACK_OCCURRED.exists(),
             factory_reset_result_acked: marker::FACTORY_RESET_RESULT_ACKED.exists(),
             update_validation_acked: marker::UPDATE_VALIDATION_ACKED.exists(),
+            primary_mac,
+            update_progress,
+            boot_time,
+            last_operation,
+            last_factory_reset: FactoryResetService::last_factory_reset(),
         })
     }
 
     async fn shutdown(&self) -> Result<()> {
         if self.has_publish_endpoint {
-            let endpoint = Self::PUBLISH_ENDPOINT;
+            let endpoint =
+                Self::versioned(Self::PUBLISH_ENDPOINT, &self.api_versions.publish_endpoint);
             let pkg_name = env!("CARGO_PKG_NAME");
             let url = self.build_url(&format!("{endpoint}/{pkg_name}"));
             info!("DELETE {url}");
@@ -352,6 +664,42 @@ impl DeviceServiceClient for OmnectDeviceServiceClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{config::NetworkInterfaceConfig, http_client::unix_socket_client};
+
+    mod factory_reset {
+        use super::*;
+
+        fn factory_reset(preserve: &[&str]) -> FactoryReset {
+            FactoryReset {
+                mode: FactoryResetMode::Mode1,
+                preserve: preserve.iter().map(|key| key.to_string()).collect(),
+            }
+        }
+
+        #[test]
+        fn preserve_keys_are_trimmed() {
+            let mut factory_reset = factory_reset(&[" network", "firewall \t"]);
+
+            factory_reset.normalize_preserve().unwrap();
+
+            assert_eq!(factory_reset.preserve, ["network", "firewall"]);
+        }
+
+        #[test]
+        fn empty_preserve_keys_are_rejected() {
+            assert!(
+                factory_reset(&["network", ""])
+                    .normalize_preserve()
+                    .is_err()
+            );
+            assert!(factory_reset(&["  "]).normalize_preserve().is_err());
+        }
+
+        #[test]
+        fn empty_preserve_list_is_valid() {
+            assert!(factory_reset(&[]).normalize_preserve().is_ok());
+        }
+    }
 
     mod build_url {
         use super::*;
@@ -360,6 +708,8 @@ mod tests {
             OmnectDeviceServiceClient {
                 client: reqwest::Client::new(),
                 has_publish_endpoint: false,
+                api_versions: DeviceServiceApiVersions::default(),
+                fallback_common_name: None,
             }
         }
 
@@ -425,6 +775,25 @@ mod tests {
             assert!(!version_req.matches(&Version::parse("0.30.0").unwrap()));
             assert!(!version_req.matches(&Version::parse("0.1.0").unwrap()));
         }
+
+        #[test]
+        fn newer_endpoints_are_not_provided_by_required_version() {
+            for requirement in [
+                OmnectDeviceServiceClient::RESTART_WPA_SUPPLICANT_VERSION,
+                OmnectDeviceServiceClient::UPDATE_PROGRESS_VERSION,
+            ] {
+                let provides = |version| {
+                    OmnectDeviceServiceClient::provides(
+                        &Version::parse(version).unwrap(),
+                        requirement,
+                    )
+                };
+
+                assert!(!provides("0.39.0"), "{requirement}");
+                assert!(!provides("0.40.0"), "{requirement}");
+                assert!(provides("0.41.0"), "{requirement}");
+            }
+        }
     }
 
     mod healthcheck_info {
@@ -439,6 +808,7 @@ mod tests {
                 system_info: SystemInfo {
                     fleet_id: Some("test-fleet".to_string()),
                     omnect_device_service_version: version.to_string(),
+                    boot_time: None,
                 },
                 update_validation_status: UpdateValidationStatus {
                     status: "idle".to_string(),
@@ -446,6 +816,24 @@ mod tests {
             }
         }
 
+        #[test]
+        fn system_info_boot_time_is_optional() {
+            let with_boot_time: SystemInfo = serde_json::from_str(
+                r#"{"fleet_id": null, "omnect_device_service_version": "0.40.0", "boot_time": "2024-01-01T00:00:00Z"}"#,
+            )
+            .unwrap();
+            let without_boot_time: SystemInfo = serde_json::from_str(
+                r#"{"fleet_id": null, "omnect_device_service_version": "0.40.0"}"#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                with_boot_time.boot_time.as_deref(),
+                Some("2024-01-01T00:00:00Z")
+            );
+            assert_eq!(without_boot_time.boot_time, None);
+        }
+
         #[test]
         fn detects_version_mismatch_when_below_requirement() {
             let status = create_test_status("0.38.0");
@@ -472,6 +860,224 @@ mod tests {
             assert!(!mismatch);
         }
 
+        fn create_test_interface(
+            name: &str,
+            mac: &str,
+            online: bool,
+            addr: &str,
+        ) -> NetworkInterface {
+            NetworkInterface {
+                online,
+                ipv4: Ipv4Info {
+                    addrs: vec![Ipv4AddrInfo {
+                        addr: addr.to_string(),
+                    }],
+                },
+                file: PathBuf::from(format!("/etc/systemd/network/10-{name}.network")),
+                mac: mac.to_string(),
+                name: name.to_string(),
+            }
+        }
+
+        #[test]
+        fn online_interface_is_found_by_address() {
+            let mut status = create_test_status("0.40.0");
+            status.network_status.network_interfaces = vec![
+                create_test_interface("eth0", "00:11:22:33:44:55", true, "192.168.1.100"),
+                create_test_interface("eth1", "66:77:88:99:aa:bb", true, "10.0.0.5"),
+            ];
+
+            let iface = status
+                .network_status
+                .online_interface_with("10.0.0.5".parse().unwrap());
+
+            assert_eq!(iface.map(|iface| iface.name.as_str()), Some("eth1"));
+        }
+
+        #[test]
+        fn offline_interface_is_not_found_by_address() {
+            let mut status = create_test_status("0.40.0");
+            status.network_status.network_interfaces = vec![create_test_interface(
+                "eth0",
+                "00:11:22:33:44:55",
+                false,
+                "192.168.1.100",
+            )];
+
+            assert!(
+                status
+                    .network_status
+                    .online_interface_with("192.168.1.100".parse().unwrap())
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn serving_address_is_first_address_of_online_interface() {
+            let mut status = create_test_status("0.40.0");
+            status.network_status.network_interfaces = vec![
+                create_test_interface("eth0", "00:11:22:33:44:55", false, "192.168.1.100"),
+                create_test_interface("eth1", "66:77:88:99:aa:bb", true, "10.0.0.5"),
+            ];
+
+            assert_eq!(
+                status
+                    .network_status
+                    .serving_address_or(None, Some("device.local"))
+                    .unwrap(),
+                "10.0.0.5"
+            );
+        }
+
+        mod multiple_addresses {
+            use super::*;
+
+            /// Status with an offline eth0, and eth1 and eth2 online with the given addresses
+            fn status_with_addresses(eth1_addrs: &[&str]) -> NetworkStatus {
+                let mut eth1 = create_test_interface("eth1", "66:77:88:99:aa:bb", true, "");
+                eth1.ipv4.addrs = eth1_addrs
+                    .iter()
+                    .map(|addr| Ipv4AddrInfo {
+                        addr: addr.to_string(),
+                    })
+                    .collect();
+
+                NetworkStatus {
+                    network_interfaces: vec![
+                        create_test_interface("eth0", "00:11:22:33:44:55", false, "192.168.1.100"),
+                        eth1,
+                        create_test_interface("eth2", "cc:dd:ee:ff:00:11", true, "172.16.0.7"),
+                    ],
+                }
+            }
+
+            fn serving_address(status: &NetworkStatus, preferred: Option<&str>) -> String {
+                status
+                    .serving_address_or(preferred.map(|ip| ip.parse().unwrap()), None)
+                    .unwrap()
+            }
+
+            #[test]
+            fn first_address_without_preference() {
+                let status = status_with_addresses(&["10.0.0.5", "192.168.0.42"]);
+
+                assert_eq!(serving_address(&status, None), "10.0.0.5");
+            }
+
+            #[test]
+            fn preferred_address_of_serving_interface() {
+                let status = status_with_addresses(&["10.0.0.5", "192.168.0.42"]);
+
+                assert_eq!(
+                    serving_address(&status, Some("192.168.0.42")),
+                    "192.168.0.42"
+                );
+            }
+
+            #[test]
+            fn preferred_address_of_another_online_interface() {
+                let status = status_with_addresses(&["10.0.0.5", "192.168.0.42"]);
+
+                assert_eq!(serving_address(&status, Some("172.16.0.7")), "172.16.0.7");
+            }
+
+            #[test]
+            fn ipv4_mapped_preferred_address_matches() {
+                let status = status_with_addresses(&["10.0.0.5", "192.168.0.42"]);
+
+                assert_eq!(
+                    serving_address(&status, Some("::ffff:192.168.0.42")),
+                    "192.168.0.42"
+                );
+            }
+
+            #[test]
+            fn unknown_or_offline_preferred_address_falls_back_to_first() {
+                let status = status_with_addresses(&["10.0.0.5", "192.168.0.42"]);
+
+                assert_eq!(serving_address(&status, Some("10.0.0.99")), "10.0.0.5");
+                assert_eq!(serving_address(&status, Some("192.168.1.100")), "10.0.0.5");
+            }
+
+            #[test]
+            fn online_interface_without_address_is_skipped() {
+                let status = status_with_addresses(&[]);
+
+                assert_eq!(serving_address(&status, None), "172.16.0.7");
+            }
+        }
+
+        #[test]
+        fn serving_address_falls_back_without_online_interface() {
+            let mut status = create_test_status("0.40.0");
+            status.network_status.network_interfaces = vec![create_test_interface(
+                "eth0",
+                "00:11:22:33:44:55",
+                false,
+                "192.168.1.100",
+            )];
+
+            assert_eq!(
+                status
+                    .network_status
+                    .serving_address_or(None, Some("device.local"))
+                    .unwrap(),
+                "device.local"
+            );
+            assert!(
+                status
+                    .network_status
+                    .serving_address_or(None, None)
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn interfaces_are_sorted_and_deduplicated() {
+            let mut status = create_test_status("0.40.0");
+            status.network_status.network_interfaces = vec![
+                create_test_interface("wlan0", "66:77:88:99:aa:bb", true, "10.0.0.5"),
+                create_test_interface("eth0", "00:11:22:33:44:55", false, "192.168.1.100"),
+                create_test_interface("eth1", "aa:bb:cc:dd:ee:ff", true, "192.168.2.100"),
+                create_test_interface("eth0", "00:11:22:33:44:55", true, "192.168.1.100"),
+            ];
+
+            status.network_status.normalize();
+
+            let interfaces: Vec<(&str, bool)> = status
+                .network_status
+                .network_interfaces
+                .iter()
+                .map(|iface| (iface.name.as_str(), iface.online))
+                .collect();
+            assert_eq!(
+                interfaces,
+                vec![("eth0", true), ("eth1", true), ("wlan0", true)]
+            );
+        }
+
+        #[test]
+        fn include_filter_keeps_only_matching_interfaces() {
+            let filter = NetworkInterfaceConfig::new(Some("^eth"), Some("^eth1$")).unwrap();
+
+            assert!(filter.is_allowed("eth0"));
+            assert!(!filter.is_allowed("eth1"));
+            assert!(!filter.is_allowed("wlan0"));
+        }
+
+        #[test]
+        fn empty_patterns_allow_all_interfaces() {
+            let filter = NetworkInterfaceConfig::new(Some(""), Some("")).unwrap();
+
+            assert!(filter.is_allowed("docker0"));
+            assert!(filter.is_allowed("veth0"));
+        }
+
+        #[test]
+        fn invalid_pattern_is_rejected() {
+            assert!(NetworkInterfaceConfig::new(Some("(eth"), None).is_err());
+        }
+
         #[test]
         fn healthcheck_includes_network_rollback_status() {
             // Verify marker file check is callable without panicking
@@ -479,6 +1085,55 @@ mod tests {
         }
     }
 
+    mod unique_addresses {
+        use super::*;
+
+        #[test]
+        fn keeps_resolver_order_and_drops_non_adjacent_duplicates() {
+            let addrs = [
+                "192.0.2.10",
+                "2001:db8::1",
+                "192.0.2.10",
+                "192.0.2.11",
+                "2001:db8::1",
+            ]
+            .map(|addr| addr.parse::<IpAddr>().unwrap());
+
+            assert_eq!(
+                OmnectDeviceServiceClient::unique_addresses(addrs.into_iter()),
+                vec!["192.0.2.10", "2001:db8::1", "192.0.2.11"]
+            );
+        }
+    }
+
+    mod update_progress {
+        use super::*;
+
+        #[test]
+        fn parses_percentage() {
+            assert_eq!(
+                OmnectDeviceServiceClient::parse_update_progress("42").unwrap(),
+                42
+            );
+            assert_eq!(
+                OmnectDeviceServiceClient::parse_update_progress("100\n").unwrap(),
+                100
+            );
+        }
+
+        #[test]
+        fn rejects_out_of_range_percentage() {
+            assert!(OmnectDeviceServiceClient::parse_update_progress("101").is_err());
+        }
+
+        #[test]
+        fn rejects_invalid_body() {
+            assert!(OmnectDeviceServiceClient::parse_update_progress("").is_err());
+            assert!(OmnectDeviceServiceClient::parse_update_progress("-1").is_err());
+            assert!(OmnectDeviceServiceClient::parse_update_progress("\"42%\"").is_err());
+        }
+    }
+
     mod publish_endpoint_state {
         use super::*;
 
@@ -487,6 +1142,8 @@ mod tests {
             let client = OmnectDeviceServiceClient {
                 client: reqwest::Client::new(),
                 has_publish_endpoint: false,
+                api_versions: DeviceServiceApiVersions::default(),
+                fallback_common_name: None,
             };
 
             assert!(!client.has_publish_endpoint);
@@ -497,6 +1154,8 @@ mod tests {
             let mut client = OmnectDeviceServiceClient {
                 client: reqwest::Client::new(),
                 has_publish_endpoint: false,
+                api_versions: DeviceServiceApiVersions::default(),
+                fallback_common_name: None,
             };
 
             // Simulate registration
@@ -511,31 +1170,51 @@ mod tests {
 
         #[test]
         fn api_endpoints_are_correctly_defined() {
-            assert_eq!(OmnectDeviceServiceClient::STATUS_ENDPOINT, "/status/v1");
-            assert_eq!(
-                OmnectDeviceServiceClient::REPUBLISH_ENDPOINT,
-                "/republish/v1/"
-            );
+            assert_eq!(OmnectDeviceServiceClient::STATUS_ENDPOINT, "/status");
+            assert_eq!(OmnectDeviceServiceClient::REPUBLISH_ENDPOINT, "/republish");
             assert_eq!(
                 OmnectDeviceServiceClient::FACTORY_RESET_ENDPOINT,
-                "/factory-reset/v1"
+                "/factory-reset"
             );
-            assert_eq!(OmnectDeviceServiceClient::REBOOT_ENDPOINT, "/reboot/v1");
+            assert_eq!(OmnectDeviceServiceClient::REBOOT_ENDPOINT, "/reboot");
             assert_eq!(
                 OmnectDeviceServiceClient::RELOAD_NETWORK_ENDPOINT,
-                "/reload-network/v1"
+                "/reload-network"
+            );
+            assert_eq!(
+                OmnectDeviceServiceClient::RESTART_WPA_SUPPLICANT_ENDPOINT,
+                "/restart-wpa-supplicant"
             );
             assert_eq!(
                 OmnectDeviceServiceClient::LOAD_UPDATE_ENDPOINT,
-                "/fwupdate/load/v1"
+                "/fwupdate/load"
             );
             assert_eq!(
                 OmnectDeviceServiceClient::RUN_UPDATE_ENDPOINT,
-                "/fwupdate/run/v1"
+                "/fwupdate/run"
             );
             assert_eq!(
                 OmnectDeviceServiceClient::PUBLISH_ENDPOINT,
-                "/publish-endpoint/v1"
+                "/publish-endpoint"
+            );
+        }
+
+        #[test]
+        fn default_api_versions_are_v1() {
+            let versions = DeviceServiceApiVersions::default();
+            assert_eq!(
+                OmnectDeviceServiceClient::versioned(
+                    OmnectDeviceServiceClient::STATUS_ENDPOINT,
+                    &versions.status
+                ),
+                "/status/v1"
+            );
+            assert_eq!(
+                OmnectDeviceServiceClient::versioned(
+                    OmnectDeviceServiceClient::UPDATE_PROGRESS_ENDPOINT,
+                    &versions.update_progress
+                ),
+                "/fwupdate/progress/v1"
             );
         }
 
@@ -545,4 +1224,211 @@ mod tests {
             assert!(version_req.is_ok());
         }
     }
+
+    mod api_versions {
+        use super::*;
+        use tempfile::TempDir;
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::UnixListener,
+        };
+
+        const STATUS_BODY: &str = r#"{
+            "NetworkStatus": { "network_status": [] },
+            "SystemInfo": { "fleet_id": null, "omnect_device_service_version": "0.40.0" },
+            "UpdateValidationStatus": { "status": "NoUpdate" }
+        }"#;
+
+        /// Serve a single status response and return the request line
+        async fn requested_status_path(api_versions: DeviceServiceApiVersions) -> String {
+            let tmp = TempDir::new().unwrap();
+            let socket_path = tmp.path().join("api.sock");
+            let listener = UnixListener::bind(&socket_path).unwrap();
+
+            let server = tokio::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{STATUS_BODY}",
+                    STATUS_BODY.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+                request.lines().next().unwrap_or_default().to_string()
+            });
+
+            let client = OmnectDeviceServiceClient {
+                client: unix_socket_client(&socket_path.to_string_lossy()).unwrap(),
+                has_publish_endpoint: false,
+                api_versions,
+                fallback_common_name: None,
+            };
+            client.status().await.unwrap();
+
+            server.await.unwrap()
+        }
+
+        #[tokio::test]
+        async fn status_uses_default_version() {
+            let request_line = requested_status_path(DeviceServiceApiVersions::default()).await;
+
+            assert!(request_line.starts_with("GET /status/v1 "));
+        }
+
+        #[tokio::test]
+        async fn status_uses_overridden_version() {
+            let api_versions = DeviceServiceApiVersions {
+                status: "v2".to_string(),
+                ..Default::default()
+            };
+
+            let request_line = requested_status_path(api_versions).await;
+
+            assert!(request_line.starts_with("GET /status/v2 "));
+        }
+    }
+
+    mod ip_address {
+        use super::*;
+        use tempfile::TempDir;
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::UnixListener,
+        };
+
+        const OFFLINE_STATUS_BODY: &str = r#"{
+            "NetworkStatus": { "network_status": [
+                {
+                    "online": false,
+                    "ipv4": { "addrs": [ { "addr": "192.168.1.100" } ] },
+                    "file": "/etc/systemd/network/10-eth0.network",
+                    "mac": "00:11:22:33:44:55",
+                    "name": "eth0"
+                }
+            ] },
+            "SystemInfo": { "fleet_id": null, "omnect_device_service_version": "0.40.0" },
+            "UpdateValidationStatus": { "status": "NoUpdate" }
+        }"#;
+
+        /// Query the ip address from a device service reporting all interfaces offline
+        async fn ip_address_with_all_offline(fallback_common_name: Option<&str>) -> Result<String> {
+            let tmp = TempDir::new().unwrap();
+            let socket_path = tmp.path().join("api.sock");
+            let listener = UnixListener::bind(&socket_path).unwrap();
+
+            tokio::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 4096];
+                let _ = stream.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{OFFLINE_STATUS_BODY}",
+                    OFFLINE_STATUS_BODY.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            });
+
+            let client = OmnectDeviceServiceClient {
+                client: unix_socket_client(&socket_path.to_string_lossy()).unwrap(),
+                has_publish_endpoint: false,
+                api_versions: DeviceServiceApiVersions::default(),
+                fallback_common_name: fallback_common_name.map(str::to_string),
+            };
+            client.ip_address(None).await
+        }
+
+        #[tokio::test]
+        async fn all_offline_returns_configured_fallback() {
+            assert_eq!(
+                ip_address_with_all_offline(Some("device.local"))
+                    .await
+                    .unwrap(),
+                "device.local"
+            );
+        }
+
+        #[tokio::test]
+        async fn all_offline_fails_without_fallback() {
+            let err = ip_address_with_all_offline(None).await.unwrap_err();
+
+            assert!(err.to_string().contains("failed to get ip address"));
+        }
+    }
+
+    mod status_parsing {
+        use super::*;
+        use tempfile::TempDir;
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::UnixListener,
+        };
+
+        /// Query the status from a device service answering with the given body
+        async fn status_with_body(content_type: &str, body: &'static str) -> Result<Status> {
+            let tmp = TempDir::new().unwrap();
+            let socket_path = tmp.path().join("api.sock");
+            let listener = UnixListener::bind(&socket_path).unwrap();
+            let content_type = content_type.to_string();
+
+            tokio::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 4096];
+                let _ = stream.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            });
+
+            let client = OmnectDeviceServiceClient {
+                client: unix_socket_client(&socket_path.to_string_lossy()).unwrap(),
+                has_publish_endpoint: false,
+                api_versions: DeviceServiceApiVersions::default(),
+                fallback_common_name: None,
+            };
+            client.status().await
+        }
+
+        #[tokio::test]
+        async fn html_body_error_quotes_content_type_and_body() {
+            let err = status_with_body("text/html", "<html><body>Bad Gateway</body></html>")
+                .await
+                .unwrap_err();
+
+            let msg = err.to_string();
+            assert!(msg.starts_with("failed to parse status"));
+            assert!(msg.contains("content-type: text/html"));
+            assert!(msg.contains("<html><body>Bad Gateway</body></html>"));
+        }
+
+        #[tokio::test]
+        async fn empty_body_is_reported_as_empty_status() {
+            let err = status_with_body("application/json", "").await.unwrap_err();
+
+            assert_eq!(err.to_string(), "device service returned empty status");
+        }
+
+        #[test]
+        fn whitespace_body_is_reported_as_empty_status() {
+            let err = OmnectDeviceServiceClient::parse_status(" \n", None).unwrap_err();
+
+            assert_eq!(err.to_string(), "device service returned empty status");
+        }
+
+        #[test]
+        fn long_body_is_truncated() {
+            let body = "x".repeat(1000);
+
+            let err = OmnectDeviceServiceClient::parse_status(&body, None).unwrap_err();
+
+            let msg = err.to_string();
+            assert!(msg.contains("content-type: none"));
+            assert!(msg.contains(&format!(
+                "{}...",
+                "x".repeat(OmnectDeviceServiceClient::BODY_SNIPPET_MAX_CHARS)
+            )));
+            assert!(!msg.contains(&body));
+        }
+    }
 }
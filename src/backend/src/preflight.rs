@@ -0,0 +1,361 @@
+//! Startup self-check of critical runtime prerequisites
+//!
+//! All checks are run and reported before the server starts, so a
+//! misconfigured device shows every problem at once instead of failing
+//! on the first one somewhere during startup.
+
+use crate::{config::AppConfig, services::certificate::CertificateService};
+use anyhow::{Context, Result, bail, ensure};
+use log::{error, info};
+use std::{env, fs, os::unix::fs::PermissionsExt, path::Path};
+
+#[cfg(not(any(test, feature = "mock")))]
+const REQUIRED_ENV_VARS: &[&str] = &["KEYCLOAK_URL"];
+#[cfg(any(test, feature = "mock"))]
+const REQUIRED_ENV_VARS: &[&str] = &[];
+
+/// Provided by the IoT Edge runtime, which doesn't exist in demo mode
+#[cfg(not(any(test, feature = "mock")))]
+const IOT_EDGE_ENV_VARS: &[&str] = &[
+    "IOTEDGE_MODULEID",
+    "IOTEDGE_MODULEGENERATIONID",
+    "IOTEDGE_APIVERSION",
+    "IOTEDGE_WORKLOADURI",
+];
+#[cfg(any(test, feature = "mock"))]
+const IOT_EDGE_ENV_VARS: &[&str] = &[];
+
+/// Validate all critical prerequisites and log a report
+///
+/// In demo mode the IoT Edge environment and the device service socket are
+/// not checked, as canned data is served instead.
+///
+/// # Returns
+/// Error listing every failed check if any check failed
+pub fn preflight() -> Result<()> {
+    let config = AppConfig::try_get();
+    // without a valid configuration the mode is unknown, so check everything
+    let demo_mode = config.as_ref().is_ok_and(|config| config.demo_mode);
+
+    let mut checks = vec![("environment", check_env_vars(&required_env_vars(demo_mode)))];
+
+    // checks of configured paths depend on a valid configuration
+    match config {
+        Ok(config) => {
+            checks.push(("configuration", Ok(())));
+            checks.extend(config_checks(config));
+        }
+        Err(e) => checks.push(("configuration", Err(e))),
+    }
+
+    report(checks)
+}
+
+fn required_env_vars(demo_mode: bool) -> Vec<&'static str> {
+    let mut vars = REQUIRED_ENV_VARS.to_vec();
+    if !demo_mode {
+        vars.extend(IOT_EDGE_ENV_VARS);
+    }
+    vars
+}
+
+fn config_checks(config: &AppConfig) -> Vec<(&'static str, Result<()>)> {
+    let mut checks = vec![
+        ("data directory", check_dir_writable(&config.paths.data_dir)),
+        (
+            "certificate",
+            check_certificate(&config.certificate.cert_path, &config.certificate.key_path),
+        ),
+        (
+            "centrifugo binary",
+            check_executable(&config.centrifugo.binary_path),
+        ),
+    ];
+
+    if !config.demo_mode {
+        checks.push((
+            "device service socket",
+            check_parent_exists(&config.device_service.socket_path),
+        ));
+    }
+
+    checks
+}
+
+/// Log the result of every check and fail once if any check failed
+fn report(checks: Vec<(&str, Result<()>)>) -> Result<()> {
+    let mut failures = vec![];
+    for (name, result) in checks {
+        match result {
+            Ok(()) => info!("preflight {name}: ok"),
+            Err(e) => {
+                error!("preflight {name}: {e:#}");
+                failures.push(format!("{name}: {e:#}"));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        bail!(
+            "preflight failed ({} check(s)): {}",
+            failures.len(),
+            failures.join("; ")
+        );
+    }
+
+    Ok(())
+}
+
+fn check_env_vars(vars: &[&str]) -> Result<()> {
+    let missing: Vec<&str> = vars
+        .iter()
+        .copied()
+        .filter(|var| env::var(var).is_err())
+        .collect();
+
+    ensure!(
+        missing.is_empty(),
+        "missing environment variables: {}",
+        missing.join(", ")
+    );
+    Ok(())
+}
+
+fn check_dir_writable(dir: &Path) -> Result<()> {
+    ensure!(dir.is_dir(), "{dir:?} is not a directory");
+
+    let probe = dir.join(".preflight");
+    fs::write(&probe, b"").with_context(|| format!("{dir:?} is not writable"))?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+/// A missing certificate is fine, it is created on startup
+fn check_certificate(cert_path: &Path, key_path: &Path) -> Result<()> {
+    if !cert_path.exists() {
+        info!("no certificate at {cert_path:?} yet, it will be created");
+        return Ok(());
+    }
+
+    let pem =
+        fs::read(cert_path).with_context(|| format!("failed to read certificate {cert_path:?}"))?;
+    CertificateService::parse_cert_info(&pem)
+        .with_context(|| format!("invalid certificate {cert_path:?}"))?;
+
+    let key =
+        fs::read_to_string(key_path).with_context(|| format!("failed to read key {key_path:?}"))?;
+    ensure!(
+        key.contains("PRIVATE KEY"),
+        "invalid key {key_path:?}: no private key found"
+    );
+    Ok(())
+}
+
+fn check_executable(path: &Path) -> Result<()> {
+    let metadata = fs::metadata(path).with_context(|| format!("failed to find {path:?}"))?;
+
+    ensure!(metadata.is_file(), "{path:?} is not a file");
+    ensure!(
+        metadata.permissions().mode() & 0o111 != 0,
+        "{path:?} is not executable"
+    );
+    Ok(())
+}
+
+fn check_parent_exists(path: &Path) -> Result<()> {
+    let parent = path
+        .parent()
+        .with_context(|| format!("{path:?} has no parent directory"))?;
+
+    ensure!(parent.is_dir(), "directory {parent:?} does not exist");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{CertificateParams, KeyPair};
+    use tempfile::TempDir;
+
+    mod env_vars {
+        use super::*;
+
+        #[test]
+        fn reports_all_missing_vars() {
+            let err = check_env_vars(&[
+                "OMNECT_UI_PREFLIGHT_MISSING_A",
+                "OMNECT_UI_PREFLIGHT_MISSING_B",
+            ])
+            .unwrap_err()
+            .to_string();
+
+            assert!(err.contains("OMNECT_UI_PREFLIGHT_MISSING_A"));
+            assert!(err.contains("OMNECT_UI_PREFLIGHT_MISSING_B"));
+        }
+
+        #[test]
+        fn accepts_present_vars() {
+            assert!(check_env_vars(&["PATH"]).is_ok());
+        }
+
+        #[test]
+        fn demo_mode_skips_iot_edge_vars() {
+            let demo = required_env_vars(true);
+            let device = required_env_vars(false);
+
+            assert!(IOT_EDGE_ENV_VARS.iter().all(|var| !demo.contains(var)));
+            assert!(IOT_EDGE_ENV_VARS.iter().all(|var| device.contains(var)));
+            assert!(demo.iter().all(|var| device.contains(var)));
+        }
+    }
+
+    mod report {
+        use super::*;
+        use anyhow::anyhow;
+
+        #[test]
+        fn lists_every_failed_check() {
+            let err = report(vec![
+                (
+                    "environment",
+                    Err(anyhow!("missing environment variables: A")),
+                ),
+                ("configuration", Err(anyhow!("failed to parse UI_PORT"))),
+                ("data directory", Ok(())),
+            ])
+            .unwrap_err()
+            .to_string();
+
+            assert!(err.starts_with("preflight failed (2 check(s))"));
+            assert!(err.contains("environment: missing environment variables: A"));
+            assert!(err.contains("configuration: failed to parse UI_PORT"));
+        }
+
+        #[test]
+        fn passes_if_all_checks_pass() {
+            assert!(report(vec![("environment", Ok(())), ("configuration", Ok(()))]).is_ok());
+        }
+    }
+
+    mod data_dir {
+        use super::*;
+
+        #[test]
+        fn accepts_writable_dir() {
+            let dir = TempDir::new().unwrap();
+
+            assert!(check_dir_writable(dir.path()).is_ok());
+            assert!(!dir.path().join(".preflight").exists());
+        }
+
+        #[test]
+        fn rejects_missing_dir() {
+            let dir = TempDir::new().unwrap();
+
+            assert!(check_dir_writable(&dir.path().join("missing")).is_err());
+        }
+    }
+
+    mod certificate {
+        use super::*;
+
+        fn write_cert(dir: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+            let key_pair = KeyPair::generate().unwrap();
+            let cert = CertificateParams::new(vec!["localhost".to_string()])
+                .unwrap()
+                .self_signed(&key_pair)
+                .unwrap();
+
+            let cert_path = dir.join("cert.pem");
+            let key_path = dir.join("key.pem");
+            fs::write(&cert_path, cert.pem()).unwrap();
+            fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+            (cert_path, key_path)
+        }
+
+        #[test]
+        fn accepts_valid_cert_and_key() {
+            let dir = TempDir::new().unwrap();
+            let (cert_path, key_path) = write_cert(dir.path());
+
+            assert!(check_certificate(&cert_path, &key_path).is_ok());
+        }
+
+        #[test]
+        fn accepts_missing_cert() {
+            let dir = TempDir::new().unwrap();
+
+            assert!(
+                check_certificate(&dir.path().join("cert.pem"), &dir.path().join("key.pem"))
+                    .is_ok()
+            );
+        }
+
+        #[test]
+        fn rejects_invalid_cert() {
+            let dir = TempDir::new().unwrap();
+            let (cert_path, key_path) = write_cert(dir.path());
+            fs::write(&cert_path, "not a certificate").unwrap();
+
+            assert!(check_certificate(&cert_path, &key_path).is_err());
+        }
+
+        #[test]
+        fn rejects_missing_key() {
+            let dir = TempDir::new().unwrap();
+            let (cert_path, key_path) = write_cert(dir.path());
+            fs::remove_file(&key_path).unwrap();
+
+            assert!(check_certificate(&cert_path, &key_path).is_err());
+        }
+    }
+
+    mod executable {
+        use super::*;
+
+        #[test]
+        fn accepts_executable_file() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("centrifugo");
+            fs::write(&path, b"").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+            assert!(check_executable(&path).is_ok());
+        }
+
+        #[test]
+        fn rejects_non_executable_file() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("centrifugo");
+            fs::write(&path, b"").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+            assert!(check_executable(&path).is_err());
+        }
+
+        #[test]
+        fn rejects_missing_file() {
+            let dir = TempDir::new().unwrap();
+
+            assert!(check_executable(&dir.path().join("centrifugo")).is_err());
+        }
+    }
+
+    mod socket_path {
+        use super::*;
+
+        #[test]
+        fn accepts_existing_parent() {
+            let dir = TempDir::new().unwrap();
+
+            assert!(check_parent_exists(&dir.path().join("device-service.sock")).is_ok());
+        }
+
+        #[test]
+        fn rejects_missing_parent() {
+            let dir = TempDir::new().unwrap();
+
+            assert!(check_parent_exists(&dir.path().join("missing/device-service.sock")).is_err());
+        }
+    }
+}
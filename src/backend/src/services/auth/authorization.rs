@@ -3,7 +3,8 @@
 //! Handles token validation and role-based access control independent of HTTP concerns.
 
 use crate::{
-    config::AppConfig, keycloak_client::SingleSignOnProvider,
+    config::{AppConfig, RoleConfig},
+    keycloak_client::{SingleSignOnProvider, TokenClaims},
     omnect_device_service_client::DeviceServiceClient,
 };
 use anyhow::{Result, bail, ensure};
@@ -14,7 +15,7 @@ pub struct AuthorizationService;
 impl AuthorizationService {
     /// Validate SSO token and check user claims for authorization
     ///
-    /// Uses the tenant configuration from AppConfig.
+    /// Uses the tenant allowlist and role names from AppConfig.
     ///
     /// # Arguments
     /// * `single_sign_on` - Single sign-on provider for token verification
@@ -25,9 +26,9 @@ impl AuthorizationService {
     /// Result indicating success or authorization failure
     ///
     /// # Authorization Rules
-    /// - User must have tenant in their tenant_list
-    /// - FleetAdministrator role grants full access
-    /// - FleetOperator role requires fleet_id in fleet_list
+    /// - User's tenant_list must contain at least one configured tenant
+    /// - Admin role (`FleetAdministrator` by default) grants full access
+    /// - Operator role (`FleetOperator` by default) requires fleet_id in fleet_list
     pub async fn validate_token_and_claims<ServiceClient, SingleSignOn>(
         single_sign_on: &SingleSignOn,
         service_client: &ServiceClient,
@@ -38,14 +39,27 @@ impl AuthorizationService {
         SingleSignOn: SingleSignOnProvider,
     {
         let claims = single_sign_on.verify_token(token).await?;
-        let tenant = &AppConfig::get().tenant;
+        let config = AppConfig::get();
 
+        Self::validate_claims(&claims, service_client, &config.tenants, &config.roles).await
+    }
+
+    /// Check verified token claims against the tenant allowlist and role names
+    async fn validate_claims<ServiceClient>(
+        claims: &TokenClaims,
+        service_client: &ServiceClient,
+        tenants: &[String],
+        role_config: &RoleConfig,
+    ) -> Result<()>
+    where
+        ServiceClient: DeviceServiceClient,
+    {
         // Validate tenant authorization
         let Some(tenant_list) = &claims.tenant_list else {
             bail!("failed to authorize user: no tenant list in token");
         };
         ensure!(
-            tenant_list.contains(tenant),
+            Self::is_tenant_allowed(tenant_list, tenants),
             "failed to authorize user: insufficient permissions for tenant"
         );
 
@@ -54,13 +68,13 @@ impl AuthorizationService {
             bail!("failed to authorize user: no roles in token");
         };
 
-        // FleetAdministrator has full access
-        if roles.iter().any(|r| r == "FleetAdministrator") {
+        // Admin has full access
+        if roles.contains(&role_config.admin) {
             return Ok(());
         }
 
-        // FleetOperator requires fleet validation
-        if roles.iter().any(|r| r == "FleetOperator") {
+        // Operator requires fleet validation
+        if roles.contains(&role_config.operator) {
             let Some(fleet_list) = &claims.fleet_list else {
                 bail!("failed to authorize user: no fleet list in token");
             };
@@ -74,6 +88,11 @@ impl AuthorizationService {
 
         bail!("failed to authorize user: insufficient role permissions")
     }
+
+    /// Check whether any tenant in the token is part of the configured allowlist
+    fn is_tenant_allowed(tenant_list: &[String], allowed: &[String]) -> bool {
+        tenant_list.iter().any(|t| allowed.contains(t))
+    }
 }
 
 #[cfg(test)]
@@ -345,6 +364,85 @@ mod tests {
         }
     }
 
+    mod custom_roles {
+        use super::*;
+
+        fn roles() -> RoleConfig {
+            RoleConfig {
+                admin: "DeviceAdmin".to_string(),
+                operator: "DeviceOperator".to_string(),
+            }
+        }
+
+        fn tenants() -> Vec<String> {
+            vec!["cp".to_string()]
+        }
+
+        #[tokio::test]
+        async fn custom_admin_role_grants_access() {
+            let claims = create_claims(Some(vec!["DeviceAdmin"]), Some(vec!["cp"]), None);
+            let device_mock = DeviceServiceClient::default();
+
+            let result =
+                AuthorizationService::validate_claims(&claims, &device_mock, &tenants(), &roles())
+                    .await;
+
+            assert!(result.is_ok());
+        }
+
+        #[tokio::test]
+        async fn custom_operator_role_requires_fleet() {
+            let claims = create_claims(
+                Some(vec!["DeviceOperator"]),
+                Some(vec!["cp"]),
+                Some(vec!["Fleet1"]),
+            );
+            let mut device_mock = DeviceServiceClient::default();
+            device_mock
+                .expect_fleet_id()
+                .returning(|| Box::pin(async { Ok("Fleet1".to_string()) }));
+
+            let result =
+                AuthorizationService::validate_claims(&claims, &device_mock, &tenants(), &roles())
+                    .await;
+
+            assert!(result.is_ok());
+        }
+
+        #[tokio::test]
+        async fn default_admin_role_is_replaced() {
+            let claims = create_claims(Some(vec!["FleetAdministrator"]), Some(vec!["cp"]), None);
+            let device_mock = DeviceServiceClient::default();
+
+            let result =
+                AuthorizationService::validate_claims(&claims, &device_mock, &tenants(), &roles())
+                    .await;
+
+            assert!(
+                result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("insufficient role permissions")
+            );
+        }
+
+        #[tokio::test]
+        async fn default_admin_role_grants_access_by_default() {
+            let claims = create_claims(Some(vec!["FleetAdministrator"]), Some(vec!["cp"]), None);
+            let device_mock = DeviceServiceClient::default();
+
+            let result = AuthorizationService::validate_claims(
+                &claims,
+                &device_mock,
+                &tenants(),
+                &RoleConfig::default(),
+            )
+            .await;
+
+            assert!(result.is_ok());
+        }
+    }
+
     mod fleet_observer {
         use super::*;
 
@@ -434,6 +532,39 @@ mod tests {
         }
     }
 
+    mod tenant_allowlist {
+        use super::*;
+
+        fn strings(values: &[&str]) -> Vec<String> {
+            values.iter().map(|v| v.to_string()).collect()
+        }
+
+        #[test]
+        fn matches_any_configured_tenant() {
+            let allowed = strings(&["cp", "acme"]);
+
+            assert!(AuthorizationService::is_tenant_allowed(
+                &strings(&["other", "acme"]),
+                &allowed
+            ));
+            assert!(AuthorizationService::is_tenant_allowed(
+                &strings(&["cp"]),
+                &allowed
+            ));
+        }
+
+        #[test]
+        fn rejects_tenants_outside_allowlist() {
+            let allowed = strings(&["cp", "acme"]);
+
+            assert!(!AuthorizationService::is_tenant_allowed(
+                &strings(&["other", "foo"]),
+                &allowed
+            ));
+            assert!(!AuthorizationService::is_tenant_allowed(&[], &allowed));
+        }
+    }
+
     mod token_verification {
         use super::*;
 
@@ -1,7 +1,9 @@
 pub mod authorization;
 pub mod password;
+pub mod session;
 pub mod token;
 
 pub use authorization::AuthorizationService;
 pub use password::PasswordService;
+pub use session::SessionRegistry;
 pub use token::TokenManager;
@@ -3,13 +3,17 @@
 //! Handles password hashing, storage, and validation independent of HTTP concerns.
 
 use crate::config::AppConfig;
-use anyhow::{Context, Result, anyhow, ensure};
+use anyhow::{Context, Result, anyhow, bail, ensure};
 use argon2::{
     Argon2, PasswordHash, PasswordVerifier,
     password_hash::{PasswordHasher, SaltString, rand_core::OsRng},
 };
 use log::debug;
-use std::{fs::File, io::Write};
+use omnect_ui_core::types::PasswordPolicy;
+use std::{
+    fs::File,
+    io::{ErrorKind, Write},
+};
 
 #[cfg(any(test, feature = "mock"))]
 use std::sync::{LazyLock, Mutex, MutexGuard};
@@ -61,6 +65,39 @@ impl PasswordService {
             .context("failed to verify password")
     }
 
+    /// Check a new password against the password policy
+    ///
+    /// # Arguments
+    /// * `password` - The plaintext password to check
+    /// * `policy` - The password policy to enforce
+    ///
+    /// # Returns
+    /// Error listing every violated rule if the password does not satisfy the policy
+    pub fn validate_strength(password: &str, policy: &PasswordPolicy) -> Result<()> {
+        let mut violations = vec![];
+
+        if password.chars().count() < policy.min_length {
+            violations.push(format!("at least {} characters", policy.min_length));
+        }
+        if policy.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push("an uppercase letter".to_string());
+        }
+        if policy.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push("a lowercase letter".to_string());
+        }
+        if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push("a digit".to_string());
+        }
+        if policy.require_special && password.chars().all(char::is_alphanumeric) {
+            violations.push("a special character".to_string());
+        }
+
+        if !violations.is_empty() {
+            bail!("password must contain {}", violations.join(", "));
+        }
+        Ok(())
+    }
+
     /// Hash a password using Argon2
     fn hash_password(password: &str) -> Result<String> {
         debug!("hash_password() called");
@@ -134,6 +171,20 @@ impl PasswordService {
             .try_exists()
             .unwrap_or(false)
     }
+
+    /// Check if the password file exists but does not hold a valid hash
+    ///
+    /// A corrupt file (e.g. empty or truncated) can never validate any password,
+    /// as opposed to a valid hash that merely does not match.
+    ///
+    /// # Returns
+    /// true if the password file is structurally invalid, false if it is valid or missing
+    pub fn password_file_corrupt() -> bool {
+        match std::fs::read_to_string(&AppConfig::get().paths.password_file) {
+            Ok(hash) => hash.is_empty() || PasswordHash::new(&hash).is_err(),
+            Err(e) => e.kind() == ErrorKind::InvalidData,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +198,48 @@ mod tests {
         assert!(hash.starts_with("$argon2"));
     }
 
+    fn strict_policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: true,
+        }
+    }
+
+    #[test]
+    fn test_validate_strength_accepts_compliant_password() {
+        assert!(PasswordService::validate_strength("Secret#2024", &strict_policy()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_strength_reports_all_violations() {
+        let err = PasswordService::validate_strength("abc", &strict_policy())
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("at least 8 characters"));
+        assert!(err.contains("an uppercase letter"));
+        assert!(err.contains("a digit"));
+        assert!(err.contains("a special character"));
+        assert!(!err.contains("a lowercase letter"));
+    }
+
+    #[test]
+    fn test_validate_strength_counts_characters_not_bytes() {
+        let policy = PasswordPolicy {
+            min_length: 4,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_special: false,
+        };
+
+        assert!(PasswordService::validate_strength("äöü", &policy).is_err());
+        assert!(PasswordService::validate_strength("äöüß", &policy).is_ok());
+    }
+
     #[test]
     fn test_store_and_check_password() {
         let _lock = PasswordService::lock_for_test();
@@ -164,4 +257,70 @@ mod tests {
         // Cleanup
         let _ = std::fs::remove_file(password_file);
     }
+
+    mod corrupt_password_file {
+        use super::*;
+
+        fn write_password_file(content: &[u8]) {
+            std::fs::write(&AppConfig::get().paths.password_file, content)
+                .expect("should write password file");
+        }
+
+        #[test]
+        fn valid_hash_is_not_corrupt() {
+            let _lock = PasswordService::lock_for_test();
+
+            PasswordService::store_or_update_password("testpass").expect("should store password");
+
+            assert!(!PasswordService::password_file_corrupt());
+
+            let _ = std::fs::remove_file(&AppConfig::get().paths.password_file);
+        }
+
+        #[test]
+        fn missing_file_is_not_corrupt() {
+            let _lock = PasswordService::lock_for_test();
+
+            let _ = std::fs::remove_file(&AppConfig::get().paths.password_file);
+
+            assert!(!PasswordService::password_file_corrupt());
+        }
+
+        #[test]
+        fn unparseable_hash_is_corrupt() {
+            let _lock = PasswordService::lock_for_test();
+
+            write_password_file(b"$argon2id$v=19$m=19456");
+
+            assert!(PasswordService::password_file_corrupt());
+            assert!(PasswordService::validate_password("testpass").is_err());
+
+            let _ = std::fs::remove_file(&AppConfig::get().paths.password_file);
+        }
+
+        #[test]
+        fn empty_or_binary_file_is_corrupt() {
+            let _lock = PasswordService::lock_for_test();
+
+            write_password_file(b"");
+            assert!(PasswordService::password_file_corrupt());
+
+            write_password_file(&[0xff, 0xfe, 0x00]);
+            assert!(PasswordService::password_file_corrupt());
+
+            let _ = std::fs::remove_file(&AppConfig::get().paths.password_file);
+        }
+
+        #[test]
+        fn wrong_password_is_not_corrupt() {
+            let _lock = PasswordService::lock_for_test();
+
+            PasswordService::store_or_update_password("testpass").expect("should store password");
+
+            assert!(PasswordService::validate_password("otherpass").is_err());
+            assert!(!PasswordService::password_file_corrupt());
+
+            let _ = std::fs::remove_file(&AppConfig::get().paths.password_file);
+        }
+    }
 }
@@ -0,0 +1,148 @@
+use crate::{
+    clock::{Clock, SystemClock},
+    services::auth::token::TOKEN_EXPIRE_HOURS,
+};
+use log::{error, info};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Registry of the last activity of authenticated sessions
+///
+/// Sessions are identified by their session token. A session is expired once
+/// it was idle for longer than the configured idle timeout, independent of
+/// the expiry of its token.
+///
+/// Sessions unknown to the registry, e.g. after a restart of omnect-ui, are
+/// registered as active on their first request.
+#[derive(Clone)]
+pub struct SessionRegistry {
+    inner: Arc<SessionRegistryInner>,
+}
+
+struct SessionRegistryInner {
+    idle_timeout: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    last_activity: Mutex<HashMap<String, Duration>>,
+}
+
+impl SessionRegistry {
+    /// Entries idle for longer than this belong to expired tokens and are removed
+    const PRUNE_AFTER: Duration = Duration::from_secs(2 * TOKEN_EXPIRE_HOURS * 3600);
+
+    /// Create a new SessionRegistry
+    ///
+    /// # Arguments
+    /// * `idle_timeout` - Maximum time between two requests of a session, `None` disables the timeout
+    pub fn new(idle_timeout: Option<Duration>) -> Self {
+        Self::with_clock(idle_timeout, Arc::new(SystemClock))
+    }
+
+    /// Create a new SessionRegistry using the given clock for activity tracking
+    ///
+    /// # Arguments
+    /// * `idle_timeout` - Maximum time between two requests of a session, `None` disables the timeout
+    /// * `clock` - Time source
+    pub fn with_clock(idle_timeout: Option<Duration>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inner: Arc::new(SessionRegistryInner {
+                idle_timeout,
+                clock,
+                last_activity: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Record activity of a session
+    ///
+    /// Returns false if the session was idle for longer than the idle timeout.
+    /// The last activity of an expired session is not updated, so it stays
+    /// expired.
+    pub fn touch(&self, token: &str) -> bool {
+        let Some(idle_timeout) = self.inner.idle_timeout else {
+            return true;
+        };
+
+        let Ok(mut last_activity) = self.inner.last_activity.lock() else {
+            error!("failed to lock session registry");
+            return false;
+        };
+
+        let now = self.inner.clock.now_since_epoch();
+        last_activity.retain(|_, last| now.saturating_sub(*last) <= Self::PRUNE_AFTER);
+
+        match last_activity.get(token) {
+            Some(last) if now.saturating_sub(*last) > idle_timeout => {
+                info!("session expired after {}s idle", idle_timeout.as_secs());
+                false
+            }
+            _ => {
+                last_activity.insert(token.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+
+    const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+    fn registry(idle_timeout: Option<Duration>) -> (SessionRegistry, Arc<FakeClock>) {
+        let clock = Arc::new(FakeClock::new(1_700_000_000));
+        (
+            SessionRegistry::with_clock(idle_timeout, clock.clone()),
+            clock,
+        )
+    }
+
+    #[test]
+    fn active_session_passes() {
+        let (registry, clock) = registry(Some(IDLE_TIMEOUT));
+
+        assert!(registry.touch("token"));
+        clock.advance(IDLE_TIMEOUT);
+        assert!(registry.touch("token"));
+        clock.advance(IDLE_TIMEOUT);
+        assert!(registry.touch("token"));
+    }
+
+    #[test]
+    fn idle_session_is_rejected() {
+        let (registry, clock) = registry(Some(IDLE_TIMEOUT));
+
+        assert!(registry.touch("token"));
+        clock.advance(IDLE_TIMEOUT + Duration::from_secs(1));
+        assert!(!registry.touch("token"));
+
+        // rejected requests don't revive the session
+        assert!(!registry.touch("token"));
+    }
+
+    #[test]
+    fn sessions_expire_independently() {
+        let (registry, clock) = registry(Some(IDLE_TIMEOUT));
+
+        assert!(registry.touch("idle"));
+        clock.advance(IDLE_TIMEOUT);
+        assert!(registry.touch("active"));
+        clock.advance(Duration::from_secs(1));
+
+        assert!(!registry.touch("idle"));
+        assert!(registry.touch("active"));
+    }
+
+    #[test]
+    fn disabled_timeout_never_expires() {
+        let (registry, clock) = registry(None);
+
+        assert!(registry.touch("token"));
+        clock.advance(Duration::from_secs(24 * 3600));
+        assert!(registry.touch("token"));
+    }
+}
@@ -1,13 +1,13 @@
+use crate::clock::{Clock, SystemClock};
 use anyhow::Result;
-use jsonwebtoken::{
-    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode, get_current_timestamp,
-};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 const TOKEN_SUBJECT: &str = "omnect-ui";
-const TOKEN_EXPIRE_HOURS: u64 = 2;
-const TOKEN_TIME_TOLERANCE_SECS: u64 = 15 * 60;
+pub const TOKEN_EXPIRE_HOURS: u64 = 2;
+/// Default tolerance of token expiry checks against a skewed device clock
+pub const DEFAULT_TOKEN_CLOCK_SKEW_SECS: u64 = 15 * 60;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
@@ -31,6 +31,8 @@ pub struct TokenManager {
 
 struct TokenManagerInner {
     key: Vec<u8>,
+    clock_skew: Duration,
+    clock: Arc<dyn Clock>,
 }
 
 impl TokenManager {
@@ -38,10 +40,23 @@ impl TokenManager {
     ///
     /// # Arguments
     /// * `secret` - Secret key for HMAC-SHA256 signing
-    pub fn new(secret: &str) -> Self {
+    /// * `clock_skew` - Time a token is still accepted after its expiry
+    pub fn new(secret: &str, clock_skew: Duration) -> Self {
+        Self::with_clock(secret, clock_skew, Arc::new(SystemClock))
+    }
+
+    /// Create a new TokenManager using the given clock for issuing and expiry checks
+    ///
+    /// # Arguments
+    /// * `secret` - Secret key for HMAC-SHA256 signing
+    /// * `clock_skew` - Time a token is still accepted after its expiry
+    /// * `clock` - Time source
+    pub fn with_clock(secret: &str, clock_skew: Duration, clock: Arc<dyn Clock>) -> Self {
         Self {
             inner: Arc::new(TokenManagerInner {
                 key: secret.as_bytes().to_vec(),
+                clock_skew,
+                clock,
             }),
         }
     }
@@ -50,7 +65,7 @@ impl TokenManager {
     ///
     /// Returns a signed JWT token string
     pub fn create_token(&self) -> Result<String> {
-        let iat = get_current_timestamp();
+        let iat = self.inner.clock.now_since_epoch().as_secs();
         let exp = iat + TOKEN_EXPIRE_HOURS * 3600;
 
         let claims = Claims {
@@ -71,32 +86,39 @@ impl TokenManager {
     ///
     /// Validates:
     /// - Signature
-    /// - Expiration (with configurable time tolerance)
+    /// - Expiration (with configurable clock skew)
     /// - Required subject claim
     ///
     /// Returns true if token is valid, false otherwise
     pub fn verify_token(&self, token: &str) -> bool {
         let mut validation = Validation::new(Algorithm::HS256);
-        validation.leeway = TOKEN_TIME_TOLERANCE_SECS;
         validation.sub = Some(TOKEN_SUBJECT.to_string());
-        validation.validate_exp = true;
+        // expiration is checked against the injected clock below
+        validation.validate_exp = false;
 
-        decode::<Claims>(
+        let Ok(token_data) = decode::<Claims>(
             token,
             &DecodingKey::from_secret(&self.inner.key),
             &validation,
-        )
-        .is_ok()
+        ) else {
+            return false;
+        };
+
+        let now = self.inner.clock.now_since_epoch().as_secs();
+        token_data.claims.exp + self.inner.clock_skew.as_secs() >= now
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::FakeClock;
+
+    const CLOCK_SKEW: Duration = Duration::from_secs(DEFAULT_TOKEN_CLOCK_SKEW_SECS);
 
     #[test]
     fn test_create_and_verify_token() {
-        let manager = TokenManager::new("test-secret-key!");
+        let manager = TokenManager::new("test-secret-key!", CLOCK_SKEW);
         let token = manager.create_token().expect("should create token");
 
         assert!(!token.is_empty());
@@ -105,16 +127,67 @@ mod tests {
 
     #[test]
     fn test_verify_invalid_token() {
-        let manager = TokenManager::new("test-secret-key!");
+        let manager = TokenManager::new("test-secret-key!", CLOCK_SKEW);
 
         assert!(!manager.verify_token("invalid.token.here"));
         assert!(!manager.verify_token(""));
     }
 
+    #[test]
+    fn test_token_valid_until_expiry_plus_tolerance() {
+        let clock = Arc::new(FakeClock::new(1_700_000_000));
+        let manager = TokenManager::with_clock("test-secret-key!", CLOCK_SKEW, clock.clone());
+        let token = manager.create_token().expect("should create token");
+
+        clock.advance(Duration::from_secs(
+            TOKEN_EXPIRE_HOURS * 3600 + DEFAULT_TOKEN_CLOCK_SKEW_SECS,
+        ));
+        assert!(manager.verify_token(&token));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(!manager.verify_token(&token));
+    }
+
+    #[test]
+    fn test_token_valid_within_configured_clock_skew() {
+        let clock = Arc::new(FakeClock::new(1_700_000_000));
+        let manager =
+            TokenManager::with_clock("test-secret-key!", Duration::from_secs(60), clock.clone());
+        let token = manager.create_token().expect("should create token");
+
+        // expired 30s ago, within the skew window
+        clock.advance(Duration::from_secs(TOKEN_EXPIRE_HOURS * 3600 + 30));
+        assert!(manager.verify_token(&token));
+
+        // expired 61s ago, beyond the skew window
+        clock.advance(Duration::from_secs(31));
+        assert!(!manager.verify_token(&token));
+    }
+
+    #[test]
+    fn test_token_issued_at_clock_time() {
+        let clock = Arc::new(FakeClock::new(1_700_000_000));
+        let manager = TokenManager::with_clock("test-secret-key!", CLOCK_SKEW, clock);
+        let token = manager.create_token().expect("should create token");
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_exp = false;
+        let claims = decode::<Claims>(
+            &token,
+            &DecodingKey::from_secret(b"test-secret-key!"),
+            &validation,
+        )
+        .expect("should decode token")
+        .claims;
+
+        assert_eq!(claims.iat, 1_700_000_000);
+        assert_eq!(claims.exp, 1_700_000_000 + TOKEN_EXPIRE_HOURS * 3600);
+    }
+
     #[test]
     fn test_verify_token_wrong_secret() {
-        let manager1 = TokenManager::new("first-secret-key!");
-        let manager2 = TokenManager::new("other-secret-key!");
+        let manager1 = TokenManager::new("first-secret-key!", CLOCK_SKEW);
+        let manager2 = TokenManager::new("other-secret-key!", CLOCK_SKEW);
         let token = manager1.create_token().expect("should create token");
 
         // Token created with secret1 should not verify with secret2
@@ -0,0 +1,526 @@
+//! Centrifugo process settings
+//!
+//! Builds the environment centrifugo is spawned with and allows to change its
+//! log level at runtime. A changed log level takes effect when centrifugo is
+//! respawned by the supervised server restart. After spawning, readiness is
+//! probed by its health endpoint while the spawned process keeps running.
+
+use crate::{
+    config::{CentrifugoConfig, CertificateConfig},
+    http_client::handle_http_response,
+    services::network::NetworkConfigService,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use log::{debug, info};
+use reqwest::Client;
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, SignatureScheme,
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{
+        CryptoProvider, ring::default_provider, verify_tls12_signature, verify_tls13_signature,
+    },
+    pki_types::{CertificateDer, ServerName, UnixTime},
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{sync::Arc, sync::RwLock, time::Duration};
+use tokio::process::Child;
+
+/// Log levels supported by centrifugo
+pub const LOG_LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "none"];
+
+/// Log level set at runtime, overrides the configured one
+static LOG_LEVEL_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Header authenticating a publication, as expected by centrifugo's publish API
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Parameters of the readiness probe after spawning centrifugo
+#[derive(Clone, Copy, Debug)]
+pub struct ReadinessProbe {
+    /// Maximum number of health requests
+    pub attempts: u32,
+    /// Timeout of a single health request
+    pub request_timeout: Duration,
+    /// Delay between two health requests
+    pub interval: Duration,
+}
+
+impl Default for ReadinessProbe {
+    fn default() -> Self {
+        Self {
+            attempts: 20,
+            request_timeout: Duration::from_millis(500),
+            interval: Duration::from_millis(250),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetLogLevelRequest {
+    pub level: String,
+}
+
+pub struct CentrifugoService;
+
+impl CentrifugoService {
+    /// Log level centrifugo is (re)spawned with
+    ///
+    /// # Arguments
+    /// * `config` - Centrifugo configuration providing the default log level
+    pub fn log_level(config: &CentrifugoConfig) -> String {
+        LOG_LEVEL_OVERRIDE
+            .read()
+            .ok()
+            .and_then(|level| level.clone())
+            .unwrap_or_else(|| config.log_level.clone())
+    }
+
+    /// Validate a centrifugo log level
+    ///
+    /// # Errors
+    /// Returns error if `level` is not one of [`LOG_LEVELS`]
+    pub fn validate_log_level(level: &str) -> Result<()> {
+        if !LOG_LEVELS.contains(&level) {
+            bail!(
+                "invalid log level '{level}', expected one of: {}",
+                LOG_LEVELS.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Set the log level and restart centrifugo to apply it
+    ///
+    /// # Errors
+    /// Returns error if the server restart could not be triggered
+    pub fn set_log_level(request: &SetLogLevelRequest) -> Result<()> {
+        Self::validate_log_level(&request.level)?;
+
+        info!("set centrifugo log level to {}", request.level);
+
+        *LOG_LEVEL_OVERRIDE
+            .write()
+            .map_err(|_| anyhow!("failed to lock log level"))? = Some(request.level.clone());
+
+        // centrifugo is respawned as part of the server restart
+        NetworkConfigService::trigger_server_restart()
+    }
+
+    /// Wait until the spawned centrifugo reports to be healthy
+    ///
+    /// Another process holding the port may answer as well, so centrifugo
+    /// also has to be still running: if it failed to bind the port it exits.
+    ///
+    /// # Arguments
+    /// * `centrifugo` - Spawned centrifugo process
+    /// * `client` - Client of the local centrifugo, see [`Self::local_client`]
+    /// * `health_url` - Health endpoint of centrifugo
+    /// * `probe` - Number of attempts, request timeout and interval between attempts
+    ///
+    /// # Errors
+    /// Returns error if centrifugo exited or was not healthy within all attempts
+    pub async fn wait_until_ready(
+        centrifugo: &mut Child,
+        client: &Client,
+        health_url: &str,
+        probe: ReadinessProbe,
+    ) -> Result<()> {
+        let ensure_running = |centrifugo: &mut Child| -> Result<()> {
+            if let Some(status) = centrifugo
+                .try_wait()
+                .context("failed to check centrifugo process")?
+            {
+                bail!("centrifugo exited with {status}, its port is probably in use");
+            }
+            Ok(())
+        };
+
+        for attempt in 1..=probe.attempts {
+            ensure_running(centrifugo)?;
+
+            match client
+                .get(health_url)
+                .timeout(probe.request_timeout)
+                .send()
+                .await
+            {
+                Ok(res) if res.status().is_success() => {
+                    ensure_running(centrifugo)?;
+                    debug!("centrifugo healthy at {health_url} after {attempt} attempt(s)");
+                    return Ok(());
+                }
+                Ok(res) => debug!("centrifugo not ready at {health_url}: {}", res.status()),
+                Err(e) => debug!("centrifugo not ready at {health_url}: {e}"),
+            }
+
+            if attempt < probe.attempts {
+                tokio::time::sleep(probe.interval).await;
+            }
+        }
+
+        bail!(
+            "centrifugo not healthy at {health_url} after {} attempts",
+            probe.attempts
+        )
+    }
+
+    /// Publish to the local centrifugo, e.g. the canned data of the demo mode
+    ///
+    /// Publications go to centrifugo on `localhost` only, so the API key
+    /// never leaves the device.
+    ///
+    /// # Arguments
+    /// * `client` - Client of the local centrifugo, see [`Self::local_client`]
+    /// * `config` - Centrifugo configuration providing port and API key
+    /// * `publication` - Publication as sent to centrifugo's publish API
+    ///
+    /// # Errors
+    /// Returns error if centrifugo is unreachable or rejects the publication
+    pub async fn publish(
+        client: &Client,
+        config: &CentrifugoConfig,
+        publication: &Value,
+    ) -> Result<()> {
+        let res = client
+            .post(format!("https://localhost:{}/api/publish", config.port))
+            .header(API_KEY_HEADER, &config.api_key)
+            .json(publication)
+            .send()
+            .await
+            .context("failed to send publication to centrifugo")?;
+
+        handle_http_response(res, "centrifugo publish").await?;
+        Ok(())
+    }
+
+    /// Client of the local centrifugo's HTTP API
+    ///
+    /// Centrifugo serves the device certificate, which is issued for the device
+    /// address rather than `localhost`. Instead of the host name, the server
+    /// certificate is verified to be exactly the device certificate.
+    ///
+    /// # Arguments
+    /// * `certificate` - Certificate configuration providing the device certificate
+    ///
+    /// # Errors
+    /// Returns error if the device certificate can't be read
+    pub fn local_client(certificate: &CertificateConfig) -> Result<Client> {
+        let mut pem = std::io::BufReader::new(
+            std::fs::File::open(&certificate.cert_path)
+                .context("failed to open certificate file")?,
+        );
+        let device_certificate = rustls_pemfile::certs(&mut pem)
+            .next()
+            .context("no certificate found in pem file")?
+            .context("failed to parse certificate pem")?;
+
+        let provider = Arc::new(default_provider());
+        let tls = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .context("failed to create tls config")?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(PinnedCertificate {
+                certificate: device_certificate,
+                provider,
+            }))
+            .with_no_client_auth();
+
+        Client::builder()
+            .tls_backend_preconfigured(tls)
+            .build()
+            .context("failed to create centrifugo client")
+    }
+
+    /// Environment centrifugo is spawned with
+    ///
+    /// # Arguments
+    /// * `config` - Centrifugo configuration
+    /// * `certificate` - Certificate configuration for the TLS server
+    /// * `log_level` - Log level centrifugo is started with
+    pub fn spawn_envs(
+        config: &CentrifugoConfig,
+        certificate: &CertificateConfig,
+        log_level: String,
+    ) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "CENTRIFUGO_HTTP_SERVER_TLS_CERT_PEM",
+                certificate.cert_path.to_string_lossy().to_string(),
+            ),
+            (
+                "CENTRIFUGO_HTTP_SERVER_TLS_KEY_PEM",
+                certificate.key_path.to_string_lossy().to_string(),
+            ),
+            ("CENTRIFUGO_HTTP_SERVER_PORT", config.port.clone()),
+            (
+                "CENTRIFUGO_CLIENT_TOKEN_HMAC_SECRET_KEY",
+                config.client_token.clone(),
+            ),
+            ("CENTRIFUGO_HTTP_API_KEY", config.api_key.clone()),
+            ("CENTRIFUGO_LOG_LEVEL", log_level),
+        ]
+    }
+}
+
+/// Accepts exactly the pinned certificate, regardless of the host name
+#[derive(Debug)]
+struct PinnedCertificate {
+    certificate: CertificateDer<'static>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertificate {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if end_entity.as_ref() != self.certificate.as_ref() {
+            return Err(rustls::Error::InvalidCertificate(
+                CertificateError::UnknownIssuer,
+            ));
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    mod spawn_envs {
+        use super::*;
+
+        fn env<'a>(envs: &'a [(&'static str, String)], key: &str) -> Option<&'a str> {
+            envs.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.as_str())
+        }
+
+        #[test]
+        fn includes_configured_log_level() {
+            let config = AppConfig::get();
+
+            let envs = CentrifugoService::spawn_envs(
+                &config.centrifugo,
+                &config.certificate,
+                CentrifugoService::log_level(&config.centrifugo),
+            );
+
+            assert_eq!(
+                env(&envs, "CENTRIFUGO_LOG_LEVEL"),
+                Some(config.centrifugo.log_level.as_str())
+            );
+        }
+
+        #[test]
+        fn includes_given_log_level() {
+            let config = AppConfig::get();
+
+            let envs = CentrifugoService::spawn_envs(
+                &config.centrifugo,
+                &config.certificate,
+                "debug".to_string(),
+            );
+
+            assert_eq!(env(&envs, "CENTRIFUGO_LOG_LEVEL"), Some("debug"));
+            assert_eq!(
+                env(&envs, "CENTRIFUGO_HTTP_API_KEY"),
+                Some(config.centrifugo.api_key.as_str())
+            );
+        }
+    }
+
+    mod pinned_certificate {
+        use super::*;
+        use crate::services::certificate::CertificateService;
+
+        fn certificate(common_name: &str) -> CertificateDer<'static> {
+            let (pem, _) = CertificateService::self_signed_certificate(common_name).unwrap();
+            rustls_pemfile::certs(&mut pem.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap()
+        }
+
+        fn verify(pinned: &CertificateDer<'static>, presented: &CertificateDer<'_>) -> bool {
+            PinnedCertificate {
+                certificate: pinned.clone(),
+                provider: Arc::new(default_provider()),
+            }
+            .verify_server_cert(
+                presented,
+                &[],
+                &ServerName::try_from("localhost").unwrap(),
+                &[],
+                UnixTime::now(),
+            )
+            .is_ok()
+        }
+
+        #[test]
+        fn device_certificate_is_accepted_for_localhost() {
+            let device = certificate("192.168.0.100");
+
+            assert!(verify(&device, &device));
+        }
+
+        #[test]
+        fn other_certificate_is_rejected() {
+            let device = certificate("192.168.0.100");
+            let other = certificate("192.168.0.100");
+
+            assert!(!verify(&device, &other));
+        }
+    }
+
+    mod log_level_validation {
+        use super::*;
+
+        #[test]
+        fn accepts_centrifugo_levels() {
+            for level in LOG_LEVELS {
+                assert!(CentrifugoService::validate_log_level(level).is_ok());
+            }
+        }
+
+        #[test]
+        fn rejects_unknown_level() {
+            assert!(CentrifugoService::validate_log_level("verbose").is_err());
+            assert!(CentrifugoService::validate_log_level("DEBUG").is_err());
+            assert!(CentrifugoService::validate_log_level("").is_err());
+        }
+    }
+
+    mod readiness_probe {
+        use super::*;
+        use tokio::{io::AsyncWriteExt, net::TcpListener, process::Command};
+
+        fn probe() -> ReadinessProbe {
+            ReadinessProbe {
+                attempts: 3,
+                request_timeout: Duration::from_millis(100),
+                interval: Duration::from_millis(10),
+            }
+        }
+
+        /// Server answering every request with 200, like a healthy centrifugo
+        async fn healthy_server() -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let _ = stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                        .await;
+                }
+            });
+
+            format!("http://127.0.0.1:{port}/health")
+        }
+
+        fn spawn(command: &str) -> Child {
+            Command::new("sh")
+                .args(["-c", command])
+                .kill_on_drop(true)
+                .spawn()
+                .unwrap()
+        }
+
+        #[tokio::test]
+        async fn succeeds_if_healthy_and_running() {
+            let health_url = healthy_server().await;
+            let mut centrifugo = spawn("sleep 5");
+
+            assert!(
+                CentrifugoService::wait_until_ready(
+                    &mut centrifugo,
+                    &Client::new(),
+                    &health_url,
+                    probe()
+                )
+                .await
+                .is_ok()
+            );
+        }
+
+        #[tokio::test]
+        async fn fails_if_port_is_held_by_another_process() {
+            let health_url = healthy_server().await;
+            // centrifugo exits if it can't bind the port
+            let mut centrifugo = spawn("exit 1");
+            centrifugo.wait().await.unwrap();
+
+            let err = CentrifugoService::wait_until_ready(
+                &mut centrifugo,
+                &Client::new(),
+                &health_url,
+                probe(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(err.to_string().contains("centrifugo exited"));
+        }
+
+        #[tokio::test]
+        async fn fails_on_closed_port() {
+            let port = {
+                let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+                listener.local_addr().unwrap().port()
+            };
+            let mut centrifugo = spawn("sleep 5");
+
+            let err = CentrifugoService::wait_until_ready(
+                &mut centrifugo,
+                &Client::new(),
+                &format!("http://127.0.0.1:{port}/health"),
+                probe(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(err.to_string().contains("after 3 attempts"));
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Factory reset bookkeeping
+//!
+//! Persists the last factory reset request (mode and preserved keys), so the
+//! UI can show what was preserved after the device comes back from the reset.
+
+use crate::config::AppConfig;
+use anyhow::{Context, Result};
+use log::{error, info};
+use omnect_ui_core::types::FactoryResetRequest;
+use std::fs;
+
+pub struct FactoryResetService;
+
+impl FactoryResetService {
+    /// Persist a factory reset request accepted by the device service
+    ///
+    /// # Errors
+    /// Returns error if the request could not be written
+    pub fn record(request: &FactoryResetRequest) -> Result<()> {
+        let path = &AppConfig::get().paths.last_factory_reset_file;
+
+        info!(
+            "recording factory reset with mode {} preserving {:?}",
+            request.mode, request.preserve
+        );
+
+        let json = serde_json::to_string(&request).context("failed to serialize factory reset")?;
+        fs::write(path, json).context("failed to write last factory reset file")
+    }
+
+    /// Persist a factory reset request, logging errors instead of propagating them
+    pub fn record_or_log(request: &FactoryResetRequest) {
+        if let Err(e) = Self::record(request) {
+            error!("failed to record factory reset: {e:#}");
+        }
+    }
+
+    /// Last factory reset request, None if there was none or it is unreadable
+    pub fn last_factory_reset() -> Option<FactoryResetRequest> {
+        let json = fs::read_to_string(&AppConfig::get().paths.last_factory_reset_file).ok()?;
+
+        serde_json::from_str(&json)
+            .inspect_err(|e| error!("failed to parse last factory reset file: {e:#}"))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::omnect_device_service_client::FactoryReset;
+
+    #[test]
+    fn records_mode_and_preserve_keys() {
+        let factory_reset: FactoryReset =
+            serde_json::from_str(r#"{"mode": 2, "preserve": ["network", "certificates"]}"#)
+                .expect("should deserialize");
+
+        FactoryResetService::record(&FactoryResetRequest::from(&factory_reset)).unwrap();
+
+        assert_eq!(
+            FactoryResetService::last_factory_reset(),
+            Some(FactoryResetRequest {
+                mode: 2,
+                preserve: vec!["network".to_string(), "certificates".to_string()],
+            })
+        );
+    }
+}
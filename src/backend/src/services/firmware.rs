@@ -6,23 +6,353 @@
 #![allow(clippy::await_holding_lock)]
 
 use crate::{config::AppConfig, omnect_device_service_client::DeviceServiceClient};
-use actix_multipart::Field;
+use actix_multipart::MultipartError;
+use actix_web::{error::PayloadError, web::Bytes};
 use anyhow::{Context, Result};
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
 use log::{debug, error, info};
+use omnect_ui_core::types::UpdateManifest;
+use serde::Serialize;
 use std::{
-    os::unix::fs::OpenOptionsExt, // Required for .mode() on OpenOptions
+    os::unix::fs::{OpenOptionsExt, PermissionsExt}, // Required for .mode() on OpenOptions
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Instant,
 };
 use tokio::{fs, io::AsyncWriteExt};
+use uuid::Uuid;
 
 #[cfg(any(test, feature = "mock"))]
-use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::sync::{LazyLock, MutexGuard};
 
 #[cfg(any(test, feature = "mock"))]
 #[allow(dead_code)]
 static DATA_FOLDER_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 
+/// Error of an upload aborted by the client, e.g. a closed browser tab
+#[derive(Debug)]
+pub struct UploadCancelled;
+
+impl std::fmt::Display for UploadCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "upload cancelled by client")
+    }
+}
+
+impl std::error::Error for UploadCancelled {}
+
+impl UploadCancelled {
+    /// Whether a multipart error is caused by the client aborting the payload
+    pub fn is_cause_of(err: &MultipartError) -> bool {
+        matches!(
+            err,
+            MultipartError::Incomplete | MultipartError::Payload(PayloadError::Incomplete(_))
+        )
+    }
+}
+
+/// Error of an upload not fitting into the free space of the data folder
+#[derive(Debug)]
+pub struct InsufficientStorage {
+    /// Bytes needed for the upload including the configured margin
+    pub required: u64,
+    /// Bytes free after clearing the data folder
+    pub available: u64,
+}
+
+impl std::fmt::Display for InsufficientStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "insufficient storage: {} bytes required, {} bytes available",
+            self.required, self.available
+        )
+    }
+}
+
+impl std::error::Error for InsufficientStorage {}
+
+impl InsufficientStorage {
+    /// Check an upload of `size` bytes against the free space
+    ///
+    /// # Arguments
+    /// * `size` - Declared size of the upload
+    /// * `margin` - Free space that has to remain after the upload
+    /// * `available` - Bytes free after clearing the data folder
+    pub fn check(size: u64, margin: u64, available: u64) -> Result<()> {
+        let required = size.saturating_add(margin);
+
+        if required > available {
+            return Err(Self {
+                required,
+                available,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Lock allowing only one firmware upload at a time
+///
+/// All uploads write the same update file after clearing the data folder, so
+/// concurrent uploads (e.g. from two browser tabs) would corrupt each other.
+#[derive(Clone, Default)]
+pub struct UploadLock {
+    active: Arc<AtomicBool>,
+}
+
+/// Held while an upload is in progress, releases the [`UploadLock`] on drop
+pub struct UploadLockGuard {
+    active: Arc<AtomicBool>,
+}
+
+impl UploadLock {
+    /// Acquire the lock, None if another upload is in progress
+    pub fn try_acquire(&self) -> Option<UploadLockGuard> {
+        self.active
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| UploadLockGuard {
+                active: self.active.clone(),
+            })
+    }
+}
+
+impl Drop for UploadLockGuard {
+    fn drop(&mut self) {
+        self.active.store(false, Ordering::Release);
+    }
+}
+
+/// Byte range of an uploaded chunk, parsed from a `Content-Range` header
+///
+/// E.g. `bytes 0-1023/4096` for the first KiB of a 4 KiB file, `end` is inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentRange {
+    pub start: u64,
+    pub end: u64,
+    pub total: u64,
+}
+
+impl ContentRange {
+    /// Number of bytes in the range
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Whether the range ends with the last byte of the file
+    pub fn is_last(&self) -> bool {
+        self.end + 1 == self.total
+    }
+}
+
+impl FromStr for ContentRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = || -> Option<Self> {
+            let (range, total) = s.trim().strip_prefix("bytes ")?.split_once('/')?;
+            let (start, end) = range.split_once('-')?;
+
+            Some(Self {
+                start: start.parse().ok()?,
+                end: end.parse().ok()?,
+                total: total.parse().ok()?,
+            })
+        };
+
+        let Some(range) = parse() else {
+            anyhow::bail!("invalid content range '{s}', expected 'bytes <start>-<end>/<total>'");
+        };
+        anyhow::ensure!(
+            range.start <= range.end && range.end < range.total,
+            "invalid content range '{s}': range exceeds total size"
+        );
+
+        Ok(range)
+    }
+}
+
+/// Error of a chunk not continuing the upload, e.g. sent twice or after a gap
+#[derive(Debug)]
+pub struct ChunkOutOfOrder {
+    /// Progress of the upload the chunk was rejected for, None if there is none
+    pub status: Option<ChunkUploadStatus>,
+}
+
+impl std::fmt::Display for ChunkOutOfOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.status {
+            Some(status) => write!(f, "chunk out of order: expected offset {}", status.received),
+            None => write!(f, "chunk out of order: no upload in progress"),
+        }
+    }
+}
+
+impl std::error::Error for ChunkOutOfOrder {}
+
+/// Progress of a chunked firmware upload
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkUploadStatus {
+    /// Id of the upload session, to be sent with all further chunks
+    pub upload_id: String,
+    /// Number of bytes received so far, i.e. the offset of the next chunk
+    pub received: u64,
+    pub total: u64,
+    /// Set once the last chunk was written to the update file
+    pub complete: bool,
+}
+
+/// Upload session of a firmware uploaded in chunks
+///
+/// A chunk starting at offset 0 begins a new session, all further chunks have
+/// to continue exactly where the previous one ended. A client resumes an
+/// interrupted upload by sending the next chunk from `received` on.
+#[derive(Clone, Default)]
+pub struct ChunkedUpload {
+    session: Arc<Mutex<Option<ChunkUploadStatus>>>,
+}
+
+impl ChunkedUpload {
+    /// Append a chunk to the partial file, renamed to `target` after the last chunk
+    ///
+    /// # Arguments
+    /// * `upload_id` - Id of the session, ignored for the first chunk
+    /// * `range` - Range of the chunk within the file
+    /// * `data` - Content of the chunk
+    /// * `target` - Update file, the partial file is written next to it
+    /// * `mode` - Permissions of the update file
+    ///
+    /// # Returns
+    /// Progress of the upload, `ChunkOutOfOrder` if the chunk doesn't continue it
+    pub async fn append(
+        &self,
+        upload_id: Option<&str>,
+        range: ContentRange,
+        data: &[u8],
+        target: &Path,
+        mode: u32,
+    ) -> Result<ChunkUploadStatus> {
+        anyhow::ensure!(
+            data.len() as u64 == range.size(),
+            "chunk size {} doesn't match content range of {} bytes",
+            data.len(),
+            range.size()
+        );
+
+        let partial_file = Self::partial_file(target);
+        let status = if range.start == 0 {
+            ChunkUploadStatus {
+                upload_id: Uuid::new_v4().to_string(),
+                received: 0,
+                total: range.total,
+                complete: false,
+            }
+        } else {
+            let current = self.status();
+            match current {
+                Some(status)
+                    if !status.complete
+                        && upload_id == Some(status.upload_id.as_str())
+                        && status.total == range.total
+                        && status.received == range.start =>
+                {
+                    status
+                }
+                _ => return Err(ChunkOutOfOrder { status: current }.into()),
+            }
+        };
+
+        // the partial file may have been removed in between, e.g. by another upload
+        let written = match fs::metadata(&partial_file).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+        if range.start != 0 && written != status.received {
+            self.reset();
+            return Err(ChunkOutOfOrder { status: None }.into());
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(range.start != 0)
+            .truncate(range.start == 0)
+            .mode(mode)
+            .open(&partial_file)
+            .await
+            .context("failed to open partial update file")?;
+        file.write_all(data)
+            .await
+            .context("failed to write chunk to partial update file")?;
+        file.flush()
+            .await
+            .context("failed to flush partial update file")?;
+
+        let mut status = ChunkUploadStatus {
+            received: status.received + range.size(),
+            ..status
+        };
+
+        if range.is_last() {
+            file.set_permissions(std::fs::Permissions::from_mode(mode))
+                .await
+                .context("failed to set update file permissions")?;
+            fs::rename(&partial_file, target)
+                .await
+                .context("failed to finalize update file")?;
+            status.complete = true;
+            info!("chunked firmware upload completed: {} bytes", status.total);
+        }
+
+        self.set_status(status.clone());
+
+        Ok(status)
+    }
+
+    /// Progress of the current upload session, None if there is none
+    pub fn status(&self) -> Option<ChunkUploadStatus> {
+        match self.session.lock() {
+            Ok(session) => session.clone(),
+            Err(e) => {
+                error!("failed to lock chunked upload: {e}");
+                None
+            }
+        }
+    }
+
+    fn set_status(&self, status: ChunkUploadStatus) {
+        match self.session.lock() {
+            Ok(mut session) => *session = Some(status),
+            Err(e) => error!("failed to lock chunked upload: {e}"),
+        }
+    }
+
+    fn reset(&self) {
+        match self.session.lock() {
+            Ok(mut session) => *session = None,
+            Err(e) => error!("failed to lock chunked upload: {e}"),
+        }
+    }
+
+    /// File the chunks are appended to until the upload is complete
+    fn partial_file(target: &Path) -> PathBuf {
+        let mut name = target.file_name().unwrap_or_default().to_os_string();
+        name.push(".part");
+        target.with_file_name(name)
+    }
+}
+
+/// Result of loading an update, i.e. the manifest of the uploaded update
+pub type LoadUpdateResult = UpdateManifest;
+
 /// Service for firmware update file operations
 pub struct FirmwareService;
 
@@ -38,12 +368,104 @@ impl FirmwareService {
 
     /// Handle uploaded firmware file via streaming - clears data folder and writes stream to file
     ///
+    /// A partially written file is removed if the upload fails.
+    ///
     /// # Arguments
     /// * `field` - The multipart field containing the file stream
     ///
     /// # Returns
-    /// Result indicating success or failure
-    pub async fn receive_firmware(mut field: Field) -> Result<()> {
+    /// Result indicating success or failure, `UploadCancelled` if the client aborted the upload
+    pub async fn receive_firmware<S>(field: S) -> Result<()>
+    where
+        S: Stream<Item = Result<Bytes, MultipartError>> + Unpin,
+    {
+        // Clear data folder before writing new firmware
+        if let Err(e) = Self::clear_data_folder().await {
+            error!("failed to clear data folder: {e:#}");
+        }
+
+        let local_update_file = &AppConfig::get().paths.local_update_file;
+        let result =
+            Self::write_firmware(field, local_update_file, AppConfig::get().update_file_mode).await;
+
+        if result.is_err()
+            && let Err(e) = fs::remove_file(local_update_file).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            error!("failed to remove partial update file: {e:#}");
+        }
+
+        result
+    }
+
+    /// Check that an upload of the declared size fits into the data folder
+    ///
+    /// Files in the data folder count as free, as they are removed before the
+    /// upload is written.
+    ///
+    /// # Arguments
+    /// * `size` - Declared size of the upload, e.g. its `Content-Length`
+    ///
+    /// # Returns
+    /// `InsufficientStorage` if less than the size plus the configured margin is free
+    pub async fn ensure_free_space(size: u64) -> Result<()> {
+        let data_dir = &AppConfig::get().paths.data_dir;
+
+        let stat = rustix::fs::statvfs(data_dir).context("failed to get free space")?;
+        let free = stat.f_bavail.saturating_mul(stat.f_frsize);
+        let reclaimable = Self::data_folder_size().await.unwrap_or_else(|e| {
+            error!("failed to get size of data folder: {e:#}");
+            0
+        });
+
+        InsufficientStorage::check(
+            size,
+            AppConfig::get().upload_space_margin,
+            free.saturating_add(reclaimable),
+        )
+    }
+
+    /// Handle a chunk of a firmware uploaded in chunks
+    ///
+    /// The data folder is cleared when the first chunk arrives. The update file
+    /// is only written once the upload is complete.
+    ///
+    /// # Arguments
+    /// * `uploads` - Session of the chunked upload
+    /// * `upload_id` - Id of the session, None for the first chunk
+    /// * `range` - Range of the chunk within the file
+    /// * `data` - Content of the chunk
+    ///
+    /// # Returns
+    /// Progress of the upload, `ChunkOutOfOrder` if the chunk doesn't continue it
+    pub async fn receive_firmware_chunk(
+        uploads: &ChunkedUpload,
+        upload_id: Option<&str>,
+        range: ContentRange,
+        data: &[u8],
+    ) -> Result<ChunkUploadStatus> {
+        if range.start == 0
+            && let Err(e) = Self::clear_data_folder().await
+        {
+            error!("failed to clear data folder: {e:#}");
+        }
+
+        uploads
+            .append(
+                upload_id,
+                range,
+                data,
+                &AppConfig::get().paths.local_update_file,
+                AppConfig::get().update_file_mode,
+            )
+            .await
+    }
+
+    /// Stream the firmware into the update file, readable according to `mode`
+    async fn write_firmware<S>(mut field: S, local_update_file: &Path, mode: u32) -> Result<()>
+    where
+        S: Stream<Item = Result<Bytes, MultipartError>> + Unpin,
+    {
         const WRITE_BUFFER_SIZE: usize = 512 * 1024;
         const FLUSH_INTERVAL_BYTES: usize = 5 * 1024 * 1024;
         const FLUSH_INTERVAL_SECS: u64 = 10;
@@ -56,22 +478,19 @@ impl FirmwareService {
         let mut total_bytes = 0;
         let mut bytes_since_flush = 0;
 
-        // Clear data folder before writing new firmware
-        if let Err(e) = Self::clear_data_folder().await {
-            error!("failed to clear data folder: {e:#}");
-        }
-
-        let local_update_file = &AppConfig::get().paths.local_update_file;
-
         // 1. Create the destination file with permissions set atomically
         let file = fs::OpenOptions::new()
             .create(true)
             .write(true)
             .truncate(true)
-            .mode(0o750)
+            .mode(mode)
             .open(local_update_file)
             .await
             .context("failed to create update file")?;
+        // the mode above is reduced by the umask and ignored for an existing file
+        file.set_permissions(std::fs::Permissions::from_mode(mode))
+            .await
+            .context("failed to set update file permissions")?;
         let mut file = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_SIZE, file);
 
         // 2. Stream chunks to the file with timeout protection
@@ -100,9 +519,17 @@ impl FirmwareService {
                 break;
             };
 
-            let data = chunk
-                .map_err(|e| anyhow::anyhow!(e.to_string()))
-                .context("failed to read chunk from stream")?;
+            let data = match chunk {
+                Ok(data) => data,
+                Err(e) if UploadCancelled::is_cause_of(&e) => {
+                    info!("firmware upload cancelled after {total_bytes} bytes");
+                    return Err(UploadCancelled.into());
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!(e.to_string()))
+                        .context("failed to read chunk from stream");
+                }
+            };
 
             let chunk_len = data.len();
             total_bytes += chunk_len;
@@ -142,15 +569,25 @@ impl FirmwareService {
     /// * `service_client` - Device service client for loading the update
     ///
     /// # Returns
-    /// Result with the response data from the device service
-    pub async fn load_update<SC: DeviceServiceClient>(service_client: &SC) -> Result<String> {
+    /// Result with the manifest of the loaded update, an error if the device
+    /// service response is not a valid manifest
+    pub async fn load_update<SC: DeviceServiceClient>(
+        service_client: &SC,
+    ) -> Result<LoadUpdateResult> {
         use crate::omnect_device_service_client::LoadUpdate;
 
-        service_client
+        let body = service_client
             .load_update(LoadUpdate {
                 update_file_path: AppConfig::get().paths.host_update_file.clone(),
             })
-            .await
+            .await?;
+
+        Self::parse_load_update(&body)
+    }
+
+    /// Parse the device service response of loading an update
+    fn parse_load_update(body: &str) -> Result<LoadUpdateResult> {
+        serde_json::from_str(body).context("failed to parse load update response")
     }
 
     /// Run the firmware update via the device service client
@@ -168,6 +605,21 @@ impl FirmwareService {
         service_client.run_update(run_update).await
     }
 
+    /// Total size of the files in the data folder
+    async fn data_folder_size() -> Result<u64> {
+        let mut entries = fs::read_dir(&AppConfig::get().paths.data_dir).await?;
+        let mut size = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() {
+                size += metadata.len();
+            }
+        }
+
+        Ok(size)
+    }
+
     /// Clear all files in the data folder
     async fn clear_data_folder() -> Result<()> {
         debug!("clear_data_folder() called");
@@ -195,8 +647,316 @@ mod tests {
     #[double]
     use crate::omnect_device_service_client::DeviceServiceClient;
 
-    // Note: Streaming tests would require mocking actix_multipart::Field which is complex.
-    // Focusing on file system operations for now.
+    mod receive_firmware {
+        use super::*;
+        use futures_util::stream;
+
+        fn chunk(data: &'static [u8]) -> Result<Bytes, MultipartError> {
+            Ok(Bytes::from_static(data))
+        }
+
+        #[tokio::test]
+        async fn writes_complete_stream_to_update_file() {
+            let _lock = FirmwareService::lock_for_test();
+            fs::create_dir_all(&AppConfig::get().paths.data_dir)
+                .await
+                .expect("should create data dir");
+
+            let field = stream::iter(vec![chunk(b"firm"), chunk(b"ware")]);
+            FirmwareService::receive_firmware(field)
+                .await
+                .expect("upload should succeed");
+
+            let content = fs::read(&AppConfig::get().paths.local_update_file)
+                .await
+                .expect("should read update file");
+            assert_eq!(content, b"firmware");
+        }
+
+        #[tokio::test]
+        async fn update_file_gets_configured_mode() {
+            let dir = tempfile::TempDir::new().expect("should create temp dir");
+            let update_file = dir.path().join("update.tar");
+            // an existing file keeps its mode on open, so it has to be set explicitly
+            std::fs::write(&update_file, b"old").expect("should write old file");
+
+            let field = stream::iter(vec![chunk(b"firmware")]);
+            FirmwareService::write_firmware(field, &update_file, 0o644)
+                .await
+                .expect("upload should succeed");
+
+            let mode = std::fs::metadata(&update_file)
+                .expect("should read metadata")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o7777, 0o644);
+        }
+
+        #[tokio::test]
+        async fn update_file_gets_default_mode() {
+            let _lock = FirmwareService::lock_for_test();
+            fs::create_dir_all(&AppConfig::get().paths.data_dir)
+                .await
+                .expect("should create data dir");
+
+            let field = stream::iter(vec![chunk(b"firmware")]);
+            FirmwareService::receive_firmware(field)
+                .await
+                .expect("upload should succeed");
+
+            let mode = std::fs::metadata(&AppConfig::get().paths.local_update_file)
+                .expect("should read metadata")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o7777, 0o750);
+        }
+
+        #[tokio::test]
+        async fn interrupted_stream_is_cancelled_and_cleaned_up() {
+            let _lock = FirmwareService::lock_for_test();
+            fs::create_dir_all(&AppConfig::get().paths.data_dir)
+                .await
+                .expect("should create data dir");
+
+            let field = stream::iter(vec![
+                chunk(b"partial"),
+                Err(MultipartError::Payload(PayloadError::Incomplete(None))),
+            ]);
+            let err = FirmwareService::receive_firmware(field)
+                .await
+                .expect_err("upload should fail");
+
+            assert!(err.is::<UploadCancelled>());
+            assert!(!AppConfig::get().paths.local_update_file.exists());
+        }
+
+        #[tokio::test]
+        async fn other_stream_errors_are_not_cancellations() {
+            let _lock = FirmwareService::lock_for_test();
+            fs::create_dir_all(&AppConfig::get().paths.data_dir)
+                .await
+                .expect("should create data dir");
+
+            let field = stream::iter(vec![chunk(b"partial"), Err(MultipartError::Nested)]);
+            let err = FirmwareService::receive_firmware(field)
+                .await
+                .expect_err("upload should fail");
+
+            assert!(!err.is::<UploadCancelled>());
+            assert!(!AppConfig::get().paths.local_update_file.exists());
+        }
+    }
+
+    mod insufficient_storage {
+        use super::*;
+
+        #[test]
+        fn accepts_upload_leaving_margin() {
+            assert!(InsufficientStorage::check(900, 100, 1000).is_ok());
+        }
+
+        #[test]
+        fn rejects_upload_eating_into_margin() {
+            let err = InsufficientStorage::check(901, 100, 1000).unwrap_err();
+
+            let err = err.downcast_ref::<InsufficientStorage>().unwrap();
+            assert_eq!(err.required, 1001);
+            assert_eq!(err.available, 1000);
+        }
+
+        #[test]
+        fn huge_declared_size_does_not_overflow() {
+            assert!(InsufficientStorage::check(u64::MAX, 100, 1000).is_err());
+        }
+
+        #[tokio::test]
+        async fn existing_update_file_counts_as_free() {
+            let _lock = FirmwareService::lock_for_test();
+            fs::create_dir_all(&AppConfig::get().paths.data_dir)
+                .await
+                .expect("should create data dir");
+            fs::write(&AppConfig::get().paths.local_update_file, b"firmware")
+                .await
+                .expect("should write update file");
+
+            let size = FirmwareService::data_folder_size()
+                .await
+                .expect("should get size");
+
+            assert!(size >= 8);
+        }
+    }
+
+    mod content_range {
+        use super::*;
+
+        #[test]
+        fn parses_valid_range() {
+            let range: ContentRange = "bytes 0-1023/4096".parse().unwrap();
+
+            assert_eq!(
+                range,
+                ContentRange {
+                    start: 0,
+                    end: 1023,
+                    total: 4096
+                }
+            );
+            assert_eq!(range.size(), 1024);
+            assert!(!range.is_last());
+            assert!(
+                "bytes 3072-4095/4096"
+                    .parse::<ContentRange>()
+                    .unwrap()
+                    .is_last()
+            );
+        }
+
+        #[test]
+        fn rejects_invalid_range() {
+            for value in [
+                "0-1023/4096",
+                "bytes 0-1023/*",
+                "bytes */4096",
+                "bytes 1024-1023/4096",
+                "bytes 0-4096/4096",
+            ] {
+                assert!(value.parse::<ContentRange>().is_err(), "{value}");
+            }
+        }
+    }
+
+    mod chunked_upload {
+        use super::*;
+
+        fn range(start: u64, end: u64, total: u64) -> ContentRange {
+            ContentRange { start, end, total }
+        }
+
+        #[tokio::test]
+        async fn resumes_after_rejected_chunk() {
+            let dir = tempfile::TempDir::new().expect("should create temp dir");
+            let target = dir.path().join("update.tar");
+            let uploads = ChunkedUpload::default();
+
+            let status = uploads
+                .append(None, range(0, 3, 8), b"firm", &target, 0o640)
+                .await
+                .expect("first chunk should be accepted");
+            // an overlapping chunk, e.g. resent after a lost response, reports the progress
+            let err = uploads
+                .append(
+                    Some(&status.upload_id),
+                    range(2, 3, 8),
+                    b"rm",
+                    &target,
+                    0o640,
+                )
+                .await
+                .expect_err("chunk should be rejected");
+            let out_of_order = err.downcast_ref::<ChunkOutOfOrder>().unwrap();
+            assert_eq!(out_of_order.status.as_ref().unwrap().received, 4);
+
+            let status = uploads
+                .append(
+                    Some(&status.upload_id),
+                    range(4, 7, 8),
+                    b"ware",
+                    &target,
+                    0o640,
+                )
+                .await
+                .expect("next chunk should be accepted");
+
+            assert!(status.complete);
+            assert_eq!(std::fs::read(&target).unwrap(), b"firmware");
+            assert!(!ChunkedUpload::partial_file(&target).exists());
+            let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+            assert_eq!(mode & 0o7777, 0o640);
+        }
+
+        #[tokio::test]
+        async fn rejects_chunk_of_unknown_upload() {
+            let dir = tempfile::TempDir::new().expect("should create temp dir");
+            let target = dir.path().join("update.tar");
+            let uploads = ChunkedUpload::default();
+
+            uploads
+                .append(None, range(0, 3, 8), b"firm", &target, 0o640)
+                .await
+                .expect("first chunk should be accepted");
+            let err = uploads
+                .append(Some("unknown"), range(4, 7, 8), b"ware", &target, 0o640)
+                .await
+                .expect_err("chunk should be rejected");
+
+            assert!(err.is::<ChunkOutOfOrder>());
+            assert!(!target.exists());
+        }
+
+        #[tokio::test]
+        async fn rejects_chunk_not_matching_its_range() {
+            let dir = tempfile::TempDir::new().expect("should create temp dir");
+            let target = dir.path().join("update.tar");
+
+            let err = ChunkedUpload::default()
+                .append(None, range(0, 3, 8), b"firmware", &target, 0o640)
+                .await
+                .expect_err("chunk should be rejected");
+
+            assert!(!err.is::<ChunkOutOfOrder>());
+        }
+
+        #[tokio::test]
+        async fn removed_partial_file_ends_upload() {
+            let dir = tempfile::TempDir::new().expect("should create temp dir");
+            let target = dir.path().join("update.tar");
+            let uploads = ChunkedUpload::default();
+
+            let status = uploads
+                .append(None, range(0, 3, 8), b"firm", &target, 0o640)
+                .await
+                .expect("first chunk should be accepted");
+            std::fs::remove_file(ChunkedUpload::partial_file(&target)).unwrap();
+
+            let err = uploads
+                .append(
+                    Some(&status.upload_id),
+                    range(4, 7, 8),
+                    b"ware",
+                    &target,
+                    0o640,
+                )
+                .await
+                .expect_err("chunk should be rejected");
+
+            assert!(err.is::<ChunkOutOfOrder>());
+            assert!(uploads.status().is_none());
+        }
+    }
+
+    mod upload_lock {
+        use super::*;
+
+        #[test]
+        fn second_acquire_fails_while_held() {
+            let lock = UploadLock::default();
+
+            let guard = lock.try_acquire();
+
+            assert!(guard.is_some());
+            assert!(lock.clone().try_acquire().is_none());
+        }
+
+        #[test]
+        fn dropping_guard_releases_lock() {
+            let lock = UploadLock::default();
+
+            drop(lock.try_acquire());
+
+            assert!(lock.try_acquire().is_some());
+        }
+    }
 
     mod clear_data_folder {
         use super::*;
@@ -294,6 +1054,37 @@ mod tests {
         use super::*;
         use crate::omnect_device_service_client::LoadUpdate;
 
+        const MANIFEST: &str = r#"{
+            "updateId": { "provider": "omnect", "name": "gateway", "version": "4.0.24" },
+            "isDeployable": true,
+            "compatibility": [
+                { "manufacturer": "conplement-ag", "model": "omnect-raspberrypi4-64-gateway-devel", "compatibilityid": "2" }
+            ],
+            "createdDateTime": "2024-01-19T12:00:00Z",
+            "manifestVersion": "5"
+        }"#;
+
+        #[test]
+        fn parses_valid_response() {
+            let result = FirmwareService::parse_load_update(MANIFEST).unwrap();
+
+            assert_eq!(result.update_id.version, "4.0.24");
+            assert!(result.is_deployable);
+            assert_eq!(result.compatibility[0].compatibilityid, "2");
+            assert_eq!(result.manifest_version, "5");
+        }
+
+        #[test]
+        fn rejects_malformed_response() {
+            let err = FirmwareService::parse_load_update("update loaded successfully").unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("failed to parse load update response")
+            );
+
+            assert!(FirmwareService::parse_load_update(r#"{"updateId": {}}"#).is_err());
+        }
+
         #[tokio::test]
         async fn forwards_request_to_device_service() {
             let mut device_mock = DeviceServiceClient::default();
@@ -304,12 +1095,25 @@ mod tests {
                     req.update_file_path == AppConfig::get().paths.host_update_file
                 })
                 .times(1)
-                .returning(|_| Box::pin(async { Ok("update loaded successfully".to_string()) }));
+                .returning(|_| Box::pin(async { Ok(MANIFEST.to_string()) }));
 
             let result = FirmwareService::load_update(&device_mock).await;
 
             assert!(result.is_ok());
-            assert_eq!(result.unwrap(), "update loaded successfully");
+            assert_eq!(result.unwrap().update_id.name, "gateway");
+        }
+
+        #[tokio::test]
+        async fn returns_error_on_malformed_response() {
+            let mut device_mock = DeviceServiceClient::default();
+
+            device_mock
+                .expect_load_update()
+                .returning(|_| Box::pin(async { Ok("not a manifest".to_string()) }));
+
+            let result = FirmwareService::load_update(&device_mock).await;
+
+            assert!(result.is_err());
         }
 
         #[tokio::test]
@@ -0,0 +1,45 @@
+//! Tenant and fleet assignment of the device
+//!
+//! Shown in the UI so operators can confirm they manage the intended device.
+
+use crate::{config::AppConfig, omnect_device_service_client::DeviceServiceClient};
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// Tenant and fleet the device is assigned to
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceIdentity {
+    /// Tenant configured for this device, the first one if several are allowed
+    pub tenant: String,
+    /// Fleet reported by the device service as `fleetId`, None if the device is not assigned to a fleet
+    pub fleet_id: Option<String>,
+}
+
+pub struct IdentityService;
+
+impl IdentityService {
+    /// Get the tenant and fleet assignment of the device
+    ///
+    /// # Arguments
+    /// * `service_client` - Device service client for retrieving the fleet id
+    ///
+    /// # Returns
+    /// Result with the device identity, a missing fleet id is no error
+    pub async fn identity<T>(service_client: &T) -> Result<DeviceIdentity>
+    where
+        T: DeviceServiceClient,
+    {
+        // status() instead of fleet_id(), which fails for a device without fleet
+        let status = service_client
+            .status()
+            .await
+            .context("failed to get device status")?;
+
+        // at least one tenant is configured, enforced when loading the configuration
+        Ok(DeviceIdentity {
+            tenant: AppConfig::get().tenants[0].clone(),
+            fleet_id: status.system_info.fleet_id,
+        })
+    }
+}
@@ -6,9 +6,13 @@
 //! boot cycle.
 
 use log::{error, info};
+use omnect_ui_core::types::DeviceOperation;
+use serde::{Deserialize, Serialize};
 use std::io::ErrorKind;
 use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{fs, io};
+use uuid::Uuid;
 
 /// A volatile marker file in `/tmp/`.
 pub struct MarkerFile {
@@ -42,6 +46,19 @@ impl MarkerFile {
         }
     }
 
+    /// Set the marker file holding a value, logging errors instead of propagating them.
+    pub fn set_value_or_log(&self, value: &str) {
+        info!("Setting {} marker to {value} at: {}", self.label, self.path);
+        if let Err(e) = fs::write(self.path(), value) {
+            error!("Failed to set {} marker: {e}", self.label);
+        }
+    }
+
+    /// Value of the marker file, None if it is not set or unreadable.
+    pub fn value(&self) -> Option<String> {
+        fs::read_to_string(self.path()).ok()
+    }
+
     /// Clear the marker file. NotFound is silently ignored.
     pub fn clear(&self) {
         match fs::remove_file(self.path()) {
@@ -67,3 +84,128 @@ pub static FACTORY_RESET_RESULT_ACKED: MarkerFile = MarkerFile::new(
 /// Set when user dismisses the update validation modal.
 pub static UPDATE_VALIDATION_ACKED: MarkerFile =
     MarkerFile::new("/tmp/update_validation_acked", "update validation acked");
+
+/// Set to the device operation (reboot, factory reset, update) accepted by the
+/// device service. Cleared implicitly by the reboot ending the operation, a
+/// record outliving [`DEVICE_OPERATION_MAX_AGE`] is ignored.
+pub static LAST_DEVICE_OPERATION: MarkerFile =
+    MarkerFile::new("/tmp/last_device_operation", "last device operation");
+
+/// Time after which a recorded device operation is considered to have ended
+/// without a reboot, e.g. an update failing before it is installed
+///
+/// Above the 10 minutes the UI waits for the device to come back.
+pub const DEVICE_OPERATION_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// Device operation recorded in the last device operation marker
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct DeviceOperationRecord {
+    /// Correlation id handed out to the client starting the operation
+    pub id: String,
+    pub operation: DeviceOperation,
+    /// Seconds since the unix epoch the operation was started at, records
+    /// without it are considered stale
+    #[serde(default)]
+    pub started_at: u64,
+}
+
+impl DeviceOperationRecord {
+    /// Whether the operation was started longer than [`DEVICE_OPERATION_MAX_AGE`] before `now`
+    fn is_stale(&self, now: SystemTime) -> bool {
+        let started_at = UNIX_EPOCH + Duration::from_secs(self.started_at);
+
+        now.duration_since(started_at)
+            .is_ok_and(|age| age > DEVICE_OPERATION_MAX_AGE)
+    }
+}
+
+/// Record a device operation that ends with a reboot, so a reloaded UI can resume waiting for it.
+///
+/// Returns the id the operation was recorded with.
+pub fn record_device_operation(operation: DeviceOperation) -> String {
+    let record = DeviceOperationRecord {
+        id: Uuid::new_v4().to_string(),
+        operation,
+        started_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+
+    match serde_json::to_string(&record) {
+        Ok(value) => LAST_DEVICE_OPERATION.set_value_or_log(&value),
+        Err(e) => error!("Failed to serialize device operation: {e}"),
+    }
+
+    record.id
+}
+
+/// Device operation in progress, if any.
+pub fn last_device_operation() -> Option<DeviceOperation> {
+    last_device_operation_record().map(|record| record.operation)
+}
+
+/// Device operation in progress together with its id, if any.
+///
+/// A stale record is cleared, its operation ended without a reboot.
+pub fn last_device_operation_record() -> Option<DeviceOperationRecord> {
+    let record: DeviceOperationRecord = LAST_DEVICE_OPERATION
+        .value()
+        .and_then(|value| serde_json::from_str(&value).ok())?;
+
+    if record.is_stale(SystemTime::now()) {
+        info!("{} was started too long ago, ignoring it", record.operation);
+        LAST_DEVICE_OPERATION.clear();
+        return None;
+    }
+
+    Some(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod device_operation_record {
+        use super::*;
+
+        fn record(started_at: u64) -> DeviceOperationRecord {
+            DeviceOperationRecord {
+                id: "id".to_string(),
+                operation: DeviceOperation::Update,
+                started_at,
+            }
+        }
+
+        #[test]
+        fn recent_record_is_not_stale() {
+            let now = UNIX_EPOCH + Duration::from_secs(10_000);
+
+            assert!(!record(10_000).is_stale(now));
+            assert!(!record(10_000 - DEVICE_OPERATION_MAX_AGE.as_secs()).is_stale(now));
+        }
+
+        #[test]
+        fn record_older_than_max_age_is_stale() {
+            let now = UNIX_EPOCH + Duration::from_secs(10_000);
+
+            assert!(record(10_000 - DEVICE_OPERATION_MAX_AGE.as_secs() - 1).is_stale(now));
+        }
+
+        #[test]
+        fn record_without_start_time_is_stale() {
+            let record: DeviceOperationRecord =
+                serde_json::from_str(r#"{"id": "id", "operation": "update"}"#).unwrap();
+
+            assert!(record.is_stale(SystemTime::now()));
+        }
+
+        #[test]
+        fn record_started_after_now_is_not_stale() {
+            // e.g. the clock was set back by an NTP sync
+            let now = UNIX_EPOCH + Duration::from_secs(10_000);
+
+            assert!(!record(20_000).is_stale(now));
+        }
+    }
+}
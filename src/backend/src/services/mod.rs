@@ -5,7 +5,13 @@
 //! easily tested and reused.
 
 pub mod auth;
+pub mod centrifugo;
 pub mod certificate;
+pub mod factory_reset;
 pub mod firmware;
+pub mod identity;
 pub mod marker;
 pub mod network;
+pub mod operation;
+pub mod republish;
+pub mod schedule;
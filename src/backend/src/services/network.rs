@@ -1,17 +1,28 @@
-use crate::{omnect_device_service_client::DeviceServiceClient, services::marker};
-use anyhow::{Context, Result};
+use crate::{
+    clock::Clock,
+    omnect_device_service_client::{DeviceServiceClient, NetworkInterface, NetworkStatus},
+    services::marker,
+};
+use anyhow::{Context, Result, ensure};
 use ini::Ini;
-use log::{debug, error, info};
-pub use omnect_ui_core::types::{NetworkConfigRequest, SetNetworkConfigResponse};
+use log::{debug, error, info, warn};
+pub use omnect_ui_core::types::{NetworkConfigRequest, SetNetworkConfigResponse, WifiConfig};
 use serde::{Deserialize, Serialize};
 use serde_valid::Validate;
 use std::{
+    collections::HashMap,
     fs,
-    io::ErrorKind,
-    path::Path,
+    io::{ErrorKind, Write},
+    net::{IpAddr, Ipv4Addr},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
     time::{Duration, SystemTime},
 };
-use tokio::{sync::broadcast, time::sleep};
+use tokio::{
+    sync::{OwnedMutexGuard, broadcast},
+    time::sleep,
+};
 
 // ============================================================================
 // Macros
@@ -19,7 +30,10 @@ use tokio::{sync::broadcast, time::sleep};
 
 macro_rules! network_path {
     ($filename:expr) => {
-        Path::new("/network/").join($filename)
+        crate::config::AppConfig::get()
+            .paths
+            .network_dir
+            .join($filename)
     };
 }
 
@@ -35,6 +49,25 @@ macro_rules! network_backup_file {
     };
 }
 
+// wpa_supplicant@<iface> reads its config from its own directory, not from systemd-networkd's
+macro_rules! wpa_supplicant_path {
+    ($filename:expr) => {
+        Path::new("/wpa_supplicant/").join($filename)
+    };
+}
+
+macro_rules! wpa_supplicant_file {
+    ($name:expr) => {
+        wpa_supplicant_path!(format!("wpa_supplicant-{}.conf", $name))
+    };
+}
+
+macro_rules! wpa_supplicant_backup_file {
+    ($name:expr) => {
+        wpa_supplicant_path!(format!("wpa_supplicant-{}.conf.old", $name))
+    };
+}
+
 macro_rules! network_rollback_file {
     () => {
         Path::new("/tmp/network_rollback.json")
@@ -53,22 +86,211 @@ macro_rules! clear_rollback {
 
 static SERVER_RESTART_TX: std::sync::OnceLock<broadcast::Sender<()>> = std::sync::OnceLock::new();
 
+/// Per adapter locks serializing configuration changes of the same interface
+///
+/// Only adapters currently being configured have an entry, see `AdapterGuard`.
+static ADAPTER_LOCKS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(Default::default);
+
 // ============================================================================
 // Constants
 // ============================================================================
 
-const ROLLBACK_TIMEOUT_SECS: u64 = 90;
+pub const ROLLBACK_TIMEOUT_SECS: u64 = 90;
 
 // ============================================================================
 // Structs
 // ============================================================================
 
+/// Query of a DNS resolution test
+#[derive(Deserialize, Debug)]
+pub struct ResolveRequest {
+    pub host: String,
+}
+
+/// Query of a raw `.network` configuration upload, the file is the request body
+#[derive(Deserialize, Debug)]
+pub struct RawNetworkConfigRequest {
+    /// Name of the network interface to configure
+    pub name: String,
+    /// Whether to enable automatic rollback protection, required for the
+    /// interface serving the UI
+    #[serde(default)]
+    pub enable_rollback: bool,
+}
+
+/// Error of a raw configuration rejected before anything is written
+#[derive(Debug)]
+pub struct RawNetworkConfigRejected(pub String);
+
+impl std::fmt::Display for RawNetworkConfigRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "raw network config rejected: {}", self.0)
+    }
+}
+
+impl std::error::Error for RawNetworkConfigRejected {}
+
+/// Addresses a hostname resolved to by the resolver of the container
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveResponse {
+    pub host: String,
+    pub addresses: Vec<String>,
+}
+
+/// Live network status and on-disk configuration, e.g. for support tickets
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkExport {
+    pub network_interfaces: Vec<ExportedInterface>,
+    pub network_files: Vec<NetworkFile>,
+}
+
+/// Live network status of an interface
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedInterface {
+    pub name: String,
+    pub mac: String,
+    pub online: bool,
+    pub ipv4_addresses: Vec<String>,
+}
+
+impl From<&NetworkInterface> for ExportedInterface {
+    fn from(iface: &NetworkInterface) -> Self {
+        Self {
+            name: iface.name.clone(),
+            mac: iface.mac.clone(),
+            online: iface.online,
+            ipv4_addresses: iface.ipv4.addrs.iter().map(|a| a.addr.clone()).collect(),
+        }
+    }
+}
+
+/// Contents of the `.network` file of an interface
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkFile {
+    pub name: String,
+    pub path: PathBuf,
+    /// None if the file could not be read
+    pub content: Option<String>,
+}
+
+/// Response of an explicit rollback cancellation
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRollbackResponse {
+    /// Whether a rollback was pending and has been cancelled
+    pub rollback_pending: bool,
+}
+
+/// Response of a rollback requested before its deadline
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackNowResponse {
+    /// Whether a rollback was pending and has been executed
+    pub rollback_executed: bool,
+}
+
+/// Status of a pending network rollback
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackStatus {
+    /// Whether a rollback is pending
+    pub pending: bool,
+    /// Seconds left until the rollback is executed, 0 if it is due
+    pub remaining_seconds: Option<u64>,
+    /// Name of the network interface whose configuration is rolled back
+    pub adapter: Option<String>,
+}
+
+impl RollbackStatus {
+    fn of(rollback: Option<&Rollback>, clock: &impl Clock) -> Self {
+        match rollback {
+            Some(rollback) => Self {
+                pending: true,
+                remaining_seconds: Some(rollback.remaining_seconds(clock)),
+                adapter: Some(rollback.network_config.name.clone()),
+            },
+            None => Self {
+                pending: false,
+                remaining_seconds: None,
+                adapter: None,
+            },
+        }
+    }
+}
+
+/// Entry of the history of executed rollbacks
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutedRollback {
+    /// Seconds since the unix epoch the rollback was executed at
+    pub timestamp: u64,
+    /// Name of the network interface whose configuration was rolled back
+    pub adapter: String,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct Rollback {
     network_config: NetworkConfigRequest,
     deadline: SystemTime,
 }
 
+impl Rollback {
+    /// Create a rollback which is due after the rollback timeout
+    ///
+    /// # Arguments
+    /// * `network_config` - Network configuration to roll back
+    /// * `restart_margin` - Time added for the server restart preceding the rollback
+    /// * `clock` - Time source
+    fn new(
+        network_config: NetworkConfigRequest,
+        restart_margin: Duration,
+        clock: &impl Clock,
+    ) -> Self {
+        Self {
+            // the rollback restores the backup by adapter name, the wifi
+            // credentials must not end up in the rollback file
+            network_config: NetworkConfigRequest {
+                wifi: None,
+                ..network_config
+            },
+            deadline: clock.now() + Duration::from_secs(ROLLBACK_TIMEOUT_SECS) + restart_margin,
+        }
+    }
+
+    /// Time left until the rollback is due or None if the deadline is reached
+    fn remaining_time(&self, clock: &impl Clock) -> Option<Duration> {
+        self.deadline.duration_since(clock.now()).ok()
+    }
+
+    /// Whole seconds left until the rollback is due, 0 if it is due
+    fn remaining_seconds(&self, clock: &impl Clock) -> u64 {
+        self.remaining_time(clock)
+            .map_or(0, |remaining| remaining.as_secs())
+    }
+}
+
+/// Held lock of an adapter
+///
+/// On release, locks nobody holds or waits for are removed from `ADAPTER_LOCKS`,
+/// including those left behind by cancelled waiters.
+struct AdapterGuard(Option<OwnedMutexGuard<()>>);
+
+impl Drop for AdapterGuard {
+    fn drop(&mut self) {
+        // release first, so the own lock counts as unused unless someone waits for it
+        drop(self.0.take());
+        ADAPTER_LOCKS
+            .lock()
+            .unwrap()
+            .retain(|_, lock| Arc::strong_count(lock) > 1);
+    }
+}
+
 // ============================================================================
 // Service
 // ============================================================================
@@ -94,241 +316,910 @@ impl NetworkConfigService {
     /// # Arguments
     /// * `service_client` - Device service client for network reload
     /// * `request` - Network configuration request with optional rollback settings
+    /// * `clock` - Time source of the rollback deadline
     ///
     /// # Returns
     /// Result with the network config response including rollback timeout, or an error
     pub async fn set_network_config<T>(
         service_client: &T,
         request: &NetworkConfigRequest,
+        clock: &impl Clock,
     ) -> Result<SetNetworkConfigResponse>
     where
         T: DeviceServiceClient,
     {
         info!("set network config: {request:?}");
 
+        let request = &Self::with_consistent_ip_changed(request);
+
         request.validate().context("network validation failed")?;
+        Self::validate_dns(request)?;
+        if let Some(wifi) = &request.wifi {
+            Self::validate_wifi(wifi)?;
+        }
+        ensure!(
+            crate::config::AppConfig::get()
+                .network_interfaces
+                .is_allowed(&request.name),
+            "network interface {} is excluded by configuration",
+            request.name
+        );
+
+        // concurrent requests for the same adapter would race on backup and rollback files
+        let _guard = Self::lock_adapter(&request.name).await;
 
         let enable_rollback = request.enable_rollback.unwrap_or(false);
         let switching_to_dhcp = request.switching_to_dhcp;
 
-        if let Err(err1) =
-            Self::apply_network_config(service_client, request, enable_rollback, switching_to_dhcp)
-                .await
+        if let Err(err1) = Self::apply_network_config(
+            service_client,
+            request,
+            enable_rollback,
+            switching_to_dhcp,
+            clock,
+        )
+        .await
         {
-            if let Err(err2) = Self::rollback_network_config(&request.name) {
+            if let Err(err2) = Self::restore_network_config(service_client, &request.name).await {
                 error!("failed to rollback network config: {err2:#}");
             }
             return Err(err1);
         }
 
+        let rollback_enabled =
+            enable_rollback && request.is_server_addr && (request.ip_changed || switching_to_dhcp);
+
         Ok(SetNetworkConfigResponse {
-            rollback_timeout_seconds: ROLLBACK_TIMEOUT_SECS,
+            rollback_timeout_seconds: if rollback_enabled {
+                Self::advertised_rollback_timeout(clock)
+            } else {
+                ROLLBACK_TIMEOUT_SECS
+            },
             ui_port: crate::config::AppConfig::get().ui.port,
-            rollback_enabled: enable_rollback
-                && request.is_server_addr
-                && (request.ip_changed || switching_to_dhcp),
+            rollback_enabled,
+            healthcheck_require_auth: crate::config::AppConfig::get().ui.healthcheck_require_auth,
         })
     }
 
-    /// Process any pending network configuration rollback
+    /// Set the network configuration of an interface from a raw `.network` file
+    ///
+    /// The file replaces the configuration written by [`Self::set_network_config`]
+    /// and is backed up and rolled back the same way. A configuration of the
+    /// interface serving the UI is only accepted with rollback enabled, as it
+    /// may cut off the client. If the serving interface can't be determined,
+    /// e.g. for an address not known to the device service, every interface is
+    /// treated as serving the UI.
     ///
     /// # Arguments
-    /// * `service_client` - Device service client for rollback operations
+    /// * `service_client` - Device service client for status and network reload
+    /// * `request` - Interface to configure and rollback setting
+    /// * `content` - Content of the `.network` file
+    /// * `preferred` - Address the client connected to, identifies the serving interface
+    /// * `clock` - Time source of the rollback deadline
     ///
     /// # Returns
-    /// Result indicating success or failure
-    pub async fn process_pending_rollback<T>(service_client: T) -> Result<()>
+    /// Result with the network config response including rollback timeout, or an
+    /// error, which is a [`RawNetworkConfigRejected`] if nothing has been written
+    pub async fn set_raw_network_config<T>(
+        service_client: &T,
+        request: &RawNetworkConfigRequest,
+        content: &str,
+        preferred: Option<IpAddr>,
+        clock: &impl Clock,
+    ) -> Result<SetNetworkConfigResponse>
     where
-        T: DeviceServiceClient + Clone + Send + Sync + 'static,
+        T: DeviceServiceClient,
     {
-        if Self::rollback_exists() {
-            // load rollback
-            let path = network_rollback_file!();
-            let rollback: Rollback = serde_json::from_reader(
-                std::fs::OpenOptions::new()
-                    .read(true)
-                    .open(path)
-                    .context(format!("failed to open rollback file: {path:?}"))?,
-            )
-            .context(format!("failed to deserialize rollback: {path:?}"))?;
-
-            // check if deadline reached
-            if let Ok(remaining_time) = rollback.deadline.duration_since(SystemTime::now()) {
-                info!("pending rollback found: {rollback:?}");
-                info!(
-                    "await cancel rollback within: {}s",
-                    remaining_time.as_secs()
-                );
-                sleep(remaining_time).await;
-                return Box::pin(Self::process_pending_rollback(service_client)).await;
-            }
+        info!("set raw network config: {request:?}");
 
-            info!("rollback: {rollback:?}");
-            Self::rollback_network_config(&rollback.network_config.name)?;
-            service_client.reload_network().await?;
-            Self::mark_rollback_occurred()?;
-            Self::trigger_server_restart()?;
-
-            clear_rollback!();
-        } else {
-            info!("no rollback found");
+        Self::validate_raw_network_config(&request.name, content)
+            .map_err(|e| RawNetworkConfigRejected(format!("{e:#}")))?;
+        ensure!(
+            crate::config::AppConfig::get()
+                .network_interfaces
+                .is_allowed(&request.name),
+            "network interface {} is excluded by configuration",
+            request.name
+        );
+
+        let status = service_client
+            .status()
+            .await
+            .context("failed to get device status")?;
+        let serves_ui = Self::may_serve_ui(&status.network_status, &request.name, preferred);
+
+        if serves_ui && !request.enable_rollback {
+            return Err(RawNetworkConfigRejected(format!(
+                "network interface {} may serve the UI and requires rollback to be enabled",
+                request.name
+            ))
+            .into());
         }
-        Ok(())
-    }
 
-    /// Cancel any pending network configuration rollback
-    pub fn cancel_rollback() {
-        if Self::rollback_exists() {
-            clear_rollback!();
-            info!("pending network rollback cancelled");
+        let enable_rollback = serves_ui || request.enable_rollback;
+
+        // concurrent requests for the same adapter would race on backup and rollback files
+        let _guard = Self::lock_adapter(&request.name).await;
+
+        if let Err(err1) = Self::apply_raw_network_config(
+            service_client,
+            &request.name,
+            content,
+            enable_rollback,
+            clock,
+        )
+        .await
+        {
+            if let Err(err2) = Self::restore_network_config(service_client, &request.name).await {
+                error!("failed to rollback network config: {err2:#}");
+            }
+            return Err(err1);
         }
+
+        Ok(SetNetworkConfigResponse {
+            rollback_timeout_seconds: if enable_rollback {
+                Self::advertised_rollback_timeout(clock)
+            } else {
+                ROLLBACK_TIMEOUT_SECS
+            },
+            ui_port: crate::config::AppConfig::get().ui.port,
+            rollback_enabled: enable_rollback,
+            healthcheck_require_auth: crate::config::AppConfig::get().ui.healthcheck_require_auth,
+        })
     }
 
-    /// Check if a rollback exists
+    /// Whether an interface may serve the UI to the client
     ///
-    /// # Returns
-    /// true if rollback file exists, false otherwise
-    pub fn rollback_exists() -> bool {
-        network_rollback_file!().exists()
+    /// # Arguments
+    /// * `status` - Network status of the device
+    /// * `name` - Name of the interface
+    /// * `preferred` - Address the client connected to, None if unknown
+    fn may_serve_ui(status: &NetworkStatus, name: &str, preferred: Option<IpAddr>) -> bool {
+        preferred
+            .and_then(|addr| status.online_interface_with(addr))
+            .is_none_or(|iface| iface.name == name)
     }
 
-    fn mark_rollback_occurred() -> Result<()> {
-        marker::NETWORK_ROLLBACK_OCCURRED
-            .set()
-            .context("failed to write rollback occurred marker")
+    /// Validate a raw `.network` file of the given interface
+    ///
+    /// The file has to parse as INI, have a `[Network]` section and a `[Match]`
+    /// section matching exactly the interface, as it is stored under its name.
+    pub fn validate_raw_network_config(name: &str, content: &str) -> Result<()> {
+        let ini = Ini::load_from_str(content).context("invalid INI syntax")?;
+
+        let matched = ini
+            .section(Some("Match"))
+            .context("missing [Match] section")?
+            .get("Name");
+        ensure!(
+            matched == Some(name),
+            "[Match] section has to match Name={name}"
+        );
+        ensure!(
+            ini.section(Some("Network")).is_some(),
+            "missing [Network] section"
+        );
+
+        Ok(())
     }
 
-    /// Rollback network configuration to the previous backup
+    /// Process any pending network configuration rollback
     ///
     /// # Arguments
-    /// * `network_name` - Name of the network interface to rollback
+    /// * `service_client` - Device service client for rollback operations
+    /// * `clock` - Time source the rollback deadline is checked against
     ///
     /// # Returns
     /// Result indicating success or failure
-    fn rollback_network_config(network_name: &String) -> Result<()> {
-        let config_file = network_config_file!(network_name);
-        let backup_file = network_backup_file!(network_name);
+    pub async fn process_pending_rollback<T>(service_client: T, clock: &impl Clock) -> Result<()>
+    where
+        T: DeviceServiceClient + Clone + Send + Sync + 'static,
+    {
+        let Some((guard, rollback)) = Self::lock_pending_rollback().await? else {
+            info!("no rollback found");
+            return Ok(());
+        };
 
-        Self::rename_if_exists(&backup_file, &config_file)?;
-        Ok(())
+        // check if deadline reached
+        if let Some(remaining_time) = rollback.remaining_time(clock) {
+            // configuration changes of the adapter must not wait for the deadline
+            drop(guard);
+            info!("pending rollback found: {rollback:?}");
+            info!(
+                "await cancel rollback within: {}s",
+                remaining_time.as_secs()
+            );
+            sleep(remaining_time).await;
+            return Box::pin(Self::process_pending_rollback(service_client, clock)).await;
+        }
+
+        Self::execute_rollback(&service_client, &rollback, clock).await
     }
 
-    /// Atomically copy a file if it exists
+    /// Execute a pending rollback immediately, without waiting for its deadline
+    ///
+    /// The task awaiting the deadline finds no rollback anymore when it wakes up.
     ///
     /// # Arguments
-    /// * `src` - Source file path
-    /// * `dest` - Destination file path
+    /// * `service_client` - Device service client for the network reload
+    /// * `clock` - Time source of the rollback history entry
     ///
     /// # Returns
-    /// Result with bool indicating if copy happened (true) or source didn't exist (false)
-    fn copy_if_exists(src: &Path, dest: &Path) -> Result<bool> {
-        match fs::copy(src, dest) {
-            Ok(_) => Ok(true),
-            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
-            Err(e) => Err(e).context(format!("failed to copy {src:?} to {dest:?}")),
-        }
+    /// Whether a rollback was pending and has been executed, or an error if executing it failed
+    pub async fn rollback_now<T>(
+        service_client: &T,
+        clock: &impl Clock,
+    ) -> Result<RollbackNowResponse>
+    where
+        T: DeviceServiceClient,
+    {
+        let Some((_guard, rollback)) = Self::lock_pending_rollback().await? else {
+            return Ok(RollbackNowResponse {
+                rollback_executed: false,
+            });
+        };
+
+        info!("rollback requested before deadline");
+        Self::execute_rollback(service_client, &rollback, clock).await?;
+
+        Ok(RollbackNowResponse {
+            rollback_executed: true,
+        })
     }
 
-    /// Atomically rename a file if it exists
+    /// Lock the adapter of the pending rollback and load the rollback under the lock
     ///
-    /// # Arguments
-    /// * `src` - Source file path
-    /// * `dest` - Destination file path
+    /// Until the lock is held a concurrent request may execute, cancel or
+    /// replace the rollback, so it is checked again afterwards.
     ///
     /// # Returns
-    /// Result with bool indicating if rename happened (true) or source didn't exist (false)
-    fn rename_if_exists(src: &Path, dest: &Path) -> Result<bool> {
-        match fs::rename(src, dest) {
-            Ok(_) => Ok(true),
-            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
-            Err(e) => Err(e).context(format!("failed to rename {src:?} to {dest:?}")),
+    /// The held adapter lock and the rollback, `None` if no rollback is pending
+    async fn lock_pending_rollback() -> Result<Option<(AdapterGuard, Rollback)>> {
+        loop {
+            if !Self::rollback_exists() {
+                return Ok(None);
+            }
+            let name = Self::load_rollback()?.network_config.name;
+            let guard = Self::lock_adapter(&name).await;
+
+            if !Self::rollback_exists() {
+                return Ok(None);
+            }
+            let rollback = Self::load_rollback()?;
+            if rollback.network_config.name == name {
+                return Ok(Some((guard, rollback)));
+            }
+            // replaced by a rollback of another adapter, lock that one instead
         }
     }
 
-    /// Trigger a server restart by sending signal through the restart channel
+    /// Status of the pending rollback, read from the rollback file only
     ///
-    /// # Returns
-    /// Result indicating success or failure
+    /// # Arguments
+    /// * `clock` - Time source the remaining time is computed with
     ///
-    /// # Errors
-    /// Returns error if the restart channel has not been initialized or if sending fails
-    fn trigger_server_restart() -> Result<()> {
-        let tx = SERVER_RESTART_TX
-            .get()
-            .context("failed to trigger restart: channel not initialized")?;
-
-        tx.send(()).context("failed to send restart signal")?;
+    /// # Returns
+    /// The rollback status or an error if the rollback file could not be read
+    pub fn rollback_status(clock: &impl Clock) -> Result<RollbackStatus> {
+        let rollback = Self::rollback_exists()
+            .then(Self::load_rollback)
+            .transpose()?;
 
-        Ok(())
+        Ok(RollbackStatus::of(rollback.as_ref(), clock))
     }
 
-    /// Apply network configuration to systemd-networkd
-    ///
-    /// # Arguments
-    /// * `service_client` - Device service client for network reload
-    /// * `network` - Network configuration to apply
-    /// * `enable_rollback` - Whether to enable automatic rollback for IP changes
+    /// Seconds until the stored rollback is due, as advertised to the client
     ///
-    /// # Returns
-    /// Result indicating success or failure
-    async fn apply_network_config<T>(
+    /// Read back from the rollback file, so the countdown shown to the operator
+    /// matches the deadline the restarted server acts on.
+    fn advertised_rollback_timeout(clock: &impl Clock) -> u64 {
+        match Self::load_rollback() {
+            Ok(rollback) => rollback.remaining_seconds(clock),
+            Err(e) => {
+                warn!("failed to read back rollback deadline: {e:#}");
+                ROLLBACK_TIMEOUT_SECS
+            }
+        }
+    }
+
+    fn load_rollback() -> Result<Rollback> {
+        let path = network_rollback_file!();
+        serde_json::from_reader(
+            std::fs::OpenOptions::new()
+                .read(true)
+                .open(path)
+                .context(format!("failed to open rollback file: {path:?}"))?,
+        )
+        .context(format!("failed to deserialize rollback: {path:?}"))
+    }
+
+    async fn execute_rollback<T>(
         service_client: &T,
-        network: &NetworkConfigRequest,
-        enable_rollback: bool,
-        switching_to_dhcp: bool,
+        rollback: &Rollback,
+        clock: &impl Clock,
     ) -> Result<()>
     where
         T: DeviceServiceClient,
     {
-        info!("apply network config");
-
-        Self::backup_current_network_config(service_client, &network.name).await?;
-        Self::write_network_config(network)?;
+        info!("rollback: {rollback:?}");
+        Self::restore_network_config(service_client, &rollback.network_config.name).await?;
         service_client.reload_network().await?;
-
-        if network.is_server_addr && (network.ip_changed || switching_to_dhcp) {
-            // Only create rollback if user explicitly requested it
-            if enable_rollback {
-                Self::create_rollback(network)?;
-            }
-            // Always restart server when server IP changes (regardless of rollback)
-            Self::trigger_server_restart()?;
+        if let Err(e) = Self::record_executed_rollback(&rollback.network_config.name, clock) {
+            error!("failed to record executed rollback: {e:#}");
         }
+        Self::mark_rollback_occurred()?;
+        Self::trigger_server_restart()?;
 
+        clear_rollback!();
         Ok(())
     }
 
-    /// Backup the current network configuration file
+    /// Cancel any pending network configuration rollback
+    ///
+    /// # Returns
+    /// true if a rollback was pending and has been cancelled, false otherwise
+    pub fn cancel_rollback() -> bool {
+        if Self::rollback_exists() {
+            clear_rollback!();
+            info!("pending network rollback cancelled");
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Resolve a hostname to verify DNS works
+    ///
+    /// The resolver of the container is used, which only matches the one of the
+    /// device if the container shares the host's network and `/etc/resolv.conf`.
     ///
     /// # Arguments
-    /// * `service_client` - Device service client for retrieving network interfaces
-    /// * `network_name` - Name of the network interface to backup
+    /// * `service_client` - Device service client used for the resolution
+    /// * `host` - Hostname to resolve, must be validated by `validate_hostname`
     ///
     /// # Returns
-    /// Result indicating success or failure
-    async fn backup_current_network_config<T>(
-        service_client: &T,
-        network_name: &String,
-    ) -> Result<()>
+    /// The resolved addresses or an error if the resolution failed
+    pub async fn resolve<T>(service_client: &T, host: &str) -> Result<ResolveResponse>
     where
         T: DeviceServiceClient,
     {
-        info!("backup {network_name}");
+        let addresses = service_client.resolve(host).await?;
 
-        let config_file = network_config_file!(&network_name);
-        let backup_file = network_backup_file!(&network_name);
+        Ok(ResolveResponse {
+            host: host.to_string(),
+            addresses,
+        })
+    }
 
-        if !Self::copy_if_exists(&config_file, &backup_file)? {
-            info!("current config file not found ({network_name})");
+    /// Export the network status together with the `.network` file of each interface
+    ///
+    /// # Arguments
+    /// * `service_client` - Device service client providing the network status
+    ///
+    /// # Returns
+    /// The export or an error if the network status could not be fetched
+    pub async fn export<T>(service_client: &T) -> Result<NetworkExport>
+    where
+        T: DeviceServiceClient,
+    {
+        let network_interfaces = service_client
+            .status()
+            .await?
+            .network_status
+            .network_interfaces;
+
+        let network_files = network_interfaces
+            .iter()
+            .map(|iface| NetworkFile {
+                name: iface.name.clone(),
+                path: iface.file.clone(),
+                content: Self::mounted_network_file(&iface.file).and_then(|path| {
+                    fs::read_to_string(&path)
+                        .inspect_err(|e| warn!("failed to read {}: {e}", path.display()))
+                        .ok()
+                }),
+            })
+            .collect();
+
+        Ok(NetworkExport {
+            network_interfaces: network_interfaces.iter().map(Into::into).collect(),
+            network_files,
+        })
+    }
 
-            let status = service_client
-                .status()
-                .await
-                .context("failed to get device status")?;
+    /// Path of a `.network` file reported by the device service within the container
+    ///
+    /// The device service reports host paths, whose directory is mounted at the
+    /// configured network directory.
+    fn mounted_network_file(host_path: &Path) -> Option<PathBuf> {
+        let file_name = host_path.file_name();
+        if file_name.is_none() {
+            warn!("invalid network file path: {}", host_path.display());
+        }
+        file_name.map(|file_name| network_path!(file_name))
+    }
 
-            debug!(
-                "network interfaces: {:?}",
-                status.network_status.network_interfaces
+    /// Validate a hostname according to RFC 1123
+    ///
+    /// Labels of 1 to 63 letters, digits and hyphens, not starting or ending
+    /// with a hyphen, and at most 253 characters in total.
+    pub fn validate_hostname(host: &str) -> Result<()> {
+        let host = host.strip_suffix('.').unwrap_or(host);
+
+        ensure!(
+            !host.is_empty() && host.len() <= 253,
+            "invalid hostname: must have 1 to 253 characters"
+        );
+
+        for label in host.split('.') {
+            ensure!(
+                (1..=63).contains(&label.len())
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                    && !label.starts_with('-')
+                    && !label.ends_with('-'),
+                "invalid hostname: malformed label \"{label}\""
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Check if a rollback exists
+    ///
+    /// # Returns
+    /// true if rollback file exists, false otherwise
+    pub fn rollback_exists() -> bool {
+        network_rollback_file!().exists()
+    }
+
+    /// Append an executed rollback to the rollback history
+    ///
+    /// The history keeps the last `NETWORK_ROLLBACK_HISTORY_SIZE` entries,
+    /// older entries are pruned.
+    ///
+    /// # Arguments
+    /// * `adapter` - Name of the network interface that was rolled back
+    /// * `clock` - Time source of the entry's timestamp
+    ///
+    /// # Errors
+    /// Returns error if the history file could not be written
+    pub fn record_executed_rollback(adapter: &str, clock: &impl Clock) -> Result<()> {
+        let config = crate::config::AppConfig::get();
+
+        Self::append_rollback_history(
+            &config.paths.network_rollback_history_file,
+            ExecutedRollback {
+                timestamp: clock.now_since_epoch().as_secs(),
+                adapter: adapter.to_string(),
+            },
+            config.network_rollback_history_size,
+        )
+    }
+
+    /// Executed rollbacks, oldest first
+    pub fn rollback_history() -> Vec<ExecutedRollback> {
+        Self::read_rollback_history(
+            &crate::config::AppConfig::get()
+                .paths
+                .network_rollback_history_file,
+        )
+    }
+
+    fn read_rollback_history(path: &Path) -> Vec<ExecutedRollback> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .inspect_err(|e| warn!("failed to parse rollback history, starting anew: {e}"))
+                .unwrap_or_default(),
+            Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+            Err(e) => {
+                warn!("failed to read rollback history, starting anew: {e}");
+                vec![]
+            }
+        }
+    }
+
+    fn append_rollback_history(
+        path: &Path,
+        entry: ExecutedRollback,
+        max_entries: usize,
+    ) -> Result<()> {
+        if max_entries == 0 {
+            debug!("rollback history disabled");
+            return Ok(());
+        }
+
+        info!("record executed rollback: {entry:?}");
+
+        let mut history = Self::read_rollback_history(path);
+        history.push(entry);
+        let excess = history.len().saturating_sub(max_entries);
+        history.drain(..excess);
+
+        let json = serde_json::to_string_pretty(&history)
+            .context("failed to serialize rollback history")?;
+        fs::write(path, json).context(format!("failed to write rollback history: {path:?}"))
+    }
+
+    fn mark_rollback_occurred() -> Result<()> {
+        marker::NETWORK_ROLLBACK_OCCURRED
+            .set()
+            .context("failed to write rollback occurred marker")
+    }
+
+    /// Rollback network configuration to the previous backup and restart
+    /// wpa_supplicant if its configuration changed
+    ///
+    /// # Arguments
+    /// * `service_client` - Device service client restarting wpa_supplicant
+    /// * `network_name` - Name of the network interface to rollback
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    async fn restore_network_config<T>(service_client: &T, network_name: &String) -> Result<()>
+    where
+        T: DeviceServiceClient,
+    {
+        if Self::rollback_network_config(network_name)? {
+            service_client.restart_wpa_supplicant(network_name).await?;
+        }
+        Ok(())
+    }
+
+    /// Rollback network configuration to the previous backup
+    ///
+    /// A wpa_supplicant config without backup has been added by the rolled
+    /// back change and is removed.
+    ///
+    /// # Arguments
+    /// * `network_name` - Name of the network interface to rollback
+    ///
+    /// # Returns
+    /// Whether the wpa_supplicant config changed, or an error
+    fn rollback_network_config(network_name: &String) -> Result<bool> {
+        let config_file = network_config_file!(network_name);
+        let backup_file = network_backup_file!(network_name);
+
+        Self::rename_if_exists(&backup_file, &config_file)?;
+
+        let wpa_file = wpa_supplicant_file!(network_name);
+        Ok(
+            Self::rename_if_exists(&wpa_supplicant_backup_file!(network_name), &wpa_file)?
+                || Self::remove_if_exists(&wpa_file)?,
+        )
+    }
+
+    /// Correct `ip_changed` of a static configuration to match `ip` and `previous_ip`
+    ///
+    /// A stale flag either triggers the rollback and server restart flow for an
+    /// unchanged address or skips it for a changed one.
+    fn with_consistent_ip_changed(request: &NetworkConfigRequest) -> NetworkConfigRequest {
+        let mut request = request.clone();
+
+        if let (false, Some(ip), Some(previous_ip)) =
+            (request.dhcp, &request.ip, &request.previous_ip)
+        {
+            let ip_changed = ip != previous_ip;
+            if request.ip_changed != ip_changed {
+                warn!(
+                    "correct inconsistent ip_changed={} for {ip} (previous {previous_ip})",
+                    request.ip_changed
+                );
+                request.ip_changed = ip_changed;
+            }
+        }
+
+        request
+    }
+
+    /// Reject DNS servers which can never be valid for the requested configuration
+    ///
+    /// Resolution can't be verified at write time, but the unspecified address,
+    /// broadcast addresses and the interface's own network address are caught.
+    /// DNS-over-TLS and DNSSEC are only accepted along with static DNS servers.
+    fn validate_dns(request: &NetworkConfigRequest) -> Result<()> {
+        ensure!(
+            (request.dns_over_tls.is_none() && request.dnssec.is_none())
+                || (!request.dhcp && !request.dns.is_empty()),
+            "DNS-over-TLS and DNSSEC require static DNS servers"
+        );
+
+        if request.dhcp {
+            return Ok(());
+        }
+
+        let subnet = match (&request.ip, request.netmask) {
+            (Some(ip), Some(prefix)) if prefix <= 32 => ip.parse::<Ipv4Addr>().ok().map(|ip| {
+                let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+                let network = u32::from(ip) & mask;
+                (
+                    prefix,
+                    Ipv4Addr::from(network),
+                    Ipv4Addr::from(network | !mask),
+                )
+            }),
+            _ => None,
+        };
+
+        for dns in &request.dns {
+            let addr: Ipv4Addr = dns
+                .parse()
+                .with_context(|| format!("invalid DNS server {dns}: not an IPv4 address"))?;
+
+            ensure!(
+                !addr.is_unspecified(),
+                "invalid DNS server {dns}: unspecified address"
+            );
+            ensure!(
+                !addr.is_broadcast(),
+                "invalid DNS server {dns}: broadcast address"
+            );
+
+            if let Some((prefix, network, broadcast)) = subnet {
+                // /31 and /32 have no network or broadcast address
+                if prefix < 31 {
+                    ensure!(
+                        addr != network,
+                        "invalid DNS server {dns}: network address of the interface"
+                    );
+                    ensure!(
+                        addr != broadcast,
+                        "invalid DNS server {dns}: broadcast address of the interface"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate wifi credentials according to the WPA rules
+    ///
+    /// The SSID has 1 to 32 bytes, the PSK is either a passphrase of 8 to 63
+    /// printable ASCII characters or a raw key of 64 hex digits.
+    fn validate_wifi(wifi: &WifiConfig) -> Result<()> {
+        ensure!(
+            (1..=32).contains(&wifi.ssid.len()),
+            "invalid wifi SSID: must have 1 to 32 bytes"
+        );
+
+        let is_passphrase = (8..=63).contains(&wifi.psk.len())
+            && wifi
+                .psk
+                .chars()
+                .all(|c| c.is_ascii() && !c.is_ascii_control());
+        let is_raw_key = wifi.psk.len() == 64 && wifi.psk.chars().all(|c| c.is_ascii_hexdigit());
+
+        ensure!(
+            is_passphrase || is_raw_key,
+            "invalid wifi PSK: must be 8 to 63 printable ASCII characters or 64 hex digits"
+        );
+
+        Ok(())
+    }
+
+    /// Acquire the lock serializing configuration changes of the given adapter
+    async fn lock_adapter(name: &str) -> AdapterGuard {
+        let lock = ADAPTER_LOCKS
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_default()
+            .clone();
+
+        AdapterGuard(Some(lock.lock_owned().await))
+    }
+
+    /// Atomically copy a file if it exists
+    ///
+    /// # Arguments
+    /// * `src` - Source file path
+    /// * `dest` - Destination file path
+    ///
+    /// # Returns
+    /// Result with bool indicating if copy happened (true) or source didn't exist (false)
+    fn copy_if_exists(src: &Path, dest: &Path) -> Result<bool> {
+        match fs::copy(src, dest) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).context(format!("failed to copy {src:?} to {dest:?}")),
+        }
+    }
+
+    /// Atomically rename a file if it exists
+    ///
+    /// # Arguments
+    /// * `src` - Source file path
+    /// * `dest` - Destination file path
+    ///
+    /// # Returns
+    /// Result with bool indicating if rename happened (true) or source didn't exist (false)
+    fn rename_if_exists(src: &Path, dest: &Path) -> Result<bool> {
+        match fs::rename(src, dest) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).context(format!("failed to rename {src:?} to {dest:?}")),
+        }
+    }
+
+    /// Remove a file if it exists
+    ///
+    /// # Arguments
+    /// * `path` - File path
+    ///
+    /// # Returns
+    /// Result with bool indicating if removal happened (true) or file didn't exist (false)
+    fn remove_if_exists(path: &Path) -> Result<bool> {
+        match fs::remove_file(path) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).context(format!("failed to remove {path:?}")),
+        }
+    }
+
+    /// Trigger a server restart by sending signal through the restart channel
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    ///
+    /// # Errors
+    /// Returns error if the restart channel has not been initialized or if sending fails
+    pub fn trigger_server_restart() -> Result<()> {
+        let tx = SERVER_RESTART_TX
+            .get()
+            .context("failed to trigger restart: channel not initialized")?;
+
+        tx.send(()).context("failed to send restart signal")?;
+
+        Ok(())
+    }
+
+    /// Apply network configuration to systemd-networkd
+    ///
+    /// # Arguments
+    /// * `service_client` - Device service client for network reload
+    /// * `network` - Network configuration to apply
+    /// * `enable_rollback` - Whether to enable automatic rollback for IP changes
+    /// * `switching_to_dhcp` - Whether the interface switches from a static address to DHCP
+    /// * `clock` - Time source of the rollback deadline
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    async fn apply_network_config<T>(
+        service_client: &T,
+        network: &NetworkConfigRequest,
+        enable_rollback: bool,
+        switching_to_dhcp: bool,
+        clock: &impl Clock,
+    ) -> Result<()>
+    where
+        T: DeviceServiceClient,
+    {
+        info!("apply network config");
+
+        Self::backup_current_network_config(service_client, &network.name).await?;
+        Self::write_network_config(network)?;
+        service_client.reload_network().await?;
+        if network.wifi.is_some() {
+            service_client.restart_wpa_supplicant(&network.name).await?;
+        }
+
+        if network.is_server_addr && (network.ip_changed || switching_to_dhcp) {
+            // Only create rollback if user explicitly requested it
+            if enable_rollback {
+                Self::create_rollback(network, clock)?;
+            }
+            // Always restart server when server IP changes (regardless of rollback)
+            Self::trigger_server_restart()?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a raw `.network` file to systemd-networkd
+    ///
+    /// # Arguments
+    /// * `service_client` - Device service client for network reload
+    /// * `name` - Name of the network interface
+    /// * `content` - Validated content of the `.network` file
+    /// * `enable_rollback` - Whether to start the rollback countdown and restart the server
+    /// * `clock` - Time source of the rollback deadline
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    async fn apply_raw_network_config<T>(
+        service_client: &T,
+        name: &str,
+        content: &str,
+        enable_rollback: bool,
+        clock: &impl Clock,
+    ) -> Result<()>
+    where
+        T: DeviceServiceClient,
+    {
+        info!("apply raw network config");
+
+        let name = name.to_string();
+        Self::backup_current_network_config(service_client, &name).await?;
+
+        let config_path = network_config_file!(&name);
+        info!(
+            "write raw network config to {config_path:?} ({} bytes)",
+            content.len()
+        );
+        debug!("raw network config: {content:?}");
+        Self::write_atomically(content.as_bytes(), &config_path)
+            .context(format!("failed to write network config: {config_path:?}"))?;
+
+        service_client.reload_network().await?;
+
+        if enable_rollback {
+            // a rollback restores the backup of the adapter, nothing else of the request is used
+            Self::create_rollback(
+                &NetworkConfigRequest {
+                    is_server_addr: true,
+                    ip_changed: true,
+                    name,
+                    dhcp: false,
+                    ip: None,
+                    previous_ip: None,
+                    netmask: None,
+                    gateway: vec![],
+                    dns: vec![],
+                    enable_rollback: Some(true),
+                    switching_to_dhcp: false,
+                    ipv6_accept_ra: None,
+                    link_local_addressing: None,
+                    dns_over_tls: None,
+                    dnssec: None,
+                    wifi: None,
+                },
+                clock,
+            )?;
+            Self::trigger_server_restart()?;
+        }
+
+        Ok(())
+    }
+
+    /// Backup the current network configuration file
+    ///
+    /// # Arguments
+    /// * `service_client` - Device service client for retrieving network interfaces
+    /// * `network_name` - Name of the network interface to backup
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    async fn backup_current_network_config<T>(
+        service_client: &T,
+        network_name: &String,
+    ) -> Result<()>
+    where
+        T: DeviceServiceClient,
+    {
+        info!("backup {network_name}");
+
+        let config_file = network_config_file!(&network_name);
+        let backup_file = network_backup_file!(&network_name);
+
+        // a stale backup would be restored instead of removing a newly added config
+        if !Self::copy_if_exists(
+            &wpa_supplicant_file!(&network_name),
+            &wpa_supplicant_backup_file!(&network_name),
+        )? {
+            Self::remove_if_exists(&wpa_supplicant_backup_file!(&network_name))?;
+        }
+
+        if !Self::copy_if_exists(&config_file, &backup_file)? {
+            info!("current config file not found ({network_name})");
+
+            let status = service_client
+                .status()
+                .await
+                .context("failed to get device status")?;
+
+            debug!(
+                "network interfaces: {:?}",
+                status.network_status.network_interfaces
             );
 
             // find network file
@@ -362,12 +1253,138 @@ impl NetworkConfigService {
     /// # Returns
     /// Result indicating success or failure
     fn write_network_config(network: &NetworkConfigRequest) -> Result<()> {
-        let mut ini = Ini::new();
+        let ini = Self::network_config_ini(network)?;
 
-        ini.with_section(Some("Match".to_owned()))
-            .set("Name", &network.name);
+        let config_path = network_config_file!(&network.name);
 
-        let mut network_section = ini.with_section(Some("Network").to_owned());
+        info!("write network config to {config_path:?}: {ini:?}");
+
+        Self::write_ini_atomically(&ini, &config_path)
+            .context(format!("failed to write network config: {config_path:?}"))?;
+
+        if let Some(wifi) = &network.wifi {
+            Self::write_wpa_supplicant_config(&wpa_supplicant_file!(&network.name), wifi)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace a configuration file by a complete new one
+    ///
+    /// The content is written to a temporary file in the same directory first,
+    /// which then atomically replaces `path`. An interrupted write leaves the
+    /// previous file intact.
+    ///
+    /// # Arguments
+    /// * `ini` - Configuration to write
+    /// * `path` - Path of the configuration file
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    fn write_ini_atomically(ini: &Ini, path: &Path) -> Result<()> {
+        let temp_path = Self::write_ini_to_temp_file(ini, path)?;
+
+        fs::rename(&temp_path, path).context(format!("failed to rename {temp_path:?} to {path:?}"))
+    }
+
+    /// Replace a configuration file by the given content, see [`Self::write_ini_atomically`]
+    fn write_atomically(content: &[u8], path: &Path) -> Result<()> {
+        let temp_path = Self::write_to_temp_file(content, path)?;
+
+        fs::rename(&temp_path, path).context(format!("failed to rename {temp_path:?} to {path:?}"))
+    }
+
+    /// Write a configuration to the temporary file used to replace `path`
+    ///
+    /// # Returns
+    /// Path of the written temporary file
+    fn write_ini_to_temp_file(ini: &Ini, path: &Path) -> Result<PathBuf> {
+        let mut content = vec![];
+        ini.write_to(&mut content)
+            .context(format!("failed to serialize config for {path:?}"))?;
+
+        Self::write_to_temp_file(&content, path)
+    }
+
+    /// Write content to the temporary file used to replace `path`
+    ///
+    /// The temporary file is hidden and has no `.network` extension, so a
+    /// leftover of an interrupted write is ignored by systemd-networkd.
+    ///
+    /// # Returns
+    /// Path of the written temporary file
+    fn write_to_temp_file(content: &[u8], path: &Path) -> Result<PathBuf> {
+        let file_name = path
+            .file_name()
+            .context(format!("invalid config path: {path:?}"))?;
+        let temp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+        let mut file = fs::File::create(&temp_path)
+            .context(format!("failed to create temp file: {temp_path:?}"))?;
+        file.write_all(content)
+            .context(format!("failed to write temp file: {temp_path:?}"))?;
+        // make sure the content is on disk before it replaces the config
+        file.sync_all()
+            .context(format!("failed to sync temp file: {temp_path:?}"))?;
+
+        Ok(temp_path)
+    }
+
+    /// Write wifi credentials to a wpa_supplicant configuration file
+    ///
+    /// The file is only readable by its owner since it contains the PSK.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the wpa_supplicant configuration file
+    /// * `wifi` - Wifi credentials to write
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    fn write_wpa_supplicant_config(path: &Path, wifi: &WifiConfig) -> Result<()> {
+        // don't log the content, it contains the PSK
+        info!("write wpa_supplicant config to {path:?}");
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .context(format!("failed to open wpa_supplicant config: {path:?}"))?;
+
+        file.write_all(Self::wpa_supplicant_config(wifi).as_bytes())
+            .context(format!("failed to write wpa_supplicant config: {path:?}"))
+    }
+
+    /// Build the wpa_supplicant configuration for wifi credentials
+    ///
+    /// The SSID is hex encoded so it needs no escaping, a raw key is written
+    /// unquoted and a passphrase quoted.
+    fn wpa_supplicant_config(wifi: &WifiConfig) -> String {
+        let ssid: String = wifi.ssid.bytes().map(|b| format!("{b:02x}")).collect();
+        let psk = if wifi.psk.len() == 64 && wifi.psk.chars().all(|c| c.is_ascii_hexdigit()) {
+            wifi.psk.clone()
+        } else {
+            format!("\"{}\"", wifi.psk)
+        };
+
+        format!("network={{\n\tssid={ssid}\n\tpsk={psk}\n\tkey_mgmt=WPA-PSK\n}}\n")
+    }
+
+    /// Build the systemd-networkd configuration for a network interface
+    ///
+    /// # Arguments
+    /// * `network` - Network configuration to convert
+    ///
+    /// # Returns
+    /// INI representation of the network configuration
+    fn network_config_ini(network: &NetworkConfigRequest) -> Result<Ini> {
+        let mut ini = Ini::new();
+
+        ini.with_section(Some("Match".to_owned()))
+            .set("Name", &network.name);
+
+        let mut network_section = ini.with_section(Some("Network").to_owned());
 
         if network.dhcp {
             network_section.set("DHCP", "yes");
@@ -381,134 +1398,603 @@ impl NetworkConfigService {
                 network_section.add("Gateway", gateway.to_string());
             }
 
-            for dns in &network.dns {
-                network_section.add("DNS", dns.to_string());
+            for dns in &network.dns {
+                network_section.add("DNS", dns.to_string());
+            }
+        }
+
+        if let Some(accept_ra) = network.ipv6_accept_ra {
+            network_section.set("IPv6AcceptRA", if accept_ra { "yes" } else { "no" });
+        }
+
+        if let Some(link_local_addressing) = &network.link_local_addressing {
+            network_section.set("LinkLocalAddressing", link_local_addressing);
+        }
+
+        if let Some(dns_over_tls) = network.dns_over_tls {
+            network_section.set("DNSOverTLS", if dns_over_tls { "yes" } else { "no" });
+        }
+
+        if let Some(dnssec) = network.dnssec {
+            network_section.set("DNSSEC", if dnssec { "yes" } else { "no" });
+        }
+
+        Ok(ini)
+    }
+
+    /// Create a rollback entry for network configuration changes
+    ///
+    /// # Arguments
+    /// * `network` - Network configuration to create rollback for
+    /// * `clock` - Time source of the rollback deadline
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    fn create_rollback(network: &NetworkConfigRequest, clock: &impl Clock) -> Result<()> {
+        let rollback = Rollback::new(
+            network.clone(),
+            crate::config::AppConfig::get().network_rollback_restart_margin,
+            clock,
+        );
+
+        info!("create rollback: {rollback:?}");
+
+        let path = network_rollback_file!();
+
+        serde_json::to_writer_pretty(
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .mode(0o600)
+                .open(path)
+                .context(format!("failed to open rollback file for write: {path:?}"))?,
+            &rollback,
+        )
+        .context(format!("failed to serialize rollback: {path:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+
+    fn create_valid_dhcp_config() -> NetworkConfigRequest {
+        NetworkConfigRequest {
+            is_server_addr: false,
+            ip_changed: false,
+            name: "eth0".to_string(),
+            dhcp: true,
+            previous_ip: Some("192.168.1.100".to_string()),
+            ip: None,
+            netmask: None,
+            gateway: vec![],
+            dns: vec![],
+            enable_rollback: None,
+            switching_to_dhcp: true,
+            ipv6_accept_ra: None,
+            link_local_addressing: None,
+            dns_over_tls: None,
+            dnssec: None,
+            wifi: None,
+        }
+    }
+
+    fn create_valid_static_config() -> NetworkConfigRequest {
+        NetworkConfigRequest {
+            is_server_addr: false,
+            ip_changed: false,
+            name: "eth0".to_string(),
+            dhcp: false,
+            previous_ip: Some("192.168.1.100".to_string()),
+            ip: Some("192.168.1.101".to_string()),
+            netmask: Some(24),
+            gateway: vec!["192.168.1.1".to_string()],
+            dns: vec!["8.8.8.8".to_string(), "8.8.4.4".to_string()],
+            enable_rollback: None,
+            switching_to_dhcp: false,
+            ipv6_accept_ra: None,
+            link_local_addressing: None,
+            dns_over_tls: None,
+            dnssec: None,
+            wifi: None,
+        }
+    }
+
+    mod validation {
+        use super::*;
+
+        #[test]
+        fn valid_dhcp_config_passes() {
+            let request = create_valid_dhcp_config();
+            assert!(request.validate().is_ok());
+        }
+
+        #[test]
+        fn valid_static_config_passes() {
+            let request = create_valid_static_config();
+            assert!(request.validate().is_ok());
+        }
+
+        #[test]
+        fn empty_interface_name_fails() {
+            let mut request = create_valid_dhcp_config();
+            request.name = String::new();
+            assert!(request.validate().is_err());
+        }
+
+        #[test]
+        fn netmask_above_32_fails() {
+            let mut request = create_valid_static_config();
+            request.netmask = Some(33);
+            assert!(request.validate().is_err());
+        }
+
+        #[test]
+        fn netmask_at_boundary_passes() {
+            let mut request = create_valid_static_config();
+            request.netmask = Some(32);
+            assert!(request.validate().is_ok());
+        }
+
+        #[test]
+        fn netmask_zero_passes() {
+            let mut request = create_valid_static_config();
+            request.netmask = Some(0);
+            assert!(request.validate().is_ok());
+        }
+
+        #[test]
+        fn allowed_link_local_addressing_values_pass() {
+            for value in ["yes", "no", "ipv4", "ipv6"] {
+                let mut request = create_valid_dhcp_config();
+                request.link_local_addressing = Some(value.to_string());
+                assert!(request.validate().is_ok(), "{value} should be valid");
+            }
+        }
+
+        #[test]
+        fn unknown_link_local_addressing_value_fails() {
+            let mut request = create_valid_dhcp_config();
+            request.link_local_addressing = Some("fallback".to_string());
+            assert!(request.validate().is_err());
+        }
+    }
+
+    mod dns_validation {
+        use super::*;
+
+        fn static_config_with_dns(dns: &str) -> NetworkConfigRequest {
+            let mut request = create_valid_static_config();
+            request.dns = vec![dns.to_string()];
+            request
+        }
+
+        #[test]
+        fn valid_dns_passes() {
+            let request = static_config_with_dns("192.168.1.53");
+            assert!(NetworkConfigService::validate_dns(&request).is_ok());
+        }
+
+        #[test]
+        fn unspecified_address_fails() {
+            let request = static_config_with_dns("0.0.0.0");
+            let err = NetworkConfigService::validate_dns(&request).unwrap_err();
+            assert!(err.to_string().contains("unspecified address"));
+        }
+
+        #[test]
+        fn broadcast_addresses_fail() {
+            for dns in ["255.255.255.255", "192.168.1.255"] {
+                let request = static_config_with_dns(dns);
+                let err = NetworkConfigService::validate_dns(&request).unwrap_err();
+                assert!(err.to_string().contains("broadcast address"), "{dns}");
+            }
+        }
+
+        #[test]
+        fn network_address_of_interface_fails() {
+            let request = static_config_with_dns("192.168.1.0");
+            let err = NetworkConfigService::validate_dns(&request).unwrap_err();
+            assert!(err.to_string().contains("network address"));
+        }
+
+        #[test]
+        fn dns_security_with_static_dns_passes() {
+            let mut request = create_valid_static_config();
+            request.dns_over_tls = Some(true);
+            request.dnssec = Some(false);
+            assert!(NetworkConfigService::validate_dns(&request).is_ok());
+        }
+
+        #[test]
+        fn dns_security_without_static_dns_fails() {
+            let mut without_dns = create_valid_static_config();
+            without_dns.dns = vec![];
+            without_dns.dns_over_tls = Some(true);
+
+            let mut dhcp = create_valid_dhcp_config();
+            dhcp.dns = vec!["8.8.8.8".to_string()];
+            dhcp.dnssec = Some(true);
+
+            for request in [without_dns, dhcp] {
+                let err = NetworkConfigService::validate_dns(&request).unwrap_err();
+                assert!(err.to_string().contains("require static DNS servers"));
+            }
+        }
+
+        #[test]
+        fn dns_is_ignored_for_dhcp() {
+            let mut request = create_valid_dhcp_config();
+            request.dns = vec!["0.0.0.0".to_string()];
+            assert!(NetworkConfigService::validate_dns(&request).is_ok());
+        }
+    }
+
+    mod hostname_validation {
+        use super::*;
+
+        #[test]
+        fn valid_hostnames_pass() {
+            for host in ["omnect.io", "localhost", "my-host.example.com.", "10.0.0.1"] {
+                assert!(
+                    NetworkConfigService::validate_hostname(host).is_ok(),
+                    "{host}"
+                );
+            }
+        }
+
+        #[test]
+        fn invalid_hostnames_fail() {
+            let long_label = format!("{}.com", "a".repeat(64));
+            for host in [
+                "",
+                ".",
+                "a..b",
+                "-omnect.io",
+                "omnect-.io",
+                "omnect io",
+                "omnect.io;ls",
+                &long_label,
+            ] {
+                assert!(
+                    NetworkConfigService::validate_hostname(host).is_err(),
+                    "{host}"
+                );
+            }
+        }
+    }
+
+    mod raw_network_config {
+        use super::*;
+        use tempfile::TempDir;
+
+        const VALID: &str =
+            "[Match]\nName=eth0\n\n[Network]\nAddress=192.168.1.100/24\nDNS=1.1.1.1\nDNS=8.8.8.8\n";
+
+        #[test]
+        fn valid_config_passes() {
+            assert!(NetworkConfigService::validate_raw_network_config("eth0", VALID).is_ok());
+        }
+
+        #[test]
+        fn invalid_syntax_fails() {
+            let err = NetworkConfigService::validate_raw_network_config(
+                "eth0",
+                "[Match\nName=eth0\n[Network]\nDHCP=yes\n",
+            )
+            .unwrap_err();
+
+            assert!(err.to_string().contains("invalid INI syntax"));
+        }
+
+        #[test]
+        fn missing_sections_fail() {
+            for content in ["[Network]\nDHCP=yes\n", "[Match]\nName=eth0\n"] {
+                assert!(
+                    NetworkConfigService::validate_raw_network_config("eth0", content).is_err(),
+                    "{content}"
+                );
+            }
+        }
+
+        #[test]
+        fn config_of_other_interface_fails() {
+            let err = NetworkConfigService::validate_raw_network_config("eth1", VALID).unwrap_err();
+
+            assert!(err.to_string().contains("Name=eth1"));
+        }
+
+        #[test]
+        fn config_is_written_verbatim() {
+            let temp_dir = TempDir::new().expect("failed to create temp dir");
+            let config_path = temp_dir.path().join("10-eth0.network");
+            fs::write(&config_path, "[Match]\nName=eth0\n\n[Network]\nDHCP=yes\n").unwrap();
+
+            NetworkConfigService::write_atomically(VALID.as_bytes(), &config_path)
+                .expect("failed to write network config");
+
+            assert_eq!(fs::read_to_string(&config_path).unwrap(), VALID);
+            assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        }
+
+        mod may_serve_ui {
+            use super::*;
+            use crate::omnect_device_service_client::{Ipv4AddrInfo, Ipv4Info};
+
+            fn status() -> NetworkStatus {
+                let iface = |name: &str, addr: &str| NetworkInterface {
+                    online: true,
+                    ipv4: Ipv4Info {
+                        addrs: vec![Ipv4AddrInfo {
+                            addr: addr.to_string(),
+                        }],
+                    },
+                    file: PathBuf::from(format!("/run/systemd/network/10-{name}.network")),
+                    mac: "00:00:5e:00:53:01".to_string(),
+                    name: name.to_string(),
+                };
+
+                NetworkStatus {
+                    network_interfaces: vec![
+                        iface("eth0", "10.0.0.5"),
+                        iface("eth1", "192.168.0.42"),
+                    ],
+                }
+            }
+
+            fn may_serve_ui(name: &str, preferred: Option<&str>) -> bool {
+                NetworkConfigService::may_serve_ui(
+                    &status(),
+                    name,
+                    preferred.map(|ip| ip.parse().unwrap()),
+                )
+            }
+
+            #[test]
+            fn only_interface_with_client_address_serves_ui() {
+                assert!(may_serve_ui("eth1", Some("192.168.0.42")));
+                assert!(!may_serve_ui("eth0", Some("192.168.0.42")));
+            }
+
+            #[test]
+            fn any_interface_may_serve_ui_for_unknown_address() {
+                for preferred in [None, Some("172.16.0.7")] {
+                    assert!(may_serve_ui("eth0", preferred), "{preferred:?}");
+                    assert!(may_serve_ui("eth1", preferred), "{preferred:?}");
+                }
+            }
+        }
+    }
+
+    mod ip_changed_consistency {
+        use super::*;
+
+        #[test]
+        fn consistent_changed_ip_is_kept() {
+            let mut request = create_valid_static_config();
+            request.ip_changed = true;
+
+            let request = NetworkConfigService::with_consistent_ip_changed(&request);
+
+            assert!(request.ip_changed);
+        }
+
+        #[test]
+        fn consistent_unchanged_ip_is_kept() {
+            let mut request = create_valid_static_config();
+            request.ip = request.previous_ip.clone();
+
+            let request = NetworkConfigService::with_consistent_ip_changed(&request);
+
+            assert!(!request.ip_changed);
+        }
+
+        #[test]
+        fn same_ip_flagged_as_changed_is_corrected() {
+            let mut request = create_valid_static_config();
+            request.ip = request.previous_ip.clone();
+            request.ip_changed = true;
+
+            let request = NetworkConfigService::with_consistent_ip_changed(&request);
+
+            assert!(!request.ip_changed);
+        }
+
+        #[test]
+        fn different_ip_flagged_as_unchanged_is_corrected() {
+            let request = create_valid_static_config();
+            assert!(!request.ip_changed);
+
+            let request = NetworkConfigService::with_consistent_ip_changed(&request);
+
+            assert!(request.ip_changed);
+        }
+
+        #[test]
+        fn dhcp_config_is_left_untouched() {
+            let mut request = create_valid_dhcp_config();
+            request.ip_changed = true;
+
+            let request = NetworkConfigService::with_consistent_ip_changed(&request);
+
+            assert!(request.ip_changed);
+        }
+    }
+
+    mod wifi {
+        use super::*;
+        use std::os::unix::fs::PermissionsExt;
+        use tempfile::TempDir;
+
+        const PSK: &str = "correct horse battery";
+
+        fn wifi_config(ssid: &str, psk: &str) -> WifiConfig {
+            WifiConfig {
+                ssid: ssid.to_string(),
+                psk: psk.to_string(),
+            }
+        }
+
+        #[test]
+        fn valid_credentials_pass() {
+            assert!(NetworkConfigService::validate_wifi(&wifi_config("omnect", PSK)).is_ok());
+            assert!(
+                NetworkConfigService::validate_wifi(&wifi_config("omnect", &"a1".repeat(32)))
+                    .is_ok()
+            );
+        }
+
+        #[test]
+        fn invalid_ssid_length_fails() {
+            for ssid in ["", &"x".repeat(33)] {
+                let err = NetworkConfigService::validate_wifi(&wifi_config(ssid, PSK)).unwrap_err();
+                assert!(err.to_string().contains("invalid wifi SSID"), "{ssid}");
+            }
+        }
+
+        #[test]
+        fn invalid_psk_fails() {
+            let too_long_passphrase = "x".repeat(64);
+            for psk in ["short", &too_long_passphrase, "pass\nphrase"] {
+                let err =
+                    NetworkConfigService::validate_wifi(&wifi_config("omnect", psk)).unwrap_err();
+                assert!(err.to_string().contains("invalid wifi PSK"), "{psk}");
             }
         }
 
-        let config_path = network_config_file!(&network.name);
+        #[test]
+        fn writes_wpa_supplicant_config() {
+            let temp_dir = TempDir::new().expect("failed to create temp dir");
+            let path = temp_dir.path().join("wpa_supplicant-wlan0.conf");
 
-        info!("write network config to {config_path:?}: {ini:?}");
+            NetworkConfigService::write_wpa_supplicant_config(&path, &wifi_config("omnect", PSK))
+                .expect("failed to write wpa_supplicant config");
 
-        ini.write_to_file(&config_path)
-            .context(format!("failed to write network config: {config_path:?}"))?;
+            let contents = fs::read_to_string(&path).expect("failed to read config");
+            assert_eq!(
+                contents,
+                "network={\n\tssid=6f6d6e656374\n\tpsk=\"correct horse battery\"\n\tkey_mgmt=WPA-PSK\n}\n"
+            );
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
 
-        Ok(())
-    }
+        #[test]
+        fn raw_key_is_written_unquoted() {
+            let key = "a1".repeat(32);
+            let contents =
+                NetworkConfigService::wpa_supplicant_config(&wifi_config("omnect", &key));
+            assert!(contents.contains(&format!("\tpsk={key}\n")));
+        }
 
-    /// Create a rollback entry for network configuration changes
-    ///
-    /// # Arguments
-    /// * `network` - Network configuration to create rollback for
-    ///
-    /// # Returns
-    /// Result indicating success or failure
-    fn create_rollback(network: &NetworkConfigRequest) -> Result<()> {
-        let rollback = Rollback {
-            network_config: network.clone(),
-            deadline: SystemTime::now() + Duration::from_secs(ROLLBACK_TIMEOUT_SECS),
-        };
+        #[test]
+        fn psk_is_absent_from_logged_request() {
+            let mut request = create_valid_dhcp_config();
+            request.name = "wlan0".to_string();
+            request.wifi = Some(wifi_config("omnect", PSK));
 
-        info!("create rollback: {rollback:?}");
+            let logged = format!("{request:?}");
+            assert!(logged.contains("omnect"));
+            assert!(!logged.contains(PSK));
 
-        let path = network_rollback_file!();
+            let ini = NetworkConfigService::network_config_ini(&request).unwrap();
+            assert!(!format!("{ini:?}").contains(PSK));
+        }
 
-        serde_json::to_writer_pretty(
-            std::fs::OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(path)
-                .context(format!("failed to open rollback file for write: {path:?}"))?,
-            &rollback,
-        )
-        .context(format!("failed to serialize rollback: {path:?}"))
-    }
-}
+        #[test]
+        fn psk_is_absent_from_logged_rollback() {
+            let mut request = create_valid_dhcp_config();
+            request.name = "wlan0".to_string();
+            request.wifi = Some(wifi_config("omnect", PSK));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+            let rollback = Rollback::new(request, Duration::ZERO, &SystemClock);
 
-    fn create_valid_dhcp_config() -> NetworkConfigRequest {
-        NetworkConfigRequest {
-            is_server_addr: false,
-            ip_changed: false,
-            name: "eth0".to_string(),
-            dhcp: true,
-            previous_ip: Some("192.168.1.100".to_string()),
-            ip: None,
-            netmask: None,
-            gateway: vec![],
-            dns: vec![],
-            enable_rollback: None,
-            switching_to_dhcp: true,
+            assert!(!format!("{rollback:?}").contains(PSK));
         }
-    }
 
-    fn create_valid_static_config() -> NetworkConfigRequest {
-        NetworkConfigRequest {
-            is_server_addr: false,
-            ip_changed: false,
-            name: "eth0".to_string(),
-            dhcp: false,
-            previous_ip: Some("192.168.1.100".to_string()),
-            ip: Some("192.168.1.101".to_string()),
-            netmask: Some(24),
-            gateway: vec!["192.168.1.1".to_string()],
-            dns: vec!["8.8.8.8".to_string(), "8.8.4.4".to_string()],
-            enable_rollback: None,
-            switching_to_dhcp: false,
+        #[test]
+        fn psk_is_absent_from_stored_rollback() {
+            let mut request = create_valid_dhcp_config();
+            request.name = "wlan0".to_string();
+            request.wifi = Some(wifi_config("omnect", PSK));
+
+            let rollback = Rollback::new(request, Duration::ZERO, &SystemClock);
+            let stored = serde_json::to_string(&rollback).unwrap();
+
+            assert!(!stored.contains(PSK));
+            assert!(stored.contains("wlan0"));
         }
     }
 
-    mod validation {
+    mod export {
         use super::*;
 
         #[test]
-        fn valid_dhcp_config_passes() {
-            let request = create_valid_dhcp_config();
-            assert!(request.validate().is_ok());
+        fn network_file_is_read_from_mounted_directory() {
+            assert_eq!(
+                NetworkConfigService::mounted_network_file(Path::new(
+                    "/run/systemd/network/10-eth0.network"
+                )),
+                Some(
+                    crate::config::AppConfig::get()
+                        .paths
+                        .network_dir
+                        .join("10-eth0.network")
+                )
+            );
         }
 
         #[test]
-        fn valid_static_config_passes() {
-            let request = create_valid_static_config();
-            assert!(request.validate().is_ok());
+        fn path_without_file_name_is_ignored() {
+            assert_eq!(
+                NetworkConfigService::mounted_network_file(Path::new("/")),
+                None
+            );
         }
+    }
 
-        #[test]
-        fn empty_interface_name_fails() {
-            let mut request = create_valid_dhcp_config();
-            request.name = String::new();
-            assert!(request.validate().is_err());
-        }
+    mod ini_generation {
+        use super::*;
+        use tempfile::TempDir;
 
-        #[test]
-        fn netmask_above_32_fails() {
-            let mut request = create_valid_static_config();
-            request.netmask = Some(33);
-            assert!(request.validate().is_err());
+        fn dhcp_ini() -> Ini {
+            NetworkConfigService::network_config_ini(&create_valid_dhcp_config()).unwrap()
         }
 
         #[test]
-        fn netmask_at_boundary_passes() {
-            let mut request = create_valid_static_config();
-            request.netmask = Some(32);
-            assert!(request.validate().is_ok());
+        fn atomic_write_replaces_existing_config() {
+            let temp_dir = TempDir::new().expect("failed to create temp dir");
+            let config_path = temp_dir.path().join("10-eth0.network");
+            fs::write(&config_path, "[Match]\nName=old\n").unwrap();
+
+            NetworkConfigService::write_ini_atomically(&dhcp_ini(), &config_path)
+                .expect("failed to write network config");
+
+            let written = Ini::load_from_file(&config_path).expect("failed to load ini");
+            assert_eq!(written.get_from(Some("Network"), "DHCP"), Some("yes"));
+            assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
         }
 
         #[test]
-        fn netmask_zero_passes() {
-            let mut request = create_valid_static_config();
-            request.netmask = Some(0);
-            assert!(request.validate().is_ok());
+        fn interrupted_write_leaves_original_config_intact() {
+            let temp_dir = TempDir::new().expect("failed to create temp dir");
+            let config_path = temp_dir.path().join("10-eth0.network");
+            let original = "[Match]\nName=eth0\n\n[Network]\nAddress=192.168.1.100/24\n";
+            fs::write(&config_path, original).unwrap();
+
+            // interrupted before the rename
+            let temp_path = NetworkConfigService::write_ini_to_temp_file(&dhcp_ini(), &config_path)
+                .expect("failed to write temp file");
+
+            assert_eq!(fs::read_to_string(&config_path).unwrap(), original);
+            assert_eq!(temp_path.parent(), config_path.parent());
+            assert_ne!(
+                temp_path.extension().and_then(|e| e.to_str()),
+                Some("network")
+            );
         }
-    }
-
-    mod ini_generation {
-        use super::*;
-        use tempfile::TempDir;
 
         #[test]
         fn write_network_config_creates_valid_ini_for_dhcp() {
@@ -525,6 +2011,11 @@ mod tests {
                 dns: vec![],
                 enable_rollback: None,
                 switching_to_dhcp: true,
+                ipv6_accept_ra: None,
+                link_local_addressing: None,
+                dns_over_tls: None,
+                dnssec: None,
+                wifi: None,
             };
 
             // Use the internal write function logic but with a temp path
@@ -560,6 +2051,11 @@ mod tests {
                 dns: vec!["8.8.8.8".to_string(), "8.8.4.4".to_string()],
                 enable_rollback: None,
                 switching_to_dhcp: false,
+                ipv6_accept_ra: None,
+                link_local_addressing: None,
+                dns_over_tls: None,
+                dnssec: None,
+                wifi: None,
             };
 
             // Replicate the write logic
@@ -602,6 +2098,77 @@ mod tests {
         }
     }
 
+    mod network_config_ini {
+        use super::*;
+
+        fn network_value<'a>(ini: &'a Ini, key: &str) -> Option<&'a str> {
+            ini.section(Some("Network"))
+                .and_then(|section| section.get(key))
+        }
+
+        #[test]
+        fn emits_ipv6_and_link_local_options_when_set() {
+            let mut config = create_valid_static_config();
+            config.ipv6_accept_ra = Some(false);
+            config.link_local_addressing = Some("ipv4".to_string());
+
+            let ini =
+                NetworkConfigService::network_config_ini(&config).expect("failed to build ini");
+
+            assert_eq!(network_value(&ini, "Address"), Some("192.168.1.101/24"));
+            assert_eq!(network_value(&ini, "IPv6AcceptRA"), Some("no"));
+            assert_eq!(network_value(&ini, "LinkLocalAddressing"), Some("ipv4"));
+        }
+
+        #[test]
+        fn emits_ipv6_accept_ra_yes() {
+            let mut config = create_valid_dhcp_config();
+            config.ipv6_accept_ra = Some(true);
+
+            let ini =
+                NetworkConfigService::network_config_ini(&config).expect("failed to build ini");
+
+            assert_eq!(network_value(&ini, "DHCP"), Some("yes"));
+            assert_eq!(network_value(&ini, "IPv6AcceptRA"), Some("yes"));
+        }
+
+        #[test]
+        fn omits_options_when_not_set() {
+            let config = create_valid_dhcp_config();
+
+            let ini =
+                NetworkConfigService::network_config_ini(&config).expect("failed to build ini");
+
+            assert_eq!(network_value(&ini, "IPv6AcceptRA"), None);
+            assert_eq!(network_value(&ini, "LinkLocalAddressing"), None);
+            assert_eq!(network_value(&ini, "DNSOverTLS"), None);
+            assert_eq!(network_value(&ini, "DNSSEC"), None);
+        }
+
+        #[test]
+        fn emits_dns_over_tls_and_dnssec_when_set() {
+            let mut config = create_valid_static_config();
+            config.dns_over_tls = Some(true);
+            config.dnssec = Some(true);
+
+            let ini =
+                NetworkConfigService::network_config_ini(&config).expect("failed to build ini");
+
+            assert_eq!(network_value(&ini, "DNSOverTLS"), Some("yes"));
+            assert_eq!(network_value(&ini, "DNSSEC"), Some("yes"));
+
+            let mut config = create_valid_static_config();
+            config.dns_over_tls = Some(false);
+            config.dnssec = Some(false);
+
+            let ini =
+                NetworkConfigService::network_config_ini(&config).expect("failed to build ini");
+
+            assert_eq!(network_value(&ini, "DNSOverTLS"), Some("no"));
+            assert_eq!(network_value(&ini, "DNSSEC"), Some("no"));
+        }
+    }
+
     mod rollback_response {
         use super::*;
 
@@ -611,6 +2178,7 @@ mod tests {
                 rollback_timeout_seconds: ROLLBACK_TIMEOUT_SECS,
                 ui_port: 1977,
                 rollback_enabled: true,
+                healthcheck_require_auth: false,
             };
 
             assert_eq!(response.rollback_timeout_seconds, 90);
@@ -622,6 +2190,7 @@ mod tests {
                 rollback_timeout_seconds: ROLLBACK_TIMEOUT_SECS,
                 ui_port: 1977,
                 rollback_enabled: true,
+                healthcheck_require_auth: false,
             };
 
             assert!(response.rollback_enabled);
@@ -633,12 +2202,150 @@ mod tests {
                 rollback_timeout_seconds: ROLLBACK_TIMEOUT_SECS,
                 ui_port: 1977,
                 rollback_enabled: false,
+                healthcheck_require_auth: false,
             };
 
             assert!(!response.rollback_enabled);
         }
     }
 
+    mod rollback_deadline {
+        use super::*;
+        use crate::clock::FakeClock;
+
+        #[test]
+        fn deadline_is_rollback_timeout_after_creation() {
+            let clock = FakeClock::new(1_700_000_000);
+            let rollback = Rollback::new(create_valid_dhcp_config(), Duration::ZERO, &clock);
+
+            assert_eq!(
+                rollback.deadline,
+                clock.now() + Duration::from_secs(ROLLBACK_TIMEOUT_SECS)
+            );
+            assert_eq!(
+                rollback.remaining_time(&clock),
+                Some(Duration::from_secs(ROLLBACK_TIMEOUT_SECS))
+            );
+        }
+
+        #[test]
+        fn remaining_time_decreases_with_clock() {
+            let clock = FakeClock::new(1_700_000_000);
+            let rollback = Rollback::new(create_valid_dhcp_config(), Duration::ZERO, &clock);
+
+            clock.advance(Duration::from_secs(60));
+
+            assert_eq!(
+                rollback.remaining_time(&clock),
+                Some(Duration::from_secs(ROLLBACK_TIMEOUT_SECS - 60))
+            );
+        }
+
+        #[test]
+        fn restart_margin_extends_deadline() {
+            let clock = FakeClock::new(1_700_000_000);
+            let margin = Duration::from_secs(5);
+            let rollback = Rollback::new(create_valid_dhcp_config(), margin, &clock);
+
+            assert_eq!(
+                rollback.deadline,
+                clock.now() + Duration::from_secs(ROLLBACK_TIMEOUT_SECS) + margin
+            );
+            assert_eq!(
+                rollback.remaining_seconds(&clock),
+                ROLLBACK_TIMEOUT_SECS + 5
+            );
+        }
+
+        #[test]
+        fn advertised_time_matches_stored_deadline_after_restart() {
+            let clock = FakeClock::new(1_700_000_000);
+            let rollback =
+                Rollback::new(create_valid_dhcp_config(), Duration::from_secs(5), &clock);
+            let advertised = rollback.remaining_seconds(&clock);
+
+            // the restarted server reads the rollback back from the file
+            let stored: Rollback =
+                serde_json::from_str(&serde_json::to_string(&rollback).unwrap()).unwrap();
+            assert_eq!(stored.deadline, rollback.deadline);
+
+            let restart_duration = Duration::from_secs(3);
+            clock.advance(restart_duration);
+
+            assert_eq!(
+                RollbackStatus::of(Some(&stored), &clock).remaining_seconds,
+                Some(advertised - restart_duration.as_secs())
+            );
+            assert_eq!(
+                clock.now() + Duration::from_secs(advertised - restart_duration.as_secs()),
+                stored.deadline
+            );
+        }
+
+        #[test]
+        fn remaining_time_is_none_after_deadline() {
+            let clock = FakeClock::new(1_700_000_000);
+            let rollback = Rollback::new(create_valid_dhcp_config(), Duration::ZERO, &clock);
+
+            clock.advance(Duration::from_secs(ROLLBACK_TIMEOUT_SECS + 1));
+
+            assert_eq!(rollback.remaining_time(&clock), None);
+        }
+    }
+
+    mod rollback_status {
+        use super::*;
+        use crate::clock::FakeClock;
+
+        #[test]
+        fn no_rollback_is_not_pending() {
+            let clock = FakeClock::new(1_700_000_000);
+
+            assert_eq!(
+                RollbackStatus::of(None, &clock),
+                RollbackStatus {
+                    pending: false,
+                    remaining_seconds: None,
+                    adapter: None,
+                }
+            );
+        }
+
+        #[test]
+        fn future_deadline_reports_remaining_time() {
+            let clock = FakeClock::new(1_700_000_000);
+            let rollback = Rollback::new(create_valid_dhcp_config(), Duration::ZERO, &clock);
+
+            clock.advance(Duration::from_secs(30));
+
+            assert_eq!(
+                RollbackStatus::of(Some(&rollback), &clock),
+                RollbackStatus {
+                    pending: true,
+                    remaining_seconds: Some(ROLLBACK_TIMEOUT_SECS - 30),
+                    adapter: Some(rollback.network_config.name.clone()),
+                }
+            );
+        }
+
+        #[test]
+        fn past_deadline_is_due() {
+            let clock = FakeClock::new(1_700_000_000);
+            let rollback = Rollback::new(create_valid_dhcp_config(), Duration::ZERO, &clock);
+
+            clock.advance(Duration::from_secs(ROLLBACK_TIMEOUT_SECS + 1));
+
+            assert_eq!(
+                RollbackStatus::of(Some(&rollback), &clock),
+                RollbackStatus {
+                    pending: true,
+                    remaining_seconds: Some(0),
+                    adapter: Some(rollback.network_config.name.clone()),
+                }
+            );
+        }
+    }
+
     mod rollback_processing {
         use super::*;
         use crate::omnect_device_service_client::MockDeviceServiceClient;
@@ -649,11 +2356,234 @@ mod tests {
 
             // This verifies the function accepts an owned MockDeviceServiceClient
             // and completes correctly when the rollback file is missing.
-            let result = NetworkConfigService::process_pending_rollback(service_client).await;
+            let result =
+                NetworkConfigService::process_pending_rollback(service_client, &SystemClock).await;
             assert!(result.is_ok());
         }
     }
 
+    mod rollback_history {
+        use super::*;
+        use crate::clock::FakeClock;
+        use tempfile::TempDir;
+
+        fn executed(timestamp: u64, adapter: &str) -> ExecutedRollback {
+            ExecutedRollback {
+                timestamp,
+                adapter: adapter.to_string(),
+            }
+        }
+
+        #[test]
+        fn executed_rollbacks_are_appended() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("history.json");
+
+            NetworkConfigService::append_rollback_history(&path, executed(1, "eth0"), 10).unwrap();
+            NetworkConfigService::append_rollback_history(&path, executed(2, "wlan0"), 10).unwrap();
+
+            assert_eq!(
+                NetworkConfigService::read_rollback_history(&path),
+                vec![executed(1, "eth0"), executed(2, "wlan0")]
+            );
+        }
+
+        #[test]
+        fn oldest_entries_are_pruned_beyond_cap() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("history.json");
+
+            for timestamp in 1..=5 {
+                NetworkConfigService::append_rollback_history(
+                    &path,
+                    executed(timestamp, "eth0"),
+                    3,
+                )
+                .unwrap();
+            }
+
+            assert_eq!(
+                NetworkConfigService::read_rollback_history(&path),
+                vec![
+                    executed(3, "eth0"),
+                    executed(4, "eth0"),
+                    executed(5, "eth0")
+                ]
+            );
+        }
+
+        #[test]
+        fn zero_cap_disables_history() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("history.json");
+
+            NetworkConfigService::append_rollback_history(&path, executed(1, "eth0"), 0).unwrap();
+
+            assert!(!path.exists());
+        }
+
+        #[test]
+        fn corrupt_history_is_replaced() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("history.json");
+            fs::write(&path, "not json").unwrap();
+
+            NetworkConfigService::append_rollback_history(&path, executed(1, "eth0"), 10).unwrap();
+
+            assert_eq!(
+                NetworkConfigService::read_rollback_history(&path),
+                vec![executed(1, "eth0")]
+            );
+        }
+
+        #[test]
+        fn record_executed_rollback_uses_clock_and_adapter() {
+            let clock = FakeClock::new(1_700_000_000);
+
+            NetworkConfigService::record_executed_rollback("eth-history-test", &clock).unwrap();
+
+            assert!(
+                NetworkConfigService::rollback_history()
+                    .contains(&executed(1_700_000_000, "eth-history-test"))
+            );
+        }
+    }
+
+    mod concurrency {
+        use super::*;
+        use crate::omnect_device_service_client::{
+            Ipv4Info, MockDeviceServiceClient, NetworkInterface, NetworkStatus, Status, SystemInfo,
+            UpdateValidationStatus,
+        };
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::time::timeout;
+
+        const ADAPTER: &str = "eth-concurrency-test";
+        /// Distribution default the device service reports before the UI wrote a config
+        const DEFAULT_FILE: &str = "90-eth-concurrency-test.network";
+
+        fn status_with_adapter() -> Status {
+            Status {
+                network_status: NetworkStatus {
+                    network_interfaces: vec![NetworkInterface {
+                        online: true,
+                        ipv4: Ipv4Info { addrs: vec![] },
+                        file: Path::new("/etc/systemd/network").join(DEFAULT_FILE),
+                        mac: "00:11:22:33:44:55".to_string(),
+                        name: ADAPTER.to_string(),
+                    }],
+                },
+                system_info: SystemInfo {
+                    fleet_id: None,
+                    omnect_device_service_version: "0.40.0".to_string(),
+                    boot_time: None,
+                },
+                update_validation_status: UpdateValidationStatus {
+                    status: "NoUpdate".to_string(),
+                },
+            }
+        }
+
+        #[tokio::test]
+        async fn adapter_lock_is_exclusive_and_removed_once_released() {
+            let guard = NetworkConfigService::lock_adapter("eth-lock-test").await;
+
+            assert!(
+                timeout(
+                    Duration::from_millis(10),
+                    NetworkConfigService::lock_adapter("eth-lock-test")
+                )
+                .await
+                .is_err()
+            );
+            let other = timeout(
+                Duration::from_millis(10),
+                NetworkConfigService::lock_adapter("wlan-lock-test"),
+            )
+            .await;
+            assert!(other.is_ok());
+
+            drop(guard);
+            assert!(!ADAPTER_LOCKS.lock().unwrap().contains_key("eth-lock-test"));
+        }
+
+        #[tokio::test]
+        async fn concurrent_requests_for_same_adapter_keep_backup_consistent() {
+            // The device service calls are slow, so overlapping requests would both
+            // back up the default file before either has written its config.
+            let active = Arc::new(AtomicUsize::new(0));
+            let max_active = Arc::new(AtomicUsize::new(0));
+            let busy = {
+                let (active, max_active) = (active.clone(), max_active.clone());
+                move || {
+                    let (active, max_active) = (active.clone(), max_active.clone());
+                    async move {
+                        let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_active.fetch_max(now, Ordering::SeqCst);
+                        sleep(Duration::from_millis(50)).await;
+                        active.fetch_sub(1, Ordering::SeqCst);
+                    }
+                }
+            };
+
+            let mut service_client = MockDeviceServiceClient::new();
+            {
+                let busy = busy.clone();
+                service_client.expect_status().returning(move || {
+                    let busy = busy();
+                    Box::pin(async move {
+                        busy.await;
+                        Ok(status_with_adapter())
+                    })
+                });
+            }
+            service_client.expect_reload_network().returning(move || {
+                let busy = busy();
+                Box::pin(async move {
+                    busy.await;
+                    Ok(())
+                })
+            });
+
+            let config_file = network_config_file!(ADAPTER);
+            let backup_file = network_backup_file!(ADAPTER);
+            let _ = fs::remove_file(&config_file);
+            let _ = fs::remove_file(&backup_file);
+            fs::write(network_path!(DEFAULT_FILE), "[Network]\nDHCP=yes\n").unwrap();
+
+            let mut dhcp = create_valid_dhcp_config();
+            dhcp.name = ADAPTER.to_string();
+            let mut static_ip = create_valid_static_config();
+            static_ip.name = ADAPTER.to_string();
+
+            let (first, second) = tokio::join!(
+                NetworkConfigService::set_network_config(&service_client, &dhcp, &SystemClock),
+                NetworkConfigService::set_network_config(&service_client, &static_ip, &SystemClock),
+            );
+
+            assert!(first.is_ok());
+            assert!(second.is_ok());
+            assert_eq!(max_active.load(Ordering::SeqCst), 1);
+
+            // the request applied last backed up the config of the one applied first
+            let written = [dhcp, static_ip].map(|request| {
+                let mut content = Vec::new();
+                NetworkConfigService::network_config_ini(&request)
+                    .unwrap()
+                    .write_to(&mut content)
+                    .unwrap();
+                String::from_utf8(content).unwrap()
+            });
+            let config = fs::read_to_string(&config_file).unwrap();
+            let backup = fs::read_to_string(&backup_file).unwrap();
+            assert!(written.contains(&config));
+            assert!(written.contains(&backup));
+            assert_ne!(config, backup);
+
+            assert!(!ADAPTER_LOCKS.lock().unwrap().contains_key(ADAPTER));
+        }
+    }
+
     mod serde {
         use super::*;
 
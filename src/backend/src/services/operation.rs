@@ -0,0 +1,92 @@
+//! Correlation of long-running device operations
+//!
+//! Reboot and update hand out an operation id, which the client can use to
+//! query the status of exactly this operation later, e.g. while reconnecting.
+//! Operations end with a reboot clearing the volatile record, so an unknown id
+//! means the operation is over (or never existed).
+
+use crate::services::marker;
+use omnect_ui_core::types::DeviceOperation;
+use serde::Serialize;
+
+/// Response of a started device operation
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStarted {
+    pub operation_id: String,
+}
+
+/// Status of a device operation known to omnect-ui
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationState {
+    /// Accepted by the device service, the device did not reboot yet
+    InProgress,
+}
+
+/// Status report of a device operation
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStatus {
+    pub operation_id: String,
+    pub operation: DeviceOperation,
+    pub state: OperationState,
+}
+
+pub struct OperationService;
+
+impl OperationService {
+    /// Record a device operation accepted by the device service
+    ///
+    /// # Arguments
+    /// * `operation` - Operation ending with a reboot
+    pub fn start(operation: DeviceOperation) -> OperationStarted {
+        OperationStarted {
+            operation_id: marker::record_device_operation(operation),
+        }
+    }
+
+    /// Status of the operation with the given id, None if it is unknown
+    ///
+    /// # Arguments
+    /// * `operation_id` - Id returned when the operation was started
+    pub fn status(operation_id: &str) -> Option<OperationStatus> {
+        marker::last_device_operation_record()
+            .filter(|record| record.id == operation_id)
+            .map(|record| OperationStatus {
+                operation_id: record.id,
+                operation: record.operation,
+                state: OperationState::InProgress,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // single test, as all operations share one volatile marker file
+    #[test]
+    fn only_the_last_started_operation_is_reported() {
+        let first = OperationService::start(DeviceOperation::Reboot);
+        let second = OperationService::start(DeviceOperation::Update);
+
+        assert_ne!(first.operation_id, second.operation_id);
+        assert!(OperationService::status(&first.operation_id).is_none());
+        assert_eq!(
+            OperationService::status(&second.operation_id),
+            Some(OperationStatus {
+                operation_id: second.operation_id.clone(),
+                operation: DeviceOperation::Update,
+                state: OperationState::InProgress,
+            })
+        );
+        assert_eq!(
+            marker::last_device_operation(),
+            Some(DeviceOperation::Update)
+        );
+
+        marker::LAST_DEVICE_OPERATION.clear();
+        assert!(OperationService::status(&second.operation_id).is_none());
+    }
+}
@@ -0,0 +1,285 @@
+//! Manual republish of the device state
+//!
+//! On request the device service publishes all its data again, e.g. to
+//! refresh stale data in the UI after a hiccup. Manual requests are throttled,
+//! so repeated clicks don't flood the device service. Loading the UI triggers a
+//! republish as well, depending on the configured [`IndexRepublish`] mode.
+
+use crate::clock::{Clock, SystemClock};
+use anyhow::{Result, bail};
+use log::{debug, error};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Minimum time between two republishes requested by the UI
+pub const REPUBLISH_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Republish behavior when the UI is loaded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndexRepublish {
+    /// Republish on every load
+    #[default]
+    Always,
+    /// Republish unless the last republish was less than [`REPUBLISH_MIN_INTERVAL`] ago
+    Debounced,
+    /// Never republish, data arrives by the regular publish flow or a manual refresh
+    Never,
+}
+
+impl IndexRepublish {
+    /// Whether loading the UI republishes, checks the index slot of `throttle` if debounced
+    pub fn should_republish(self, throttle: &RepublishThrottle) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Debounced => throttle.is_due(RepublishTrigger::Index),
+            Self::Never => false,
+        }
+    }
+}
+
+impl FromStr for IndexRepublish {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "always" => Ok(Self::Always),
+            "debounced" => Ok(Self::Debounced),
+            "never" => Ok(Self::Never),
+            _ => bail!("invalid index republish mode '{s}', expected always, debounced or never"),
+        }
+    }
+}
+
+impl fmt::Display for IndexRepublish {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::Debounced => write!(f, "debounced"),
+            Self::Never => write!(f, "never"),
+        }
+    }
+}
+
+/// Source of a republish, each source is throttled on its own
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepublishTrigger {
+    /// "Refresh" command of the UI
+    Manual,
+    /// Loading the UI
+    Index,
+}
+
+/// Throttle of republishes requested by the UI
+#[derive(Clone)]
+pub struct RepublishThrottle {
+    inner: Arc<RepublishThrottleInner>,
+}
+
+struct RepublishThrottleInner {
+    min_interval: Duration,
+    clock: Arc<dyn Clock>,
+    last_manual_republish: Mutex<Option<Duration>>,
+    last_index_republish: Mutex<Option<Duration>>,
+}
+
+impl RepublishThrottle {
+    /// Create a new RepublishThrottle
+    ///
+    /// # Arguments
+    /// * `min_interval` - Minimum time between two republishes
+    pub fn new(min_interval: Duration) -> Self {
+        Self::with_clock(min_interval, Arc::new(SystemClock))
+    }
+
+    /// Create a new RepublishThrottle using the given clock
+    ///
+    /// # Arguments
+    /// * `min_interval` - Minimum time between two republishes
+    /// * `clock` - Time source
+    pub fn with_clock(min_interval: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inner: Arc::new(RepublishThrottleInner {
+                min_interval,
+                clock,
+                last_manual_republish: Mutex::new(None),
+                last_index_republish: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Whether the minimum interval since the last republish of `trigger` has passed
+    ///
+    /// Returns false if the republish has to be skipped. Only a succeeded
+    /// republish takes the slot, see [`Self::record`].
+    pub fn is_due(&self, trigger: RepublishTrigger) -> bool {
+        let Ok(last_republish) = self.last_republish(trigger).lock() else {
+            error!("failed to lock republish throttle");
+            return false;
+        };
+
+        let now = self.inner.clock.now_since_epoch();
+
+        match *last_republish {
+            Some(last) if now.saturating_sub(last) < self.inner.min_interval => {
+                debug!(
+                    "{trigger:?} republish skipped: last one less than {}s ago",
+                    self.inner.min_interval.as_secs()
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Record a succeeded republish of `trigger`
+    pub fn record(&self, trigger: RepublishTrigger) {
+        let Ok(mut last_republish) = self.last_republish(trigger).lock() else {
+            error!("failed to lock republish throttle");
+            return;
+        };
+
+        *last_republish = Some(self.inner.clock.now_since_epoch());
+    }
+
+    fn last_republish(&self, trigger: RepublishTrigger) -> &Mutex<Option<Duration>> {
+        match trigger {
+            RepublishTrigger::Manual => &self.inner.last_manual_republish,
+            RepublishTrigger::Index => &self.inner.last_index_republish,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+
+    fn throttle() -> (RepublishThrottle, Arc<FakeClock>) {
+        let clock = Arc::new(FakeClock::new(1_700_000_000));
+        (
+            RepublishThrottle::with_clock(REPUBLISH_MIN_INTERVAL, clock.clone()),
+            clock,
+        )
+    }
+
+    /// Check and record a manual republish like the republish handler does
+    fn republish(throttle: &RepublishThrottle) -> bool {
+        let due = throttle.is_due(RepublishTrigger::Manual);
+        if due {
+            throttle.record(RepublishTrigger::Manual);
+        }
+        due
+    }
+
+    #[test]
+    fn first_republish_passes() {
+        let (throttle, _) = throttle();
+
+        assert!(republish(&throttle));
+    }
+
+    #[test]
+    fn rapid_repeats_are_skipped() {
+        let (throttle, clock) = throttle();
+
+        assert!(republish(&throttle));
+        clock.advance(REPUBLISH_MIN_INTERVAL - Duration::from_secs(1));
+        assert!(!republish(&throttle));
+    }
+
+    #[test]
+    fn republish_passes_after_min_interval() {
+        let (throttle, clock) = throttle();
+
+        assert!(republish(&throttle));
+        clock.advance(REPUBLISH_MIN_INTERVAL);
+        assert!(republish(&throttle));
+    }
+
+    #[test]
+    fn skipped_republishes_dont_extend_the_interval() {
+        let (throttle, clock) = throttle();
+
+        assert!(republish(&throttle));
+        clock.advance(Duration::from_secs(3));
+        assert!(!republish(&throttle));
+        clock.advance(Duration::from_secs(2));
+        assert!(republish(&throttle));
+    }
+
+    #[test]
+    fn failed_republishes_dont_take_the_slot() {
+        let (throttle, _) = throttle();
+
+        // nothing recorded, as the republish failed
+        assert!(throttle.is_due(RepublishTrigger::Manual));
+        assert!(throttle.is_due(RepublishTrigger::Manual));
+    }
+
+    #[test]
+    fn triggers_dont_share_the_slot() {
+        let (throttle, _) = throttle();
+
+        throttle.record(RepublishTrigger::Index);
+        assert!(throttle.is_due(RepublishTrigger::Manual));
+        throttle.record(RepublishTrigger::Manual);
+        assert!(!throttle.is_due(RepublishTrigger::Index));
+        assert!(!throttle.is_due(RepublishTrigger::Manual));
+    }
+
+    mod index_republish {
+        use super::*;
+
+        #[test]
+        fn always_republishes_on_every_load() {
+            let (throttle, _) = throttle();
+
+            assert!(IndexRepublish::Always.should_republish(&throttle));
+            assert!(IndexRepublish::Always.should_republish(&throttle));
+        }
+
+        #[test]
+        fn never_skips_republish() {
+            let (throttle, _) = throttle();
+
+            assert!(!IndexRepublish::Never.should_republish(&throttle));
+            // a skipped republish doesn't block a manual one
+            assert!(republish(&throttle));
+        }
+
+        #[test]
+        fn debounced_skips_rapid_loads() {
+            let (throttle, clock) = throttle();
+
+            assert!(IndexRepublish::Debounced.should_republish(&throttle));
+            throttle.record(RepublishTrigger::Index);
+            assert!(!IndexRepublish::Debounced.should_republish(&throttle));
+            clock.advance(REPUBLISH_MIN_INTERVAL);
+            assert!(IndexRepublish::Debounced.should_republish(&throttle));
+        }
+
+        #[test]
+        fn debounced_ignores_manual_republishes() {
+            let (throttle, _) = throttle();
+
+            assert!(republish(&throttle));
+            assert!(IndexRepublish::Debounced.should_republish(&throttle));
+        }
+
+        #[test]
+        fn parses_config_values() {
+            for mode in [
+                IndexRepublish::Always,
+                IndexRepublish::Debounced,
+                IndexRepublish::Never,
+            ] {
+                assert_eq!(mode.to_string().parse::<IndexRepublish>().unwrap(), mode);
+            }
+            assert!("sometimes".parse::<IndexRepublish>().is_err());
+        }
+    }
+}
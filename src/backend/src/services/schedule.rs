@@ -0,0 +1,361 @@
+//! Scheduled device operations
+//!
+//! Reboot and update can be requested for a later point in time, e.g. for a
+//! maintenance window at night. Pending operations are kept in memory only:
+//! they can be cancelled until they are due and are dropped when the omnect-ui
+//! process restarts. The scheduler is created once per process and runs the
+//! operations on the runtime of `main`, so pending operations survive the
+//! server restart after a network change, which stops the server workers
+//! together with the tasks spawned on them.
+
+use crate::clock::{Clock, SystemClock};
+use anyhow::{Context, Result, anyhow, ensure};
+use log::{error, info};
+use omnect_ui_core::types::DeviceOperation;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime},
+};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use tokio::{runtime::Handle, task::JoinHandle};
+
+/// Longest wait before the wall clock is checked again
+///
+/// The scheduled time is wall clock time, which may be adjusted (e.g. by NTP)
+/// while waiting. Such an adjustment delays or advances an operation by at
+/// most this interval.
+const RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Query parameters of operations that can be scheduled
+#[derive(Debug, Default, Deserialize)]
+pub struct ScheduleQuery {
+    /// Point in time (RFC 3339) to run the operation at, immediately if unset
+    pub scheduled_at: Option<String>,
+}
+
+/// Operation waiting for its scheduled time
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledOperation {
+    pub schedule_id: String,
+    pub operation: DeviceOperation,
+    pub scheduled_at: String,
+}
+
+struct PendingOperation {
+    at: SystemTime,
+    scheduled: ScheduledOperation,
+    task: JoinHandle<()>,
+}
+
+/// Scheduler of device operations
+#[derive(Clone)]
+pub struct Scheduler {
+    inner: Arc<SchedulerInner>,
+}
+
+struct SchedulerInner {
+    clock: Arc<dyn Clock>,
+    runtime: Handle,
+    pending: Mutex<HashMap<String, PendingOperation>>,
+}
+
+impl SchedulerInner {
+    fn pending(&self) -> Result<MutexGuard<'_, HashMap<String, PendingOperation>>> {
+        self.pending
+            .lock()
+            .map_err(|_| anyhow!("failed to lock scheduled operations"))
+    }
+}
+
+impl Scheduler {
+    /// Create a new Scheduler running operations on the given runtime
+    ///
+    /// # Arguments
+    /// * `runtime` - Runtime outliving server restarts, e.g. the one of `main`
+    pub fn new(runtime: Handle) -> Self {
+        Self::with_clock(Arc::new(SystemClock), runtime)
+    }
+
+    /// Create a new Scheduler using the given clock
+    ///
+    /// # Arguments
+    /// * `clock` - Time source
+    /// * `runtime` - Runtime outliving server restarts, e.g. the one of `main`
+    pub fn with_clock(clock: Arc<dyn Clock>, runtime: Handle) -> Self {
+        Self {
+            inner: Arc::new(SchedulerInner {
+                clock,
+                runtime,
+                pending: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Parse a requested point in time, which has to be in the future
+    ///
+    /// # Arguments
+    /// * `scheduled_at` - RFC 3339 timestamp, e.g. `2026-01-31T02:00:00+01:00`
+    pub fn parse_scheduled_at(&self, scheduled_at: &str) -> Result<SystemTime> {
+        let at: SystemTime = OffsetDateTime::parse(scheduled_at, &Rfc3339)
+            .with_context(|| {
+                format!("invalid scheduled_at '{scheduled_at}', expected an RFC 3339 timestamp")
+            })?
+            .into();
+
+        ensure!(
+            at > self.inner.clock.now(),
+            "scheduled_at '{scheduled_at}' is not in the future"
+        );
+
+        Ok(at)
+    }
+
+    /// Run an operation at the given point in time
+    ///
+    /// # Arguments
+    /// * `operation` - Operation to report in the list of pending operations
+    /// * `at` - Point in time to run the operation at
+    /// * `action` - Performs the operation, a failure is logged
+    pub fn schedule<F>(
+        &self,
+        operation: DeviceOperation,
+        at: SystemTime,
+        action: F,
+    ) -> Result<ScheduledOperation>
+    where
+        F: Future<Output = Result<()>> + Send + 'static,
+    {
+        let scheduled = ScheduledOperation {
+            schedule_id: uuid::Uuid::new_v4().to_string(),
+            operation,
+            scheduled_at: OffsetDateTime::from(at)
+                .format(&Rfc3339)
+                .context("failed to format scheduled time")?,
+        };
+
+        // hold the lock until the operation is registered, so that even a due
+        // task finds it
+        let mut pending = self.inner.pending()?;
+
+        let inner = self.inner.clone();
+        let schedule_id = scheduled.schedule_id.clone();
+        let task = self.inner.runtime.spawn(async move {
+            while let Some(remaining) = at
+                .duration_since(inner.clock.now())
+                .ok()
+                .filter(|remaining| !remaining.is_zero())
+            {
+                tokio::time::sleep(remaining.min(RECHECK_INTERVAL)).await;
+            }
+
+            // deregister before running, a cancel request is too late from now on
+            match inner.pending() {
+                Ok(mut pending) if pending.remove(&schedule_id).is_some() => {}
+                Ok(_) => return,
+                Err(e) => {
+                    error!("scheduled {operation} ({schedule_id}) skipped: {e:#}");
+                    return;
+                }
+            }
+
+            info!("run scheduled {operation} ({schedule_id})");
+            if let Err(e) = action.await {
+                error!("scheduled {operation} ({schedule_id}) failed: {e:#}");
+            }
+        });
+
+        info!(
+            "scheduled {operation} ({}) at {}",
+            scheduled.schedule_id, scheduled.scheduled_at
+        );
+        pending.insert(
+            scheduled.schedule_id.clone(),
+            PendingOperation {
+                at,
+                scheduled: scheduled.clone(),
+                task,
+            },
+        );
+
+        Ok(scheduled)
+    }
+
+    /// Operations waiting for their scheduled time, the next one first
+    pub fn pending(&self) -> Result<Vec<ScheduledOperation>> {
+        let pending = self.inner.pending()?;
+        let mut operations: Vec<_> = pending.values().collect();
+        operations.sort_by_key(|operation| operation.at);
+
+        Ok(operations
+            .into_iter()
+            .map(|operation| operation.scheduled.clone())
+            .collect())
+    }
+
+    /// Cancel a pending operation
+    ///
+    /// Returns false if there is no pending operation with the given id.
+    ///
+    /// # Arguments
+    /// * `schedule_id` - Id returned when the operation was scheduled
+    pub fn cancel(&self, schedule_id: &str) -> Result<bool> {
+        let Some(operation) = self.inner.pending()?.remove(schedule_id) else {
+            return Ok(false);
+        };
+
+        operation.task.abort();
+        info!(
+            "cancelled scheduled {} ({schedule_id})",
+            operation.scheduled.operation
+        );
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const SHORT_DELAY: Duration = Duration::from_millis(100);
+
+    fn flagging_action(flag: Arc<AtomicBool>) -> impl Future<Output = Result<()>> {
+        async move {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    mod parse_scheduled_at {
+        use super::*;
+
+        fn scheduler() -> Scheduler {
+            // 2023-11-14T22:13:20Z
+            Scheduler::with_clock(Arc::new(FakeClock::new(1_700_000_000)), Handle::current())
+        }
+
+        #[tokio::test]
+        async fn accepts_future_timestamps() {
+            let at = scheduler()
+                .parse_scheduled_at("2023-11-15T02:00:00+01:00")
+                .unwrap();
+
+            assert_eq!(
+                at,
+                SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_010_000)
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_past_timestamps() {
+            let err = scheduler()
+                .parse_scheduled_at("2023-11-14T22:00:00Z")
+                .unwrap_err();
+
+            assert!(err.to_string().contains("not in the future"));
+        }
+
+        #[tokio::test]
+        async fn rejects_invalid_timestamps() {
+            assert!(scheduler().parse_scheduled_at("tomorrow 2am").is_err());
+            assert!(scheduler().parse_scheduled_at("2023-11-15 02:00").is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn due_operation_runs_and_is_removed() {
+        let scheduler = Scheduler::new(Handle::current());
+        let ran = Arc::new(AtomicBool::new(false));
+
+        let scheduled = scheduler
+            .schedule(
+                DeviceOperation::Reboot,
+                SystemTime::now() + SHORT_DELAY,
+                flagging_action(ran.clone()),
+            )
+            .unwrap();
+        assert_eq!(scheduler.pending().unwrap(), vec![scheduled]);
+
+        tokio::time::sleep(SHORT_DELAY * 5).await;
+
+        assert!(ran.load(Ordering::SeqCst));
+        assert!(scheduler.pending().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_operation_does_not_run() {
+        let scheduler = Scheduler::new(Handle::current());
+        let ran = Arc::new(AtomicBool::new(false));
+
+        let scheduled = scheduler
+            .schedule(
+                DeviceOperation::Update,
+                SystemTime::now() + SHORT_DELAY,
+                flagging_action(ran.clone()),
+            )
+            .unwrap();
+
+        assert!(scheduler.cancel(&scheduled.schedule_id).unwrap());
+        assert!(!scheduler.cancel(&scheduled.schedule_id).unwrap());
+
+        tokio::time::sleep(SHORT_DELAY * 5).await;
+
+        assert!(!ran.load(Ordering::SeqCst));
+        assert!(scheduler.pending().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pending_operations_are_listed_next_first() {
+        let scheduler = Scheduler::new(Handle::current());
+        let ran = Arc::new(AtomicBool::new(false));
+        let now = SystemTime::now();
+
+        let later = scheduler
+            .schedule(
+                DeviceOperation::Update,
+                now + Duration::from_secs(7200),
+                flagging_action(ran.clone()),
+            )
+            .unwrap();
+        let next = scheduler
+            .schedule(
+                DeviceOperation::Reboot,
+                now + Duration::from_secs(3600),
+                flagging_action(ran.clone()),
+            )
+            .unwrap();
+
+        assert_eq!(scheduler.pending().unwrap(), vec![next, later]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn due_time_follows_wall_clock_adjustments() {
+        let clock = Arc::new(FakeClock::new(1_700_000_000));
+        let scheduler = Scheduler::with_clock(clock.clone(), Handle::current());
+        let ran = Arc::new(AtomicBool::new(false));
+
+        scheduler
+            .schedule(
+                DeviceOperation::Reboot,
+                clock.now() + Duration::from_secs(3600),
+                flagging_action(ran.clone()),
+            )
+            .unwrap();
+
+        // the wall clock stood still (e.g. was set back), so it is not due yet
+        tokio::time::sleep(Duration::from_secs(7200)).await;
+        assert!(!ran.load(Ordering::SeqCst));
+
+        // the wall clock jumped forward past the scheduled time
+        clock.advance(Duration::from_secs(3600));
+        tokio::time::sleep(RECHECK_INTERVAL * 2).await;
+        assert!(ran.load(Ordering::SeqCst));
+        assert!(scheduler.pending().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::config_with;
+use omnect_ui::{
+    api::Api,
+    config::{AppConfig, NetworkInterfaceConfig},
+};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+fn config() -> &'static AppConfig {
+    config_with(&[
+        ("KEYCLOAK_URL", "https://keycloak.example.com/realms/fleet/"),
+        ("KEYCLOAK_CLIENT_ID", "fleet-ui"),
+    ])
+}
+
+#[tokio::test]
+async fn auth_config_returns_configured_keycloak_settings() {
+    let config = config();
+
+    let app = test::init_service(App::new().route(
+        "/auth/config",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::auth_config),
+    ))
+    .await;
+    let req = test::TestRequest::get().uri("/auth/config").to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "keycloakUrl": "https://keycloak.example.com/realms/fleet/",
+            "realm": "fleet",
+            "clientId": "fleet-ui",
+        })
+    );
+    assert_eq!(json["keycloakUrl"], config.keycloak.url);
+    assert!(!json.to_string().contains(&config.centrifugo.client_token));
+}
+
+#[tokio::test]
+async fn config_json_returns_frontend_config() {
+    let config = config();
+
+    let app = test::init_service(App::new().route(
+        "/config.json",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::config_json),
+    ))
+    .await;
+    let req = test::TestRequest::get().uri("/config.json").to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["KEYCLOAK_URL"], config.keycloak.url);
+    assert_eq!(json["BASE_PATH"], config.ui.base_path);
+    assert_eq!(
+        json["UPDATE_COMPLETE_STATUSES"],
+        serde_json::json!(config.ui.update_complete_statuses)
+    );
+    assert_eq!(
+        json["NETWORK_INTERFACE_EXCLUDE"],
+        NetworkInterfaceConfig::DEFAULT_EXCLUDE
+    );
+}
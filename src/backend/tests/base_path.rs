@@ -0,0 +1,137 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::{
+    config_with,
+    fixtures::{HealthcheckInfoBuilder, healthcheck_client},
+};
+use omnect_ui::{
+    api::{Api, IndexPage, StaticResources},
+    config::AppConfig,
+    services::republish::{REPUBLISH_MIN_INTERVAL, RepublishThrottle},
+};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+fn config() -> &'static AppConfig {
+    config_with(&[("BASE_PATH", "/omnect/")])
+}
+
+async fn call_healthcheck(uri: &str) -> actix_web::dev::ServiceResponse {
+    let config = config();
+
+    let api = Api {
+        service_client: healthcheck_client(HealthcheckInfoBuilder::default().build()),
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).service(
+        web::scope(&config.ui.base_path).route(
+            "/healthcheck",
+            web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::healthcheck),
+        ),
+    ))
+    .await;
+
+    let req = test::TestRequest::get().uri(uri).to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn base_path_is_normalized() {
+    assert_eq!(config().ui.base_path, "/omnect");
+}
+
+#[tokio::test]
+async fn healthcheck_is_served_below_base_path() {
+    let resp = call_healthcheck("/omnect/healthcheck").await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn healthcheck_is_not_served_at_root() {
+    let resp = call_healthcheck("/healthcheck").await;
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+fn static_resources() -> StaticResources {
+    // index.html as emitted by `vite build --base=/static`
+    StaticResources::from([(
+        "index.html",
+        static_files::Resource {
+            data: include_bytes!("fixtures/index.html"),
+            modified: 0,
+            mime_type: "text/html",
+        },
+    )])
+}
+
+async fn call_index(uri: &str) -> String {
+    let config = config();
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_republish()
+        .returning(|| Box::pin(async { Ok(()) }));
+
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .app_data(web::Data::new(IndexPage::new(&static_resources()).unwrap()))
+            .app_data(web::Data::new(RepublishThrottle::new(
+                REPUBLISH_MIN_INTERVAL,
+            )))
+            .service(
+                web::scope(&config.ui.base_path)
+                    .route(
+                        "/",
+                        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::index),
+                    )
+                    .default_service(
+                        web::route().to(Api::<DeviceServiceClient, SingleSignOnProvider>::index),
+                    ),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri(uri).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    String::from_utf8(test::read_body(resp).await.to_vec()).unwrap()
+}
+
+fn referenced_urls(html: &str) -> Vec<&str> {
+    ["src=\"", "href=\""]
+        .iter()
+        .flat_map(|attr| html.match_indices(attr).map(move |(i, _)| i + attr.len()))
+        .map(|start| {
+            let len = html[start..].find('"').unwrap();
+            &html[start..start + len]
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn index_references_all_files_below_base_path() {
+    for uri in ["/omnect/", "/omnect", "/omnect/network/eth0"] {
+        let html = call_index(uri).await;
+        let urls = referenced_urls(&html);
+
+        assert_eq!(urls.len(), 7, "{uri}: {urls:?}");
+        for url in urls {
+            assert!(url.starts_with("/omnect/"), "{uri}: {url}");
+        }
+        assert!(html.contains("<script src=\"/omnect/config.js\"></script>"));
+    }
+}
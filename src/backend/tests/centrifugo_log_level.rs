@@ -0,0 +1,24 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::api::Api;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+#[tokio::test]
+async fn set_centrifugo_log_level_rejects_unknown_level() {
+    let app = test::init_service(App::new().route(
+        "/centrifugo/log-level",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::set_centrifugo_log_level),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/centrifugo/log-level")
+        .set_json(serde_json::json!({ "level": "verbose" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::config_with;
+use omnect_ui::config::AppConfig;
+
+const PUBLISH_URL: &str = "https://centrifugo.local:{port}/centrifugo/api/publish";
+
+fn config() -> &'static AppConfig {
+    config_with(&[("CENTRIFUGO_PUBLISH_URL", PUBLISH_URL)])
+}
+
+#[test]
+fn configured_publish_url_is_used_for_publish_endpoint() {
+    let config = config();
+
+    assert_eq!(
+        config.centrifugo.publish_endpoint.url,
+        PUBLISH_URL.replace("{port}", &config.centrifugo.port)
+    );
+}
+
+#[test]
+fn publish_endpoint_keeps_api_key_header() {
+    let config = config();
+
+    assert!(
+        config
+            .centrifugo
+            .publish_endpoint
+            .headers
+            .iter()
+            .any(|header| header.name == "X-API-Key" && header.value == config.centrifugo.api_key)
+    );
+}
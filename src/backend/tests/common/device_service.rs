@@ -0,0 +1,78 @@
+//! Fake device service answering HTTP requests on a unix socket
+
+use super::config_with;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::UnixListener,
+};
+
+/// Device service status body with the given `network_status` array
+pub fn status_body(network_status: &str, version: &str) -> String {
+    format!(
+        r#"{{
+    "NetworkStatus": {{ "network_status": {network_status} }},
+    "SystemInfo": {{ "fleet_id": null, "omnect_device_service_version": "{version}" }},
+    "UpdateValidationStatus": {{ "status": "NoUpdate" }}
+}}"#
+    )
+}
+
+/// Device service of this process, recording the requested paths
+pub struct FakeDeviceService {
+    requests: Arc<Mutex<Vec<String>>>,
+    _socket_dir: TempDir,
+}
+
+impl FakeDeviceService {
+    /// Serve the JSON body returned by `respond` for the requested path
+    ///
+    /// The socket is configured as `DEVICE_SERVICE_SOCKET_PATH`, so this has to
+    /// be called before the configuration is accessed.
+    pub fn start(respond: impl Fn(&str) -> String + Send + 'static) -> Self {
+        let socket_dir = TempDir::new().unwrap();
+        let socket_path = socket_dir.path().join("api.sock");
+        config_with(&[("DEVICE_SERVICE_SOCKET_PATH", socket_path.to_str().unwrap())]);
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let path = request
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_string();
+
+                let body = respond(&path);
+                // recorded before responding, so the client sees it once it has the response
+                recorded.lock().unwrap().push(path);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        Self {
+            requests,
+            _socket_dir: socket_dir,
+        }
+    }
+
+    /// Whether a path starting with `prefix` was requested
+    pub fn requested(&self, prefix: &str) -> bool {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|path| path.starts_with(prefix))
+    }
+}
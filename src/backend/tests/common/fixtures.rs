@@ -0,0 +1,133 @@
+//! Builders of device service data returned by client mocks
+
+use omnect_ui::omnect_device_service_client::{
+    HealthcheckInfo, Ipv4AddrInfo, Ipv4Info, NetworkInterface, NetworkStatus, Status, SystemInfo,
+    UpdateValidationStatus, VersionInfo,
+};
+use omnect_ui_core::types::FactoryResetRequest;
+use std::path::PathBuf;
+
+#[mockall_double::double]
+use omnect_ui::omnect_device_service_client::DeviceServiceClient;
+
+/// Healthcheck of a compatible device service without update or operation in progress
+pub struct HealthcheckInfoBuilder {
+    info: HealthcheckInfo,
+}
+
+impl Default for HealthcheckInfoBuilder {
+    fn default() -> Self {
+        Self {
+            info: HealthcheckInfo {
+                version_info: VersionInfo {
+                    required: ">=0.39.0".to_string(),
+                    current: "0.40.0".to_string(),
+                    mismatch: false,
+                },
+                update_validation_status: UpdateValidationStatus {
+                    status: "NoUpdate".to_string(),
+                },
+                network_rollback_occurred: false,
+                factory_reset_result_acked: false,
+                update_validation_acked: false,
+                primary_mac: None,
+                update_progress: None,
+                boot_time: None,
+                last_operation: None,
+                last_factory_reset: None,
+            },
+        }
+    }
+}
+
+impl HealthcheckInfoBuilder {
+    /// Report a device service older than required if `mismatch`
+    pub fn version_mismatch(mut self, mismatch: bool) -> Self {
+        if mismatch {
+            self.info.version_info.current = "0.38.0".to_string();
+        }
+        self.info.version_info.mismatch = mismatch;
+        self
+    }
+
+    pub fn update_progress(mut self, update_progress: Option<u8>) -> Self {
+        self.info.update_progress = update_progress;
+        self
+    }
+
+    pub fn last_factory_reset(mut self, last_factory_reset: FactoryResetRequest) -> Self {
+        self.info.last_factory_reset = Some(last_factory_reset);
+        self
+    }
+
+    pub fn build(self) -> HealthcheckInfo {
+        self.info
+    }
+}
+
+/// Status of a device service without fleet and network interfaces
+pub struct StatusBuilder {
+    status: Status,
+}
+
+impl Default for StatusBuilder {
+    fn default() -> Self {
+        Self {
+            status: Status {
+                network_status: NetworkStatus {
+                    network_interfaces: vec![],
+                },
+                system_info: SystemInfo {
+                    fleet_id: None,
+                    omnect_device_service_version: "0.40.0".to_string(),
+                    boot_time: None,
+                },
+                update_validation_status: UpdateValidationStatus {
+                    status: "NoUpdate".to_string(),
+                },
+            },
+        }
+    }
+}
+
+impl StatusBuilder {
+    pub fn fleet_id(mut self, fleet_id: Option<&str>) -> Self {
+        self.status.system_info.fleet_id = fleet_id.map(str::to_string);
+        self
+    }
+
+    /// Add an online eth0 interface serving 192.168.0.100, configured by `file`
+    pub fn eth0(mut self, file: impl Into<PathBuf>) -> Self {
+        self.status
+            .network_status
+            .network_interfaces
+            .push(NetworkInterface {
+                online: true,
+                ipv4: Ipv4Info {
+                    addrs: vec![Ipv4AddrInfo {
+                        addr: "192.168.0.100".to_string(),
+                    }],
+                },
+                file: file.into(),
+                mac: "00:00:5e:00:53:01".to_string(),
+                name: "eth0".to_string(),
+            });
+        self
+    }
+
+    pub fn build(self) -> Status {
+        self.status
+    }
+}
+
+/// Device service client mock answering every healthcheck with `info`
+pub fn healthcheck_client(info: HealthcheckInfo) -> DeviceServiceClient {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_healthcheck_info()
+        .returning(move |_| {
+            let info = info.clone();
+            Box::pin(async move { Ok(info) })
+        });
+    device_service_client_mock
+}
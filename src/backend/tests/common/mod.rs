@@ -0,0 +1,28 @@
+//! Helpers shared by the integration tests
+//!
+//! Every test file is a crate of its own and uses a part of these helpers only.
+#![allow(dead_code)]
+
+pub mod device_service;
+pub mod fixtures;
+pub mod server;
+
+use omnect_ui::config::AppConfig;
+use std::sync::Once;
+
+/// Load the configuration with the given environment variables set
+///
+/// The configuration is loaded once per process, so the variables are set
+/// before the first access and only by the first call.
+pub fn config_with(vars: &[(&str, &str)]) -> &'static AppConfig {
+    static ENV: Once = Once::new();
+
+    ENV.call_once(|| {
+        for (key, value) in vars {
+            unsafe {
+                std::env::set_var(key, value);
+            }
+        }
+    });
+    AppConfig::get()
+}
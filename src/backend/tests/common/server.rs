@@ -0,0 +1,45 @@
+//! Real server for tests depending on connections or worker lifetimes
+
+use actix_web::{App, HttpServer, dev::ServerHandle, web};
+use omnect_ui::api::LocalAddr;
+use tokio::task::JoinHandle;
+
+/// Running server with its own workers like the one of `run_server` in main
+///
+/// Unlike `test::init_service`, connections are accepted on a socket, so the
+/// local address of a connection is known, and stopping the server stops its
+/// workers and the tasks spawned on them.
+pub struct TestServer {
+    /// Base url of the server
+    pub url: String,
+    handle: ServerHandle,
+    running: JoinHandle<std::io::Result<()>>,
+}
+
+impl TestServer {
+    /// Start a server with a single worker on a free local port
+    ///
+    /// # Arguments
+    /// * `configure` - Adds app data and routes of the app
+    pub fn start(configure: impl Fn(&mut web::ServiceConfig) + Send + Clone + 'static) -> Self {
+        let server = HttpServer::new(move || App::new().configure(configure.clone()))
+            .on_connect(LocalAddr::on_connect)
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let url = format!("http://{}", server.addrs()[0]);
+        let server = server.disable_signals().run();
+
+        Self {
+            url,
+            handle: server.handle(),
+            running: tokio::spawn(server),
+        }
+    }
+
+    /// Stop the server gracefully and wait for it
+    pub async fn stop(self) {
+        self.handle.stop(true).await;
+        self.running.await.unwrap().unwrap();
+    }
+}
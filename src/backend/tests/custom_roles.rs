@@ -0,0 +1,82 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, http::header::ContentType, test, web};
+use common::config_with;
+use omnect_ui::{api::Api, config::AppConfig, keycloak_client::TokenClaims};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+const ADMIN_ROLE: &str = "DeviceAdmin";
+
+fn config() -> &'static AppConfig {
+    config_with(&[("ADMIN_ROLE", ADMIN_ROLE)])
+}
+
+fn make_api(
+    role: &str,
+    fleets: Option<Vec<&str>>,
+) -> Api<DeviceServiceClient, SingleSignOnProvider> {
+    let claims = TokenClaims {
+        roles: Some(vec![role.to_string()]),
+        tenant_list: Some(vec!["cp".to_string()]),
+        fleet_list: fleets.map(|fs| fs.into_iter().map(String::from).collect()),
+    };
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_fleet_id()
+        .returning(|| Box::pin(async { Ok("Fleet1".to_string()) }));
+    let mut single_sign_on_provider_mock = SingleSignOnProvider::default();
+    single_sign_on_provider_mock
+        .expect_verify_token()
+        .returning(move |_| {
+            let claims = claims.clone();
+            Box::pin(async move { Ok(claims) })
+        });
+
+    Api {
+        service_client: device_service_client_mock,
+        single_sign_on: single_sign_on_provider_mock,
+    }
+}
+
+async fn validate(api: Api<DeviceServiceClient, SingleSignOnProvider>) -> StatusCode {
+    assert_eq!(config().roles.admin, ADMIN_ROLE);
+
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/validate",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::validate_portal_token),
+    ))
+    .await;
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(ContentType::plaintext())
+        .set_payload("dummy")
+        .to_request();
+
+    test::call_service(&app, req).await.status()
+}
+
+#[tokio::test]
+async fn custom_admin_role_grants_access() {
+    assert_eq!(validate(make_api(ADMIN_ROLE, None)).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn replaced_default_admin_role_is_rejected() {
+    assert_eq!(
+        validate(make_api("FleetAdministrator", None)).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn default_operator_role_still_works() {
+    assert_eq!(
+        validate(make_api("FleetOperator", Some(vec!["Fleet1"]))).await,
+        StatusCode::OK
+    );
+}
@@ -0,0 +1,35 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::{api::Api, config::AppConfig};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+#[tokio::test]
+async fn debug_config_omits_secrets() {
+    let app = test::init_service(App::new().route(
+        "/debug/config",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::debug_config),
+    ))
+    .await;
+    let req = test::TestRequest::get().uri("/debug/config").to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body = test::read_body(resp).await;
+    let text = std::str::from_utf8(&body).unwrap();
+    let config = AppConfig::get();
+    assert!(!text.contains(&config.centrifugo.client_token));
+    assert!(!text.contains(&config.centrifugo.api_key));
+    assert!(!text.contains("clientToken"));
+    assert!(!text.contains("apiKey"));
+
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["ui"]["port"], config.ui.port);
+    assert_eq!(json["tenants"], serde_json::json!(config.tenants));
+    assert_eq!(json["centrifugo"]["port"], config.centrifugo.port);
+    assert!(json["deviceService"]["requiredVersion"].is_string());
+    assert!(json["timeouts"]["networkRollbackSecs"].is_u64());
+}
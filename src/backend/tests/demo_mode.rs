@@ -0,0 +1,43 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::config_with;
+use omnect_ui::{
+    api::Api, demo_device_service_client::DemoDeviceServiceClient, middleware::DemoModeMw,
+};
+
+#[mockall_double::double]
+use omnect_ui::keycloak_client::SingleSignOnProvider;
+
+type DemoApi = Api<DemoDeviceServiceClient, SingleSignOnProvider>;
+
+#[tokio::test]
+async fn demo_mode_serves_status_but_blocks_reboot() {
+    config_with(&[("DEMO_MODE", "true")]);
+
+    let api = DemoApi::new(DemoDeviceServiceClient, SingleSignOnProvider::default())
+        .await
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .route("/healthcheck", web::get().to(DemoApi::healthcheck))
+            .route("/reboot", web::post().to(DemoApi::reboot).wrap(DemoModeMw)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/healthcheck").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["versionInfo"]["mismatch"], false);
+    assert!(body["primaryMac"].is_string());
+
+    let req = test::TestRequest::post().uri("/reboot").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let body = test::read_body(resp).await;
+    assert_eq!(body, "demo mode");
+}
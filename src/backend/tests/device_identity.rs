@@ -0,0 +1,61 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::fixtures::StatusBuilder;
+use omnect_ui::{api::Api, config::AppConfig};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+async fn call_identity(fleet_id: Option<&'static str>) -> actix_web::dev::ServiceResponse {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_status()
+        .times(1)
+        .returning(move || {
+            let status = StatusBuilder::default().fleet_id(fleet_id).build();
+            Box::pin(async move { Ok(status) })
+        });
+
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/device/identity",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::device_identity),
+    ))
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/device/identity")
+        .to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn identity_reports_tenant_and_fleet_id() {
+    let resp = call_identity(Some("fleet-1")).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "tenant": AppConfig::get().tenants[0],
+            "fleetId": "fleet-1",
+        })
+    );
+}
+
+#[tokio::test]
+async fn identity_without_fleet_id_reports_null() {
+    let resp = call_identity(None).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["tenant"], AppConfig::get().tenants[0]);
+    assert!(body["fleetId"].is_null());
+}
@@ -0,0 +1,89 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::{api::Api, services::factory_reset::FactoryResetService};
+use omnect_ui_core::types::FactoryResetRequest;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+async fn call_factory_reset(
+    device_service_client_mock: DeviceServiceClient,
+    body: serde_json::Value,
+) -> actix_web::dev::ServiceResponse {
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/factory-reset",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::factory_reset),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/factory-reset")
+        .set_json(body)
+        .to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn factory_reset_records_preserve_keys() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_factory_reset()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let resp = call_factory_reset(
+        device_service_client_mock,
+        serde_json::json!({"mode": 3, "preserve": ["network", "firewall"]}),
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        FactoryResetService::last_factory_reset(),
+        Some(FactoryResetRequest {
+            mode: 3,
+            preserve: vec!["network".to_string(), "firewall".to_string()],
+        })
+    );
+}
+
+#[tokio::test]
+async fn factory_reset_rejects_empty_preserve_key() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_factory_reset().never();
+
+    let resp = call_factory_reset(
+        device_service_client_mock,
+        serde_json::json!({"mode": 1, "preserve": ["network", " "]}),
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn factory_reset_passes_trimmed_preserve_keys() {
+    // same request as recorded by the test above, as all tests share the record file
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_factory_reset()
+        .withf(|factory_reset| {
+            serde_json::to_value(factory_reset).unwrap()["preserve"]
+                == serde_json::json!(["network", "firewall"])
+        })
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let resp = call_factory_reset(
+        device_service_client_mock,
+        serde_json::json!({"mode": 3, "preserve": [" network", "firewall "]}),
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+}
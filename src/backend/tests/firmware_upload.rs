@@ -0,0 +1,174 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::{api::Api, services::firmware::UploadLock};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+#[tokio::test]
+async fn upload_is_rejected_while_another_one_is_in_progress() {
+    let upload_lock = UploadLock::default();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(upload_lock.clone()))
+            .route(
+                "/update/file",
+                web::post()
+                    .to(Api::<DeviceServiceClient, SingleSignOnProvider>::upload_firmware_file),
+            ),
+    )
+    .await;
+
+    // the first upload holds the lock
+    let _first_upload = upload_lock.try_acquire().expect("lock should be free");
+
+    let req = test::TestRequest::post()
+        .uri("/update/file")
+        .insert_header(("content-type", "multipart/form-data; boundary=boundary"))
+        .set_payload("--boundary--\r\n")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let body = test::read_body(resp).await;
+    assert_eq!(body, "upload already in progress");
+}
+
+#[tokio::test]
+async fn upload_larger_than_free_space_is_rejected_up_front() {
+    std::fs::create_dir_all(&omnect_ui::config::AppConfig::get().paths.data_dir)
+        .expect("should create data dir");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(UploadLock::default()))
+            .route(
+                "/update/file",
+                web::post()
+                    .to(Api::<DeviceServiceClient, SingleSignOnProvider>::upload_firmware_file),
+            ),
+    )
+    .await;
+
+    // the declared size is checked before the (here truncated) stream is read
+    let req = test::TestRequest::post()
+        .uri("/update/file")
+        .insert_header(("content-type", "multipart/form-data; boundary=boundary"))
+        .set_payload("--boundary--\r\n")
+        .insert_header(("content-length", (1u64 << 60).to_string()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::INSUFFICIENT_STORAGE);
+    let body = test::read_body(resp).await;
+    assert!(body.starts_with(b"insufficient storage"));
+}
+
+mod chunked_upload {
+    use super::*;
+    use omnect_ui::{
+        api::UPLOAD_ID_HEADER,
+        config::AppConfig,
+        services::firmware::{ChunkedUpload, FirmwareService},
+    };
+
+    type MockApi = Api<DeviceServiceClient, SingleSignOnProvider>;
+
+    async fn app() -> impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+    > {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(UploadLock::default()))
+                .app_data(web::Data::new(ChunkedUpload::default()))
+                .route(
+                    "/update/file/chunk",
+                    web::post().to(MockApi::upload_firmware_chunk),
+                ),
+        )
+        .await
+    }
+
+    fn chunk(
+        content_range: &str,
+        upload_id: Option<&str>,
+        data: &'static [u8],
+    ) -> test::TestRequest {
+        let req = test::TestRequest::post()
+            .uri("/update/file/chunk")
+            .insert_header(("content-range", content_range))
+            .set_payload(data);
+
+        match upload_id {
+            Some(id) => req.insert_header((UPLOAD_ID_HEADER, id)),
+            None => req,
+        }
+    }
+
+    #[tokio::test]
+    async fn file_is_assembled_from_two_chunks() {
+        let _lock = FirmwareService::lock_for_test();
+        std::fs::create_dir_all(&AppConfig::get().paths.data_dir).expect("should create data dir");
+        let app = app().await;
+
+        let resp = test::call_service(&app, chunk("bytes 0-3/8", None, b"firm").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["received"], 4);
+        assert_eq!(body["complete"], false);
+        let upload_id = body["uploadId"].as_str().unwrap().to_string();
+
+        let resp = test::call_service(
+            &app,
+            chunk("bytes 4-7/8", Some(&upload_id), b"ware").to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["received"], 8);
+        assert_eq!(body["complete"], true);
+
+        let content = std::fs::read(&AppConfig::get().paths.local_update_file)
+            .expect("should read update file");
+        assert_eq!(content, b"firmware");
+    }
+
+    #[tokio::test]
+    async fn out_of_order_chunk_is_rejected() {
+        let _lock = FirmwareService::lock_for_test();
+        std::fs::create_dir_all(&AppConfig::get().paths.data_dir).expect("should create data dir");
+        let app = app().await;
+
+        let resp =
+            test::call_service(&app, chunk("bytes 0-3/12", None, b"firm").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let upload_id = body["uploadId"].as_str().unwrap().to_string();
+
+        // the chunk from offset 4 is missing
+        let resp = test::call_service(
+            &app,
+            chunk("bytes 8-11/12", Some(&upload_id), b"ware").to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["uploadId"], upload_id);
+        assert_eq!(body["received"], 4);
+    }
+
+    #[tokio::test]
+    async fn chunk_without_content_range_is_rejected() {
+        let app = app().await;
+
+        let req = test::TestRequest::post()
+            .uri("/update/file/chunk")
+            .set_payload("firmware")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::fixtures::{HealthcheckInfoBuilder, healthcheck_client};
+use omnect_ui::api::{Api, UNAVAILABLE_REASON_HEADER, VERSION_MISMATCH_REASON};
+use omnect_ui::omnect_device_service_client::HealthcheckInfo;
+use omnect_ui_core::types::FactoryResetRequest;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+async fn call_healthcheck(
+    mismatch: bool,
+    update_progress: Option<u8>,
+) -> actix_web::dev::ServiceResponse {
+    call_healthcheck_with(
+        HealthcheckInfoBuilder::default()
+            .version_mismatch(mismatch)
+            .update_progress(update_progress)
+            .build(),
+    )
+    .await
+}
+
+async fn call_healthcheck_with(
+    healthcheck_info: HealthcheckInfo,
+) -> actix_web::dev::ServiceResponse {
+    let api = Api {
+        service_client: healthcheck_client(healthcheck_info),
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/healthcheck",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::healthcheck),
+    ))
+    .await;
+    let req = test::TestRequest::get().uri("/healthcheck").to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn healthcheck_version_mismatch_reports_reason() {
+    let resp = call_healthcheck(true, None).await;
+
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        resp.headers()
+            .get(UNAVAILABLE_REASON_HEADER)
+            .and_then(|v| v.to_str().ok()),
+        Some(VERSION_MISMATCH_REASON)
+    );
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["versionInfo"]["mismatch"], true);
+}
+
+#[tokio::test]
+async fn healthcheck_without_mismatch_has_no_reason() {
+    let resp = call_healthcheck(false, None).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get(UNAVAILABLE_REASON_HEADER).is_none());
+}
+
+#[tokio::test]
+async fn healthcheck_includes_update_progress() {
+    let resp = call_healthcheck(false, Some(42)).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["updateProgress"], 42);
+}
+
+#[tokio::test]
+async fn healthcheck_includes_last_factory_reset() {
+    let resp = call_healthcheck_with(
+        HealthcheckInfoBuilder::default()
+            .last_factory_reset(FactoryResetRequest {
+                mode: 1,
+                preserve: vec!["network".to_string()],
+            })
+            .build(),
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["lastFactoryReset"]["mode"], 1);
+    assert_eq!(
+        body["lastFactoryReset"]["preserve"],
+        serde_json::json!(["network"])
+    );
+}
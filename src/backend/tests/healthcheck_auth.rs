@@ -0,0 +1,68 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, middleware::Condition, test, web};
+use common::{
+    config_with,
+    fixtures::{HealthcheckInfoBuilder, healthcheck_client},
+};
+use omnect_ui::{api::Api, auth::TokenManager, config::AppConfig, middleware::AuthMw};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+fn config() -> &'static AppConfig {
+    config_with(&[("HEALTHCHECK_REQUIRE_AUTH", "true")])
+}
+
+async fn call_healthcheck(authorization: Option<String>) -> actix_web::dev::ServiceResponse {
+    let config = config();
+
+    let api = Api {
+        service_client: healthcheck_client(HealthcheckInfoBuilder::default().build()),
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let token_manager =
+        TokenManager::new(&config.centrifugo.client_token, config.ui.token_clock_skew);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .app_data(web::Data::new(token_manager))
+            .route(
+                "/healthcheck",
+                web::get()
+                    .to(Api::<DeviceServiceClient, SingleSignOnProvider>::healthcheck)
+                    .wrap(Condition::new(config.ui.healthcheck_require_auth, AuthMw)),
+            ),
+    )
+    .await;
+
+    let mut req = test::TestRequest::get().uri("/healthcheck");
+    if let Some(authorization) = authorization {
+        req = req.insert_header(("Authorization", authorization));
+    }
+    test::call_service(&app, req.to_request()).await
+}
+
+#[tokio::test]
+async fn healthcheck_requires_auth_when_enabled() {
+    let resp = call_healthcheck(None).await;
+
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn healthcheck_accepts_bearer_token_when_auth_enabled() {
+    let token = TokenManager::new(
+        &config().centrifugo.client_token,
+        config().ui.token_clock_skew,
+    )
+    .create_token()
+    .unwrap();
+
+    let resp = call_healthcheck(Some(format!("Bearer {token}"))).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+}
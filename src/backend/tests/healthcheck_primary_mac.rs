@@ -0,0 +1,41 @@
+mod common;
+
+use common::device_service::{FakeDeviceService, status_body};
+use omnect_ui::omnect_device_service_client::{DeviceServiceClient, OmnectDeviceServiceClient};
+
+const NETWORK_STATUS: &str = r#"[
+        { "online": true, "ipv4": { "addrs": [{ "addr": "10.0.0.5" }] },
+          "file": "/etc/systemd/network/10-eth0.network", "mac": "00:11:22:33:44:55", "name": "eth0" },
+        { "online": true, "ipv4": { "addrs": [{ "addr": "172.16.0.7" }, { "addr": "192.168.0.42" }] },
+          "file": "/etc/systemd/network/10-eth1.network", "mac": "66:77:88:99:aa:bb", "name": "eth1" },
+        { "online": false, "ipv4": { "addrs": [{ "addr": "192.168.1.100" }] },
+          "file": "/etc/systemd/network/10-eth2.network", "mac": "cc:dd:ee:ff:00:11", "name": "eth2" }
+    ]"#;
+
+// single test, as the configuration is loaded once per process
+#[tokio::test]
+async fn primary_mac_is_taken_from_interface_serving_the_address() {
+    let _device_service = FakeDeviceService::start(|_| status_body(NETWORK_STATUS, "0.40.0"));
+    let client = OmnectDeviceServiceClient::new().unwrap();
+    let primary_mac = async |addr: Option<&str>| {
+        client
+            .healthcheck_info(addr.map(|addr| addr.parse().unwrap()))
+            .await
+            .unwrap()
+            .primary_mac
+    };
+
+    // the second address of a later interface, not the first online interface
+    assert_eq!(
+        primary_mac(Some("192.168.0.42")).await.as_deref(),
+        Some("66:77:88:99:aa:bb")
+    );
+    assert_eq!(
+        primary_mac(Some("::ffff:10.0.0.5")).await.as_deref(),
+        Some("00:11:22:33:44:55")
+    );
+    // offline, unknown or undetermined serving addresses have no primary interface
+    assert_eq!(primary_mac(Some("192.168.1.100")).await, None);
+    assert_eq!(primary_mac(Some("127.0.0.1")).await, None);
+    assert_eq!(primary_mac(None).await, None);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, http::header::RETRY_AFTER, test, web};
+use common::{
+    config_with,
+    fixtures::{HealthcheckInfoBuilder, healthcheck_client},
+};
+use omnect_ui::{api::Api, config::AppConfig};
+use std::time::Duration;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+fn config() -> &'static AppConfig {
+    config_with(&[("HEALTHCHECK_RETRY_AFTER_SECS", "120")])
+}
+
+async fn call_healthcheck(mismatch: bool) -> actix_web::dev::ServiceResponse {
+    let api = Api {
+        service_client: healthcheck_client(
+            HealthcheckInfoBuilder::default()
+                .version_mismatch(mismatch)
+                .build(),
+        ),
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/healthcheck",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::healthcheck),
+    ))
+    .await;
+    let req = test::TestRequest::get().uri("/healthcheck").to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn version_mismatch_reports_configured_retry_after() {
+    assert_eq!(
+        config().ui.healthcheck_retry_after,
+        Some(Duration::from_secs(120))
+    );
+
+    let resp = call_healthcheck(true).await;
+
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        resp.headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok()),
+        Some("120")
+    );
+}
+
+#[tokio::test]
+async fn healthy_response_has_no_retry_after() {
+    config();
+
+    let resp = call_healthcheck(false).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get(RETRY_AFTER).is_none());
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::device_service::{FakeDeviceService, status_body};
+use omnect_ui::{
+    omnect_device_service_client::{DeviceServiceClient, OmnectDeviceServiceClient},
+    services::marker,
+};
+use omnect_ui_core::types::DeviceOperation;
+use std::sync::{Arc, Mutex};
+
+// single test, as the last device operation is a volatile marker file shared by all tests
+#[tokio::test]
+async fn update_progress_is_only_queried_during_update() {
+    let version = Arc::new(Mutex::new("0.41.0"));
+    let served_version = version.clone();
+    let device_service = FakeDeviceService::start(move |path| {
+        if path.starts_with("/fwupdate/progress") {
+            "42".to_string()
+        } else {
+            status_body("[]", &served_version.lock().unwrap())
+        }
+    });
+    let client = OmnectDeviceServiceClient::new().unwrap();
+    let progress_queried = || device_service.requested("/fwupdate/progress");
+
+    marker::LAST_DEVICE_OPERATION.clear();
+    let info = client.healthcheck_info(None).await.unwrap();
+    assert_eq!(info.update_progress, None);
+    assert!(!progress_queried());
+
+    marker::record_device_operation(DeviceOperation::Reboot);
+    let info = client.healthcheck_info(None).await.unwrap();
+    assert_eq!(info.update_progress, None);
+    assert!(!progress_queried());
+
+    // device service versions without the endpoint aren't asked for progress
+    *version.lock().unwrap() = "0.40.0";
+    marker::record_device_operation(DeviceOperation::Update);
+    let info = client.healthcheck_info(None).await.unwrap();
+    assert_eq!(info.update_progress, None);
+    assert!(!progress_queried());
+
+    *version.lock().unwrap() = "0.41.0";
+    let info = client.healthcheck_info(None).await.unwrap();
+    assert_eq!(info.update_progress, Some(42));
+    assert!(progress_queried());
+
+    marker::LAST_DEVICE_OPERATION.clear();
+}
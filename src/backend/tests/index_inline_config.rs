@@ -0,0 +1,99 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::config_with;
+use omnect_ui::{
+    api::{Api, IndexPage, StaticResources},
+    config::AppConfig,
+    services::republish::{REPUBLISH_MIN_INTERVAL, RepublishThrottle},
+};
+use std::sync::OnceLock;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+/// Frontend config provided by the operator instead of the generated one
+const PROVIDED_APP_CONFIG: &str =
+    "window.__APP_CONFIG__ = {KEYCLOAK_URL:\"https://keycloak.operator.example\",BASE_PATH:\"\"};";
+
+fn config() -> &'static AppConfig {
+    static APP_CONFIG_PATH: OnceLock<std::path::PathBuf> = OnceLock::new();
+    let app_config_path = APP_CONFIG_PATH.get_or_init(|| {
+        let path = std::env::temp_dir().join(format!(
+            "omnect-ui-provided-app-config-{}.js",
+            std::process::id()
+        ));
+        std::fs::write(&path, PROVIDED_APP_CONFIG).unwrap();
+        path
+    });
+
+    config_with(&[
+        ("INLINE_APP_CONFIG", "true"),
+        ("GENERATE_APP_CONFIG", "false"),
+        ("APP_CONFIG_PATH", app_config_path.to_str().unwrap()),
+    ])
+}
+
+async fn call_index(index_html: &'static [u8]) -> String {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_republish()
+        .returning(|| Box::pin(async { Ok(()) }));
+
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let static_resources = StaticResources::from([(
+        "index.html",
+        static_files::Resource {
+            data: index_html,
+            modified: 0,
+            mime_type: "text/html",
+        },
+    )]);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .app_data(web::Data::new(IndexPage::new(&static_resources).unwrap()))
+            .app_data(web::Data::new(RepublishThrottle::new(
+                REPUBLISH_MIN_INTERVAL,
+            )))
+            .route(
+                "/",
+                web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::index),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body = test::read_body(resp).await;
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn index_inlines_provided_app_config() {
+    assert!(config().ui.inline_app_config);
+    assert!(!config().ui.generate_app_config);
+
+    let html = call_index(include_bytes!("fixtures/index.html")).await;
+
+    assert!(!html.contains("src=\"config.js\""));
+    assert!(html.contains(&format!("<script>{PROVIDED_APP_CONFIG}</script>")));
+}
+
+#[tokio::test]
+async fn config_tag_of_ui_source_index_is_replaced() {
+    config();
+
+    // the tag is matched literally, so it has to be kept in sync with the UI
+    let html = call_index(include_bytes!("../../ui/index.html")).await;
+
+    assert!(!html.contains("src=\"config.js\""));
+    assert!(html.contains(&format!("<script>{PROVIDED_APP_CONFIG}</script>")));
+}
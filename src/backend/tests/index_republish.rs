@@ -0,0 +1,60 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::config_with;
+use omnect_ui::{
+    api::{Api, IndexPage, StaticResources},
+    config::AppConfig,
+    services::republish::{IndexRepublish, REPUBLISH_MIN_INTERVAL, RepublishThrottle},
+};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+fn config() -> &'static AppConfig {
+    config_with(&[("INDEX_REPUBLISH", "never")])
+}
+
+fn static_resources() -> StaticResources {
+    StaticResources::from([(
+        "index.html",
+        static_files::Resource {
+            data: b"<html></html>",
+            modified: 0,
+            mime_type: "text/html",
+        },
+    )])
+}
+
+#[tokio::test]
+async fn index_skips_republish_if_disabled() {
+    assert_eq!(config().ui.index_republish, IndexRepublish::Never);
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_republish().never();
+
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .app_data(web::Data::new(IndexPage::new(&static_resources()).unwrap()))
+            .app_data(web::Data::new(RepublishThrottle::new(
+                REPUBLISH_MIN_INTERVAL,
+            )))
+            .route(
+                "/",
+                web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::index),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+}
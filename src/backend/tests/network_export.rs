@@ -0,0 +1,125 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, http::header, test, web};
+use common::fixtures::StatusBuilder;
+use omnect_ui::{api::Api, config::AppConfig};
+use std::io::Write;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+async fn call_export(
+    device_service_client_mock: DeviceServiceClient,
+) -> actix_web::dev::ServiceResponse {
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/network/export",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::export_network),
+    ))
+    .await;
+
+    let req = test::TestRequest::get().uri("/network/export").to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn export_includes_status_and_network_file() {
+    // a file name of its own, the other tests must not find it
+    let content = "[Match]\nName=eth0\n\n[Network]\nDHCP=yes\n";
+    std::fs::write(
+        AppConfig::get().paths.network_dir.join("10-export.network"),
+        content,
+    )
+    .unwrap();
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_status().returning(|| {
+        let status = StatusBuilder::default()
+            .eth0("/run/systemd/network/10-export.network")
+            .build();
+        Box::pin(async move { Ok(status) })
+    });
+
+    let resp = call_export(device_service_client_mock).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let disposition = resp
+        .headers()
+        .get(header::CONTENT_DISPOSITION)
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(disposition.starts_with("attachment"));
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        body["networkInterfaces"],
+        serde_json::json!([{
+            "name": "eth0",
+            "mac": "00:00:5e:00:53:01",
+            "online": true,
+            "ipv4Addresses": ["192.168.0.100"],
+        }])
+    );
+
+    let file = &body["networkFiles"][0];
+    assert_eq!(file["name"], "eth0");
+    // the reported host path is kept, the file is read from the mounted directory
+    assert_eq!(file["path"], "/run/systemd/network/10-export.network");
+    assert_eq!(file["content"], content);
+}
+
+#[tokio::test]
+async fn export_does_not_read_host_path() {
+    let mut network_file = tempfile::NamedTempFile::new().unwrap();
+    write!(network_file, "[Match]\nName=eth0\n\n[Network]\nDHCP=yes\n").unwrap();
+    let path = network_file.path().to_path_buf();
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_status()
+        .returning(move || {
+            let status = StatusBuilder::default().eth0(path.clone()).build();
+            Box::pin(async move { Ok(status) })
+        });
+
+    let resp = call_export(device_service_client_mock).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["networkFiles"][0]["content"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn export_reports_unreadable_network_file_as_null() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_status().returning(|| {
+        let status = StatusBuilder::default()
+            .eth0("/nonexistent/10-unreadable.network")
+            .build();
+        Box::pin(async move { Ok(status) })
+    });
+
+    let resp = call_export(device_service_client_mock).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["networkFiles"][0]["content"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn export_fails_if_status_is_unavailable() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_status()
+        .returning(|| Box::pin(async { Err(anyhow::anyhow!("device service unavailable")) }));
+
+    let resp = call_export(device_service_client_mock).await;
+
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
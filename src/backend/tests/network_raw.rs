@@ -0,0 +1,127 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::fixtures::StatusBuilder;
+use omnect_ui::{
+    api::Api, config::AppConfig, network_config::NetworkConfigService,
+    omnect_device_service_client::Status,
+};
+use std::path::PathBuf;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+const SERVING_ETH0: &str = "[Match]\nName=eth0\n\n[Network]\nAddress=192.168.0.200/24\n";
+const PREVIOUS_ETH0: &str = "[Match]\nName=eth0\n\n[Network]\nDHCP=yes\n";
+
+// must match the rollback file used by NetworkConfigService
+const ROLLBACK_FILE: &str = "/tmp/network_rollback.json";
+
+fn status_serving_eth0() -> Status {
+    StatusBuilder::default()
+        .eth0("/run/systemd/network/10-eth0.network")
+        .build()
+}
+
+async fn call_set_raw(
+    device_service_client_mock: DeviceServiceClient,
+    uri: &str,
+    body: &'static str,
+) -> actix_web::dev::ServiceResponse {
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/network/raw",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::set_raw_network_config),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri(uri)
+        .insert_header(("host", "192.168.0.100:1977"))
+        .set_payload(body)
+        .to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn invalid_raw_config_is_rejected() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_status().never();
+    device_service_client_mock.expect_reload_network().never();
+
+    let resp = call_set_raw(
+        device_service_client_mock,
+        "/network/raw?name=eth0",
+        "[Match]\nName=eth0\n",
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body = test::read_body(resp).await;
+    assert!(String::from_utf8_lossy(&body).contains("missing [Network] section"));
+}
+
+#[tokio::test]
+async fn raw_config_of_serving_adapter_requires_rollback() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_status()
+        .times(1)
+        .returning(|| Box::pin(async { Ok(status_serving_eth0()) }));
+    device_service_client_mock.expect_reload_network().never();
+
+    let resp = call_set_raw(
+        device_service_client_mock,
+        "/network/raw?name=eth0",
+        SERVING_ETH0,
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body = test::read_body(resp).await;
+    assert!(String::from_utf8_lossy(&body).contains("requires rollback"));
+}
+
+#[tokio::test]
+async fn raw_config_is_applied_with_backup_and_rollback() {
+    let mut restart_rx = NetworkConfigService::setup_restart_receiver().unwrap();
+    let network_dir = &AppConfig::get().paths.network_dir;
+    std::fs::write(network_dir.join("10-eth0.network"), PREVIOUS_ETH0).unwrap();
+    let _ = std::fs::remove_file(ROLLBACK_FILE);
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_status()
+        .returning(|| Box::pin(async { Ok(status_serving_eth0()) }));
+    device_service_client_mock
+        .expect_reload_network()
+        .times(1)
+        .returning(|| Box::pin(async { Ok(()) }));
+
+    let resp = call_set_raw(
+        device_service_client_mock,
+        "/network/raw?name=eth0&enable_rollback=true",
+        SERVING_ETH0,
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["rollbackEnabled"], true);
+
+    let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+    assert_eq!(read(network_dir.join("10-eth0.network")), SERVING_ETH0);
+    assert_eq!(read(network_dir.join("10-eth0.network.old")), PREVIOUS_ETH0);
+
+    let rollback: serde_json::Value =
+        serde_json::from_str(&read(PathBuf::from(ROLLBACK_FILE))).unwrap();
+    assert_eq!(rollback["network_config"]["name"], "eth0");
+    assert!(restart_rx.try_recv().is_ok());
+
+    let _ = std::fs::remove_file(ROLLBACK_FILE);
+}
@@ -0,0 +1,75 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::api::Api;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+async fn call_resolve(
+    device_service_client_mock: DeviceServiceClient,
+    uri: &str,
+) -> actix_web::dev::ServiceResponse {
+    let api = Api::new(device_service_client_mock, SingleSignOnProvider::default())
+        .await
+        .unwrap();
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/network/resolve",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::resolve),
+    ))
+    .await;
+
+    let req = test::TestRequest::get().uri(uri).to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+async fn resolve_returns_resolved_addresses() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_resolve()
+        .withf(|hostname| hostname == "omnect.io")
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(vec!["192.0.2.10".to_string()]) }));
+
+    let resp = call_resolve(
+        device_service_client_mock,
+        "/network/resolve?host=omnect.io",
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["host"], "omnect.io");
+    assert_eq!(body["addresses"], serde_json::json!(["192.0.2.10"]));
+}
+
+#[tokio::test]
+async fn resolve_rejects_invalid_hostname() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_resolve().never();
+
+    let resp = call_resolve(
+        device_service_client_mock,
+        "/network/resolve?host=-invalid..host",
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn resolve_reports_resolution_failure() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_resolve()
+        .returning(|_| Box::pin(async { Err(anyhow::anyhow!("no address found")) }));
+
+    let resp = call_resolve(
+        device_service_client_mock,
+        "/network/resolve?host=unknown.example",
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
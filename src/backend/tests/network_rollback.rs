@@ -0,0 +1,39 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::api::Api;
+use std::path::Path;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+// must match the rollback file used by NetworkConfigService
+const ROLLBACK_FILE: &str = "/tmp/network_rollback.json";
+
+async fn call_cancel_rollback() -> serde_json::Value {
+    let app = test::init_service(App::new().route(
+        "/network/cancel-rollback",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::cancel_rollback),
+    ))
+    .await;
+    let req = test::TestRequest::post()
+        .uri("/network/cancel-rollback")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    test::read_body_json(resp).await
+}
+
+// both cases share the rollback file, so they run sequentially in one test
+#[tokio::test]
+async fn cancel_rollback_reports_whether_rollback_was_pending() {
+    std::fs::write(ROLLBACK_FILE, "{}").expect("failed to create rollback file");
+
+    let body = call_cancel_rollback().await;
+    assert_eq!(body["rollbackPending"], true);
+    assert!(!Path::new(ROLLBACK_FILE).exists());
+
+    let body = call_cancel_rollback().await;
+    assert_eq!(body["rollbackPending"], false);
+}
@@ -0,0 +1,89 @@
+use actix_web::{App, http::StatusCode, test, web};
+use futures_util::future::join_all;
+use omnect_ui::{api::Api, network_config::NetworkConfigService};
+use std::path::Path;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+// must match the rollback file used by NetworkConfigService
+const ROLLBACK_FILE: &str = "/tmp/network_rollback.json";
+
+/// Rollback of `eth-rollback-now` whose deadline is far in the future
+const PENDING_ROLLBACK: &str = r#"{
+    "network_config": {
+        "isServerAddr": true,
+        "ipChanged": true,
+        "name": "eth-rollback-now",
+        "dhcp": false,
+        "ip": "192.168.0.200",
+        "previousIp": "192.168.0.100",
+        "netmask": 24,
+        "gateway": [],
+        "dns": []
+    },
+    "deadline": { "secs_since_epoch": 4102444800, "nanos_since_epoch": 0 }
+}"#;
+
+async fn call_rollback_now(
+    device_service_client_mock: DeviceServiceClient,
+    times: usize,
+) -> Vec<serde_json::Value> {
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/network/rollback-now",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::rollback_now),
+    ))
+    .await;
+
+    // concurrent requests must not execute the rollback twice
+    join_all((0..times).map(|_| async {
+        let req = test::TestRequest::post()
+            .uri("/network/rollback-now")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        test::read_body_json::<serde_json::Value, _>(resp).await
+    }))
+    .await
+}
+
+// both requests share the rollback file, so they run in one test
+#[tokio::test]
+async fn rollback_now_executes_pending_rollback_before_deadline() {
+    let mut restart_rx =
+        NetworkConfigService::setup_restart_receiver().expect("restart channel already set up");
+    std::fs::write(ROLLBACK_FILE, PENDING_ROLLBACK).expect("failed to create rollback file");
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_reload_network()
+        .times(1)
+        .returning(|| {
+            Box::pin(async {
+                // keeps the first request busy while the second one arrives
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                Ok(())
+            })
+        });
+
+    let mut bodies = call_rollback_now(device_service_client_mock, 2).await;
+    bodies.sort_by_key(|body| !body["rollbackExecuted"].as_bool().unwrap());
+
+    assert_eq!(bodies[0]["rollbackExecuted"], true);
+    assert!(!Path::new(ROLLBACK_FILE).exists());
+    assert!(restart_rx.try_recv().is_ok());
+    assert!(
+        NetworkConfigService::rollback_history()
+            .iter()
+            .any(|entry| entry.adapter == "eth-rollback-now")
+    );
+
+    // nothing left to roll back
+    assert_eq!(bodies[1]["rollbackExecuted"], false);
+}
@@ -0,0 +1,83 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::{
+    api::Api, auth::TokenManager, config::AppConfig, middleware::AuthMw,
+    services::schedule::Scheduler,
+};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+type MockApi = Api<DeviceServiceClient, SingleSignOnProvider>;
+
+// single test, as all operations share one volatile marker file
+#[tokio::test]
+async fn run_update_returns_id_reported_by_operations_endpoint() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_run_update()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let config = AppConfig::get();
+    let token_manager =
+        TokenManager::new(&config.centrifugo.client_token, config.ui.token_clock_skew);
+    let authorization = format!("Bearer {}", token_manager.create_token().unwrap());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .app_data(web::Data::new(token_manager))
+            .app_data(web::Data::new(Scheduler::new(
+                tokio::runtime::Handle::current(),
+            )))
+            .route("/update/run", web::post().to(MockApi::run_update))
+            .route(
+                "/operations/{id}",
+                web::get().to(MockApi::operation_status).wrap(AuthMw),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/update/run")
+        .set_json(serde_json::json!({ "validate_iothub_connection": false }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let operation_id = body["operationId"].as_str().unwrap().to_string();
+    assert!(!operation_id.is_empty());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/operations/{operation_id}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/operations/{operation_id}"))
+        .insert_header(("Authorization", authorization.as_str()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["operationId"], operation_id);
+    assert_eq!(body["operation"], "update");
+    assert_eq!(body["state"], "inProgress");
+
+    let req = test::TestRequest::get()
+        .uri("/operations/unknown")
+        .insert_header(("Authorization", authorization.as_str()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    omnect_ui::services::marker::LAST_DEVICE_OPERATION.clear();
+}
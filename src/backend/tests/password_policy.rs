@@ -0,0 +1,38 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::config_with;
+use omnect_ui::api::Api;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+#[tokio::test]
+async fn password_policy_returns_configured_values() {
+    config_with(&[
+        ("PASSWORD_MIN_LENGTH", "12"),
+        ("PASSWORD_REQUIRE_DIGIT", "true"),
+        ("PASSWORD_REQUIRE_SPECIAL", "true"),
+    ]);
+
+    let app = test::init_service(App::new().route(
+        "/password-policy",
+        web::get().to(Api::<DeviceServiceClient, SingleSignOnProvider>::password_policy),
+    ))
+    .await;
+    let req = test::TestRequest::get()
+        .uri("/password-policy")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["minLength"], 12);
+    assert_eq!(body["requireUppercase"], false);
+    assert_eq!(body["requireLowercase"], false);
+    assert_eq!(body["requireDigit"], true);
+    assert_eq!(body["requireSpecial"], true);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::server::TestServer;
+use omnect_ui::{api::Api, network_config::NetworkConfigService};
+use std::net::IpAddr;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+// a real server, the local address is only known for an accepted connection
+#[actix_web::test]
+async fn regenerate_certificate_uses_local_address_and_restarts_server() {
+    let mut restart_rx = NetworkConfigService::setup_restart_receiver().unwrap();
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_ip_address()
+        .withf(|preferred| *preferred == Some(IpAddr::from([127, 0, 0, 1])))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok("127.0.0.1".to_string()) }));
+
+    let api = web::Data::new(
+        Api::new(device_service_client_mock, SingleSignOnProvider::default())
+            .await
+            .unwrap(),
+    );
+    let server = TestServer::start(move |cfg| {
+        cfg.app_data(api.clone()).route(
+            "/certificate/regenerate",
+            web::post()
+                .to(Api::<DeviceServiceClient, SingleSignOnProvider>::regenerate_certificate),
+        );
+    });
+
+    // the host header is chosen by the client and must not be trusted
+    let resp = reqwest::Client::new()
+        .post(format!("{}/certificate/regenerate", server.url))
+        .header("host", "192.168.0.42:1977")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["commonName"], "127.0.0.1");
+    assert!(restart_rx.try_recv().is_ok());
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn regenerate_certificate_fails_without_ip_address() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_ip_address()
+        .returning(|_| Box::pin(async { Err(anyhow::anyhow!("no online interface")) }));
+
+    let api = Api::new(device_service_client_mock, SingleSignOnProvider::default())
+        .await
+        .unwrap();
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/certificate/regenerate",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::regenerate_certificate),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/certificate/regenerate")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
@@ -0,0 +1,148 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::{
+    api::Api,
+    services::republish::{REPUBLISH_MIN_INTERVAL, RepublishThrottle},
+};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+async fn call_republish(
+    device_service_client_mock: DeviceServiceClient,
+    throttle: RepublishThrottle,
+    times: usize,
+) -> Vec<StatusCode> {
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .app_data(web::Data::new(throttle))
+            .route(
+                "/republish",
+                web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::republish),
+            ),
+    )
+    .await;
+
+    let mut statuses = vec![];
+    for _ in 0..times {
+        let req = test::TestRequest::post().uri("/republish").to_request();
+        statuses.push(test::call_service(&app, req).await.status());
+    }
+    statuses
+}
+
+#[tokio::test]
+async fn republish_calls_device_service() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_republish()
+        .times(1)
+        .returning(|| Box::pin(async { Ok(()) }));
+
+    let statuses = call_republish(
+        device_service_client_mock,
+        RepublishThrottle::new(REPUBLISH_MIN_INTERVAL),
+        1,
+    )
+    .await;
+
+    assert_eq!(statuses, vec![StatusCode::OK]);
+}
+
+#[tokio::test]
+async fn rapid_repeats_republish_once() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_republish()
+        .times(1)
+        .returning(|| Box::pin(async { Ok(()) }));
+
+    let statuses = call_republish(
+        device_service_client_mock,
+        RepublishThrottle::new(REPUBLISH_MIN_INTERVAL),
+        3,
+    )
+    .await;
+
+    assert_eq!(statuses, vec![StatusCode::OK; 3]);
+}
+
+#[tokio::test]
+async fn repeats_republish_without_min_interval() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_republish()
+        .times(2)
+        .returning(|| Box::pin(async { Ok(()) }));
+
+    let statuses = call_republish(
+        device_service_client_mock,
+        RepublishThrottle::new(Duration::ZERO),
+        2,
+    )
+    .await;
+
+    assert_eq!(statuses, vec![StatusCode::OK; 2]);
+}
+
+#[tokio::test]
+async fn republish_reports_device_service_failure() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_republish()
+        .returning(|| Box::pin(async { Err(anyhow::anyhow!("device service unavailable")) }));
+
+    let statuses = call_republish(
+        device_service_client_mock,
+        RepublishThrottle::new(REPUBLISH_MIN_INTERVAL),
+        1,
+    )
+    .await;
+
+    assert_eq!(statuses, vec![StatusCode::INTERNAL_SERVER_ERROR]);
+}
+
+#[tokio::test]
+async fn failed_republish_is_retried_immediately() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    let calls = AtomicUsize::new(0);
+    device_service_client_mock
+        .expect_republish()
+        .times(2)
+        .returning(move || {
+            // the first republish fails, the retry succeeds
+            let first = calls.fetch_add(1, Ordering::SeqCst) == 0;
+            Box::pin(async move {
+                if first {
+                    anyhow::bail!("device service unavailable")
+                }
+                Ok(())
+            })
+        });
+
+    let statuses = call_republish(
+        device_service_client_mock,
+        RepublishThrottle::new(REPUBLISH_MIN_INTERVAL),
+        3,
+    )
+    .await;
+
+    assert_eq!(
+        statuses,
+        vec![
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::OK,
+            StatusCode::OK
+        ]
+    );
+}
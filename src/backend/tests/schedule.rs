@@ -0,0 +1,278 @@
+mod common;
+
+use actix_web::{App, http::StatusCode, test, web};
+use common::server::TestServer;
+use omnect_ui::{api::Api, services::schedule::Scheduler};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime},
+};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use tokio::runtime::Handle;
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+type MockApi = Api<DeviceServiceClient, SingleSignOnProvider>;
+
+const SHORT_DELAY: Duration = Duration::from_millis(200);
+
+fn scheduled_at(delay: Duration) -> String {
+    OffsetDateTime::from(SystemTime::now() + delay)
+        .format(&Rfc3339)
+        .unwrap()
+}
+
+/// Escape the characters of an RFC 3339 timestamp that are special in a query
+fn urlencode(value: &str) -> String {
+    value.replace('+', "%2B").replace(':', "%3A")
+}
+
+/// Device service client mock flagging a reboot
+fn rebooting_client(rebooted: &Arc<AtomicBool>) -> DeviceServiceClient {
+    let rebooted = rebooted.clone();
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock
+        .expect_reboot()
+        .returning(move || {
+            rebooted.store(true, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        });
+    device_service_client_mock
+}
+
+async fn app(
+    service_client: DeviceServiceClient,
+) -> impl actix_web::dev::Service<
+    actix_http::Request,
+    Response = actix_web::dev::ServiceResponse,
+    Error = actix_web::Error,
+> {
+    app_with_scheduler(service_client, Scheduler::new(Handle::current())).await
+}
+
+async fn app_with_scheduler(
+    service_client: DeviceServiceClient,
+    scheduler: Scheduler,
+) -> impl actix_web::dev::Service<
+    actix_http::Request,
+    Response = actix_web::dev::ServiceResponse,
+    Error = actix_web::Error,
+> {
+    let api = Api {
+        service_client,
+        single_sign_on: SingleSignOnProvider::default(),
+    };
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(api))
+            .app_data(web::Data::new(scheduler))
+            .route("/reboot", web::post().to(MockApi::reboot))
+            .route("/update/run", web::post().to(MockApi::run_update))
+            .route("/schedule", web::get().to(MockApi::scheduled_operations))
+            .route(
+                "/schedule/{id}",
+                web::delete().to(MockApi::cancel_scheduled_operation),
+            ),
+    )
+    .await
+}
+
+/// Real server rebooting via `service_client`
+fn server(service_client: DeviceServiceClient, scheduler: Scheduler) -> TestServer {
+    let api = web::Data::new(Api {
+        service_client,
+        single_sign_on: SingleSignOnProvider::default(),
+    });
+    TestServer::start(move |cfg| {
+        cfg.app_data(api.clone())
+            .app_data(web::Data::new(scheduler.clone()))
+            .route("/reboot", web::post().to(MockApi::reboot));
+    })
+}
+
+async fn pending(
+    app: &impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+    >,
+) -> Vec<serde_json::Value> {
+    let req = test::TestRequest::get().uri("/schedule").to_request();
+    let resp = test::call_service(app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    test::read_body_json(resp).await
+}
+
+#[tokio::test]
+async fn scheduled_reboot_runs_when_due() {
+    let rebooted = Arc::new(AtomicBool::new(false));
+    let app = app(rebooting_client(&rebooted)).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/reboot?scheduled_at={}",
+            urlencode(&scheduled_at(SHORT_DELAY))
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["operation"], "reboot");
+    assert!(!rebooted.load(Ordering::SeqCst));
+    assert_eq!(pending(&app).await, vec![body]);
+
+    tokio::time::sleep(SHORT_DELAY * 5).await;
+
+    assert!(rebooted.load(Ordering::SeqCst));
+    assert!(pending(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn cancelled_reboot_does_not_run() {
+    let rebooted = Arc::new(AtomicBool::new(false));
+    let app = app(rebooting_client(&rebooted)).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/reboot?scheduled_at={}",
+            urlencode(&scheduled_at(SHORT_DELAY))
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let schedule_id = body["scheduleId"].as_str().unwrap();
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/schedule/{schedule_id}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/schedule/{schedule_id}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    tokio::time::sleep(SHORT_DELAY * 5).await;
+
+    assert!(!rebooted.load(Ordering::SeqCst));
+    assert!(pending(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn scheduled_update_is_listed() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_run_update().never();
+    let app = app(device_service_client_mock).await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/update/run?scheduled_at={}",
+            urlencode(&scheduled_at(Duration::from_secs(3600)))
+        ))
+        .set_json(serde_json::json!({ "validate_iothub_connection": false }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+    let pending = pending(&app).await;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["operation"], "update");
+}
+
+#[tokio::test]
+async fn invalid_or_past_schedule_is_rejected() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_reboot().never();
+    let app = app(device_service_client_mock).await;
+
+    for scheduled_at in ["tomorrow", "2020-01-01T02:00:00Z"] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/reboot?scheduled_at={}", urlencode(scheduled_at)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    assert!(pending(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn scheduled_reboot_can_be_cancelled_after_server_restart() {
+    let rebooted = Arc::new(AtomicBool::new(false));
+    // created once per process like in main, a restart only rebuilds the server
+    let scheduler = Scheduler::new(Handle::current());
+
+    let app_before_restart =
+        app_with_scheduler(rebooting_client(&rebooted), scheduler.clone()).await;
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/reboot?scheduled_at={}",
+            urlencode(&scheduled_at(SHORT_DELAY))
+        ))
+        .to_request();
+    let resp = test::call_service(&app_before_restart, req).await;
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    drop(app_before_restart);
+
+    let app = app_with_scheduler(rebooting_client(&rebooted), scheduler).await;
+    assert_eq!(pending(&app).await, vec![body.clone()]);
+
+    let schedule_id = body["scheduleId"].as_str().unwrap();
+    let req = test::TestRequest::delete()
+        .uri(&format!("/schedule/{schedule_id}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    tokio::time::sleep(SHORT_DELAY * 5).await;
+
+    assert!(!rebooted.load(Ordering::SeqCst));
+}
+
+#[actix_web::test]
+async fn scheduled_reboot_runs_after_server_restart() {
+    let rebooted = Arc::new(AtomicBool::new(false));
+    // created once per process like in main, a restart only rebuilds the server
+    let scheduler = Scheduler::new(Handle::current());
+
+    let server_before_restart = server(rebooting_client(&rebooted), scheduler.clone());
+
+    let resp = reqwest::Client::new()
+        .post(format!(
+            "{}/reboot?scheduled_at={}",
+            server_before_restart.url,
+            urlencode(&scheduled_at(SHORT_DELAY * 5))
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::ACCEPTED);
+
+    // stopping the server stops its workers and the tasks spawned on them
+    server_before_restart.stop().await;
+    assert!(!rebooted.load(Ordering::SeqCst));
+
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_reboot().never();
+    let server_after_restart = server(device_service_client_mock, scheduler.clone());
+
+    tokio::time::sleep(SHORT_DELAY * 10).await;
+
+    assert!(rebooted.load(Ordering::SeqCst));
+    assert!(scheduler.pending().unwrap().is_empty());
+
+    server_after_restart.stop().await;
+}
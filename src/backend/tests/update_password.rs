@@ -0,0 +1,53 @@
+use actix_web::{App, http::StatusCode, test, web};
+use omnect_ui::{api::Api, services::auth::PasswordService};
+
+#[mockall_double::double]
+use omnect_ui::{
+    keycloak_client::SingleSignOnProvider, omnect_device_service_client::DeviceServiceClient,
+};
+
+async fn call_update_password(body: serde_json::Value) -> actix_web::dev::ServiceResponse {
+    let app = test::init_service(App::new().route(
+        "/update-password",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::update_password),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/update-password")
+        .set_json(body)
+        .to_request();
+    test::call_service(&app, req).await
+}
+
+#[tokio::test]
+#[allow(clippy::await_holding_lock)]
+async fn update_password_requests_reauthentication() {
+    let _lock = PasswordService::lock_for_test();
+    PasswordService::store_or_update_password("Current-password-1").unwrap();
+
+    let resp = call_update_password(serde_json::json!({
+        "currentPassword": "Current-password-1",
+        "password": "New-password-123",
+    }))
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["action"], "reauthenticate");
+}
+
+#[tokio::test]
+#[allow(clippy::await_holding_lock)]
+async fn update_password_with_wrong_password_is_rejected() {
+    let _lock = PasswordService::lock_for_test();
+    PasswordService::store_or_update_password("Current-password-1").unwrap();
+
+    let resp = call_update_password(serde_json::json!({
+        "currentPassword": "wrong-password",
+        "password": "New-password-123",
+    }))
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
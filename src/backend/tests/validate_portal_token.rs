@@ -1,5 +1,5 @@
 use actix_web::{App, http::header::ContentType, test, web};
-use omnect_ui::api::Api;
+use omnect_ui::api::{Api, PORTAL_TOKEN_LIMIT_BYTES};
 use omnect_ui::keycloak_client::TokenClaims;
 
 #[mockall_double::double]
@@ -95,3 +95,32 @@ async fn validate_portal_token_fleet_observer_should_fail() {
     let api = make_api("Fleet1", claims);
     assert_status(api, actix_web::http::StatusCode::UNAUTHORIZED).await;
 }
+
+#[tokio::test]
+async fn validate_portal_token_oversized_body_is_rejected_before_verification() {
+    let mut device_service_client_mock = DeviceServiceClient::default();
+    device_service_client_mock.expect_fleet_id().never();
+    let mut single_sign_on_provider_mock = SingleSignOnProvider::default();
+    single_sign_on_provider_mock.expect_verify_token().never();
+    let api = Api {
+        service_client: device_service_client_mock,
+        single_sign_on: single_sign_on_provider_mock,
+    };
+
+    let app = test::init_service(App::new().app_data(web::Data::new(api)).route(
+        "/validate",
+        web::post().to(Api::<DeviceServiceClient, SingleSignOnProvider>::validate_portal_token),
+    ))
+    .await;
+    let req = test::TestRequest::post()
+        .uri("/validate")
+        .insert_header(ContentType::plaintext())
+        .set_payload("a".repeat(PORTAL_TOKEN_LIMIT_BYTES + 1))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(
+        resp.status(),
+        actix_web::http::StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
@@ -3,8 +3,10 @@ use crux_core::typegen::TypeGen;
 use omnect_ui_core::{
     events::{AuthEvent, DeviceEvent, UiEvent, WebSocketEvent},
     types::{
+        AdapterFormState, AuthBootstrapState, BootstrapState, DeviceOperation,
         DeviceOperationState, FactoryResetStatus, NetworkChangeState, NetworkConfigRequest,
-        NetworkFormData, NetworkFormState, UploadState,
+        NetworkFormData, NetworkFormState, OverlaySpinnerMode, PendingAcknowledgment, UploadState,
+        WebSocketError,
     },
     App,
 };
@@ -25,12 +27,19 @@ fn main() -> Result<()> {
 
     // Explicitly register other enums/structs to ensure all variants are traced
     gen.register_type::<FactoryResetStatus>()?;
+    gen.register_type::<DeviceOperation>()?;
     gen.register_type::<DeviceOperationState>()?;
     gen.register_type::<NetworkChangeState>()?;
     gen.register_type::<NetworkFormState>()?;
     gen.register_type::<UploadState>()?;
+    gen.register_type::<BootstrapState>()?;
+    gen.register_type::<AuthBootstrapState>()?;
+    gen.register_type::<PendingAcknowledgment>()?;
+    gen.register_type::<OverlaySpinnerMode>()?;
     gen.register_type::<NetworkConfigRequest>()?;
     gen.register_type::<NetworkFormData>()?;
+    gen.register_type::<AdapterFormState>()?;
+    gen.register_type::<WebSocketError>()?;
 
     // Register ODS types
     gen.register_type::<omnect_ui_core::types::OdsOnlineStatus>()?;
@@ -0,0 +1,6 @@
+/**
+ * Path prefix of all backend routes, e.g. "/omnect" behind a reverse proxy
+ *
+ * Provided by the backend via config.js, empty if served at the root.
+ */
+export const basePath: string = window.__APP_CONFIG__?.BASE_PATH ?? ""
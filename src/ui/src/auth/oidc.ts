@@ -1,14 +1,15 @@
 import { UserManager, WebStorageStateStore } from "oidc-client-ts"
+import { basePath } from "../appConfig"
 
 const config = window.__APP_CONFIG__
 
 const oidcConfig = {
 	authority: config.KEYCLOAK_URL,
 	client_id: "omnect-ui",
-	redirect_uri: `https://${window.location.hostname}:${window.location.port}/auth-callback`,
+	redirect_uri: `https://${window.location.hostname}:${window.location.port}${basePath}/auth-callback`,
 	response_type: "code",
 	scope: "openid profile email",
-	post_logout_redirect_uri: `https://${window.location.hostname}:${window.location.port}/`,
+	post_logout_redirect_uri: `https://${window.location.hostname}:${window.location.port}${basePath}/`,
 	userStore: new WebStorageStateStore({ store: window.localStorage })
 }
 
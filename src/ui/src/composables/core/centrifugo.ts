@@ -15,6 +15,7 @@ import {
 	WebSocketEventVariantFactoryResetUpdated,
 	WebSocketEventVariantUpdateValidationStatusUpdated,
 	WebSocketEventVariantTimeoutsUpdated,
+	WebSocketEventVariantConnectionFailed,
 	CentrifugoOperationVariantSubscribeAll,
 	CentrifugoOperationVariantUnsubscribeAll,
 	CentrifugoOutputVariantConnected,
@@ -24,6 +25,9 @@ import {
 } from '../../../../shared_types/generated/typescript/types/shared_types'
 import { BincodeSerializer } from '../../../../shared_types/generated/typescript/bincode/mod'
 
+// Disconnect code of centrifuge-js when the client disconnected itself
+const DISCONNECT_CALLED = 0
+
 // Event sender callback - set by index.ts to avoid circular dependency
 let sendEventCallback: ((event: Event) => Promise<void>) | null = null
 
@@ -98,6 +102,14 @@ async function parseAndSendChannelEvent(channel: string, jsonData: string): Prom
 	}
 }
 
+/**
+ * Code of a centrifugo error, null for failures without one, e.g. of the transport
+ */
+function centrifugoErrorCode(error: unknown): number | null {
+	const code = (error as { code?: unknown } | null)?.code
+	return typeof code === 'number' ? code : null
+}
+
 /**
  * Execute Centrifugo operation
  *
@@ -147,20 +159,31 @@ export async function executeCentrifugoOperation(requestId: number, operation: a
 				}
 				await sendResponse(new CentrifugoOutputVariantConnected())
 			} catch (error) {
-				const errorMessage = error instanceof Error ? error.message : String(error)
-				await sendResponse(new CentrifugoOutputVariantError(errorMessage))
+				// centrifuge-js rejects with plain `{ code, message }` objects
+				const errorMessage = (error as { message?: unknown } | null)?.message
+				await sendResponse(
+					new CentrifugoOutputVariantError(
+						centrifugoErrorCode(error),
+						typeof errorMessage === 'string' ? errorMessage : String(error)
+					)
+				)
 			}
 		}
 
 		centrifugoInstance.onConnected(() => {
 			performSubscriptions()
 		})
+		// the core classifies the code, e.g. an invalid token requires a new login
+		centrifugoInstance.onDisconnected((code, reason) => {
+			if (code === DISCONNECT_CALLED || !sendEventCallback) return
+			sendEventCallback(new EventVariantWebSocket(new WebSocketEventVariantConnectionFailed(code, reason)))
+		})
 		performSubscriptions()
 	} else if (operation instanceof CentrifugoOperationVariantUnsubscribeAll) {
 		centrifugoInstance.disconnect()
 		await sendResponse(new CentrifugoOutputVariantDisconnected())
 	} else {
 		console.error(`[Centrifugo] Unsupported operation`)
-		await sendResponse(new CentrifugoOutputVariantError('Unsupported operation'))
+		await sendResponse(new CentrifugoOutputVariantError(null, 'Unsupported operation'))
 	}
 }
\ No newline at end of file
@@ -5,6 +5,7 @@
  * converting Core's HttpRequest into fetch() calls and returning responses.
  */
 
+import { basePath } from '../../appConfig'
 import { wasmModule } from './state'
 import {
 	HttpResponse as CoreHttpResponse,
@@ -62,7 +63,7 @@ export async function executeHttpRequest(
 		// This side strips the prefix to send a relative URL to avoid HTTPS certificate issues.
 		let url = httpRequest.url
 		if (url.startsWith('https://relative')) {
-			url = url.replace('https://relative', '')
+			url = basePath + url.replace('https://relative', '')
 		}
 
 		const response = await fetch(url, fetchOptions)
@@ -11,7 +11,7 @@
  * for bincode FFI communication with the WASM module.
  */
 
-import { readonly, type DeepReadonly } from 'vue'
+import { readonly, watch, type DeepReadonly } from 'vue'
 
 // Import state
 import {
@@ -37,6 +37,9 @@ import { setEventSender as setCentrifugoEventSender } from './centrifugo'
 // Import sync
 import { setEventSender as setSyncEventSender } from './sync'
 
+// Import type conversions
+import { deviceOperationFromString, type DeviceOperationString } from './types'
+
 // Import serialization
 import { BincodeSerializer } from '../../../../shared_types/generated/typescript/bincode/mod'
 
@@ -61,14 +64,22 @@ import {
 	DeviceEventVariantNetworkFormStartEdit,
 	DeviceEventVariantNetworkFormUpdate,
 	DeviceEventVariantNetworkFormReset,
+	DeviceEventVariantNetworkFormDiscardAll,
 	DeviceEventVariantAckRollback,
+	DeviceEventVariantTriggerRollbackNow,
 	DeviceEventVariantAckFactoryResetResult,
 	DeviceEventVariantAckUpdateValidation,
+	DeviceEventVariantExpectReconnection,
 	WebSocketEventVariantSubscribeToChannels,
 	WebSocketEventVariantUnsubscribeFromChannels,
 	UiEventVariantClearError,
 	UiEventVariantClearSuccess,
+	UiEventVariantClearAllNotifications,
 	UiEventVariantSetBrowserHostname,
+	UiEventVariantToggleAdvancedMode,
+	UiEventVariantSetAdvancedMode,
+	UiEventVariantSetUpdateCompleteStatuses,
+	UiEventVariantRefresh,
 } from '../../../../shared_types/generated/typescript/types/shared_types'
 
 // Re-export types for external use
@@ -79,6 +90,7 @@ export type {
 	NetworkFormStateType,
 	NetworkFormDataType,
 	OverlaySpinnerStateType,
+	OverlaySpinnerModeType,
 	FactoryResetStatusString,
 	SystemInfo,
 	NetworkStatus,
@@ -152,6 +164,9 @@ initializeTimerWatchers()
 // Initialization
 // ============================================================================
 
+/** localStorage key of the persisted advanced mode preference */
+const ADVANCED_MODE_STORAGE_KEY = 'omnect-ui-advanced-mode'
+
 /**
  * Initialize the Crux Core
  *
@@ -189,6 +204,23 @@ async function initializeCore(): Promise<void> {
 			const hostname = window.location.hostname
 			await sendEventToCore(new EventVariantUi(new UiEventVariantSetBrowserHostname(hostname)))
 
+			// Apply the update validation statuses configured as complete, if provided
+			const updateCompleteStatuses = window.__APP_CONFIG__?.UPDATE_COMPLETE_STATUSES
+			if (updateCompleteStatuses) {
+				await sendEventToCore(
+					new EventVariantUi(new UiEventVariantSetUpdateCompleteStatuses(updateCompleteStatuses))
+				)
+			}
+
+			// Restore the advanced mode preference and persist any change of it
+			if (localStorage.getItem(ADVANCED_MODE_STORAGE_KEY) === 'true') {
+				await sendEventToCore(new EventVariantUi(new UiEventVariantSetAdvancedMode(true)))
+			}
+			watch(
+				() => viewModel.advancedMode,
+				(enabled) => localStorage.setItem(ADVANCED_MODE_STORAGE_KEY, String(enabled))
+			)
+
 			// Expose for E2E tests to spoof hostname
 			;(window as any).setBrowserHostname = (h: string) => {
 				console.log(`[useCore] Spoofing browser hostname: ${h}`)
@@ -256,14 +288,20 @@ export function useCore() {
 		checkRequiresPasswordSet: () =>
 			sendEventToCore(new EventVariantAuth(new AuthEventVariantCheckRequiresPasswordSet())),
 		reboot: () => sendEventToCore(new EventVariantDevice(new DeviceEventVariantReboot())),
-		factoryReset: (mode: string, preserve: string[]) =>
-			sendEventToCore(new EventVariantDevice(new DeviceEventVariantFactoryResetRequest(mode, preserve))),
+		factoryReset: (mode: string, preserve: string[], password?: string) =>
+			sendEventToCore(
+				new EventVariantDevice(new DeviceEventVariantFactoryResetRequest(mode, preserve, password ?? null))
+			),
 		setNetworkConfig: (config: string) =>
 			sendEventToCore(new EventVariantDevice(new DeviceEventVariantSetNetworkConfig(config))),
 		loadUpdate: (filePath: string) =>
 			sendEventToCore(new EventVariantDevice(new DeviceEventVariantLoadUpdate(filePath))),
 		runUpdate: (validateIothub: boolean) =>
 			sendEventToCore(new EventVariantDevice(new DeviceEventVariantRunUpdate(validateIothub))),
+		expectReconnection: (operation: DeviceOperationString) =>
+			sendEventToCore(
+				new EventVariantDevice(new DeviceEventVariantExpectReconnection(deviceOperationFromString(operation)))
+			),
 		subscribeToChannels: () => {
 			if (isSubscribed.value) {
 				return
@@ -281,6 +319,9 @@ export function useCore() {
 		},
 		clearError: () => sendEventToCore(new EventVariantUi(new UiEventVariantClearError())),
 		clearSuccess: () => sendEventToCore(new EventVariantUi(new UiEventVariantClearSuccess())),
+		clearAllNotifications: () => sendEventToCore(new EventVariantUi(new UiEventVariantClearAllNotifications())),
+		toggleAdvancedMode: () => sendEventToCore(new EventVariantUi(new UiEventVariantToggleAdvancedMode())),
+		refresh: () => sendEventToCore(new EventVariantUi(new UiEventVariantRefresh())),
 
 		// Network form state management
 		networkFormStartEdit: (adapterName: string) =>
@@ -289,6 +330,10 @@ export function useCore() {
 			sendEventToCore(new EventVariantDevice(new DeviceEventVariantNetworkFormUpdate(formDataJson))),
 		networkFormReset: (adapterName: string) =>
 			sendEventToCore(new EventVariantDevice(new DeviceEventVariantNetworkFormReset(adapterName))),
+		networkFormDiscardAll: () =>
+			sendEventToCore(new EventVariantDevice(new DeviceEventVariantNetworkFormDiscardAll())),
+		triggerRollbackNow: () =>
+			sendEventToCore(new EventVariantDevice(new DeviceEventVariantTriggerRollbackNow())),
 		ackRollback: () =>
 			sendEventToCore(new EventVariantDevice(new DeviceEventVariantAckRollback())),
 		ackFactoryResetResult: () =>
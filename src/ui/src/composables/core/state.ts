@@ -25,33 +25,58 @@ export const viewModel = reactive<ViewModel>({
 	updateManifest: null,
 	timeouts: null,
 	healthcheck: null,
+	uptimeSeconds: null,
 	isAuthenticated: false,
 	requiresPasswordSet: false,
+	authBootstrap: 'checking',
+	sessionExpired: false,
+	factoryResetPasswordRequired: false,
 	isLoading: false,
+	isRefreshing: false,
 	errorMessage: null,
 	successMessage: null,
+	messageSeq: 0,
 	isConnected: false,
+	websocketError: null,
 	authToken: null,
+	// Bootstrap state
+	bootstrapState: { type: 'idle' },
+	backendVersion: null,
 	// Device operation state
 	deviceOperationState: { type: 'idle' },
 	reconnectionAttempt: 0,
 	// Network change state
 	networkChangeState: { type: 'idle' },
+	newIpTimeoutSecs: null,
 	// Network form state
 	networkFormState: { type: 'idle' },
 	// Network form dirty flag
 	networkFormDirty: false,
+	stagedNetworkAdapters: [],
 	// Browser hostname and current connection detection
 	browserHostname: null,
 	currentConnectionAdapter: null,
 	deviceWentOffline: false,
+	// Connection loss outside device operations
+	healthcheckFailures: 0,
+	connectionLost: false,
 	// Network rollback modal state
 	shouldShowRollbackModal: false,
 	defaultRollbackEnabled: true,
 	// Firmware upload state
 	firmwareUploadState: { type: 'idle' },
+	// Advanced mode unlocking dangerous actions
+	advancedMode: false,
+	// Results awaiting acknowledgment
+	pendingAcknowledgments: [],
+	// Device service doesn't satisfy the required version
+	deviceServiceOutdated: false,
+	// Any transient message is shown
+	hasNotifications: false,
+	// Adapter being edited serves the UI
+	editingCurrentAdapter: false,
 	// Overlay spinner state
-	overlaySpinner: { overlay: false, title: '', text: null, timedOut: false, progress: null, countdownSeconds: null },
+	overlaySpinner: { overlay: false, title: '', text: null, timedOut: false, progress: null, countdownSeconds: null, mode: { type: 'indeterminate' }, estimatedSecondsRemaining: null },
 })
 
 /**
@@ -7,11 +7,16 @@
 
 import { viewModel, authToken, isSubscribed, wasmModule, centrifugoInstance } from './state'
 import {
+	authBootstrapStateToString,
+	websocketErrorToString,
+	pendingAcknowledgmentToString,
 	factoryResetStatusToString,
 	convertDeviceOperationState,
 	convertNetworkChangeState,
 	convertNetworkFormState,
 	convertUploadState,
+	convertBootstrapState,
+	convertOverlaySpinnerMode,
 } from './types'
 import { setViewModelUpdater } from './effects'
 import { Model as GeneratedViewModel } from '../../../../shared_types/generated/typescript/types/shared_types'
@@ -22,6 +27,23 @@ import {
 	WebSocketEventVariantSubscribeToChannels,
 } from '../../../../shared_types/generated/typescript/types/shared_types'
 
+/**
+ * Seconds elapsed since the given boot time
+ *
+ * Returns null for a missing or unparseable boot time. A boot time in the
+ * future (e.g. clock skew between browser and device) yields 0.
+ */
+export function uptimeSeconds(bootTime: string | null, now: number = Date.now()): number | null {
+	if (!bootTime) {
+		return null
+	}
+	const bootMillis = Date.parse(bootTime)
+	if (Number.isNaN(bootMillis)) {
+		return null
+	}
+	return Math.max(0, Math.floor((now - bootMillis) / 1000))
+}
+
 // Event sender callback - set by index.ts to avoid circular dependency
 let sendEventCallback: ((event: Event) => Promise<void>) | null = null
 
@@ -133,18 +155,36 @@ export function updateViewModelFromCore(): void {
 					},
 					networkRollbackOccurred: coreViewModel.healthcheck.networkRollbackOccurred,
 					updateValidationAcked: coreViewModel.healthcheck.updateValidationAcked,
+					primaryMac: coreViewModel.healthcheck.primaryMac || null,
+					updateProgress: coreViewModel.healthcheck.updateProgress ?? null,
+					bootTime: coreViewModel.healthcheck.bootTime || null,
 				}
 			: null
 
+		// uptime, the healthcheck provides the boot time before the socket connects
+		viewModel.uptimeSeconds = uptimeSeconds(
+			viewModel.systemInfo?.bootTime ?? viewModel.healthcheck?.bootTime ?? null
+		)
+
 		// Boolean and string fields
 		viewModel.isAuthenticated = coreViewModel.isAuthenticated
 		viewModel.requiresPasswordSet = coreViewModel.requiresPasswordSet
+		viewModel.authBootstrap = authBootstrapStateToString(coreViewModel.authBootstrap)
+		viewModel.sessionExpired = coreViewModel.sessionExpired
+		viewModel.factoryResetPasswordRequired = coreViewModel.factoryResetPasswordRequired
 		viewModel.isLoading = coreViewModel.isLoading
+		viewModel.isRefreshing = coreViewModel.isRefreshing
 		viewModel.errorMessage = coreViewModel.errorMessage || null
 		viewModel.successMessage = coreViewModel.successMessage || null
+		viewModel.messageSeq = coreViewModel.messageSeq
 		viewModel.isConnected = coreViewModel.isConnected
+		viewModel.websocketError = coreViewModel.websocketError ? websocketErrorToString(coreViewModel.websocketError) : null
 		viewModel.authToken = coreViewModel.authToken || null
 
+		// Bootstrap state
+		viewModel.bootstrapState = convertBootstrapState(coreViewModel.bootstrapState)
+		viewModel.backendVersion = coreViewModel.backendVersion || null
+
 		// Sync the ref with the view model
 		authToken.value = viewModel.authToken
 
@@ -171,6 +211,8 @@ export function updateViewModelFromCore(): void {
 				: (coreViewModel.overlaySpinner.countdownSeconds !== null && coreViewModel.overlaySpinner.countdownSeconds !== undefined
 					? coreViewModel.overlaySpinner.countdownSeconds
 					: null),
+			mode: convertOverlaySpinnerMode(coreViewModel.overlaySpinner.mode),
+			estimatedSecondsRemaining: coreViewModel.overlaySpinner.estimatedSecondsRemaining ?? null,
 		}
 
 		// Device operation state - convert bincode variant to typed object
@@ -179,6 +221,8 @@ export function updateViewModelFromCore(): void {
 
 		// Network change state
 		viewModel.networkChangeState = convertNetworkChangeState(coreViewModel.networkChangeState)
+		viewModel.newIpTimeoutSecs =
+			coreViewModel.newIpTimeoutSecs != null ? Number(coreViewModel.newIpTimeoutSecs) : null
 
 		// Network form state
 		viewModel.networkFormState = convertNetworkFormState(coreViewModel.networkFormState)
@@ -186,6 +230,9 @@ export function updateViewModelFromCore(): void {
 		// Network form dirty flag
 		viewModel.networkFormDirty = coreViewModel.networkFormDirty
 
+		// Adapters with staged unsaved changes
+		viewModel.stagedNetworkAdapters = Array.from(coreViewModel.networkForms.keys())
+
 		// Browser hostname and current connection adapter (computed in Core)
 		viewModel.browserHostname = coreViewModel.browserHostname || null
 		viewModel.currentConnectionAdapter = coreViewModel.currentConnectionAdapter || null
@@ -193,6 +240,10 @@ export function updateViewModelFromCore(): void {
 		// Device offline tracking
 		viewModel.deviceWentOffline = coreViewModel.deviceWentOffline
 
+		// Connection loss outside device operations
+		viewModel.healthcheckFailures = coreViewModel.healthcheckFailures
+		viewModel.connectionLost = coreViewModel.connectionLost
+
 		// Network rollback modal state (computed in Core)
 		viewModel.shouldShowRollbackModal = coreViewModel.shouldShowRollbackModal
 		viewModel.defaultRollbackEnabled = coreViewModel.defaultRollbackEnabled
@@ -200,6 +251,21 @@ export function updateViewModelFromCore(): void {
 		// Firmware upload state
 		viewModel.firmwareUploadState = convertUploadState(coreViewModel.firmwareUploadState)
 
+		// Advanced mode
+		viewModel.advancedMode = coreViewModel.advancedMode
+
+		// Results awaiting acknowledgment (computed in Core)
+		viewModel.pendingAcknowledgments = coreViewModel.pendingAcknowledgments.map(pendingAcknowledgmentToString)
+
+		// Device service version check (computed in Core)
+		viewModel.deviceServiceOutdated = coreViewModel.deviceServiceOutdated
+
+		// Any transient message is shown (computed in Core)
+		viewModel.hasNotifications = coreViewModel.hasNotifications
+
+		// Adapter being edited serves the UI (computed in Core)
+		viewModel.editingCurrentAdapter = coreViewModel.editingCurrentAdapter
+
 		// Auto-subscribe logic based on authentication state transition
 		if (viewModel.isAuthenticated && !wasAuthenticated) {
 			console.log('[useCore] User authenticated, triggering subscription')
@@ -5,17 +5,24 @@
  * - Reconnection polling after reboot/factory reset
  * - New IP polling after network config changes
  * - Automatic timeout handling
+ * - Bootstrap request retries with backoff
+ * - Connection monitoring while authenticated
  */
 
 import { watch } from 'vue'
+import { basePath } from '../../appConfig'
 import { viewModel, isInitialized, wasmModule } from './state'
 import type { Event } from '../../../../shared_types/generated/typescript/types/shared_types'
 import {
 	EventVariantDevice,
 	DeviceEventVariantReconnectionCheckTick,
 	DeviceEventVariantReconnectionTimeout,
+	DeviceEventVariantConnectionCheckTick,
 	DeviceEventVariantNewIpCheckTick,
 	DeviceEventVariantNewIpCheckTimeout,
+	DeviceEventVariantNetworkChangeComplete,
+	EventVariantUi,
+	UiEventVariantBootstrapRetry,
 } from '../../../../shared_types/generated/typescript/types/shared_types'
 
 // Timer callback type - will be set by index.ts to avoid circular dependency
@@ -33,7 +40,10 @@ export function setEventSender(callback: (event: Event) => Promise<void>): void
 // ============================================================================
 
 const RECONNECTION_POLL_INTERVAL_MS = Number(import.meta.env.VITE_RECONNECTION_POLL_INTERVAL_MS) || 5000 // 5 seconds
+const CONNECTION_CHECK_INTERVAL_MS = Number(import.meta.env.VITE_CONNECTION_CHECK_INTERVAL_MS) || 10000 // 10 seconds
 const NEW_IP_POLL_INTERVAL_MS = Number(import.meta.env.VITE_NEW_IP_POLL_INTERVAL_MS) || 5000 // 5 seconds
+// Page still loaded after this time means the redirect to the new IP failed
+const REDIRECT_GRACE_MS = 10000 // 10 seconds
 
 // Optional test overrides for reconnection timeouts (production values come from Core)
 const REBOOT_TIMEOUT_OVERRIDE_MS = import.meta.env.VITE_REBOOT_TIMEOUT_MS ? Number(import.meta.env.VITE_REBOOT_TIMEOUT_MS) : null
@@ -51,6 +61,8 @@ let reconnectionCountdownDeadline: number | null = null
 let newIpIntervalId: ReturnType<typeof setInterval> | null = null
 let newIpTimeoutId: ReturnType<typeof setTimeout> | null = null
 let newIpCountdownIntervalId: ReturnType<typeof setInterval> | null = null
+let bootstrapRetryTimeoutId: ReturnType<typeof setTimeout> | null = null
+let connectionCheckIntervalId: ReturnType<typeof setInterval> | null = null
 
 // Countdown deadline for network changes (Unix timestamp in milliseconds)
 let countdownDeadline: number | null = null
@@ -274,7 +286,8 @@ export function startNewIpPolling(): void {
 	if (!switchingToDhcp) {
 		newIpIntervalId = setInterval(() => {
 			if (isInitialized.value && wasmModule.value && sendEventCallback) {
-				sendEventCallback(new EventVariantDevice(new DeviceEventVariantNewIpCheckTick()))
+				// the core measures the timeout by these timestamps, not by the number of ticks
+				sendEventCallback(new EventVariantDevice(new DeviceEventVariantNewIpCheckTick(BigInt(Date.now()))))
 			}
 		}, NEW_IP_POLL_INTERVAL_MS)
 	} else {
@@ -366,6 +379,47 @@ export function initializeTimerWatchers(): void {
 		{ deep: true }
 	)
 
+	// Monitor the connection while authenticated (Core ignores ticks during operations)
+	watch(
+		() => viewModel.isAuthenticated,
+		(isAuthenticated) => {
+			if (connectionCheckIntervalId !== null) {
+				clearInterval(connectionCheckIntervalId)
+				connectionCheckIntervalId = null
+			}
+
+			if (isAuthenticated) {
+				connectionCheckIntervalId = setInterval(() => {
+					if (isInitialized.value && wasmModule.value && sendEventCallback) {
+						sendEventCallback(new EventVariantDevice(new DeviceEventVariantConnectionCheckTick()))
+					}
+				}, CONNECTION_CHECK_INTERVAL_MS)
+			}
+		},
+		{ immediate: true }
+	)
+
+	// Watch bootstrapState to schedule retries of the initial requests (delay comes from Core)
+	watch(
+		() => viewModel.bootstrapState,
+		(newState) => {
+			if (bootstrapRetryTimeoutId !== null) {
+				clearTimeout(bootstrapRetryTimeoutId)
+				bootstrapRetryTimeoutId = null
+			}
+
+			if (newState?.type === 'waitingRetry') {
+				bootstrapRetryTimeoutId = setTimeout(() => {
+					bootstrapRetryTimeoutId = null
+					if (sendEventCallback) {
+						sendEventCallback(new EventVariantUi(new UiEventVariantBootstrapRetry()))
+					}
+				}, newState.delayMs)
+			}
+		},
+		{ deep: true }
+	)
+
 	// Watch networkChangeState for new IP polling and redirect
 	watch(
 		() => viewModel.networkChangeState,
@@ -389,10 +443,11 @@ export function initializeTimerWatchers(): void {
 				startNewIpPolling()
 			}
 
-			// Clear localStorage when entering terminal states (success, timeout, or idle)
+			// Clear localStorage when entering terminal states (success, timeout, unknown reachability, or idle)
 			if (
 				newType !== oldType &&
-				(newType === 'newIpReachable' || newType === 'newIpTimeout' || newType === 'idle')
+				(newType === 'newIpReachable' || newType === 'newIpTimeout'
+					|| newType === 'appliedReachabilityUnknown' || newType === 'idle')
 			) {
 				clearNetworkChangeState()
 			}
@@ -404,7 +459,13 @@ export function initializeTimerWatchers(): void {
 				viewModel.successMessage = null
 				viewModel.errorMessage = null
 				// Use HTTPS (server only listens on HTTPS)
-				window.location.href = `https://${newState.newIp}:${newState.uiPort}`
+				window.location.href = `https://${newState.newIp}:${newState.uiPort}${basePath}`
+				// Finish the network change, so the overlay doesn't stay if the redirect is blocked
+				setTimeout(() => {
+					if (isInitialized.value && wasmModule.value && sendEventCallback) {
+						sendEventCallback(new EventVariantDevice(new DeviceEventVariantNetworkChangeComplete()))
+					}
+				}, REDIRECT_GRACE_MS)
 			}
 		},
 		{ deep: true }
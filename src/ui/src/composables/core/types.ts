@@ -27,6 +27,22 @@ export { NetworkConfigRequest } from '../../../../shared_types/generated/typescr
 
 // Import types and variant classes for conversions
 import {
+	AuthBootstrapState,
+	AuthBootstrapStateVariantchecking,
+	AuthBootstrapStateVariantrequiresSetPassword,
+	AuthBootstrapStateVariantrequiresLogin,
+	AuthBootstrapStateVariantauthenticated,
+	WebSocketError,
+	WebSocketErrorVariantauthenticationFailed,
+	WebSocketErrorVariantconnectionLost,
+	PendingAcknowledgment,
+	PendingAcknowledgmentVariantnetworkRollback,
+	PendingAcknowledgmentVariantupdateValidation,
+	PendingAcknowledgmentVariantfactoryResetResult,
+	DeviceOperation,
+	DeviceOperationVariantreboot,
+	DeviceOperationVariantfactoryReset,
+	DeviceOperationVariantupdate,
 	DeviceOperationState,
 	DeviceOperationStateVariantidle,
 	DeviceOperationStateVariantrebooting,
@@ -42,6 +58,7 @@ import {
 	NetworkChangeStateVariantwaitingForNewIp,
 	NetworkChangeStateVariantnewIpReachable,
 	NetworkChangeStateVariantnewIpTimeout,
+	NetworkChangeStateVariantappliedReachabilityUnknown,
 	NetworkChangeStateVariantwaitingForOldIp,
 	NetworkFormState,
 	NetworkFormStateVariantidle,
@@ -58,6 +75,16 @@ import {
 	UploadStateVariantuploading,
 	UploadStateVariantcompleted,
 	UploadStateVariantfailed,
+	UploadStateVariantcancelled,
+	BootstrapState,
+	BootstrapStateVariantidle,
+	BootstrapStateVariantloading,
+	BootstrapStateVariantwaitingRetry,
+	BootstrapStateVariantcompleted,
+	BootstrapStateVariantfailed,
+	OverlaySpinnerMode,
+	OverlaySpinnerModeVariantprogress,
+	OverlaySpinnerModeVariantcountdown,
 	DeviceNetwork,
 } from '../../../../shared_types/generated/typescript/types/shared_types'
 
@@ -68,6 +95,7 @@ export {
 	NetworkFormState,
 	FactoryResetStatus,
 	UploadState,
+	BootstrapState,
 	DeviceNetwork,
 }
 
@@ -75,14 +103,22 @@ export {
 // TypeScript Discriminated Union Types
 // ============================================================================
 
+export type DeviceOperationString = 'reboot' | 'factoryReset' | 'update'
+
+export type AuthBootstrapStateString = 'checking' | 'requiresSetPassword' | 'requiresLogin' | 'authenticated'
+
+export type PendingAcknowledgmentString = 'networkRollback' | 'updateValidation' | 'factoryResetResult'
+
+export type WebSocketErrorString = 'authenticationFailed' | 'connectionLost'
+
 export type DeviceOperationStateType =
 	| { type: 'idle' }
 	| { type: 'rebooting' }
 	| { type: 'factoryResetting' }
 	| { type: 'updating' }
-	| { type: 'waitingReconnection'; operation: string; attempt: number }
-	| { type: 'reconnectionFailed'; operation: string; reason: string }
-	| { type: 'reconnectionSuccessful'; operation: string }
+	| { type: 'waitingReconnection'; operation: DeviceOperationString; attempt: number }
+	| { type: 'reconnectionFailed'; operation: DeviceOperationString; reason: string }
+	| { type: 'reconnectionSuccessful'; operation: DeviceOperationString }
 
 export type NetworkChangeStateType =
 	| { type: 'idle' }
@@ -90,6 +126,7 @@ export type NetworkChangeStateType =
 	| { type: 'waitingForNewIp'; newIp: string; oldIp: string; attempt: number; uiPort: number; rollbackTimeoutSeconds: number; switchingToDhcp: boolean }
 	| { type: 'newIpReachable'; newIp: string; uiPort: number }
 	| { type: 'newIpTimeout'; newIp: string; oldIp: string; uiPort: number; switchingToDhcp: boolean }
+	| { type: 'appliedReachabilityUnknown'; newIp: string; uiPort: number }
 	| { type: 'waitingForOldIp'; oldIp: string; uiPort: number; attempt: number }
 
 export type NetworkFormStateType =
@@ -102,6 +139,14 @@ export type UploadStateType =
 	| { type: 'uploading' }
 	| { type: 'completed' }
 	| { type: 'failed'; content: string }
+	| { type: 'cancelled' }
+
+export type BootstrapStateType =
+	| { type: 'idle' }
+	| { type: 'loading'; attempt: number }
+	| { type: 'waitingRetry'; attempt: number; delayMs: number }
+	| { type: 'completed' }
+	| { type: 'failed'; reason: string }
 
 export interface NetworkFormDataType {
 	name: string
@@ -112,6 +157,11 @@ export interface NetworkFormDataType {
 	gateways: string[]
 }
 
+export type OverlaySpinnerModeType =
+	| { type: 'indeterminate' }
+	| { type: 'progress'; value: number }
+	| { type: 'countdown'; seconds: number }
+
 export interface OverlaySpinnerStateType {
 	overlay: boolean
 	title: string
@@ -119,6 +169,8 @@ export interface OverlaySpinnerStateType {
 	timedOut: boolean
 	progress: number | null
 	countdownSeconds: number | null
+	mode: OverlaySpinnerModeType
+	estimatedSecondsRemaining: number | null
 }
 
 export type FactoryResetStatusString = 'unknown' | 'modeSupported' | 'modeUnsupported' | 'backupRestoreError' | 'configurationError'
@@ -156,21 +208,39 @@ export interface ViewModel {
 		updateValidationStatus: { status: string }
 		networkRollbackOccurred: boolean
 		updateValidationAcked: boolean
+		primaryMac: string | null
+		updateProgress: number | null
+		bootTime: string | null
 	} | null
+	// Seconds since boot, derived from the boot time of system info or healthcheck
+	uptimeSeconds: number | null
 	isAuthenticated: boolean
 	requiresPasswordSet: boolean
+	authBootstrap: AuthBootstrapStateString
+	sessionExpired: boolean
+	// Set when a factory reset has to be repeated with the user password
+	factoryResetPasswordRequired: boolean
 	isLoading: boolean
+	isRefreshing: boolean
 	errorMessage: string | null
 	successMessage: string | null
+	messageSeq: number
 	isConnected: boolean
+	// Why the last centrifugo connection attempt failed, null once connected
+	websocketError: WebSocketErrorString | null
 	authToken: string | null
 
+	// Bootstrap state (initial config/version requests)
+	bootstrapState: BootstrapStateType
+	backendVersion: string | null
+
 	// Device operation state (reboot/factory reset reconnection)
 	deviceOperationState: DeviceOperationStateType
 	reconnectionAttempt: number
 
 	// Network change state (IP change detection and polling)
 	networkChangeState: NetworkChangeStateType
+	newIpTimeoutSecs: number | null
 
 	// Network form state
 	networkFormState: NetworkFormStateType
@@ -178,6 +248,9 @@ export interface ViewModel {
 	// Network form dirty flag (tracks unsaved changes)
 	networkFormDirty: boolean
 
+	// Adapters with unsaved changes besides the active one
+	stagedNetworkAdapters: string[]
+
 	// Browser hostname
 	browserHostname: string | null
 
@@ -187,6 +260,10 @@ export interface ViewModel {
 	// Device offline tracking
 	deviceWentOffline: boolean
 
+	// Connection loss outside device operations
+	healthcheckFailures: number
+	connectionLost: boolean
+
 	// Network rollback modal state
 	shouldShowRollbackModal: boolean
 	defaultRollbackEnabled: boolean
@@ -194,6 +271,21 @@ export interface ViewModel {
 	// Firmware upload state
 	firmwareUploadState: UploadStateType
 
+	// Advanced mode unlocking dangerous actions
+	advancedMode: boolean
+
+	// Results awaiting acknowledgment (computed in Core)
+	pendingAcknowledgments: PendingAcknowledgmentString[]
+
+	// Device service doesn't satisfy the required version (computed in Core)
+	deviceServiceOutdated: boolean
+
+	// Any transient message is shown (computed in Core)
+	hasNotifications: boolean
+
+	// Adapter being edited serves the UI (computed in Core)
+	editingCurrentAdapter: boolean
+
 	// Overlay spinner state
 	overlaySpinner: OverlaySpinnerStateType
 }
@@ -215,6 +307,60 @@ export function factoryResetStatusToString(status: any): FactoryResetStatusStrin
 	return 'unknown'
 }
 
+/**
+ * Convert AuthBootstrapState class variant to string literal
+ */
+export function authBootstrapStateToString(state: AuthBootstrapState): AuthBootstrapStateString {
+	if (state instanceof AuthBootstrapStateVariantrequiresSetPassword) return 'requiresSetPassword'
+	if (state instanceof AuthBootstrapStateVariantrequiresLogin) return 'requiresLogin'
+	if (state instanceof AuthBootstrapStateVariantauthenticated) return 'authenticated'
+	if (state instanceof AuthBootstrapStateVariantchecking) return 'checking'
+	return 'checking'
+}
+
+/**
+ * Convert WebSocketError class variant to string literal
+ */
+export function websocketErrorToString(error: WebSocketError): WebSocketErrorString {
+	if (error instanceof WebSocketErrorVariantauthenticationFailed) return 'authenticationFailed'
+	if (error instanceof WebSocketErrorVariantconnectionLost) return 'connectionLost'
+	return 'connectionLost'
+}
+
+/**
+ * Convert PendingAcknowledgment class variant to string literal
+ */
+export function pendingAcknowledgmentToString(item: PendingAcknowledgment): PendingAcknowledgmentString {
+	if (item instanceof PendingAcknowledgmentVariantupdateValidation) return 'updateValidation'
+	if (item instanceof PendingAcknowledgmentVariantfactoryResetResult) return 'factoryResetResult'
+	if (item instanceof PendingAcknowledgmentVariantnetworkRollback) return 'networkRollback'
+	return 'networkRollback'
+}
+
+/**
+ * Convert DeviceOperation class variant to string literal
+ */
+export function deviceOperationToString(operation: DeviceOperation): DeviceOperationString {
+	if (operation instanceof DeviceOperationVariantfactoryReset) return 'factoryReset'
+	if (operation instanceof DeviceOperationVariantupdate) return 'update'
+	if (operation instanceof DeviceOperationVariantreboot) return 'reboot'
+	return 'reboot'
+}
+
+/**
+ * Convert string literal to DeviceOperation class variant
+ */
+export function deviceOperationFromString(operation: DeviceOperationString): DeviceOperation {
+	switch (operation) {
+		case 'factoryReset':
+			return new DeviceOperationVariantfactoryReset()
+		case 'update':
+			return new DeviceOperationVariantupdate()
+		case 'reboot':
+			return new DeviceOperationVariantreboot()
+	}
+}
+
 /**
  * Convert DeviceOperationState variant to typed object
  */
@@ -232,13 +378,13 @@ export function convertDeviceOperationState(state: DeviceOperationState): Device
 		return { type: 'updating' }
 	}
 	if (state instanceof DeviceOperationStateVariantwaitingReconnection) {
-		return { type: 'waitingReconnection', operation: state.operation, attempt: state.attempt }
+		return { type: 'waitingReconnection', operation: deviceOperationToString(state.operation), attempt: state.attempt }
 	}
 	if (state instanceof DeviceOperationStateVariantreconnectionFailed) {
-		return { type: 'reconnectionFailed', operation: state.operation, reason: state.reason }
+		return { type: 'reconnectionFailed', operation: deviceOperationToString(state.operation), reason: state.reason }
 	}
 	if (state instanceof DeviceOperationStateVariantreconnectionSuccessful) {
-		return { type: 'reconnectionSuccessful', operation: state.operation }
+		return { type: 'reconnectionSuccessful', operation: deviceOperationToString(state.operation) }
 	}
 	return { type: 'idle' }
 }
@@ -283,6 +429,9 @@ export function convertNetworkChangeState(state: NetworkChangeState): NetworkCha
 			switchingToDhcp: state.switching_to_dhcp,
 		}
 	}
+	if (state instanceof NetworkChangeStateVariantappliedReachabilityUnknown) {
+		return { type: 'appliedReachabilityUnknown', newIp: state.new_ip, uiPort: state.ui_port }
+	}
 	if (state instanceof NetworkChangeStateVariantwaitingForOldIp) {
 		return {
 			type: 'waitingForOldIp',
@@ -350,5 +499,43 @@ export function convertUploadState(state: UploadState): UploadStateType {
 	if (state instanceof UploadStateVariantfailed) {
 		return { type: 'failed', content: state.value }
 	}
+	if (state instanceof UploadStateVariantcancelled) {
+		return { type: 'cancelled' }
+	}
 	return { type: 'idle' }
 }
+
+/**
+ * Convert BootstrapState variant to typed object
+ */
+export function convertBootstrapState(state: BootstrapState): BootstrapStateType {
+	if (state instanceof BootstrapStateVariantidle) {
+		return { type: 'idle' }
+	}
+	if (state instanceof BootstrapStateVariantloading) {
+		return { type: 'loading', attempt: state.attempt }
+	}
+	if (state instanceof BootstrapStateVariantwaitingRetry) {
+		return { type: 'waitingRetry', attempt: state.attempt, delayMs: state.delay_ms }
+	}
+	if (state instanceof BootstrapStateVariantcompleted) {
+		return { type: 'completed' }
+	}
+	if (state instanceof BootstrapStateVariantfailed) {
+		return { type: 'failed', reason: state.reason }
+	}
+	return { type: 'idle' }
+}
+
+/**
+ * Convert OverlaySpinnerMode variant to typed object
+ */
+export function convertOverlaySpinnerMode(mode: OverlaySpinnerMode): OverlaySpinnerModeType {
+	if (mode instanceof OverlaySpinnerModeVariantprogress) {
+		return { type: 'progress', value: mode.value }
+	}
+	if (mode instanceof OverlaySpinnerModeVariantcountdown) {
+		return { type: 'countdown', seconds: mode.value }
+	}
+	return { type: 'indeterminate' }
+}
@@ -30,12 +30,12 @@ export function useAuthNavigation() {
     }
   )
 
-  // Watch for requiresPasswordSet state change
+  // Watch the Core's auth bootstrap state for password setup
   // Only navigate if we're not already on the set-password page
   watch(
-    () => viewModel.requiresPasswordSet,
-    async (requiresPasswordSet) => {
-      if (requiresPasswordSet && route.path !== "/set-password") {
+    () => viewModel.authBootstrap,
+    async (authBootstrap) => {
+      if (authBootstrap === "requiresSetPassword" && route.path !== "/set-password") {
         await router.push("/set-password")
       }
     }
@@ -1,5 +1,6 @@
 import { Centrifuge, type PublicationContext, SubscriptionState } from "centrifuge"
 import { type Ref, ref } from "vue"
+import { basePath } from "../appConfig"
 import type { CentrifugeSubscriptionType } from "../enums/centrifuge-subscription-type.enum"
 import { useEventHook } from "./useEventHook"
 
@@ -8,6 +9,7 @@ let globalAuthTokenRef: Ref<string | null> | undefined
 
 const centrifuge: Ref<Centrifuge | undefined> = ref(undefined)
 const connectedEvent = useEventHook()
+const disconnectedEvent = useEventHook<[number, string]>()
 const isConnected = ref(false)
 
 export function useCentrifuge() {
@@ -43,6 +45,7 @@ export function useCentrifuge() {
 				})
 				.on("disconnected", (ctx) => {
 					isConnected.value = false
+					disconnectedEvent.trigger(ctx.code, ctx.reason)
 					console.debug(`disconnected: ${ctx.code}, ${ctx.reason}`)
 				})
 				.connect()
@@ -73,7 +76,7 @@ export function useCentrifuge() {
 				? { Authorization: `Bearer ${token}` }
 				: {}
 
-			const res = await fetch("token/refresh", {
+			const res = await fetch(`${basePath}/token/refresh`, {
 				credentials: "include",
 				headers
 			});
@@ -147,5 +150,5 @@ export function useCentrifuge() {
 		}
 	}
 
-	return { subscribe, unsubscribe, unsubscribeAll, initializeCentrifuge, history, disconnect, onConnected: connectedEvent.on, isConnected, setAuthToken };
+	return { subscribe, unsubscribe, unsubscribeAll, initializeCentrifuge, history, disconnect, onConnected: connectedEvent.on, onDisconnected: disconnectedEvent.on, isConnected, setAuthToken };
 }
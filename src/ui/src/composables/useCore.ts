@@ -22,6 +22,7 @@ export type {
 	NetworkFormStateType,
 	NetworkFormDataType,
 	OverlaySpinnerStateType,
+	OverlaySpinnerModeType,
 	FactoryResetStatusString,
 	SystemInfo,
 	NetworkStatus,
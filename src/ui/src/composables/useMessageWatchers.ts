@@ -33,27 +33,24 @@ export function useMessageWatchers(options?: {
   const { viewModel, clearSuccess, clearError } = useCore()
   const { showSuccess, showError } = useSnackbar()
 
-  // Watch for success messages
+  // Watch the message sequence instead of the strings, so that a repeated
+  // identical message is shown again
   watch(
-    () => viewModel.successMessage,
-    (newMessage) => {
-      if (newMessage) {
-        showSuccess(newMessage)
-        options?.onSuccess?.(newMessage)
+    () => viewModel.messageSeq,
+    () => {
+      const successMessage = viewModel.successMessage
+      if (successMessage) {
+        showSuccess(successMessage)
+        options?.onSuccess?.(successMessage)
         clearSuccess()
       }
-    }
-  )
 
-  // Watch for error messages
-  watch(
-    () => viewModel.errorMessage,
-    (newMessage) => {
-      if (newMessage) {
+      const errorMessage = viewModel.errorMessage
+      if (errorMessage) {
         if (!options?.suppressErrorToast?.()) {
-          showError(newMessage)
+          showError(errorMessage)
         }
-        options?.onError?.(newMessage)
+        options?.onError?.(errorMessage)
         clearError()
       }
     }
@@ -1,5 +1,7 @@
 interface Window {
 	__APP_CONFIG__: {
 		KEYCLOAK_URL: string
+		BASE_PATH: string
+		UPDATE_COMPLETE_STATUSES?: string[]
 	}
 }
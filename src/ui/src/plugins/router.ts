@@ -1,4 +1,5 @@
 import { createRouter, createWebHistory } from "vue-router"
+import { basePath } from "../appConfig"
 import { getUser, login, removeUser } from "../auth/auth-service"
 import { useCore } from "../composables/useCore"
 import Callback from "../pages/Callback.vue"
@@ -20,7 +21,7 @@ const routes = [
 ]
 
 const router = createRouter({
-	history: createWebHistory(),
+	history: createWebHistory(basePath),
 	routes
 })
 
@@ -48,7 +49,7 @@ router.beforeEach(async (to) => {
 		// localStorage while the backend session is fresh — so Callback.vue is
 		// bypassed and the flag is never set.
 		try {
-			const res = await fetch("/token/validate", {
+			const res = await fetch(`${basePath}/token/validate`, {
 				method: "POST",
 				headers: { "Content-Type": "text/plain" },
 				body: user.access_token,
@@ -209,9 +209,9 @@ test.describe('Authentication', () => {
 
     await page.getByRole('button', { name: /set new password/i }).click();
 
-    // Verify success message and redirect to dashboard
+    // Verify success message and redirect to login, the session ends with the password change
     await expect(page.getByText(/password updated successfully/i)).toBeVisible();
-    await expect(page.getByText('Common Info')).toBeVisible({ timeout: 10000 });
+    await expect(page.getByPlaceholder(/enter your password/i)).toBeVisible({ timeout: 10000 });
   });
 
   test.describe('inline error display', () => {
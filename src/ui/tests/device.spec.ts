@@ -29,4 +29,38 @@ test.describe('Device Info', () => {
     await expect(page.getByText('4.5.6')).toBeVisible();
     await expect(page.getByText('omnect-device')).toBeVisible();
   });
+
+  test('displays uptime computed from boot time', async ({ page }) => {
+    const uptimeSeconds = 2 * 86400 + 3 * 3600 + 5 * 60 + 10;
+    const systemInfo = {
+      os: { name: 'Omnect OS', version: '1.2.3' },
+      azure_sdk_version: '0.1.0',
+      omnect_device_service_version: '4.5.6',
+      boot_time: new Date(Date.now() - uptimeSeconds * 1000).toISOString(),
+      hostname: 'omnect-device',
+    };
+
+    await publishToCentrifugo('SystemInfoV1', systemInfo);
+
+    const uptime = page.locator('dl > div', { has: page.getByText('Uptime', { exact: true }) }).locator('dd');
+    await expect(uptime).toHaveText('2d 3h 5m');
+  });
+
+  test('shows n/a for an unparseable boot time', async ({ page }) => {
+    const systemInfo = {
+      os: { name: 'Omnect OS', version: '1.2.3' },
+      azure_sdk_version: '0.1.0',
+      omnect_device_service_version: '4.5.6',
+      boot_time: 'not a timestamp',
+      hostname: 'omnect-device',
+    };
+
+    await publishToCentrifugo('SystemInfoV1', systemInfo);
+
+    await expect(page.getByText('omnect-device')).toBeVisible();
+    const uptime = page.locator('dl > div', { has: page.getByText('Uptime', { exact: true }) }).locator('dd');
+    await expect(uptime).toHaveText('n/a');
+    const bootTime = page.locator('dl > div', { has: page.getByText('Boot time', { exact: true }) }).locator('dd');
+    await expect(bootTime).toHaveText('n/a');
+  });
 });
@@ -5,6 +5,8 @@ import { publishToCentrifugo } from './fixtures/centrifugo';
 
 test.describe('Device Factory Reset', () => {
   test.beforeEach(async ({ page }) => {
+    // factory reset is only offered in advanced mode
+    await page.addInitScript(() => localStorage.setItem('omnect-ui-advanced-mode', 'true'));
     await setupAndLogin(page);
   });
 
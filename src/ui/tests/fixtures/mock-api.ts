@@ -65,7 +65,7 @@ export async function mockUpdatePasswordSuccess(page: Page) {
     await route.fulfill({
       status: 200,
       contentType: 'application/json',
-      body: JSON.stringify({}),
+      body: JSON.stringify({ action: 'reauthenticate' }),
     });
   });
 }
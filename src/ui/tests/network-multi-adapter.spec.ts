@@ -168,6 +168,46 @@ test.describe('Network Multi-Adapter Rollback Modal', () => {
       const wlan0IpInput = page.getByRole('textbox', { name: /IP Address/i }).first();
       await expect(wlan0IpInput).toHaveValue('192.168.2.100');
     });
+
+    test('keeping changes on switch preserves unsaved edits of both adapters', async ({ page }) => {
+      await harness.setup(page, [
+        {
+          name: 'eth0',
+          ipv4: { addrs: [{ addr: 'localhost', dhcp: false, prefix_len: 24 }] }
+        },
+        {
+          name: 'wlan0',
+          ipv4: { addrs: [{ addr: '192.168.2.100', dhcp: false, prefix_len: 24 }] }
+        }
+      ]);
+
+      // Make unsaved changes on eth0 and keep them when switching to wlan0
+      await page.getByRole('tab', { name: 'eth0' }).click();
+      const ipInput = page.locator('.v-window-item--active').getByRole('textbox', { name: /IP Address/i });
+      await ipInput.fill('192.168.1.200');
+      await page.waitForTimeout(300);
+
+      await page.getByRole('tab', { name: 'wlan0' }).click();
+      await expect(page.getByText('Unsaved Changes', { exact: true })).toBeVisible();
+      await page.locator('[data-cy=network-keep-changes-button]').click();
+      await page.waitForTimeout(300);
+
+      await expect(ipInput).toHaveValue('192.168.2.100');
+      await expect(page.getByRole('tab', { name: 'eth0' }).locator('[data-cy=network-staged-changes-icon]')).toBeVisible();
+
+      // Make unsaved changes on wlan0 as well and keep them when switching back
+      await ipInput.fill('192.168.2.200');
+      await page.waitForTimeout(300);
+
+      await page.getByRole('tab', { name: 'eth0' }).click();
+      await expect(page.getByText('Unsaved Changes', { exact: true })).toBeVisible();
+      await page.locator('[data-cy=network-keep-changes-button]').click();
+      await page.waitForTimeout(300);
+
+      await expect(ipInput).toHaveValue('192.168.1.200');
+      await expect(page.locator('.v-window-item--active [data-cy=network-discard-button]')).toBeEnabled();
+      await expect(page.getByRole('tab', { name: 'wlan0' }).locator('[data-cy=network-staged-changes-icon]')).toBeVisible();
+    });
   });
 
   test.describe('3-Adapter Scenarios', () => {
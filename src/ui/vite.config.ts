@@ -21,6 +21,15 @@ export default defineConfig({
 			}
 		}
 	},
+	experimental: {
+		// the backend relocates index.html below BASE_PATH, urls inside scripts
+		// resolve relative to the script, so they follow without being rewritten
+		renderBuiltUrl(_filename, { hostType }) {
+			if (hostType === "js") {
+				return { relative: true }
+			}
+		}
+	},
 	build: {
 		chunkSizeWarningLimit: 1000,
 		rollupOptions: {